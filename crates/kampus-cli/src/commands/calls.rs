@@ -1,24 +1,29 @@
 //! Calls command implementation - show call graph
 
-use kampus_core::graph::{FalkorValue, GraphSchema};
+use kampus_core::graph::store::{CallDirection, CallPathEntry};
+use kampus_core::graph::{GraphStore, StoreBackend};
+
+/// Maximum number of functions listed per direction
+const LIMIT: usize = 50;
 
 pub async fn run(
     function: &str,
     direction: &str,
     depth: u32,
+    backend: StoreBackend,
     db_uri: Option<&str>,
     graph_name: &str,
 ) -> anyhow::Result<()> {
-    let schema = GraphSchema::connect(db_uri, graph_name).await?;
+    let store = super::open_store(backend, db_uri, graph_name).await?;
 
     match direction {
-        "callers" => show_callers(&schema, function, depth).await?,
-        "callees" => show_callees(&schema, function, depth).await?,
-        "both" | _ => {
+        "callers" => show_callers(store.as_ref(), function, depth).await?,
+        "callees" => show_callees(store.as_ref(), function, depth).await?,
+        _ => {
             println!("=== Callers (functions that call {}) ===\n", function);
-            show_callers(&schema, function, depth).await?;
+            show_callers(store.as_ref(), function, depth).await?;
             println!("\n=== Callees (functions called by {}) ===\n", function);
-            show_callees(&schema, function, depth).await?;
+            show_callees(store.as_ref(), function, depth).await?;
         }
     }
 
@@ -26,23 +31,13 @@ pub async fn run(
 }
 
 async fn show_callers(
-    schema: &GraphSchema,
+    store: &dyn GraphStore,
     function: &str,
     depth: u32,
 ) -> anyhow::Result<()> {
-    let cypher = format!(
-        r#"
-        MATCH (target:Function {{name: '{}'}})
-        MATCH path = (caller:Function)-[:CALLS*1..{}]->(target)
-        RETURN caller.name, caller.file_path, length(path)
-        ORDER BY length(path), caller.name
-        LIMIT 50
-        "#,
-        escape_string(function),
-        depth
-    );
-
-    let results = schema.query(&cypher).await?;
+    let results = store
+        .call_paths(function, CallDirection::Callers, depth, LIMIT)
+        .await?;
 
     if results.is_empty() {
         println!("No callers found for '{}'", function);
@@ -51,44 +46,20 @@ async fn show_callers(
 
     println!("{:<30} {:<40} {:<8}", "CALLER", "FILE", "DISTANCE");
     println!("{}", "-".repeat(80));
-
-    for row in &results {
-        let name = extract_string(&row.get(0));
-        let file = extract_string(&row.get(1));
-        let distance = extract_i64(&row.get(2));
-
-        let indent = "  ".repeat((distance - 1).max(0) as usize);
-        println!(
-            "{}{:<30} {:<40} {}",
-            indent,
-            truncate(&name, 30 - indent.len()),
-            truncate(&file, 40),
-            distance
-        );
-    }
+    print_entries(&results);
 
     println!("\nFound {} caller(s).", results.len());
     Ok(())
 }
 
 async fn show_callees(
-    schema: &GraphSchema,
+    store: &dyn GraphStore,
     function: &str,
     depth: u32,
 ) -> anyhow::Result<()> {
-    let cypher = format!(
-        r#"
-        MATCH (source:Function {{name: '{}'}})
-        MATCH path = (source)-[:CALLS*1..{}]->(callee:Function)
-        RETURN callee.name, callee.file_path, length(path)
-        ORDER BY length(path), callee.name
-        LIMIT 50
-        "#,
-        escape_string(function),
-        depth
-    );
-
-    let results = schema.query(&cypher).await?;
+    let results = store
+        .call_paths(function, CallDirection::Callees, depth, LIMIT)
+        .await?;
 
     if results.is_empty() {
         println!("No callees found for '{}'", function);
@@ -97,40 +68,23 @@ async fn show_callees(
 
     println!("{:<30} {:<40} {:<8}", "CALLEE", "FILE", "DISTANCE");
     println!("{}", "-".repeat(80));
+    print_entries(&results);
 
-    for row in &results {
-        let name = extract_string(&row.get(0));
-        let file = extract_string(&row.get(1));
-        let distance = extract_i64(&row.get(2));
+    println!("\nFound {} callee(s).", results.len());
+    Ok(())
+}
 
-        let indent = "  ".repeat((distance - 1).max(0) as usize);
+fn print_entries(entries: &[CallPathEntry]) {
+    for entry in entries {
+        let indent = "  ".repeat(entry.distance.saturating_sub(1) as usize);
         println!(
             "{}{:<30} {:<40} {}",
             indent,
-            truncate(&name, 30 - indent.len()),
-            truncate(&file, 40),
-            distance
+            truncate(&entry.name, 30usize.saturating_sub(indent.len())),
+            truncate(&entry.file_path, 40),
+            entry.distance
         );
     }
-
-    println!("\nFound {} callee(s).", results.len());
-    Ok(())
-}
-
-fn extract_string(val: &Option<&FalkorValue>) -> String {
-    val.and_then(|v| match v {
-        FalkorValue::String(s) => Some(s.clone()),
-        _ => None,
-    })
-    .unwrap_or_default()
-}
-
-fn extract_i64(val: &Option<&FalkorValue>) -> i64 {
-    val.and_then(|v| match v {
-        FalkorValue::I64(n) => Some(*n),
-        _ => None,
-    })
-    .unwrap_or(0)
 }
 
 fn truncate(s: &str, max_len: usize) -> String {
@@ -143,11 +97,3 @@ fn truncate(s: &str, max_len: usize) -> String {
         format!("{}...", &s[..max_len - 3])
     }
 }
-
-fn escape_string(s: &str) -> String {
-    s.replace('\\', "\\\\")
-        .replace('\'', "\\'")
-        .replace('\n', "\\n")
-        .replace('\r', "\\r")
-        .replace('\t', "\\t")
-}
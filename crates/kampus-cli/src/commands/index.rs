@@ -1,17 +1,20 @@
 //! Index command implementation
 
+use kampus_core::graph::StoreBackend;
 use kampus_core::index::full::{FullIndexConfig, FullIndexer};
 use kampus_core::Language;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub async fn run(
     path: &str,
     jobs: Option<usize>,
     languages: Option<&str>,
     clear_existing: bool,
+    backend: StoreBackend,
     db_uri: Option<&str>,
     graph_name: &str,
 ) -> anyhow::Result<()> {
+    kampus_core::parser::grammars::register(Path::new(path))?;
     let languages = parse_languages(languages)?;
 
     let config = FullIndexConfig {
@@ -19,8 +22,10 @@ pub async fn run(
         languages,
         threads: jobs.unwrap_or_else(num_cpus::get),
         clear_existing,
+        backend,
         db_uri: db_uri.map(String::from),
         graph_name: graph_name.to_string(),
+        ..Default::default()
     };
 
     let indexer = FullIndexer::new(config);
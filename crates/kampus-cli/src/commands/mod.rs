@@ -6,3 +6,37 @@ pub mod index;
 pub mod query;
 pub mod status;
 pub mod update;
+
+use kampus_core::graph::{EmbeddedStore, GraphStore, StoreBackend};
+use std::sync::Arc;
+
+/// Open the graph store for commands that read the index
+///
+/// The embedded store is looked up in the current directory and its parents.
+pub async fn open_store(
+    backend: StoreBackend,
+    db_uri: Option<&str>,
+    graph_name: &str,
+) -> anyhow::Result<Arc<dyn GraphStore>> {
+    let cwd = std::env::current_dir()?;
+    let root = match backend {
+        StoreBackend::FalkorDb => cwd,
+        StoreBackend::Embedded => EmbeddedStore::discover(&cwd).ok_or_else(|| {
+            anyhow::anyhow!(
+                "No {} directory found. Run `kampus index --backend embedded` first.",
+                EmbeddedStore::DIR
+            )
+        })?,
+    };
+
+    Ok(kampus_core::graph::open_store(backend, db_uri, graph_name, &root).await?)
+}
+
+/// Register the runtime grammars of the project containing the current
+/// directory, so their languages can be named on the command line
+pub fn register_grammars() -> anyhow::Result<()> {
+    let cwd = std::env::current_dir()?;
+    let root = EmbeddedStore::discover(&cwd).unwrap_or(cwd);
+    kampus_core::parser::grammars::register(&root)?;
+    Ok(())
+}
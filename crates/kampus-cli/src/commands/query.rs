@@ -1,13 +1,18 @@
 //! Query command implementation
 
-use kampus_core::graph::{FalkorValue, GraphSchema};
+use kampus_core::graph::{FalkorValue, GraphSchema, StoreBackend};
 
 pub async fn run(
     cypher: &str,
     format: &str,
+    backend: StoreBackend,
     db_uri: Option<&str>,
     graph_name: &str,
 ) -> anyhow::Result<()> {
+    if backend != StoreBackend::FalkorDb {
+        anyhow::bail!("Cypher queries need the falkordb backend (current: {})", backend);
+    }
+
     let schema = GraphSchema::connect(db_uri, graph_name).await?;
     let results = schema.query(cypher).await?;
 
@@ -26,7 +31,7 @@ pub async fn run(
                 .collect();
             println!("{}", serde_json::to_string_pretty(&json_results)?);
         }
-        "table" | _ => {
+        _ => {
             if results.is_empty() {
                 println!("No results found.");
             } else {
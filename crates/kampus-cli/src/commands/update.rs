@@ -1,5 +1,6 @@
 //! Update command implementation
 
+use kampus_core::graph::StoreBackend;
 use kampus_core::index::incremental::{IncrementalConfig, IncrementalIndexer};
 use std::path::PathBuf;
 
@@ -7,6 +8,7 @@ pub async fn run(
     path: &str,
     since: Option<&str>,
     dry_run: bool,
+    backend: StoreBackend,
     db_uri: Option<&str>,
     graph_name: &str,
 ) -> anyhow::Result<()> {
@@ -15,6 +17,7 @@ pub async fn run(
         languages: None,
         since: since.map(String::from),
         dry_run,
+        backend,
         db_uri: db_uri.map(String::from),
         graph_name: graph_name.to_string(),
     };
@@ -5,6 +5,7 @@
 mod commands;
 
 use clap::{Parser, Subcommand};
+use kampus_core::graph::StoreBackend;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
 #[derive(Parser)]
@@ -16,6 +17,10 @@ struct Cli {
     #[arg(short, long, global = true)]
     verbose: bool,
 
+    /// Storage backend: falkordb, or embedded (stored in .kampus/)
+    #[arg(long, global = true, env = "KAMPUS_BACKEND", default_value = "falkordb")]
+    backend: StoreBackend,
+
     /// FalkorDB connection URI (default: redis://localhost:6379)
     #[arg(long, global = true, env = "KAMPUS_DB_URI")]
     db_uri: Option<String>,
@@ -40,7 +45,8 @@ enum Commands {
         #[arg(short, long)]
         jobs: Option<usize>,
 
-        /// Languages to index (comma-separated: py,rs,ts,js,go,cpp)
+        /// Languages to index (comma-separated: py,rs,ts,js,go,c,cpp,java,cs,rb,php,
+        /// or a grammar from .kampus/grammars.toml)
         #[arg(short, long)]
         languages: Option<String>,
 
@@ -77,21 +83,27 @@ enum Commands {
     /// Find symbols by name pattern
     #[command(
         about = "Find symbols by name pattern",
-        long_about = "Find symbols by name pattern (case-insensitive). Supports '*' wildcards.\n\nKinds:\n  function, class, struct, interface, method, trait, enum\n\nLanguages:\n  rs, py, ts, js, go, cpp\n\nExamples:\n  kampus find \"User*\"              # names starting with User\n  kampus find \"*product*\"           # names containing product\n  kampus find \"process_*\" --kind function --language rs\n  kampus find \"*product*\" --full-paths --limit 50\n\nTips for LLMs/agents:\n  - Always quote the pattern to prevent shell expansion.\n  - Use --full-paths to avoid truncated file paths.\n  - Use --kind/--language to narrow results and reduce noise.\n"
+        long_about = "Find symbols by name pattern (case-insensitive). Supports '*' wildcards.\nPatterns containing '::', '.' or '/' match qualified names instead.\n\nKinds:\n  function, class, struct, interface, method, trait, enum, component, property,\n  union, type_alias, macro, module, constant, variable, field, enum_variant\n\nLanguages:\n  rs, py, ts, js, go, c, cpp, java, cs, rb, php, and grammars from .kampus/grammars.toml\n\nExamples:\n  kampus find \"User*\"              # names starting with User\n  kampus find \"*product*\"           # names containing product\n  kampus find \"process_*\" --kind function --language rs\n  kampus find \"*\" --visibility public --language go  # exported API\n  kampus find \"crate::graph::*\"     # everything in the graph module\n  kampus find \"app.models.User\"     # a Python class by qualified name\n  kampus find \"*product*\" --full-paths --limit 50\n\nTips for LLMs/agents:\n  - Always quote the pattern to prevent shell expansion.\n  - Use --full-paths to avoid truncated file paths.\n  - Use --kind/--language to narrow results and reduce noise.\n"
     )]
     Find {
-        /// Symbol name pattern (supports * wildcards)
+        /// Symbol name or qualified name pattern (supports * wildcards)
         pattern: String,
 
         /// Symbol kind to filter (function, class, struct, interface, method)
-        /// Valid kinds: function, class, struct, interface, method, trait, enum
+        /// Valid kinds: function, class, struct, interface, method, trait, enum, component, property,
+        /// union, type_alias, macro, module, constant, variable, field, enum_variant
         #[arg(short, long, verbatim_doc_comment)]
         kind: Option<String>,
 
-        /// Language to filter (rs, py, ts, js, go, cpp)
+        /// Language to filter (rs, py, ts, js, go, c, cpp, java, cs, rb, php, or a
+        /// grammar from .kampus/grammars.toml)
         #[arg(short, long, verbatim_doc_comment)]
         language: Option<String>,
 
+        /// Visibility to filter (public, private, protected, internal, super, restricted)
+        #[arg(long)]
+        visibility: Option<String>,
+
         /// Maximum number of results
         #[arg(short = 'n', long, default_value = "20")]
         limit: usize,
@@ -102,9 +114,9 @@ enum Commands {
         full_paths: bool,
     },
 
-    /// Show the call graph for a function
+    /// Show the call graph for a function or method
     Calls {
-        /// Function name to analyze
+        /// Function or method name to analyze
         function: String,
 
         /// Direction: callers, callees, or both
@@ -153,6 +165,7 @@ async fn main() -> anyhow::Result<()> {
                 jobs,
                 languages.as_deref(),
                 !no_clear,
+                cli.backend,
                 cli.db_uri.as_deref(),
                 &cli.graph,
             )
@@ -167,18 +180,27 @@ async fn main() -> anyhow::Result<()> {
                 &path,
                 since.as_deref(),
                 dry_run,
+                cli.backend,
                 cli.db_uri.as_deref(),
                 &cli.graph,
             )
             .await
         }
         Commands::Query { cypher, format } => {
-            commands::query::run(&cypher, &format, cli.db_uri.as_deref(), &cli.graph).await
+            commands::query::run(
+                &cypher,
+                &format,
+                cli.backend,
+                cli.db_uri.as_deref(),
+                &cli.graph,
+            )
+            .await
         }
         Commands::Find {
             pattern,
             kind,
             language,
+            visibility,
             limit,
             full_paths,
         } => {
@@ -186,8 +208,10 @@ async fn main() -> anyhow::Result<()> {
                 &pattern,
                 kind.as_deref(),
                 language.as_deref(),
+                visibility.as_deref(),
                 limit,
                 full_paths,
+                cli.backend,
                 cli.db_uri.as_deref(),
                 &cli.graph,
             )
@@ -202,13 +226,14 @@ async fn main() -> anyhow::Result<()> {
                 &function,
                 &direction,
                 depth,
+                cli.backend,
                 cli.db_uri.as_deref(),
                 &cli.graph,
             )
             .await
         }
         Commands::Status { files } => {
-            commands::status::run(files, cli.db_uri.as_deref(), &cli.graph).await
+            commands::status::run(files, cli.backend, cli.db_uri.as_deref(), &cli.graph).await
         }
     }
 }
@@ -0,0 +1,39 @@
+; C definitions and references
+
+(function_definition
+  declarator: (function_declarator
+    declarator: (identifier) @name)) @definition.function
+
+(function_definition
+  declarator: (pointer_declarator
+    declarator: (function_declarator
+      declarator: (identifier) @name))) @definition.function
+
+(struct_specifier
+  name: (type_identifier) @name
+  body: (_)) @definition.struct
+
+(union_specifier
+  name: (type_identifier) @name
+  body: (_)) @definition.union
+
+(enum_specifier
+  name: (type_identifier) @name
+  body: (_)) @definition.enum
+
+(type_definition
+  declarator: (type_identifier) @name) @definition.type_alias
+
+(preproc_function_def
+  name: (identifier) @name) @definition.macro
+
+(call_expression
+  function: (identifier) @name) @reference.call
+
+(call_expression
+  function: (field_expression
+    argument: (_) @receiver
+    field: (field_identifier) @name)) @reference.call
+
+(preproc_include
+  path: [(string_literal) (system_lib_string)] @name) @reference.import
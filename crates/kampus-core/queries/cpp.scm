@@ -0,0 +1,42 @@
+; C++ definitions and references
+
+(function_definition
+  declarator: (function_declarator
+    declarator: (identifier) @name)) @definition.function
+
+(function_definition
+  declarator: (function_declarator
+    declarator: (qualified_identifier
+      name: (identifier) @name))) @definition.method
+
+(field_declaration_list
+  (function_definition
+    declarator: (function_declarator
+      declarator: (field_identifier) @name)) @definition.method)
+
+(class_specifier
+  name: (type_identifier) @name
+  body: (_)) @definition.class
+
+(struct_specifier
+  name: (type_identifier) @name
+  body: (_)) @definition.struct
+
+(base_class_clause
+  (type_identifier) @reference.inherits)
+
+(call_expression
+  function: (identifier) @name) @reference.call
+
+(call_expression
+  function: (field_expression
+    argument: (_) @receiver
+    field: (field_identifier) @name)) @reference.call
+
+(call_expression
+  function: (qualified_identifier
+    scope: (_) @receiver
+    name: (identifier) @name)) @reference.call
+
+(preproc_include
+  path: (_) @name) @reference.import
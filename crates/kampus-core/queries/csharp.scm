@@ -0,0 +1,46 @@
+; C# definitions and references
+
+(class_declaration
+  name: (identifier) @name) @definition.class
+
+(record_declaration
+  name: (identifier) @name) @definition.class
+
+(struct_declaration
+  name: (identifier) @name) @definition.struct
+
+(interface_declaration
+  name: (identifier) @name) @definition.interface
+
+(enum_declaration
+  name: (identifier) @name) @definition.enum
+
+(base_list
+  (identifier) @reference.inherits)
+
+(base_list
+  (generic_name
+    (identifier) @reference.inherits))
+
+(method_declaration
+  name: (identifier) @name) @definition.method
+
+(constructor_declaration
+  name: (identifier) @name) @definition.method
+
+(property_declaration
+  name: (identifier) @name) @definition.property
+
+(invocation_expression
+  function: (identifier) @name) @reference.call
+
+(invocation_expression
+  function: (member_access_expression
+    expression: (_) @receiver
+    name: (identifier) @name)) @reference.call
+
+(object_creation_expression
+  type: (identifier) @name) @reference.call
+
+(using_directive
+  [(qualified_name) (identifier)] @name) @reference.import
@@ -0,0 +1,36 @@
+; Java definitions and references
+
+(class_declaration
+  name: (identifier) @name) @definition.class
+
+(record_declaration
+  name: (identifier) @name) @definition.class
+
+(interface_declaration
+  name: (identifier) @name) @definition.interface
+
+(enum_declaration
+  name: (identifier) @name) @definition.enum
+
+(superclass
+  (type_identifier) @reference.inherits)
+
+(super_interfaces
+  (type_list
+    (type_identifier) @reference.inherits))
+
+(method_declaration
+  name: (identifier) @name) @definition.method
+
+(constructor_declaration
+  name: (identifier) @name) @definition.method
+
+(method_invocation
+  object: (_)? @receiver
+  name: (identifier) @name) @reference.call
+
+(object_creation_expression
+  type: (type_identifier) @name) @reference.call
+
+(import_declaration
+  [(scoped_identifier) (identifier)] @name) @reference.import
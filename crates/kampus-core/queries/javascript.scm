@@ -0,0 +1,28 @@
+; JavaScript definitions and references
+
+(function_declaration
+  name: (identifier) @name) @definition.function
+
+(variable_declarator
+  name: (identifier) @name
+  value: [(arrow_function) (function_expression)]) @definition.function
+
+(class_declaration
+  name: (identifier) @name) @definition.class
+
+(class_heritage
+  (identifier) @reference.inherits)
+
+(method_definition
+  name: (property_identifier) @name) @definition.method
+
+(call_expression
+  function: (identifier) @name) @reference.call
+
+(call_expression
+  function: (member_expression
+    object: (_) @receiver
+    property: (property_identifier) @name)) @reference.call
+
+(import_statement
+  source: (string) @name) @reference.import
@@ -0,0 +1,58 @@
+; PHP definitions and references
+
+(class_declaration
+  name: (name) @name) @definition.class
+
+(interface_declaration
+  name: (name) @name) @definition.interface
+
+(trait_declaration
+  name: (name) @name) @definition.trait
+
+(enum_declaration
+  name: (name) @name) @definition.enum
+
+(function_definition
+  name: (name) @name) @definition.function
+
+(method_declaration
+  name: (name) @name) @definition.method
+
+(base_clause
+  (name) @reference.inherits)
+
+(base_clause
+  (qualified_name
+    (name) @reference.inherits))
+
+(class_interface_clause
+  (name) @reference.inherits)
+
+(class_interface_clause
+  (qualified_name
+    (name) @reference.inherits))
+
+(declaration_list
+  (use_declaration
+    (name) @reference.inherits))
+
+(function_call_expression
+  function: (name) @name) @reference.call
+
+(function_call_expression
+  function: (qualified_name
+    (name) @name)) @reference.call
+
+(member_call_expression
+  object: (_) @receiver
+  name: (name) @name) @reference.call
+
+(scoped_call_expression
+  scope: (_) @receiver
+  name: (name) @name) @reference.call
+
+(object_creation_expression
+  (name) @name) @reference.call
+
+(namespace_use_clause
+  [(name) (qualified_name)] @name) @reference.import
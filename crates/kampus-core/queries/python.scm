@@ -0,0 +1,44 @@
+; Python definitions and references
+
+(class_definition
+  name: (identifier) @name) @definition.class
+
+(class_definition
+  superclasses: (argument_list
+    [(identifier) (attribute)] @reference.inherits))
+
+(class_definition
+  body: (block
+    (function_definition
+      name: (identifier) @name) @definition.method))
+
+(class_definition
+  body: (block
+    (decorated_definition
+      definition: (function_definition
+        name: (identifier) @name) @definition.method)))
+
+(function_definition
+  name: (identifier) @name
+  body: (block
+    .
+    (expression_statement
+      (string) @doc)?)) @definition.function
+
+(call
+  function: (identifier) @name) @reference.call
+
+(call
+  function: (attribute
+    object: (_) @receiver
+    attribute: (identifier) @name)) @reference.call
+
+(import_statement
+  name: (dotted_name) @name) @reference.import
+
+(import_statement
+  name: (aliased_import
+    name: (dotted_name) @name)) @reference.import
+
+(import_from_statement
+  module_name: (_) @name) @reference.import
@@ -0,0 +1,45 @@
+; Ruby definitions and references
+
+(class
+  name: [
+    (constant) @name
+    (scope_resolution
+      name: (_) @name)
+  ]) @definition.class
+
+(module
+  name: [
+    (constant) @name
+    (scope_resolution
+      name: (_) @name)
+  ]) @definition.module
+
+(method
+  name: (_) @name) @definition.method
+
+(singleton_method
+  name: (_) @name) @definition.method
+
+(superclass
+  [
+    (constant) @reference.inherits
+    (scope_resolution
+      name: (_) @reference.inherits)
+  ])
+
+(call
+  method: (identifier) @method
+  arguments: (argument_list
+    (constant) @reference.inherits)
+  (#any-of? @method "include" "extend" "prepend"))
+
+(call
+  receiver: (_)? @receiver
+  method: (identifier) @name) @reference.call
+
+(call
+  method: (identifier) @method
+  arguments: (argument_list
+    (string
+      (string_content) @name))
+  (#any-of? @method "require" "require_relative")) @reference.import
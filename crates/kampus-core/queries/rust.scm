@@ -0,0 +1,37 @@
+; Rust definitions and references
+
+(function_item
+  name: (identifier) @name) @definition.function
+
+(impl_item
+  body: (declaration_list
+    (function_item
+      name: (identifier) @name) @definition.method))
+
+(struct_item
+  name: (type_identifier) @name) @definition.struct
+
+(enum_item
+  name: (type_identifier) @name) @definition.enum
+
+(trait_item
+  name: (type_identifier) @name) @definition.trait
+
+(call_expression
+  function: (identifier) @name) @reference.call
+
+(call_expression
+  function: (field_expression
+    value: (_) @receiver
+    field: (field_identifier) @name)) @reference.call
+
+(call_expression
+  function: (scoped_identifier
+    path: (_) @receiver
+    name: (identifier) @name)) @reference.call
+
+(use_declaration
+  argument: (_) @name) @reference.import
+
+(extern_crate_declaration
+  name: (identifier) @name) @reference.import
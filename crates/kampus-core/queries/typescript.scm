@@ -0,0 +1,40 @@
+; TypeScript definitions and references, shared by .ts and .tsx files
+
+(function_declaration
+  name: (identifier) @name) @definition.function
+
+(variable_declarator
+  name: (identifier) @name
+  value: [(arrow_function) (function_expression)]) @definition.function
+
+(class_declaration
+  name: (type_identifier) @name) @definition.class
+
+(abstract_class_declaration
+  name: (type_identifier) @name) @definition.class
+
+(interface_declaration
+  name: (type_identifier) @name) @definition.interface
+
+(enum_declaration
+  name: (identifier) @name) @definition.enum
+
+(extends_clause
+  value: (identifier) @reference.inherits)
+
+(implements_clause
+  (type_identifier) @reference.inherits)
+
+(method_definition
+  name: (property_identifier) @name) @definition.method
+
+(call_expression
+  function: (identifier) @name) @reference.call
+
+(call_expression
+  function: (member_expression
+    object: (_) @receiver
+    property: (property_identifier) @name)) @reference.call
+
+(import_statement
+  source: (string) @name) @reference.import
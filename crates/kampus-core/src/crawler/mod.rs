@@ -2,8 +2,10 @@
 //!
 //! Uses the `ignore` crate to walk directories while respecting .gitignore files.
 
+use crate::parser::languages::{included_headers, is_cpp_source};
 use crate::Language;
-use ignore::{WalkBuilder, WalkState};
+use ignore::{WalkBuilder, WalkParallel, WalkState};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc;
 use thiserror::Error;
@@ -71,34 +73,14 @@ impl Crawler {
     }
 
     /// Crawl the directory and return all discovered source files
+    ///
+    /// `.h` headers are classified once the walk is done, so that those
+    /// included by a C++ source anywhere in the tree are taken as C++.
     pub fn crawl(&self) -> Result<Vec<SourceFile>, CrawlerError> {
         let (tx, rx) = mpsc::channel();
 
-        let mut builder = WalkBuilder::new(&self.config.root);
-        builder
-            .git_ignore(self.config.git_ignore)
-            .git_global(self.config.git_ignore)
-            .git_exclude(self.config.git_ignore)
-            .hidden(true)
-            .threads(self.config.threads);
-
-        // Add ignore patterns
-        for pattern in &self.config.ignore_patterns {
-            let mut override_builder = ignore::overrides::OverrideBuilder::new(&self.config.root);
-            override_builder
-                .add(&format!("!**/{}", pattern))
-                .ok();
-            if let Ok(overrides) = override_builder.build() {
-                builder.overrides(overrides);
-            }
-        }
-
-        // Get the languages filter
-        let languages = self.config.languages.clone();
-
-        builder.build_parallel().run(|| {
+        self.walker().run(|| {
             let tx = tx.clone();
-            let languages = languages.clone();
 
             Box::new(move |result| {
                 if let Ok(entry) = result {
@@ -109,22 +91,23 @@ impl Crawler {
                         return WalkState::Continue;
                     }
 
-                    // Get extension and detect language
-                    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                        if let Some(language) = Language::from_extension(ext) {
-                            // Filter by language if specified
-                            if let Some(ref langs) = languages {
-                                if !langs.contains(&language) {
-                                    return WalkState::Continue;
-                                }
-                            }
-
-                            let _ = tx.send(SourceFile {
-                                path: path.to_path_buf(),
-                                language,
-                            });
-                        }
+                    let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+                        return WalkState::Continue;
+                    };
+                    let Some(language) = Language::from_extension(ext) else {
+                        return WalkState::Continue;
+                    };
+                    if language == Language::C && ext.eq_ignore_ascii_case("h") {
+                        let _ = tx.send(Discovered::Header(path.to_path_buf()));
+                        return WalkState::Continue;
                     }
+                    if is_cpp_source(path) {
+                        let _ = tx.send(Discovered::Includes(includes_of(path)));
+                    }
+                    let _ = tx.send(Discovered::File(SourceFile {
+                        path: path.to_path_buf(),
+                        language,
+                    }));
                 }
                 WalkState::Continue
             })
@@ -133,11 +116,78 @@ impl Crawler {
         // Drop the original sender to close the channel
         drop(tx);
 
-        // Collect results
-        let files: Vec<SourceFile> = rx.into_iter().collect();
+        let mut files = Vec::new();
+        let mut headers = Vec::new();
+        let mut cpp_includes = HashSet::new();
+        for discovered in rx {
+            match discovered {
+                Discovered::File(file) => files.push(file),
+                Discovered::Header(path) => headers.push(path),
+                Discovered::Includes(includes) => cpp_includes.extend(includes),
+            }
+        }
+        for path in headers {
+            if let Some(language) = Language::from_path_with(&path, &cpp_includes) {
+                files.push(SourceFile { path, language });
+            }
+        }
+
+        // Filter by language if specified
+        if let Some(ref languages) = self.config.languages {
+            files.retain(|file| languages.contains(&file.language));
+        }
         Ok(files)
     }
 
+    /// Names of the `.h` headers included by the C++ sources under the root
+    ///
+    /// Pass these to [`Language::from_path_with`] to classify headers outside
+    /// of a full crawl.
+    pub fn cpp_includes(&self) -> HashSet<String> {
+        let (tx, rx) = mpsc::channel();
+
+        self.walker().run(|| {
+            let tx = tx.clone();
+
+            Box::new(move |result| {
+                if let Ok(entry) = result
+                    && entry.path().is_file()
+                    && is_cpp_source(entry.path())
+                {
+                    let _ = tx.send(includes_of(entry.path()));
+                }
+                WalkState::Continue
+            })
+        });
+
+        drop(tx);
+        rx.into_iter().flatten().collect()
+    }
+
+    /// Parallel walker over the root honoring the ignore settings
+    fn walker(&self) -> WalkParallel {
+        let mut builder = WalkBuilder::new(&self.config.root);
+        builder
+            .git_ignore(self.config.git_ignore)
+            .git_global(self.config.git_ignore)
+            .git_exclude(self.config.git_ignore)
+            .hidden(true)
+            .threads(self.config.threads);
+
+        // Add ignore patterns
+        for pattern in &self.config.ignore_patterns {
+            let mut override_builder = ignore::overrides::OverrideBuilder::new(&self.config.root);
+            override_builder
+                .add(&format!("!**/{}", pattern))
+                .ok();
+            if let Ok(overrides) = override_builder.build() {
+                builder.overrides(overrides);
+            }
+        }
+
+        builder.build_parallel()
+    }
+
     /// Get the number of files for each language
     pub fn count_by_language(&self) -> Result<std::collections::HashMap<Language, usize>, CrawlerError> {
         let files = self.crawl()?;
@@ -149,6 +199,22 @@ impl Crawler {
     }
 }
 
+/// What the walk found at a path
+enum Discovered {
+    File(SourceFile),
+    /// A `.h` header, classified once every C++ source has been read
+    Header(PathBuf),
+    /// Headers included by a C++ source
+    Includes(Vec<String>),
+}
+
+/// Headers included by the C++ source at `path`, none if it cannot be read
+fn includes_of(path: &Path) -> Vec<String> {
+    std::fs::read_to_string(path)
+        .map(|source| included_headers(&source).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
 /// Convenience function to crawl a directory
 pub fn crawl_directory(
     root: impl AsRef<Path>,
@@ -176,4 +242,64 @@ mod tests {
         assert_eq!(Language::from_extension("cpp"), Some(Language::Cpp));
         assert_eq!(Language::from_extension("txt"), None);
     }
+
+    #[test]
+    fn test_header_detection() {
+        let dir = tempfile::tempdir().unwrap();
+        let c_header = dir.path().join("list.h");
+        std::fs::write(
+            &c_header,
+            "// A list class in C\n#ifdef __cplusplus\nextern \"C\" {\n#endif\ntypedef struct list { struct list *next; } list_t;\nvoid list_free(list_t *list);\n#ifdef __cplusplus\n}\n#endif\n",
+        )
+        .unwrap();
+        let cpp_header = dir.path().join("vector.h");
+        std::fs::write(&cpp_header, "namespace util { template <typename T> class Vector {}; }\n").unwrap();
+
+        assert_eq!(Language::from_path(&c_header), Some(Language::C));
+        assert_eq!(Language::from_path(&cpp_header), Some(Language::Cpp));
+        assert_eq!(Language::from_path(&dir.path().join("main.c")), Some(Language::C));
+
+        let files = crawl_directory(dir.path(), Some(vec![Language::Cpp])).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, cpp_header);
+    }
+
+    #[test]
+    fn test_header_included_by_cpp_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let include = dir.path().join("include");
+        let src = dir.path().join("src");
+        std::fs::create_dir_all(&include).unwrap();
+        std::fs::create_dir_all(&src).unwrap();
+        let header = include.join("util.h");
+        std::fs::write(&header, "int add(int a, int b);\n").unwrap();
+        std::fs::write(include.join("list.h"), "int len(void);\n").unwrap();
+        std::fs::write(src.join("main.c"), "#include \"list.h\"\n").unwrap();
+
+        let language_of = |path: &Path| {
+            let files = crawl_directory(dir.path(), None).unwrap();
+            files.into_iter().find(|f| f.path == path).unwrap().language
+        };
+        assert_eq!(language_of(&header), Language::C);
+
+        std::fs::write(
+            src.join("util.cpp"),
+            "#include \"util.h\"\n\nint add(int a, int b) { return a + b; }\n",
+        )
+        .unwrap();
+        assert_eq!(language_of(&header), Language::Cpp);
+        assert_eq!(language_of(&include.join("list.h")), Language::C);
+        assert_eq!(Language::from_path(&header), Some(Language::C));
+
+        let crawler = Crawler::new(CrawlerConfig {
+            root: dir.path().to_path_buf(),
+            ..Default::default()
+        });
+        let cpp_includes = crawler.cpp_includes();
+        assert_eq!(cpp_includes, HashSet::from(["util.h".to_string()]));
+        assert_eq!(
+            Language::from_path_with(&header, &cpp_includes),
+            Some(Language::Cpp)
+        );
+    }
 }
@@ -0,0 +1,1173 @@
+//! Embedded graph store
+//!
+//! Keeps the whole graph in memory and persists it as JSON under `.kampus/`,
+//! so indexing and queries work without a database server. Changes are
+//! written to disk on [`GraphStore::flush`].
+
+use super::schema::GraphStats;
+use super::store::{
+    CallDirection, CallPathEntry, FileEntry, GraphStore, SEARCHABLE_KINDS, SymbolMatch,
+    SymbolQuery,
+};
+use super::writer::PendingLinks;
+use super::{GraphError, GraphResult};
+use crate::resolver::definitions::{self, DefinitionEntry};
+use crate::resolver::implements::{self, MethodSetEntry};
+use crate::resolver::{
+    CallResolver, CallTarget, Resolution, ResolvedCall, ResolvedReference, ResolvedRender,
+    SymbolEntry,
+};
+use crate::{
+    Call, FileSymbols, Import, ImportTarget, Inheritance, Language, Namespace, Reference, ReferenceKind, Render,
+    Symbol, SymbolKind,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use tokio::sync::RwLock;
+use tracing::debug;
+
+/// Version of the on-disk format
+const FORMAT_VERSION: u32 = 2;
+
+/// Kinds an inheritance parent may have; Ruby modules are mixed in
+const PARENT_KINDS: [SymbolKind; 5] = [
+    SymbolKind::Class,
+    SymbolKind::Struct,
+    SymbolKind::Interface,
+    SymbolKind::Trait,
+    SymbolKind::Module,
+];
+
+/// In-process graph store persisted to `<dir>/<graph name>.json`
+pub struct EmbeddedStore {
+    path: PathBuf,
+    graph: RwLock<EmbeddedGraph>,
+}
+
+impl EmbeddedStore {
+    /// Directory, relative to the project root, holding embedded graphs
+    pub const DIR: &'static str = ".kampus";
+
+    /// Open the graph `graph_name` in `dir`, loading it if it exists
+    pub fn open(dir: impl AsRef<Path>, graph_name: &str) -> GraphResult<Self> {
+        let path = dir.as_ref().join(format!("{}.json", graph_name));
+
+        let graph = if path.exists() {
+            let data = std::fs::read(&path)?;
+            let graph: EmbeddedGraph = serde_json::from_slice(&data)
+                .map_err(|e| GraphError::Serialization(e.to_string()))?;
+            if graph.version != FORMAT_VERSION {
+                return Err(GraphError::Schema(format!(
+                    "{} has format version {}, expected {}; re-run a full index",
+                    path.display(),
+                    graph.version,
+                    FORMAT_VERSION
+                )));
+            }
+            debug!("Loaded {} symbols from {:?}", graph.symbols.len(), path);
+            graph
+        } else {
+            EmbeddedGraph::default()
+        };
+
+        Ok(Self {
+            path,
+            graph: RwLock::new(graph),
+        })
+    }
+
+    /// Find the closest ancestor of `start` (or `start` itself) holding a
+    /// [`EmbeddedStore::DIR`] directory
+    pub fn discover(start: &Path) -> Option<PathBuf> {
+        start
+            .ancestors()
+            .find(|dir| dir.join(Self::DIR).is_dir())
+            .map(Path::to_path_buf)
+    }
+
+    /// Path of the JSON file backing this store
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Graph contents, also the on-disk format
+#[derive(Debug, Serialize, Deserialize)]
+struct EmbeddedGraph {
+    version: u32,
+    files: BTreeMap<String, FileNode>,
+    symbols: BTreeMap<String, Symbol>,
+    /// Namespaces and packages by language and qualified name
+    namespaces: BTreeMap<String, Namespace>,
+    /// Imports by importing file
+    imports: BTreeMap<String, Vec<Import>>,
+    /// Outgoing calls by caller id
+    calls: BTreeMap<String, Vec<CallEdge>>,
+    /// Parent ids by child id
+    inherits: BTreeMap<String, BTreeSet<String>>,
+    /// Implemented interface ids by Go type id
+    #[serde(default)]
+    implements: BTreeMap<String, BTreeSet<String>>,
+    /// Definition ids by C++ declaration id
+    #[serde(default)]
+    definitions: BTreeMap<String, BTreeSet<String>>,
+    /// Rendered components by rendering component id
+    #[serde(default)]
+    renders: BTreeMap<String, Vec<RenderEdge>>,
+    /// Decorator and type references by referencing symbol id
+    #[serde(default)]
+    references: BTreeMap<String, Vec<ReferenceEdge>>,
+    metadata: BTreeMap<String, String>,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+impl Default for EmbeddedGraph {
+    fn default() -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            files: BTreeMap::new(),
+            symbols: BTreeMap::new(),
+            namespaces: BTreeMap::new(),
+            imports: BTreeMap::new(),
+            calls: BTreeMap::new(),
+            inherits: BTreeMap::new(),
+            implements: BTreeMap::new(),
+            definitions: BTreeMap::new(),
+            renders: BTreeMap::new(),
+            references: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            dirty: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FileNode {
+    language: String,
+    hash: String,
+    line_count: u32,
+    /// Qualified name of the file's module or package
+    #[serde(default)]
+    namespace: Option<String>,
+    /// IDs of the partial types the file declares a part of
+    #[serde(default)]
+    partial_symbols: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CallEdge {
+    target: CallTarget,
+    line: u32,
+    receiver: Option<String>,
+    confidence: f64,
+    resolution: Resolution,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RenderEdge {
+    target: String,
+    line: u32,
+    receiver: Option<String>,
+    confidence: f64,
+    resolution: Resolution,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ReferenceEdge {
+    kind: ReferenceKind,
+    target: String,
+    line: u32,
+    receiver: Option<String>,
+    confidence: f64,
+    resolution: Resolution,
+}
+
+impl EmbeddedGraph {
+    fn namespace_key(namespace: &Namespace) -> String {
+        format!("{}:{}", namespace.language, namespace.qualified_name)
+    }
+
+    /// Remove namespaces no longer holding files, symbols or other
+    /// namespaces, innermost first
+    fn prune_namespaces(&mut self) {
+        loop {
+            let mut used: BTreeSet<String> = BTreeSet::new();
+            for file in self.files.values() {
+                if let Some(namespace) = &file.namespace {
+                    used.insert(format!("{}:{}", file.language, namespace));
+                }
+            }
+            for symbol in self.symbols.values() {
+                if let Some(namespace) = &symbol.namespace {
+                    used.insert(format!("{}:{}", symbol.language, namespace));
+                }
+            }
+            for namespace in self.namespaces.values() {
+                if let Some(parent) = &namespace.parent {
+                    used.insert(format!("{}:{}", namespace.language, parent));
+                }
+            }
+
+            let before = self.namespaces.len();
+            self.namespaces.retain(|key, _| used.contains(key));
+            if self.namespaces.len() == before {
+                break;
+            }
+        }
+    }
+
+    fn symbol_file(&self, id: &str) -> Option<String> {
+        self.symbols
+            .get(id)
+            .map(|s| s.file_path.to_string_lossy().to_string())
+    }
+
+    /// Callers of each symbol
+    fn reverse_calls(&self) -> HashMap<&str, Vec<&str>> {
+        let mut callers: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (caller_id, edges) in &self.calls {
+            for edge in edges {
+                if let CallTarget::Symbol(callee_id) = &edge.target {
+                    callers.entry(callee_id).or_default().push(caller_id);
+                }
+            }
+        }
+        callers
+    }
+}
+
+/// Whether an import was resolved to a file or to symbols in it
+fn import_reaches(import: &Import, file_path: &str) -> bool {
+    match &import.resolved {
+        Some(ImportTarget::Project { files, symbols }) => {
+            files.iter().any(|f| f.to_string_lossy() == file_path)
+                || symbols.iter().any(|s| s.file_path.to_string_lossy() == file_path)
+        }
+        _ => false,
+    }
+}
+
+fn is_callable(kind: SymbolKind) -> bool {
+    matches!(
+        kind,
+        SymbolKind::Function | SymbolKind::Method | SymbolKind::Component | SymbolKind::Macro
+    )
+}
+
+#[async_trait]
+impl GraphStore for EmbeddedStore {
+    async fn initialize(&self) -> GraphResult<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        Ok(())
+    }
+
+    async fn clear(&self) -> GraphResult<()> {
+        let mut graph = self.graph.write().await;
+        *graph = EmbeddedGraph::default();
+        graph.dirty = true;
+        Ok(())
+    }
+
+    async fn write_nodes(&self, files: &[FileSymbols]) -> GraphResult<()> {
+        let mut graph = self.graph.write().await;
+
+        for file_symbols in files {
+            let file_path = file_symbols.file_path.to_string_lossy().to_string();
+            graph.files.insert(
+                file_path,
+                FileNode {
+                    language: file_symbols
+                        .language
+                        .map(|l| l.to_string())
+                        .unwrap_or_default(),
+                    hash: file_symbols.content_hash.clone(),
+                    line_count: file_symbols.line_count,
+                    namespace: file_symbols.namespace.clone(),
+                    partial_symbols: file_symbols
+                        .symbols
+                        .iter()
+                        .filter(|s| s.is_partial)
+                        .map(|s| s.id.clone())
+                        .collect(),
+                },
+            );
+            for namespace in &file_symbols.namespaces {
+                graph
+                    .namespaces
+                    .insert(EmbeddedGraph::namespace_key(namespace), namespace.clone());
+            }
+            for symbol in &file_symbols.symbols {
+                graph.symbols.insert(symbol.id.clone(), symbol.clone());
+            }
+        }
+
+        graph.dirty = true;
+        Ok(())
+    }
+
+    async fn write_imports(&self, files: &[FileSymbols]) -> GraphResult<()> {
+        let mut graph = self.graph.write().await;
+
+        for import in files.iter().flat_map(|f| &f.imports) {
+            let file_path = import.source_file.to_string_lossy().to_string();
+            let imports = graph.imports.entry(file_path).or_default();
+            // One edge per file, target and alias, like MERGE
+            imports.retain(|i| i.target != import.target || i.alias != import.alias);
+            imports.push(import.clone());
+        }
+
+        graph.dirty = true;
+        Ok(())
+    }
+
+    async fn write_import_links(&self, imports: &[Import]) -> GraphResult<()> {
+        // Imports are stored whole by write_imports; this restores those
+        // taken by incoming_links, keeping only the files and symbols that
+        // still exist, like the MATCHes of the FalkorDB store
+        let mut graph = self.graph.write().await;
+
+        for import in imports {
+            let mut import = import.clone();
+            if let Some(ImportTarget::Project { files, symbols }) = &mut import.resolved {
+                files.retain(|f| graph.files.contains_key(f.to_string_lossy().as_ref()));
+                symbols.retain(|s| graph.symbols.contains_key(&s.id));
+            }
+            let file_path = import.source_file.to_string_lossy().to_string();
+            let imports = graph.imports.entry(file_path).or_default();
+            imports.retain(|i| i.target != import.target || i.alias != import.alias);
+            imports.push(import);
+        }
+
+        graph.dirty = true;
+        Ok(())
+    }
+
+    async fn write_calls(
+        &self,
+        calls: &[ResolvedCall],
+        _resolver: &CallResolver,
+    ) -> GraphResult<()> {
+        let mut graph = self.graph.write().await;
+
+        for resolved in calls {
+            let call = &resolved.call;
+            if let CallTarget::Symbol(callee_id) = &resolved.target
+                && !graph.symbols.contains_key(callee_id)
+            {
+                continue;
+            }
+            if !graph.symbols.contains_key(&call.caller_id) {
+                continue;
+            }
+
+            let edges = graph.calls.entry(call.caller_id.clone()).or_default();
+            edges.retain(|e| e.target != resolved.target);
+            edges.push(CallEdge {
+                target: resolved.target.clone(),
+                line: call.call_site_line,
+                receiver: call.receiver.clone(),
+                confidence: resolved.confidence,
+                resolution: resolved.resolution,
+            });
+        }
+
+        graph.dirty = true;
+        Ok(())
+    }
+
+    async fn write_renders(&self, renders: &[ResolvedRender]) -> GraphResult<()> {
+        let mut graph = self.graph.write().await;
+
+        for resolved in renders {
+            let render = &resolved.render;
+            if !graph.symbols.contains_key(&render.component_id)
+                || !graph.symbols.contains_key(&resolved.target_id)
+            {
+                continue;
+            }
+
+            let edges = graph.renders.entry(render.component_id.clone()).or_default();
+            edges.retain(|e| e.target != resolved.target_id);
+            edges.push(RenderEdge {
+                target: resolved.target_id.clone(),
+                line: render.line,
+                receiver: render.receiver.clone(),
+                confidence: resolved.confidence,
+                resolution: resolved.resolution,
+            });
+        }
+
+        graph.dirty = true;
+        Ok(())
+    }
+
+    async fn write_references(
+        &self,
+        references: &[ResolvedReference],
+        _resolver: &CallResolver,
+    ) -> GraphResult<()> {
+        let mut graph = self.graph.write().await;
+
+        for resolved in references {
+            let reference = &resolved.reference;
+            if !graph.symbols.contains_key(&reference.source_id)
+                || !graph.symbols.contains_key(&resolved.target_id)
+            {
+                continue;
+            }
+
+            let edges = graph.references.entry(reference.source_id.clone()).or_default();
+            edges.retain(|e| e.kind != reference.kind || e.target != resolved.target_id);
+            edges.push(ReferenceEdge {
+                kind: reference.kind,
+                target: resolved.target_id.clone(),
+                line: reference.line,
+                receiver: reference.receiver.clone(),
+                confidence: resolved.confidence,
+                resolution: resolved.resolution,
+            });
+        }
+
+        graph.dirty = true;
+        Ok(())
+    }
+
+    async fn write_inheritance(
+        &self,
+        links: &[Inheritance],
+        _resolver: &CallResolver,
+    ) -> GraphResult<()> {
+        let mut graph = self.graph.write().await;
+
+        let mut parents: HashMap<&str, Vec<String>> = HashMap::new();
+        for symbol in graph.symbols.values() {
+            if PARENT_KINDS.contains(&symbol.kind) {
+                parents
+                    .entry(symbol.name.as_str())
+                    .or_default()
+                    .push(symbol.id.clone());
+            }
+        }
+
+        let mut edges: Vec<(String, String)> = Vec::new();
+        for link in links {
+            if !graph.symbols.contains_key(&link.child_id) {
+                continue;
+            }
+            for parent_id in parents.get(link.parent_name.as_str()).into_iter().flatten() {
+                edges.push((link.child_id.clone(), parent_id.clone()));
+            }
+        }
+
+        for (child_id, parent_id) in edges {
+            graph.inherits.entry(child_id).or_default().insert(parent_id);
+        }
+
+        graph.dirty = true;
+        Ok(())
+    }
+
+    async fn write_implementations(&self) -> GraphResult<usize> {
+        let mut graph = self.graph.write().await;
+
+        let entries: Vec<MethodSetEntry> = graph
+            .symbols
+            .values()
+            .filter(|s| s.language == Language::Go && implements::KINDS.contains(&s.kind))
+            .map(|s| MethodSetEntry {
+                embeds: graph
+                    .inherits
+                    .get(&s.id)
+                    .into_iter()
+                    .flatten()
+                    .cloned()
+                    .collect(),
+                ..MethodSetEntry::from(s)
+            })
+            .collect();
+        let implementations = implements::implementations(&entries);
+
+        graph.implements.clear();
+        for implementation in &implementations {
+            graph
+                .implements
+                .entry(implementation.type_id.clone())
+                .or_default()
+                .insert(implementation.interface_id.clone());
+        }
+
+        graph.dirty = true;
+        Ok(implementations.len())
+    }
+
+    async fn write_definitions(&self) -> GraphResult<usize> {
+        let mut graph = self.graph.write().await;
+
+        let entries: Vec<DefinitionEntry> = graph
+            .symbols
+            .values()
+            .filter(|s| s.language == Language::Cpp && definitions::KINDS.contains(&s.kind))
+            .map(DefinitionEntry::from)
+            .collect();
+        let definitions = definitions::definitions(&entries);
+
+        graph.definitions.clear();
+        for definition in &definitions {
+            graph
+                .definitions
+                .entry(definition.declaration_id.clone())
+                .or_default()
+                .insert(definition.definition_id.clone());
+        }
+        for member in definitions::members(&entries) {
+            if let Some(method) = graph.symbols.get_mut(&member.method_id) {
+                method.parent_id = Some(member.class_id);
+            }
+        }
+
+        graph.dirty = true;
+        Ok(definitions.len())
+    }
+
+    async fn delete_file(&self, file_path: &str) -> GraphResult<()> {
+        let mut graph = self.graph.write().await;
+
+        // Partial types also declared in another file move to that file
+        let partial_symbols = graph
+            .files
+            .get(file_path)
+            .map(|f| f.partial_symbols.clone())
+            .unwrap_or_default();
+        for id in partial_symbols {
+            let other = graph
+                .files
+                .iter()
+                .find(|(path, f)| path.as_str() != file_path && f.partial_symbols.contains(&id))
+                .map(|(path, _)| PathBuf::from(path));
+            if let Some(other) = other
+                && let Some(symbol) = graph.symbols.get_mut(&id)
+            {
+                symbol.file_path = other;
+            }
+        }
+
+        let removed: BTreeSet<String> = graph
+            .symbols
+            .values()
+            .filter(|s| s.file_path.to_string_lossy() == file_path)
+            .map(|s| s.id.clone())
+            .collect();
+
+        graph.files.remove(file_path);
+        graph.imports.remove(file_path);
+        // Detach imports of the file and its symbols
+        for import in graph.imports.values_mut().flatten() {
+            if let Some(ImportTarget::Project { files, symbols }) = &mut import.resolved {
+                files.retain(|f| f.to_string_lossy() != file_path);
+                symbols.retain(|s| !removed.contains(&s.id));
+            }
+        }
+        graph.symbols.retain(|id, _| !removed.contains(id));
+
+        // Detach the removed symbols
+        graph.calls.retain(|caller_id, _| !removed.contains(caller_id));
+        for edges in graph.calls.values_mut() {
+            edges.retain(|e| match &e.target {
+                CallTarget::Symbol(id) => !removed.contains(id),
+                CallTarget::Placeholder(_) => true,
+            });
+        }
+        graph.inherits.retain(|child_id, _| !removed.contains(child_id));
+        for parents in graph.inherits.values_mut() {
+            parents.retain(|id| !removed.contains(id));
+        }
+        graph.implements.retain(|type_id, _| !removed.contains(type_id));
+        for interfaces in graph.implements.values_mut() {
+            interfaces.retain(|id| !removed.contains(id));
+        }
+        graph.definitions.retain(|id, _| !removed.contains(id));
+        for definitions in graph.definitions.values_mut() {
+            definitions.retain(|id| !removed.contains(id));
+        }
+        // Out-of-line definitions attached to a removed class
+        for symbol in graph.symbols.values_mut() {
+            if symbol.parent_id.as_ref().is_some_and(|id| removed.contains(id)) {
+                symbol.parent_id = None;
+            }
+        }
+        graph.renders.retain(|id, _| !removed.contains(id));
+        for edges in graph.renders.values_mut() {
+            edges.retain(|e| !removed.contains(&e.target));
+        }
+        graph.references.retain(|id, _| !removed.contains(id));
+        for edges in graph.references.values_mut() {
+            edges.retain(|e| !removed.contains(&e.target));
+        }
+        graph.prune_namespaces();
+
+        graph.dirty = true;
+        Ok(())
+    }
+
+    async fn incoming_links(&self, file_path: &str) -> GraphResult<PendingLinks> {
+        let graph = self.graph.read().await;
+        let mut links = PendingLinks::default();
+        let in_file = |id: &str| graph.symbol_file(id).as_deref() == Some(file_path);
+
+        for (caller_id, edges) in &graph.calls {
+            if in_file(caller_id) {
+                continue;
+            }
+            for edge in edges {
+                if let CallTarget::Symbol(callee_id) = &edge.target
+                    && in_file(callee_id)
+                    && let Some(callee) = graph.symbols.get(callee_id)
+                {
+                    links.calls.push(Call {
+                        caller_id: caller_id.clone(),
+                        callee_name: callee.name.clone(),
+                        receiver: edge.receiver.clone(),
+                        call_site_line: edge.line,
+                    });
+                }
+            }
+        }
+
+        for (child_id, parent_ids) in &graph.inherits {
+            if in_file(child_id) {
+                continue;
+            }
+            for parent_id in parent_ids {
+                if in_file(parent_id)
+                    && let Some(parent) = graph.symbols.get(parent_id)
+                {
+                    links.inheritance.push(Inheritance {
+                        child_id: child_id.clone(),
+                        parent_name: parent.name.clone(),
+                    });
+                }
+            }
+        }
+
+        for (component_id, edges) in &graph.renders {
+            if in_file(component_id) {
+                continue;
+            }
+            for edge in edges {
+                if in_file(&edge.target)
+                    && let Some(target) = graph.symbols.get(&edge.target)
+                {
+                    links.renders.push(Render {
+                        component_id: component_id.clone(),
+                        element_name: target.name.clone(),
+                        receiver: edge.receiver.clone(),
+                        line: edge.line,
+                    });
+                }
+            }
+        }
+
+        for (source_id, edges) in &graph.references {
+            if in_file(source_id) {
+                continue;
+            }
+            for edge in edges {
+                if in_file(&edge.target)
+                    && let Some(target) = graph.symbols.get(&edge.target)
+                {
+                    links.references.push(Reference {
+                        kind: edge.kind,
+                        source_id: source_id.clone(),
+                        target_name: target.name.clone(),
+                        receiver: edge.receiver.clone(),
+                        line: edge.line,
+                    });
+                }
+            }
+        }
+
+        for (importer, imports) in &graph.imports {
+            if importer == file_path {
+                continue;
+            }
+            links.imports.extend(
+                imports
+                    .iter()
+                    .filter(|i| import_reaches(i, file_path))
+                    .cloned(),
+            );
+        }
+
+        Ok(links)
+    }
+
+    async fn take_unresolved_calls(&self, names: &BTreeSet<String>) -> GraphResult<Vec<Call>> {
+        let mut graph = self.graph.write().await;
+        let mut calls = Vec::new();
+
+        for (caller_id, edges) in graph.calls.iter_mut() {
+            edges.retain(|edge| match &edge.target {
+                CallTarget::Placeholder(name) if names.contains(name) => {
+                    calls.push(Call {
+                        caller_id: caller_id.clone(),
+                        callee_name: name.clone(),
+                        receiver: edge.receiver.clone(),
+                        call_site_line: edge.line,
+                    });
+                    false
+                }
+                _ => true,
+            });
+        }
+
+        if !calls.is_empty() {
+            graph.dirty = true;
+        }
+        Ok(calls)
+    }
+
+    async fn call_resolver(&self, links: &PendingLinks) -> GraphResult<CallResolver> {
+        let graph = self.graph.read().await;
+        let mut resolver = CallResolver::new();
+
+        let names: BTreeSet<&str> = links
+            .calls
+            .iter()
+            .map(|c| c.callee_name.as_str())
+            .chain(links.renders.iter().map(|r| r.element_name.as_str()))
+            .chain(links.references.iter().map(|r| r.target_name.as_str()))
+            .collect();
+        let caller_ids: BTreeSet<&str> = links
+            .calls
+            .iter()
+            .map(|c| c.caller_id.as_str())
+            .chain(links.renders.iter().map(|r| r.component_id.as_str()))
+            .chain(links.references.iter().map(|r| r.source_id.as_str()))
+            .collect();
+
+        // Decorators and annotations name classes as well as callables, and
+        // classes can be decorated, so candidates of any kind are kept
+        for symbol in graph.symbols.values() {
+            if !(names.contains(symbol.name.as_str()) || caller_ids.contains(symbol.id.as_str())) {
+                continue;
+            }
+            resolver.add_symbol(SymbolEntry::from(symbol));
+            if let Some(parent) = symbol.parent_id.as_ref().and_then(|id| graph.symbols.get(id)) {
+                resolver.add_symbol(SymbolEntry::from(parent));
+            }
+        }
+
+        let caller_files: BTreeSet<String> = caller_ids
+            .iter()
+            .filter_map(|id| graph.symbol_file(id))
+            .collect();
+        for file in caller_files {
+            for import in graph.imports.get(&file).into_iter().flatten() {
+                resolver.add_import(import.clone());
+            }
+        }
+
+        Ok(resolver)
+    }
+
+    async fn find_symbols(&self, query: &SymbolQuery) -> GraphResult<Vec<SymbolMatch>> {
+        let graph = self.graph.read().await;
+
+        let mut matches: Vec<SymbolMatch> = graph
+            .symbols
+            .values()
+            .filter(|s| match query.kind {
+                Some(kind) => s.kind == kind,
+                None => SEARCHABLE_KINDS.contains(&s.kind),
+            })
+            .filter(|s| query.language.is_none_or(|l| s.language == l))
+            .filter(|s| query.visibility.is_none_or(|v| s.visibility == v))
+            .filter(|s| query.pattern.matches_symbol(&s.name, &s.qualified_name))
+            .map(|s| SymbolMatch {
+                name: s.name.clone(),
+                qualified_name: s.qualified_name.clone(),
+                kind: s.kind,
+                file_path: s.file_path.to_string_lossy().to_string(),
+                start_line: s.start_line,
+            })
+            .collect();
+
+        matches.sort_by(|a, b| a.qualified_name.cmp(&b.qualified_name));
+        matches.truncate(query.limit);
+        Ok(matches)
+    }
+
+    async fn call_paths(
+        &self,
+        function: &str,
+        direction: CallDirection,
+        depth: u32,
+        limit: usize,
+    ) -> GraphResult<Vec<CallPathEntry>> {
+        let graph = self.graph.read().await;
+        let callers = graph.reverse_calls();
+
+        let neighbours = |id: &str| -> Vec<&str> {
+            match direction {
+                CallDirection::Callers => callers.get(id).cloned().unwrap_or_default(),
+                CallDirection::Callees => graph
+                    .calls
+                    .get(id)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|e| match &e.target {
+                        CallTarget::Symbol(callee_id) => Some(callee_id.as_str()),
+                        CallTarget::Placeholder(_) => None,
+                    })
+                    .collect(),
+            }
+        };
+
+        // Breadth-first, so the first visit of a symbol is its shortest path.
+        // Start symbols are not marked visited so recursion shows up at
+        // distance 1, as with a Cypher path query.
+        let mut distances: HashMap<&str, u32> = HashMap::new();
+        let mut queue: VecDeque<(&str, u32)> = graph
+            .symbols
+            .values()
+            .filter(|s| is_callable(s.kind) && s.name == function)
+            .map(|s| (s.id.as_str(), 0))
+            .collect();
+
+        while let Some((id, distance)) = queue.pop_front() {
+            if distance >= depth.max(1) {
+                continue;
+            }
+            for next in neighbours(id) {
+                if !distances.contains_key(next) {
+                    distances.insert(next, distance + 1);
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        let mut seen = BTreeSet::new();
+        let mut entries: Vec<CallPathEntry> = distances
+            .into_iter()
+            .filter_map(|(id, distance)| {
+                let symbol = graph.symbols.get(id)?;
+                is_callable(symbol.kind).then(|| CallPathEntry {
+                    name: symbol.name.clone(),
+                    file_path: symbol.file_path.to_string_lossy().to_string(),
+                    distance,
+                })
+            })
+            .collect();
+        entries.sort_by(|a, b| (a.distance, &a.name).cmp(&(b.distance, &b.name)));
+        entries.retain(|e| seen.insert((e.name.clone(), e.file_path.clone())));
+        entries.truncate(limit);
+
+        Ok(entries)
+    }
+
+    async fn files(&self) -> GraphResult<Vec<FileEntry>> {
+        let graph = self.graph.read().await;
+        Ok(graph
+            .files
+            .iter()
+            .map(|(path, file)| FileEntry {
+                path: path.clone(),
+                language: file.language.clone(),
+                line_count: file.line_count,
+            })
+            .collect())
+    }
+
+    async fn stats(&self) -> GraphResult<GraphStats> {
+        let graph = self.graph.read().await;
+        let count_kind = |kind| graph.symbols.values().filter(|s| s.kind == kind).count();
+
+        Ok(GraphStats {
+            file_count: graph.files.len(),
+            function_count: count_kind(SymbolKind::Function),
+            class_count: count_kind(SymbolKind::Class),
+            struct_count: count_kind(SymbolKind::Struct),
+            component_count: count_kind(SymbolKind::Component),
+            namespace_count: graph.namespaces.len(),
+            calls_count: graph.calls.values().map(Vec::len).sum(),
+            renders_count: graph.renders.values().map(Vec::len).sum(),
+            imports_count: graph.imports.values().map(Vec::len).sum(),
+        })
+    }
+
+    async fn get_metadata(&self, key: &str) -> GraphResult<Option<String>> {
+        let graph = self.graph.read().await;
+        Ok(graph.metadata.get(key).cloned())
+    }
+
+    async fn set_metadata(&self, key: &str, value: &str) -> GraphResult<()> {
+        let mut graph = self.graph.write().await;
+        graph.metadata.insert(key.to_string(), value.to_string());
+        graph.dirty = true;
+        Ok(())
+    }
+
+    async fn flush(&self) -> GraphResult<()> {
+        let mut graph = self.graph.write().await;
+        if !graph.dirty {
+            return Ok(());
+        }
+
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let data =
+            serde_json::to_vec(&*graph).map_err(|e| GraphError::Serialization(e.to_string()))?;
+
+        // Write to a temporary file first so a crash never leaves a truncated graph
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, data)?;
+        std::fs::rename(&tmp, &self.path)?;
+
+        graph.dirty = false;
+        debug!("Saved {} symbols to {:?}", graph.symbols.len(), self.path);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::store::NamePattern;
+    use crate::graph::GraphWriter;
+    use crate::{DocSections, Language, Modifiers, Visibility};
+    use std::sync::Arc;
+
+    fn symbol(file: &str, name: &str, kind: SymbolKind, line: u32) -> Symbol {
+        Symbol {
+            id: Symbol::generate_id(Path::new(file), name),
+            name: name.to_string(),
+            qualified_name: name.to_string(),
+            namespace: None,
+            kind,
+            file_path: file.into(),
+            start_line: line,
+            end_line: line + 1,
+            byte_range: 0..0,
+            signature: None,
+            visibility: Visibility::Public,
+            is_async: false,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers::default(),
+            docstring: None,
+            doc_sections: DocSections::default(),
+            summary: None,
+            language: Language::Python,
+            parent_id: None,
+        }
+    }
+
+    fn call(caller: &Symbol, callee: &str) -> Call {
+        Call {
+            caller_id: caller.id.clone(),
+            callee_name: callee.to_string(),
+            receiver: None,
+            call_site_line: caller.start_line,
+        }
+    }
+
+    fn sample_files() -> Vec<FileSymbols> {
+        let main = symbol("app.py", "main", SymbolKind::Function, 1);
+        let load = symbol("app.py", "load", SymbolKind::Function, 5);
+        let parse = symbol("util.py", "parse", SymbolKind::Function, 1);
+
+        vec![
+            FileSymbols {
+                file_path: "app.py".into(),
+                language: Some(Language::Python),
+                calls: vec![call(&main, "load"), call(&load, "parse"), call(&load, "missing")],
+                symbols: vec![main, load],
+                ..Default::default()
+            },
+            FileSymbols {
+                file_path: "util.py".into(),
+                language: Some(Language::Python),
+                symbols: vec![parse],
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_index_and_traverse_calls() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(EmbeddedStore::open(dir.path(), "test").unwrap());
+        let writer = GraphWriter::new(store.clone());
+
+        let stats = writer.write_files(sample_files()).await.unwrap();
+        assert_eq!(stats.files_written, 2);
+        assert_eq!(stats.calls_written, 2);
+        assert_eq!(stats.calls_unresolved, 1);
+
+        let callees = store
+            .call_paths("main", CallDirection::Callees, 3, 50)
+            .await
+            .unwrap();
+        let names: Vec<(&str, u32)> = callees.iter().map(|e| (e.name.as_str(), e.distance)).collect();
+        assert_eq!(names, vec![("load", 1), ("parse", 2)]);
+
+        let callers = store
+            .call_paths("parse", CallDirection::Callers, 1, 50)
+            .await
+            .unwrap();
+        assert_eq!(callers.len(), 1);
+        assert_eq!(callers[0].name, "load");
+
+        let found = store
+            .find_symbols(&SymbolQuery {
+                pattern: NamePattern::parse("*A*"),
+                kind: None,
+                language: Some(Language::Python),
+                visibility: None,
+                limit: 10,
+            })
+            .await
+            .unwrap();
+        let names: Vec<&str> = found.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["load", "main", "parse"]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_file_keeps_incoming_links_and_placeholders() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(EmbeddedStore::open(dir.path(), "test").unwrap());
+        GraphWriter::new(store.clone())
+            .write_files(sample_files())
+            .await
+            .unwrap();
+
+        let links = store.incoming_links("util.py").await.unwrap();
+        assert_eq!(links.calls.len(), 1);
+        assert_eq!(links.calls[0].callee_name, "parse");
+
+        store.delete_file("util.py").await.unwrap();
+        assert!(store.incoming_links("util.py").await.unwrap().is_empty());
+        assert_eq!(store.stats().await.unwrap().function_count, 2);
+
+        let names = BTreeSet::from(["missing".to_string()]);
+        let unresolved = store.take_unresolved_calls(&names).await.unwrap();
+        assert_eq!(unresolved.len(), 1);
+        assert!(store.take_unresolved_calls(&names).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_imports_into_a_replaced_file_are_relinked() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(EmbeddedStore::open(dir.path(), "test").unwrap());
+        let writer = GraphWriter::new(store.clone());
+
+        let mut files = sample_files();
+        files[0].imports.push(Import {
+            source_file: "app.py".into(),
+            target: "util".to_string(),
+            alias: None,
+            items: vec!["parse".to_string()],
+            line: 1,
+            resolved: Some(ImportTarget::Project {
+                files: vec!["util.py".into()],
+                symbols: vec![crate::ImportedSymbol {
+                    name: "parse".to_string(),
+                    id: "util.py::parse".to_string(),
+                    file_path: "util.py".into(),
+                }],
+            }),
+        });
+        writer.write_files(files).await.unwrap();
+
+        let links = store.incoming_links("util.py").await.unwrap();
+        assert_eq!(links.imports.len(), 1);
+        assert_eq!(links.imports[0].source_file, Path::new("app.py"));
+
+        // `parse` is renamed: the file is still imported, the symbol is gone
+        store.delete_file("util.py").await.unwrap();
+        assert!(store.incoming_links("util.py").await.unwrap().is_empty());
+        let util = FileSymbols {
+            file_path: "util.py".into(),
+            language: Some(Language::Python),
+            symbols: vec![symbol("util.py", "parse_all", SymbolKind::Function, 1)],
+            ..Default::default()
+        };
+        let stats = writer.write_file_nodes(&util).await.unwrap();
+        assert_eq!((stats.files_written, stats.symbols_written), (1, 1));
+        store.write_import_links(&links.imports).await.unwrap();
+
+        let graph = store.graph.read().await;
+        match &graph.imports["app.py"][0].resolved {
+            Some(ImportTarget::Project { files, symbols }) => {
+                assert_eq!(files, &vec![PathBuf::from("util.py")]);
+                assert!(symbols.is_empty());
+            }
+            other => panic!("expected a project import, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_partial_type_survives_deleting_one_part() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(EmbeddedStore::open(dir.path(), "test").unwrap());
+
+        let part = |file: &str, line: u32| Symbol {
+            id: Symbol::partial_id(Language::CSharp, "App.Order"),
+            is_partial: true,
+            language: Language::CSharp,
+            ..symbol(file, "Order", SymbolKind::Class, line)
+        };
+        let files: Vec<FileSymbols> = [("Order.cs", 3), ("Order.Extra.cs", 5)]
+            .into_iter()
+            .map(|(file, line)| FileSymbols {
+                file_path: file.into(),
+                language: Some(Language::CSharp),
+                symbols: vec![part(file, line)],
+                ..Default::default()
+            })
+            .collect();
+        GraphWriter::new(store.clone()).write_files(files).await.unwrap();
+        assert_eq!(store.stats().await.unwrap().class_count, 1);
+
+        let order = Symbol::partial_id(Language::CSharp, "App.Order");
+        let file_of_order = async || {
+            let graph = store.graph.read().await;
+            graph.symbols.get(&order).map(|s| s.file_path.clone())
+        };
+
+        // The symbol moves to the remaining part
+        let first = file_of_order().await.unwrap();
+        let other = if first == Path::new("Order.cs") { "Order.Extra.cs" } else { "Order.cs" };
+        store.delete_file(&first.to_string_lossy()).await.unwrap();
+        assert_eq!(file_of_order().await, Some(PathBuf::from(other)));
+
+        store.delete_file(other).await.unwrap();
+        assert_eq!(file_of_order().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_flush_and_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(EmbeddedStore::open(dir.path(), "test").unwrap());
+        GraphWriter::new(store.clone())
+            .write_files(sample_files())
+            .await
+            .unwrap();
+        store.set_metadata("last_indexed_commit", "abc123").await.unwrap();
+        store.flush().await.unwrap();
+
+        let reopened = EmbeddedStore::open(dir.path(), "test").unwrap();
+        assert_eq!(
+            reopened.get_metadata("last_indexed_commit").await.unwrap(),
+            Some("abc123".to_string())
+        );
+        assert_eq!(reopened.stats().await.unwrap().calls_count, 3);
+        assert_eq!(reopened.files().await.unwrap().len(), 2);
+    }
+}
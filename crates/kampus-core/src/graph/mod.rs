@@ -1,12 +1,19 @@
-//! Graph database operations using FalkorDB
+//! Graph storage
 //!
-//! Provides schema definition, batch writing, and query operations.
+//! Provides the [`GraphStore`] abstraction with a FalkorDB backend and an
+//! embedded one, schema definition, batch writing, and query operations.
 
+pub mod embedded;
+pub mod query;
 pub mod schema;
+pub mod store;
 pub mod writer;
 
+pub use embedded::EmbeddedStore;
 pub use falkordb::{AsyncGraph, FalkorValue};
-pub use schema::GraphSchema;
+pub use query::{Param, Query};
+pub use schema::{GraphSchema, GraphStats};
+pub use store::{open_store, GraphStore, StoreBackend};
 pub use writer::GraphWriter;
 
 use thiserror::Error;
@@ -21,6 +28,8 @@ pub enum GraphError {
     Serialization(String),
     #[error("Schema error: {0}")]
     Schema(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 /// Result type for graph operations
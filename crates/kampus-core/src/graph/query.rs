@@ -0,0 +1,245 @@
+//! Parameterized Cypher queries
+//!
+//! Values are sent to FalkorDB as query parameters (`CYPHER name=value ...`)
+//! instead of being spliced into the query text. The query text stays
+//! constant, so the server can cache its plan, and file paths, docstrings or
+//! search patterns cannot change the meaning of a query.
+
+use super::{GraphError, GraphResult};
+use falkordb::{AsyncGraph, FalkorValue};
+use std::collections::HashMap;
+
+/// A value passed as a query parameter
+#[derive(Debug, Clone, PartialEq)]
+pub enum Param {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    List(Vec<Param>),
+    Map(Vec<(String, Param)>),
+}
+
+impl Param {
+    /// Build a map value, typically one row of an `UNWIND $rows` statement
+    pub fn map<'a>(entries: impl IntoIterator<Item = (&'a str, Param)>) -> Self {
+        Param::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    /// Encode the value as a Cypher literal
+    pub fn to_cypher(&self) -> String {
+        let mut out = String::new();
+        self.write_cypher(&mut out);
+        out
+    }
+
+    fn write_cypher(&self, out: &mut String) {
+        match self {
+            Param::Null => out.push_str("null"),
+            Param::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+            Param::Int(n) => out.push_str(&n.to_string()),
+            Param::Float(n) if n.is_finite() => out.push_str(&format!("{:?}", n)),
+            Param::Float(_) => out.push_str("null"),
+            Param::String(s) => write_string(s, out),
+            Param::List(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    item.write_cypher(out);
+                }
+                out.push(']');
+            }
+            Param::Map(entries) => {
+                out.push('{');
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    out.push('`');
+                    out.push_str(&key.replace('`', "``"));
+                    out.push_str("`: ");
+                    value.write_cypher(out);
+                }
+                out.push('}');
+            }
+        }
+    }
+}
+
+/// Write a double-quoted Cypher string literal
+///
+/// Quotes and backslashes are escaped, and control characters are written as
+/// `\uXXXX` so no raw control bytes reach the server.
+fn write_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04X}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+impl From<bool> for Param {
+    fn from(value: bool) -> Self {
+        Param::Bool(value)
+    }
+}
+
+impl From<i64> for Param {
+    fn from(value: i64) -> Self {
+        Param::Int(value)
+    }
+}
+
+impl From<u32> for Param {
+    fn from(value: u32) -> Self {
+        Param::Int(value as i64)
+    }
+}
+
+impl From<usize> for Param {
+    fn from(value: usize) -> Self {
+        Param::Int(value as i64)
+    }
+}
+
+impl From<f64> for Param {
+    fn from(value: f64) -> Self {
+        Param::Float(value)
+    }
+}
+
+impl From<&str> for Param {
+    fn from(value: &str) -> Self {
+        Param::String(value.to_string())
+    }
+}
+
+impl From<String> for Param {
+    fn from(value: String) -> Self {
+        Param::String(value)
+    }
+}
+
+impl From<&String> for Param {
+    fn from(value: &String) -> Self {
+        Param::String(value.clone())
+    }
+}
+
+impl<T: Into<Param>> From<Option<T>> for Param {
+    fn from(value: Option<T>) -> Self {
+        value.map(Into::into).unwrap_or(Param::Null)
+    }
+}
+
+impl<T: Into<Param>> From<Vec<T>> for Param {
+    fn from(values: Vec<T>) -> Self {
+        Param::List(values.into_iter().map(Into::into).collect())
+    }
+}
+
+/// A Cypher query with its parameters
+///
+/// ```
+/// use kampus_core::graph::Query;
+///
+/// let query = Query::new("MATCH (f:File {path: $path}) RETURN f.language")
+///     .param("path", "src/it's \"quoted\".rs");
+/// assert_eq!(query.params()["path"], r#""src/it's \"quoted\".rs""#);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Query {
+    cypher: String,
+    params: HashMap<String, String>,
+}
+
+impl Query {
+    pub fn new(cypher: impl Into<String>) -> Self {
+        Self {
+            cypher: cypher.into(),
+            params: HashMap::new(),
+        }
+    }
+
+    /// Bind a parameter, referenced as `$name` in the query text
+    pub fn param(mut self, name: &str, value: impl Into<Param>) -> Self {
+        self.params.insert(name.to_string(), value.into().to_cypher());
+        self
+    }
+
+    /// The query text
+    pub fn cypher(&self) -> &str {
+        &self.cypher
+    }
+
+    /// Encoded parameters by name
+    pub fn params(&self) -> &HashMap<String, String> {
+        &self.params
+    }
+
+    /// Execute the query, discarding any result rows
+    pub async fn execute(&self, graph: &mut AsyncGraph) -> GraphResult<()> {
+        graph
+            .query(&self.cypher)
+            .with_params(&self.params)
+            .execute()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Execute the query and collect its result rows
+    pub async fn fetch(&self, graph: &mut AsyncGraph) -> GraphResult<Vec<Vec<FalkorValue>>> {
+        let result = graph
+            .query(&self.cypher)
+            .with_params(&self.params)
+            .execute()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        Ok(result.data.collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_params_escape_quotes_and_control_characters() {
+        let param = Param::from("a'b\"c\\d\ne\u{0}f\u{7f}é");
+        assert_eq!(param.to_cypher(), r#""a'b\"c\\d\ne\u0000f\u007Fé""#);
+    }
+
+    #[test]
+    fn test_nested_params() {
+        let param = Param::List(vec![
+            Param::Map(vec![
+                ("id".to_string(), Param::from("x")),
+                ("line".to_string(), Param::from(3u32)),
+                ("score".to_string(), Param::from(0.5)),
+                ("doc".to_string(), Param::from(None::<String>)),
+            ]),
+            Param::from(vec!["a", "b"]),
+        ]);
+        assert_eq!(
+            param.to_cypher(),
+            r#"[{`id`: "x", `line`: 3, `score`: 0.5, `doc`: null}, ["a", "b"]]"#
+        );
+    }
+}
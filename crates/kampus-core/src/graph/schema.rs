@@ -1,12 +1,49 @@
 //! FalkorDB schema definitions
 //!
-//! Defines the graph schema for code symbols and relationships.
+//! Defines the graph schema for code symbols and relationships, and
+//! implements [`GraphStore`] on a FalkorDB graph.
+//!
+//! Modules, namespaces and packages become `Namespace` and `Package` nodes
+//! keyed by qualified name and language, which `CONTAINS` nested
+//! namespaces, files and top-level symbols.
+//!
+//! React components are `Component` nodes linked by `RENDERS` edges.
+//! Decorated definitions point at their decorators with `DECORATED_BY`
+//! edges, and functions at the types in their signatures with `USES_TYPE`.
+//! C++ declarations point at their definitions with `DECLARES` edges, and
+//! definitions back at their declarations with `DEFINES`.
+//!
+//! Nodes and edges are sent as rows of an `UNWIND $rows AS row` statement,
+//! so each batch costs a handful of round-trips rather than one per symbol.
 
-use super::{GraphError, GraphResult};
+use super::store::{
+    CallDirection, CallPathEntry, FileEntry, GraphStore, NamePattern, SEARCHABLE_KINDS,
+    SymbolMatch, SymbolQuery,
+};
+use super::writer::PendingLinks;
+use super::{GraphError, GraphResult, Param, Query};
+use crate::resolver::definitions::{self, DefinitionEntry};
+use crate::resolver::implements::{self, MethodSetEntry};
+use crate::resolver::{
+    CallResolver, CallTarget, ResolvedCall, ResolvedReference, ResolvedRender, SymbolEntry,
+};
+use crate::{
+    Call, DocEntry, FileSymbols, Import, ImportTarget, Inheritance, Language, Reference,
+    ReferenceKind, Render, SymbolKind,
+};
+use async_trait::async_trait;
 use falkordb::{AsyncGraph, FalkorClientBuilder, FalkorConnectionInfo, FalkorValue};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+/// Maximum number of rows sent in a single statement
+const BATCH_SIZE: usize = 1000;
+
+/// Node labels an inheritance parent may have
+const PARENT_LABELS: [&str; 5] = ["Class", "Struct", "Interface", "Trait", "Module"];
+
 /// Graph schema for the code index
 pub struct GraphSchema {
     graph: Arc<Mutex<AsyncGraph>>,
@@ -39,8 +76,42 @@ impl GraphSchema {
         })
     }
 
+    /// Execute a raw Cypher query
+    pub async fn query(&self, cypher: &str) -> GraphResult<Vec<Vec<FalkorValue>>> {
+        let mut graph = self.graph.lock().await;
+
+        let result = graph
+            .query(cypher)
+            .execute()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+
+        // Collect results into a Vec
+        let rows: Vec<Vec<FalkorValue>> = result.data.collect();
+        Ok(rows)
+    }
+
+    /// Execute a parameterized query and collect its result rows
+    pub async fn fetch(&self, query: &Query) -> GraphResult<Vec<Vec<FalkorValue>>> {
+        let mut graph = self.graph.lock().await;
+        query.fetch(&mut graph).await
+    }
+
+    /// Get the underlying graph handle for batch operations
+    pub fn graph(&self) -> Arc<Mutex<AsyncGraph>> {
+        self.graph.clone()
+    }
+
+    /// Get the graph name
+    pub fn name(&self) -> &str {
+        &self.graph_name
+    }
+}
+
+#[async_trait]
+impl GraphStore for GraphSchema {
     /// Initialize the schema (create indexes, constraints)
-    pub async fn initialize(&self) -> GraphResult<()> {
+    async fn initialize(&self) -> GraphResult<()> {
         let mut graph = self.graph.lock().await;
 
         // Create indexes for efficient lookups
@@ -57,6 +128,34 @@ impl GraphSchema {
             "CREATE INDEX FOR (s:Struct) ON (s.file_path)",
             "CREATE INDEX FOR (i:Interface) ON (i.name)",
             "CREATE INDEX FOR (m:Module) ON (m.name)",
+            "CREATE INDEX FOR (u:Unresolved) ON (u.name)",
+            "CREATE INDEX FOR (t:Trait) ON (t.name)",
+            "CREATE INDEX FOR (n:Namespace) ON (n.qualified_name)",
+            "CREATE INDEX FOR (p:Package) ON (p.qualified_name)",
+            "CREATE INDEX FOR (fn:Function) ON (fn.qualified_name)",
+            "CREATE INDEX FOR (m:Method) ON (m.qualified_name)",
+            "CREATE INDEX FOR (c:Class) ON (c.qualified_name)",
+            "CREATE INDEX FOR (s:Struct) ON (s.qualified_name)",
+            // Batched writes merge and match symbols by id
+            "CREATE INDEX FOR (fn:Function) ON (fn.id)",
+            "CREATE INDEX FOR (m:Method) ON (m.id)",
+            "CREATE INDEX FOR (c:Class) ON (c.id)",
+            "CREATE INDEX FOR (s:Struct) ON (s.id)",
+            "CREATE INDEX FOR (i:Interface) ON (i.id)",
+            "CREATE INDEX FOR (t:Trait) ON (t.id)",
+            "CREATE INDEX FOR (e:Enum) ON (e.id)",
+            "CREATE INDEX FOR (m:Module) ON (m.id)",
+            "CREATE INDEX FOR (c:Constant) ON (c.id)",
+            "CREATE INDEX FOR (v:Variable) ON (v.id)",
+            "CREATE INDEX FOR (c:Component) ON (c.id)",
+            "CREATE INDEX FOR (c:Component) ON (c.name)",
+            "CREATE INDEX FOR (c:Component) ON (c.qualified_name)",
+            "CREATE INDEX FOR (p:Property) ON (p.id)",
+            "CREATE INDEX FOR (u:Union) ON (u.id)",
+            "CREATE INDEX FOR (t:TypeAlias) ON (t.id)",
+            "CREATE INDEX FOR (m:Macro) ON (m.id)",
+            "CREATE INDEX FOR (f:Field) ON (f.id)",
+            "CREATE INDEX FOR (v:EnumVariant) ON (v.id)",
         ];
 
         for query in &index_queries {
@@ -67,8 +166,7 @@ impl GraphSchema {
         Ok(())
     }
 
-    /// Drop all data from the graph
-    pub async fn clear(&self) -> GraphResult<()> {
+    async fn clear(&self) -> GraphResult<()> {
         let mut graph = self.graph.lock().await;
         graph
             .query("MATCH (n) DETACH DELETE n")
@@ -78,55 +176,1055 @@ impl GraphSchema {
         Ok(())
     }
 
-    /// Delete all data for a specific file
-    pub async fn delete_file(&self, file_path: &str) -> GraphResult<()> {
+    async fn write_nodes(&self, files: &[FileSymbols]) -> GraphResult<()> {
+        let mut graph = self.graph.lock().await;
+        let rows = NodeRows::from_files(files);
+
+        unwind(
+            &mut graph,
+            r#"
+            UNWIND $rows AS row
+            MERGE (f:File {path: row.path})
+            SET f.language = row.language,
+                f.hash = row.hash,
+                f.line_count = row.line_count,
+                f.last_indexed = timestamp()
+            "#,
+            rows.files,
+        )
+        .await?;
+
+        // Labels cannot be parameters, so nodes are written once per label
+        for (label, namespace_rows) in rows.namespaces {
+            let cypher = format!(
+                r#"
+                UNWIND $rows AS row
+                MERGE (n:{} {{qualified_name: row.qualified_name, language: row.language}})
+                SET n.name = row.name
+                "#,
+                label
+            );
+            unwind(&mut graph, &cypher, namespace_rows).await?;
+        }
+
+        for (label, nested_rows) in rows.nested_namespaces {
+            let cypher = format!(
+                r#"
+                UNWIND $rows AS row
+                MATCH (p:{label} {{qualified_name: row.parent, language: row.language}})
+                MATCH (n:{label} {{qualified_name: row.qualified_name, language: row.language}})
+                MERGE (p)-[:CONTAINS]->(n)
+                "#,
+                label = label
+            );
+            unwind(&mut graph, &cypher, nested_rows).await?;
+        }
+
+        for (label, file_rows) in rows.namespace_files {
+            let cypher = format!(
+                r#"
+                UNWIND $rows AS row
+                MATCH (n:{} {{qualified_name: row.namespace, language: row.language}})
+                MATCH (f:File {{path: row.path}})
+                MERGE (n)-[:CONTAINS]->(f)
+                "#,
+                label
+            );
+            unwind(&mut graph, &cypher, file_rows).await?;
+        }
+
+        for (label, symbol_rows) in rows.symbols {
+            let cypher = format!(
+                r#"
+                UNWIND $rows AS row
+                MERGE (s:{label} {{id: row.id}})
+                SET s.name = row.name,
+                    s.qualified_name = row.qualified_name,
+                    s.file_path = row.file_path,
+                    s.start_line = row.start_line,
+                    s.end_line = row.end_line,
+                    s.signature = row.signature,
+                    s.visibility = row.visibility,
+                    s.is_async = row.is_async,
+                    s.is_partial = row.is_partial,
+                    s.decorators = row.decorators,
+                    s.is_static = row.is_static,
+                    s.is_class_method = row.is_class_method,
+                    s.is_abstract = row.is_abstract,
+                    s.is_property = row.is_property,
+                    s.is_declaration = row.is_declaration,
+                    s.docstring = row.docstring,
+                    s.doc_params = row.doc_params,
+                    s.doc_returns = row.doc_returns,
+                    s.doc_raises = row.doc_raises,
+                    s.language = row.language
+                WITH s, row
+                MATCH (f:File {{path: row.file_path}})
+                MERGE (f)-[:CONTAINS]->(s)
+                "#,
+                label = label
+            );
+            unwind(&mut graph, &cypher, symbol_rows).await?;
+        }
+
+        for ((parent_label, child_label), contains_rows) in rows.contains {
+            let cypher = format!(
+                r#"
+                UNWIND $rows AS row
+                MATCH (p:{} {{id: row.parent_id}})
+                MATCH (c:{} {{id: row.id}})
+                MERGE (p)-[:CONTAINS]->(c)
+                "#,
+                parent_label, child_label
+            );
+            unwind(&mut graph, &cypher, contains_rows).await?;
+        }
+
+        for ((namespace_label, label), symbol_rows) in rows.namespace_symbols {
+            let cypher = format!(
+                r#"
+                UNWIND $rows AS row
+                MATCH (n:{} {{qualified_name: row.namespace, language: row.language}})
+                MATCH (s:{} {{id: row.id}})
+                MERGE (n)-[:CONTAINS]->(s)
+                "#,
+                namespace_label, label
+            );
+            unwind(&mut graph, &cypher, symbol_rows).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn write_imports(&self, files: &[FileSymbols]) -> GraphResult<()> {
+        let mut graph = self.graph.lock().await;
+        let rows = files
+            .iter()
+            .flat_map(|f| &f.imports)
+            .filter(|import| !matches!(import.resolved, Some(ImportTarget::Project { .. })))
+            .map(|import| {
+                let (package, version) = match &import.resolved {
+                    Some(ImportTarget::External { package, version }) => {
+                        (package.as_str(), version.as_deref().unwrap_or(""))
+                    }
+                    _ => ("", ""),
+                };
+                import_row(
+                    import,
+                    [("package", Param::from(package)), ("version", Param::from(version))],
+                )
+            })
+            .collect();
+
+        // Create a Module node for each import target outside the project
+        // and link to it
+        unwind(
+            &mut graph,
+            r#"
+            UNWIND $rows AS row
+            MERGE (m:Module {name: row.target})
+            SET m.is_external = true,
+                m.package = CASE WHEN row.package = '' THEN m.package ELSE row.package END,
+                m.version = CASE WHEN row.package = '' THEN m.version ELSE row.version END
+            WITH m, row
+            MATCH (f:File {path: row.path})
+            MERGE (f)-[r:IMPORTS {alias: row.alias}]->(m)
+            SET r.target = row.target,
+                r.items = row.items,
+                r.line = row.line,
+                r.resolved = row.resolved
+            "#,
+            rows,
+        )
+        .await
+    }
+
+    /// Link resolved imports to the files and symbols they point at
+    ///
+    /// Each import keeps one edge per imported file, holding the import
+    /// itself so [`GraphStore::incoming_links`] can restore the edges when
+    /// that file is re-indexed, and one edge per imported symbol.
+    async fn write_import_links(&self, imports: &[Import]) -> GraphResult<()> {
+        let mut graph = self.graph.lock().await;
+        let mut file_rows = Vec::new();
+        let mut symbol_rows = Vec::new();
+
+        for import in imports {
+            let Some(ImportTarget::Project { files, symbols }) = &import.resolved else {
+                continue;
+            };
+            let path = import.source_file.to_string_lossy().to_string();
+            for file in files {
+                let file = file.to_string_lossy().to_string();
+                file_rows.push(import_row(import, [("file", Param::from(file))]));
+            }
+            for symbol in symbols {
+                symbol_rows.push(Param::map([
+                    ("path", Param::from(path.as_str())),
+                    ("file", Param::from(symbol.file_path.to_string_lossy().to_string())),
+                    ("id", Param::from(&symbol.id)),
+                    ("name", Param::from(&symbol.name)),
+                    ("line", Param::from(import.line)),
+                ]));
+            }
+        }
+
+        unwind(
+            &mut graph,
+            r#"
+            UNWIND $rows AS row
+            MATCH (f:File {path: row.path})
+            MATCH (t:File {path: row.file})
+            MERGE (f)-[r:IMPORTS {line: row.line, alias: row.alias}]->(t)
+            SET r.target = row.target,
+                r.items = row.items,
+                r.resolved = row.resolved
+            "#,
+            file_rows,
+        )
+        .await?;
+
+        // Symbols are reached through their file so the path index is used
+        unwind(
+            &mut graph,
+            r#"
+            UNWIND $rows AS row
+            MATCH (f:File {path: row.path})
+            MATCH (:File {path: row.file})-[:CONTAINS]->(s {id: row.id})
+            MERGE (f)-[r:IMPORTS {line: row.line}]->(s)
+            SET r.name = row.name
+            "#,
+            symbol_rows,
+        )
+        .await
+    }
+
+    async fn write_calls(
+        &self,
+        calls: &[ResolvedCall],
+        resolver: &CallResolver,
+    ) -> GraphResult<()> {
+        let mut graph = self.graph.lock().await;
+        let mut to_symbols: BTreeMap<(Option<&str>, Option<&str>), Vec<Param>> = BTreeMap::new();
+        let mut to_placeholders: BTreeMap<Option<&str>, Vec<Param>> = BTreeMap::new();
+
+        for resolved in calls {
+            let caller_label = label_of(resolver, &resolved.call.caller_id);
+            match &resolved.target {
+                CallTarget::Symbol(callee_id) => to_symbols
+                    .entry((caller_label, label_of(resolver, callee_id)))
+                    .or_default()
+                    .push(call_row(resolved, callee_id)),
+                CallTarget::Placeholder(name) => to_placeholders
+                    .entry(caller_label)
+                    .or_default()
+                    .push(call_row(resolved, name)),
+            }
+        }
+
+        for ((caller_label, callee_label), rows) in to_symbols {
+            let callee_match = format!(
+                "MATCH (callee{} {{id: row.callee}})",
+                label_filter(callee_label)
+            );
+            unwind(&mut graph, &call_query(caller_label, &callee_match), rows).await?;
+        }
+
+        // Unresolved calls point at a placeholder node so they can be linked
+        // once a matching symbol is indexed
+        for (caller_label, rows) in to_placeholders {
+            let callee_match = "MERGE (callee:Unresolved {name: row.callee})";
+            unwind(&mut graph, &call_query(caller_label, callee_match), rows).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn write_renders(&self, renders: &[ResolvedRender]) -> GraphResult<()> {
+        let rows: Vec<Param> = renders
+            .iter()
+            .map(|resolved| {
+                let render = &resolved.render;
+                Param::map([
+                    ("component_id", Param::from(&render.component_id)),
+                    ("target_id", Param::from(&resolved.target_id)),
+                    ("line", Param::from(render.line)),
+                    ("receiver", Param::from(render.receiver.as_deref().unwrap_or(""))),
+                    ("confidence", Param::from(resolved.confidence)),
+                    ("resolution", Param::from(resolved.resolution.as_str())),
+                ])
+            })
+            .collect();
+
+        let mut graph = self.graph.lock().await;
+        unwind(
+            &mut graph,
+            r#"
+            UNWIND $rows AS row
+            MATCH (component:Component {id: row.component_id})
+            MATCH (target:Component {id: row.target_id})
+            MERGE (component)-[r:RENDERS]->(target)
+            SET r.line = row.line,
+                r.receiver = row.receiver,
+                r.confidence = row.confidence,
+                r.resolution = row.resolution
+            "#,
+            rows,
+        )
+        .await
+    }
+
+    async fn write_references(
+        &self,
+        references: &[ResolvedReference],
+        resolver: &CallResolver,
+    ) -> GraphResult<()> {
+        type EdgeLabels<'a> = (ReferenceKind, Option<&'a str>, Option<&'a str>);
+        let mut grouped: BTreeMap<EdgeLabels, Vec<Param>> = BTreeMap::new();
+        for resolved in references {
+            let reference = &resolved.reference;
+            grouped
+                .entry((
+                    reference.kind,
+                    label_of(resolver, &reference.source_id),
+                    label_of(resolver, &resolved.target_id),
+                ))
+                .or_default()
+                .push(Param::map([
+                    ("source_id", Param::from(&reference.source_id)),
+                    ("target_id", Param::from(&resolved.target_id)),
+                    ("line", Param::from(reference.line)),
+                    ("receiver", Param::from(reference.receiver.as_deref().unwrap_or(""))),
+                    ("confidence", Param::from(resolved.confidence)),
+                    ("resolution", Param::from(resolved.resolution.as_str())),
+                ]));
+        }
+
+        let mut graph = self.graph.lock().await;
+        for ((kind, source_label, target_label), rows) in grouped {
+            let cypher = format!(
+                r#"
+                UNWIND $rows AS row
+                MATCH (source{} {{id: row.source_id}})
+                MATCH (target{} {{id: row.target_id}})
+                MERGE (source)-[r:{}]->(target)
+                SET r.line = row.line,
+                    r.receiver = row.receiver,
+                    r.confidence = row.confidence,
+                    r.resolution = row.resolution
+                "#,
+                label_filter(source_label),
+                label_filter(target_label),
+                kind.label()
+            );
+            unwind(&mut graph, &cypher, rows).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn write_inheritance(
+        &self,
+        links: &[Inheritance],
+        resolver: &CallResolver,
+    ) -> GraphResult<()> {
+        let mut graph = self.graph.lock().await;
+        let mut inheritance: BTreeMap<Option<&str>, Vec<Param>> = BTreeMap::new();
+        for link in links {
+            inheritance
+                .entry(label_of(resolver, &link.child_id))
+                .or_default()
+                .push(Param::map([
+                    ("child_id", Param::from(&link.child_id)),
+                    ("parent_name", Param::from(&link.parent_name)),
+                ]));
+        }
+
+        // Query each parent label separately so the name index is used
+        for (child_label, rows) in inheritance {
+            for parent_label in PARENT_LABELS {
+                let cypher = format!(
+                    r#"
+                    UNWIND $rows AS row
+                    MATCH (child{} {{id: row.child_id}})
+                    MATCH (parent:{} {{name: row.parent_name}})
+                    MERGE (child)-[:INHERITS]->(parent)
+                    "#,
+                    label_filter(child_label),
+                    parent_label
+                );
+                unwind(&mut graph, &cypher, rows.clone()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn write_implementations(&self) -> GraphResult<usize> {
+        let mut graph = self.graph.lock().await;
+        let language = Language::Go.to_string();
+
+        let labels: Vec<String> = implements::KINDS
+            .iter()
+            .map(|k| format!("s:{}", k.label()))
+            .collect();
+        let rows = Query::new(format!(
+            r#"
+            MATCH (s)
+            WHERE s.language = $language AND ({})
+            OPTIONAL MATCH (s)-[:INHERITS]->(e:Interface)
+            RETURN s.id, labels(s)[0], s.qualified_name, s.signature, collect(e.id)
+            "#,
+            labels.join(" OR ")
+        ))
+        .param("language", language.as_str())
+        .fetch(&mut graph)
+        .await?;
+
+        let entries: Vec<MethodSetEntry> = rows
+            .iter()
+            .filter_map(|row| match row.as_slice() {
+                [
+                    FalkorValue::String(id),
+                    FalkorValue::String(label),
+                    qualified_name,
+                    signature,
+                    embeds,
+                ] => Some(MethodSetEntry {
+                    id: id.clone(),
+                    kind: SymbolKind::from_label(label)?,
+                    qualified_name: value_string(qualified_name),
+                    signature: Some(value_string(signature)).filter(|s| !s.is_empty()),
+                    embeds: string_values(embeds),
+                }),
+                _ => None,
+            })
+            .collect();
+        let implementations = implements::implementations(&entries);
+
+        Query::new(
+            r#"
+            MATCH ()-[r:IMPLEMENTS]->(i:Interface)
+            WHERE i.language = $language
+            DELETE r
+            "#,
+        )
+        .param("language", language.as_str())
+        .execute(&mut graph)
+        .await?;
+
+        let mut grouped: BTreeMap<&str, Vec<Param>> = BTreeMap::new();
+        for implementation in &implementations {
+            grouped
+                .entry(implementation.type_kind.label())
+                .or_default()
+                .push(Param::map([
+                    ("type_id", Param::from(&implementation.type_id)),
+                    ("interface_id", Param::from(&implementation.interface_id)),
+                ]));
+        }
+        for (label, rows) in grouped {
+            let cypher = format!(
+                r#"
+                UNWIND $rows AS row
+                MATCH (t:{} {{id: row.type_id}})
+                MATCH (i:Interface {{id: row.interface_id}})
+                MERGE (t)-[:IMPLEMENTS]->(i)
+                "#,
+                label
+            );
+            unwind(&mut graph, &cypher, rows).await?;
+        }
+
+        Ok(implementations.len())
+    }
+
+    async fn write_definitions(&self) -> GraphResult<usize> {
         let mut graph = self.graph.lock().await;
-        let escaped_path = escape_string(file_path);
+        let language = Language::Cpp.to_string();
+
+        let labels: Vec<String> = definitions::KINDS
+            .iter()
+            .map(|k| format!("s:{}", k.label()))
+            .collect();
+        let rows = Query::new(format!(
+            r#"
+            MATCH (s)
+            WHERE s.language = $language AND ({})
+            OPTIONAL MATCH (p)-[:CONTAINS]->(s)
+            WHERE NOT p:File AND NOT p:Namespace
+            RETURN s.id, labels(s)[0], s.name, s.qualified_name, s.signature,
+                   s.is_declaration, p.id
+            "#,
+            labels.join(" OR ")
+        ))
+        .param("language", language.as_str())
+        .fetch(&mut graph)
+        .await?;
+
+        let entries: Vec<DefinitionEntry> = rows
+            .iter()
+            .filter_map(|row| match row.as_slice() {
+                [
+                    FalkorValue::String(id),
+                    FalkorValue::String(label),
+                    name,
+                    qualified_name,
+                    signature,
+                    is_declaration,
+                    parent_id,
+                ] => Some(DefinitionEntry {
+                    id: id.clone(),
+                    kind: SymbolKind::from_label(label)?,
+                    name: value_string(name),
+                    qualified_name: value_string(qualified_name),
+                    signature: Some(value_string(signature)).filter(|s| !s.is_empty()),
+                    is_declaration: matches!(is_declaration, FalkorValue::Bool(true)),
+                    parent_id: Some(value_string(parent_id)).filter(|s| !s.is_empty()),
+                }),
+                _ => None,
+            })
+            .collect();
+        let definitions = definitions::definitions(&entries);
+
+        Query::new(
+            r#"
+            MATCH (s)-[r:DECLARES|DEFINES]->()
+            WHERE s.language = $language
+            DELETE r
+            "#,
+        )
+        .param("language", language.as_str())
+        .execute(&mut graph)
+        .await?;
+
+        let mut grouped: BTreeMap<(&str, &str), Vec<Param>> = BTreeMap::new();
+        for definition in &definitions {
+            grouped
+                .entry((
+                    definition.declaration_kind.label(),
+                    definition.definition_kind.label(),
+                ))
+                .or_default()
+                .push(Param::map([
+                    ("declaration_id", Param::from(&definition.declaration_id)),
+                    ("definition_id", Param::from(&definition.definition_id)),
+                ]));
+        }
+        for ((declaration_label, definition_label), rows) in grouped {
+            let cypher = format!(
+                r#"
+                UNWIND $rows AS row
+                MATCH (d:{} {{id: row.declaration_id}})
+                MATCH (s:{} {{id: row.definition_id}})
+                MERGE (d)-[:DECLARES]->(s)
+                MERGE (s)-[:DEFINES]->(d)
+                "#,
+                declaration_label, definition_label
+            );
+            unwind(&mut graph, &cypher, rows).await?;
+        }
+
+        let mut grouped: BTreeMap<&str, Vec<Param>> = BTreeMap::new();
+        for member in definitions::members(&entries) {
+            grouped
+                .entry(member.class_kind.label())
+                .or_default()
+                .push(Param::map([
+                    ("class_id", Param::from(&member.class_id)),
+                    ("method_id", Param::from(&member.method_id)),
+                ]));
+        }
+        for (label, rows) in grouped {
+            let cypher = format!(
+                r#"
+                UNWIND $rows AS row
+                MATCH (c:{} {{id: row.class_id}})
+                MATCH (m:Method {{id: row.method_id}})
+                MERGE (c)-[:CONTAINS]->(m)
+                "#,
+                label
+            );
+            unwind(&mut graph, &cypher, rows).await?;
+        }
+
+        Ok(definitions.len())
+    }
+
+    async fn delete_file(&self, file_path: &str) -> GraphResult<()> {
+        let mut graph = self.graph.lock().await;
+
+        // Partial types also declared in another file move to that file
+        Query::new(
+            r#"
+            MATCH (f:File {path: $path})-[:CONTAINS]->(s)<-[:CONTAINS]-(other:File)
+            WHERE other <> f
+            SET s.file_path = other.path
+            "#,
+        )
+        .param("path", file_path)
+        .execute(&mut graph)
+        .await?;
 
         // Delete the file node and all symbols from that file
-        let query = format!(
+        Query::new(
             r#"
-            MATCH (f:File {{path: '{}'}})
+            MATCH (f:File {path: $path})
             OPTIONAL MATCH (f)-[:CONTAINS]->(s)
+            WHERE s.file_path = $path
             DETACH DELETE f, s
             "#,
-            escaped_path
-        );
-
-        graph
-            .query(&query)
-            .execute()
-            .await
-            .map_err(|e| GraphError::Query(e.to_string()))?;
+        )
+        .param("path", file_path)
+        .execute(&mut graph)
+        .await?;
 
         // Also delete any orphaned symbols from this file
-        let cleanup_query = format!(
+        Query::new(
             r#"
             MATCH (s)
-            WHERE s.file_path = '{}'
+            WHERE s.file_path = $path
             DETACH DELETE s
             "#,
-            escaped_path
-        );
+        )
+        .param("path", file_path)
+        .execute(&mut graph)
+        .await?;
 
-        graph
-            .query(&cleanup_query)
-            .execute()
-            .await
-            .map_err(|e| GraphError::Query(e.to_string()))?;
+        // Drop namespaces left empty, innermost first
+        loop {
+            let rows = Query::new(
+                r#"
+                MATCH (n)
+                WHERE (n:Namespace OR n:Package) AND NOT (n)-[:CONTAINS]->()
+                DELETE n
+                RETURN count(n)
+                "#,
+            )
+            .fetch(&mut graph)
+            .await?;
+            if rows.first().and_then(|row| row.first()).map_or(0, value_u32) == 0 {
+                break;
+            }
+        }
 
         Ok(())
     }
 
-    /// Get graph statistics
-    pub async fn stats(&self) -> GraphResult<GraphStats> {
+    /// Collect calls, inheritance, renders, references and resolved imports
+    /// from other files that point into a file
+    ///
+    /// Deleting a file drops every edge into its symbols. Callers use this
+    /// before re-indexing a file so those edges can be linked again once the
+    /// new symbols are written.
+    async fn incoming_links(&self, file_path: &str) -> GraphResult<PendingLinks> {
+        let mut graph = self.graph.lock().await;
+        let mut links = PendingLinks::default();
+
+        let rows = Query::new(
+            r#"
+            MATCH (caller)-[r:CALLS]->(callee)
+            WHERE callee.file_path = $path AND caller.file_path <> $path
+            RETURN caller.id, callee.name, r.receiver, r.call_site_line
+            "#,
+        )
+        .param("path", file_path)
+        .fetch(&mut graph)
+        .await?;
+
+        links.calls.extend(rows.iter().filter_map(|row| row_to_call(row)));
+
+        let rows = Query::new(
+            r#"
+            MATCH (child)-[:INHERITS]->(parent)
+            WHERE parent.file_path = $path AND child.file_path <> $path
+            RETURN child.id, parent.name
+            "#,
+        )
+        .param("path", file_path)
+        .fetch(&mut graph)
+        .await?;
+
+        for row in rows {
+            if let [FalkorValue::String(child_id), FalkorValue::String(parent_name)] =
+                row.as_slice()
+            {
+                links.inheritance.push(Inheritance {
+                    child_id: child_id.clone(),
+                    parent_name: parent_name.clone(),
+                });
+            }
+        }
+
+        let rows = Query::new(
+            r#"
+            MATCH (component:Component)-[r:RENDERS]->(target:Component)
+            WHERE target.file_path = $path AND component.file_path <> $path
+            RETURN component.id, target.name, r.receiver, r.line
+            "#,
+        )
+        .param("path", file_path)
+        .fetch(&mut graph)
+        .await?;
+
+        for row in rows {
+            if let [
+                FalkorValue::String(component_id),
+                FalkorValue::String(element_name),
+                receiver,
+                line,
+            ] = row.as_slice()
+            {
+                links.renders.push(Render {
+                    component_id: component_id.clone(),
+                    element_name: element_name.clone(),
+                    receiver: Some(value_string(receiver)).filter(|r| !r.is_empty()),
+                    line: value_u32(line),
+                });
+            }
+        }
+
+        let rows = Query::new(
+            r#"
+            MATCH (source)-[r:DECORATED_BY|USES_TYPE]->(target)
+            WHERE target.file_path = $path AND source.file_path <> $path
+            RETURN type(r), source.id, target.name, r.receiver, r.line
+            "#,
+        )
+        .param("path", file_path)
+        .fetch(&mut graph)
+        .await?;
+
+        for row in rows {
+            if let [
+                FalkorValue::String(label),
+                FalkorValue::String(source_id),
+                FalkorValue::String(target_name),
+                receiver,
+                line,
+            ] = row.as_slice()
+                && let Some(kind) = ReferenceKind::from_label(label)
+            {
+                links.references.push(Reference {
+                    kind,
+                    source_id: source_id.clone(),
+                    target_name: target_name.clone(),
+                    receiver: Some(value_string(receiver)).filter(|r| !r.is_empty()),
+                    line: value_u32(line),
+                });
+            }
+        }
+
+        let rows = Query::new(
+            r#"
+            MATCH (f:File)-[r:IMPORTS]->(t:File {path: $path})
+            WHERE f.path <> $path
+            RETURN f.path, r.target, r.alias, r.items, r.line, r.resolved
+            "#,
+        )
+        .param("path", file_path)
+        .fetch(&mut graph)
+        .await?;
+
+        links.imports.extend(rows.iter().filter_map(|row| row_to_import(row)));
+
+        Ok(links)
+    }
+
+    /// Collect calls currently linked to placeholders for any of `names` and
+    /// remove those edges
+    ///
+    /// Called after new symbols are written so calls from unchanged files that
+    /// could not be resolved before get another chance.
+    async fn take_unresolved_calls(
+        &self,
+        names: &BTreeSet<String>,
+    ) -> GraphResult<Vec<Call>> {
+        if names.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut graph = self.graph.lock().await;
+        let names = string_list(names);
+
+        let rows = Query::new(
+            r#"
+            MATCH (caller)-[r:CALLS]->(u:Unresolved)
+            WHERE u.name IN $names
+            RETURN caller.id, u.name, r.receiver, r.call_site_line
+            "#,
+        )
+        .param("names", names.clone())
+        .fetch(&mut graph)
+        .await?;
+
+        let calls: Vec<Call> = rows.iter().filter_map(|row| row_to_call(row)).collect();
+
+        Query::new(
+            r#"
+            MATCH ()-[r:CALLS]->(u:Unresolved)
+            WHERE u.name IN $names
+            DELETE r
+            "#,
+        )
+        .param("names", names)
+        .execute(&mut graph)
+        .await?;
+
+        Ok(calls)
+    }
+
+    /// Build a call resolver from the graph for a set of pending links
+    ///
+    /// Loads every function, method or component named by a call or render,
+    /// every symbol named by a reference, the callers themselves, the types
+    /// they belong to and the imports of the caller files. Incremental updates use this since only the changed
+    /// files are parsed.
+    async fn call_resolver(&self, links: &PendingLinks) -> GraphResult<CallResolver> {
+        let mut resolver = CallResolver::new();
+        if links.calls.is_empty() && links.renders.is_empty() && links.references.is_empty() {
+            return Ok(resolver);
+        }
+
+        let mut graph = self.graph.lock().await;
+
+        let names: BTreeSet<String> = links
+            .calls
+            .iter()
+            .map(|c| c.callee_name.clone())
+            .chain(links.renders.iter().map(|r| r.element_name.clone()))
+            .collect();
+        let caller_ids: BTreeSet<String> = links
+            .calls
+            .iter()
+            .map(|c| c.caller_id.clone())
+            .chain(links.renders.iter().map(|r| r.component_id.clone()))
+            .chain(links.references.iter().map(|r| r.source_id.clone()))
+            .collect();
+        let reference_names: BTreeSet<String> =
+            links.references.iter().map(|r| r.target_name.clone()).collect();
+
+        let rows = Query::new(
+            r#"
+            MATCH (s)
+            WHERE ((s:Function OR s:Method OR s:Component OR s:Macro) AND s.name IN $names)
+               OR s.id IN $caller_ids
+               OR (s.id IS NOT NULL AND s.name IN $reference_names)
+            OPTIONAL MATCH (p)-[:CONTAINS]->(s)
+            WHERE NOT p:File
+            RETURN s.id, s.name, labels(s)[0], s.file_path,
+                   p.id, p.name, labels(p)[0], p.file_path
+            "#,
+        )
+        .param("names", string_list(&names))
+        .param("caller_ids", string_list(&caller_ids))
+        .param("reference_names", string_list(&reference_names))
+        .fetch(&mut graph)
+        .await?;
+
+        for row in rows {
+            let parent = symbol_entry(row.get(4..8).unwrap_or_default(), None);
+            let parent_id = parent.as_ref().map(|p| p.id.clone());
+            if let Some(symbol) = symbol_entry(row.get(0..4).unwrap_or_default(), parent_id) {
+                resolver.add_symbol(symbol);
+            }
+            if let Some(parent) = parent {
+                resolver.add_symbol(parent);
+            }
+        }
+
+        let rows = Query::new(
+            r#"
+            MATCH (caller)<-[:CONTAINS*1..3]-(f:File)-[r:IMPORTS]->(m)
+            WHERE caller.id IN $caller_ids AND (m:Module OR m:File)
+            RETURN DISTINCT f.path, coalesce(r.target, m.name), r.alias, r.items, r.line,
+                r.resolved
+            "#,
+        )
+        .param("caller_ids", string_list(&caller_ids))
+        .fetch(&mut graph)
+        .await?;
+
+        for row in rows {
+            if let Some(import) = row_to_import(&row) {
+                resolver.add_import(import);
+            }
+        }
+
+        Ok(resolver)
+    }
+
+    async fn find_symbols(&self, query: &SymbolQuery) -> GraphResult<Vec<SymbolMatch>> {
+        let kind_condition = match query.kind {
+            Some(kind) => format!("s:{}", kind.label()),
+            None => {
+                let labels: Vec<String> = SEARCHABLE_KINDS
+                    .iter()
+                    .map(|k| format!("s:{}", k.label()))
+                    .collect();
+                format!("({})", labels.join(" OR "))
+            }
+        };
+
+        // The search term is passed as $term, never spliced into the query
+        let field = if query.pattern.is_qualified() {
+            "s.qualified_name"
+        } else {
+            "s.name"
+        };
+        let name_condition = match query.pattern {
+            NamePattern::Any => "true".to_string(),
+            NamePattern::Exact(_) => format!("toLower({}) = $term", field),
+            NamePattern::Prefix(_) => format!("toLower({}) STARTS WITH $term", field),
+            NamePattern::Suffix(_) => format!("toLower({}) ENDS WITH $term", field),
+            NamePattern::Contains(_) => format!("toLower({}) CONTAINS $term", field),
+        };
+        let language_condition = if query.language.is_some() {
+            "AND s.language = $language"
+        } else {
+            ""
+        };
+        let visibility_condition = if query.visibility.is_some() {
+            "AND s.visibility = $visibility"
+        } else {
+            ""
+        };
+
+        let cypher = format!(
+            r#"
+            MATCH (s)
+            WHERE {}
+              AND {}
+              {}
+              {}
+            RETURN s.name, s.qualified_name, labels(s)[0], s.file_path, s.start_line
+            ORDER BY s.qualified_name
+            LIMIT $limit
+            "#,
+            kind_condition, name_condition, language_condition, visibility_condition
+        );
+
+        let rows = self
+            .fetch(
+                &Query::new(cypher)
+                    .param("term", query.pattern.term())
+                    .param("language", query.language.map(|l| l.to_string()))
+                    .param("visibility", query.visibility.map(|v| v.to_string()))
+                    .param("limit", query.limit),
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| match row.as_slice() {
+                [
+                    FalkorValue::String(name),
+                    qualified_name,
+                    FalkorValue::String(label),
+                    file_path,
+                    line,
+                ] => {
+                    Some(SymbolMatch {
+                        name: name.clone(),
+                        qualified_name: value_string(qualified_name),
+                        kind: SymbolKind::from_label(label)?,
+                        file_path: value_string(file_path),
+                        start_line: value_u32(line),
+                    })
+                }
+                _ => None,
+            })
+            .collect())
+    }
+
+    async fn call_paths(
+        &self,
+        function: &str,
+        direction: CallDirection,
+        depth: u32,
+        limit: usize,
+    ) -> GraphResult<Vec<CallPathEntry>> {
+        // Variable-length bounds cannot be parameters
+        let cypher = match direction {
+            CallDirection::Callers => format!(
+                r#"
+                MATCH (target)
+                WHERE (target:Function OR target:Method OR target:Component OR target:Macro) AND target.name = $name
+                MATCH path = (other)-[:CALLS*1..{}]->(target)
+                WHERE other:Function OR other:Method OR other:Component OR other:Macro
+                RETURN other.name, other.file_path, min(length(path)) AS distance
+                ORDER BY distance, other.name
+                LIMIT $limit
+                "#,
+                depth.max(1)
+            ),
+            CallDirection::Callees => format!(
+                r#"
+                MATCH (source)
+                WHERE (source:Function OR source:Method OR source:Component OR source:Macro) AND source.name = $name
+                MATCH path = (source)-[:CALLS*1..{}]->(other)
+                WHERE other:Function OR other:Method OR other:Component OR other:Macro
+                RETURN other.name, other.file_path, min(length(path)) AS distance
+                ORDER BY distance, other.name
+                LIMIT $limit
+                "#,
+                depth.max(1)
+            ),
+        };
+
+        let rows = self
+            .fetch(
+                &Query::new(cypher)
+                    .param("name", function)
+                    .param("limit", limit),
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| match row.as_slice() {
+                [name, file_path, distance] => Some(CallPathEntry {
+                    name: value_string(name),
+                    file_path: value_string(file_path),
+                    distance: value_u32(distance),
+                }),
+                _ => None,
+            })
+            .collect())
+    }
+
+    async fn files(&self) -> GraphResult<Vec<FileEntry>> {
+        let rows = self
+            .fetch(&Query::new(
+                r#"
+                MATCH (f:File)
+                RETURN f.path, f.language, f.line_count
+                ORDER BY f.path
+                "#,
+            ))
+            .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| match row.as_slice() {
+                [path, language, line_count] => Some(FileEntry {
+                    path: value_string(path),
+                    language: value_string(language),
+                    line_count: value_u32(line_count),
+                }),
+                _ => None,
+            })
+            .collect())
+    }
+
+    async fn stats(&self) -> GraphResult<GraphStats> {
         let mut graph = self.graph.lock().await;
 
         // Helper to extract count from a query result
         async fn get_count(graph: &mut AsyncGraph, query: &str) -> i64 {
             match graph.query(query).execute().await {
-                Ok(result) => {
-                    result.data.into_iter().next()
+                Ok(mut result) => {
+                    result.data.next()
                         .and_then(|row| row.into_iter().next())
                         .and_then(|val| match val {
                             FalkorValue::I64(n) => Some(n),
@@ -142,35 +1240,38 @@ impl GraphSchema {
         let function_count = get_count(&mut graph, "MATCH (f:Function) RETURN count(f)").await;
         let class_count = get_count(&mut graph, "MATCH (c:Class) RETURN count(c)").await;
         let struct_count = get_count(&mut graph, "MATCH (s:Struct) RETURN count(s)").await;
+        let component_count = get_count(&mut graph, "MATCH (c:Component) RETURN count(c)").await;
         let calls_count = get_count(&mut graph, "MATCH ()-[r:CALLS]->() RETURN count(r)").await;
+        let renders_count = get_count(&mut graph, "MATCH ()-[r:RENDERS]->() RETURN count(r)").await;
         let imports_count = get_count(&mut graph, "MATCH ()-[r:IMPORTS]->() RETURN count(r)").await;
+        let namespace_count = get_count(
+            &mut graph,
+            "MATCH (n) WHERE n:Namespace OR n:Package RETURN count(n)",
+        )
+        .await;
 
         Ok(GraphStats {
             file_count: file_count as usize,
             function_count: function_count as usize,
             class_count: class_count as usize,
             struct_count: struct_count as usize,
+            component_count: component_count as usize,
             calls_count: calls_count as usize,
+            renders_count: renders_count as usize,
             imports_count: imports_count as usize,
+            namespace_count: namespace_count as usize,
         })
     }
 
-    /// Get metadata (last indexed commit, etc.)
-    pub async fn get_metadata(&self, key: &str) -> GraphResult<Option<String>> {
+    async fn get_metadata(&self, key: &str) -> GraphResult<Option<String>> {
         let mut graph = self.graph.lock().await;
 
-        let query = format!(
-            "MATCH (m:Metadata {{key: '{}'}}) RETURN m.value",
-            escape_string(key)
-        );
+        let rows = Query::new("MATCH (m:Metadata {key: $key}) RETURN m.value")
+            .param("key", key)
+            .fetch(&mut graph)
+            .await?;
 
-        let result = graph
-            .query(&query)
-            .execute()
-            .await
-            .map_err(|e| GraphError::Query(e.to_string()))?;
-
-        Ok(result.data.into_iter().next()
+        Ok(rows.into_iter().next()
             .and_then(|row| row.into_iter().next())
             .and_then(|val| match val {
                 FalkorValue::String(s) => Some(s),
@@ -178,61 +1279,348 @@ impl GraphSchema {
             }))
     }
 
-    /// Set metadata
-    pub async fn set_metadata(&self, key: &str, value: &str) -> GraphResult<()> {
+    async fn set_metadata(&self, key: &str, value: &str) -> GraphResult<()> {
         let mut graph = self.graph.lock().await;
 
-        let query = format!(
+        Query::new(
             r#"
-            MERGE (m:Metadata {{key: '{}'}})
-            SET m.value = '{}'
+            MERGE (m:Metadata {key: $key})
+            SET m.value = $value
             "#,
-            escape_string(key),
-            escape_string(value)
-        );
+        )
+        .param("key", key)
+        .param("value", value)
+        .execute(&mut graph)
+        .await
+    }
+}
 
-        graph
-            .query(&query)
-            .execute()
-            .await
-            .map_err(|e| GraphError::Query(e.to_string()))?;
+/// Run an `UNWIND $rows` statement once per batch of rows
+async fn unwind(graph: &mut AsyncGraph, cypher: &str, rows: Vec<Param>) -> GraphResult<()> {
+    let mut rows = rows.into_iter();
+    loop {
+        let batch: Vec<Param> = rows.by_ref().take(BATCH_SIZE).collect();
+        if batch.is_empty() {
+            return Ok(());
+        }
+        Query::new(cypher)
+            .param("rows", Param::List(batch))
+            .execute(graph)
+            .await?;
+    }
+}
 
-        Ok(())
+/// File, namespace and symbol rows for a set of files, grouped by the
+/// labels their statements need
+#[derive(Debug, Default)]
+struct NodeRows {
+    files: Vec<Param>,
+    namespaces: BTreeMap<&'static str, Vec<Param>>,
+    /// Namespaces nested in a namespace of the same label
+    nested_namespaces: BTreeMap<&'static str, Vec<Param>>,
+    /// Files by the label of their namespace
+    namespace_files: BTreeMap<&'static str, Vec<Param>>,
+    symbols: BTreeMap<&'static str, Vec<Param>>,
+    /// Symbols by the label of their parent and their own label
+    contains: BTreeMap<(&'static str, &'static str), Vec<Param>>,
+    /// Top-level symbols by the label of their namespace and their own label
+    namespace_symbols: BTreeMap<(&'static str, &'static str), Vec<Param>>,
+}
+
+impl NodeRows {
+    fn from_files(files: &[FileSymbols]) -> Self {
+        let mut rows = NodeRows::default();
+
+        for file_symbols in files {
+            let file_path = file_symbols.file_path.to_string_lossy().to_string();
+            let language = file_symbols
+                .language
+                .map(|l| l.to_string())
+                .unwrap_or_default();
+
+            rows.files.push(Param::map([
+                ("path", Param::from(&file_path)),
+                ("language", Param::from(&language)),
+                ("hash", Param::from(&file_symbols.content_hash)),
+                ("line_count", Param::from(file_symbols.line_count)),
+            ]));
+
+            let namespace_labels: HashMap<&str, &'static str> = file_symbols
+                .namespaces
+                .iter()
+                .map(|n| (n.qualified_name.as_str(), n.kind.label()))
+                .collect();
+
+            for namespace in &file_symbols.namespaces {
+                let label = namespace.kind.label();
+                rows.namespaces.entry(label).or_default().push(Param::map([
+                    ("qualified_name", Param::from(&namespace.qualified_name)),
+                    ("name", Param::from(&namespace.name)),
+                    ("language", Param::from(&language)),
+                ]));
+                if let Some(parent) = &namespace.parent {
+                    rows.nested_namespaces.entry(label).or_default().push(Param::map([
+                        ("parent", Param::from(parent)),
+                        ("qualified_name", Param::from(&namespace.qualified_name)),
+                        ("language", Param::from(&language)),
+                    ]));
+                }
+            }
+
+            if let Some(namespace) = &file_symbols.namespace
+                && let Some(label) = namespace_labels.get(namespace.as_str())
+            {
+                rows.namespace_files.entry(label).or_default().push(Param::map([
+                    ("namespace", Param::from(namespace)),
+                    ("path", Param::from(&file_path)),
+                    ("language", Param::from(&language)),
+                ]));
+            }
+
+            let kinds: HashMap<&str, SymbolKind> = file_symbols
+                .symbols
+                .iter()
+                .map(|s| (s.id.as_str(), s.kind))
+                .collect();
+
+            for symbol in &file_symbols.symbols {
+                let label = symbol.kind.label();
+
+                rows.symbols.entry(label).or_default().push(Param::map([
+                    ("id", Param::from(&symbol.id)),
+                    ("name", Param::from(&symbol.name)),
+                    ("qualified_name", Param::from(&symbol.qualified_name)),
+                    ("file_path", Param::from(&file_path)),
+                    ("start_line", Param::from(symbol.start_line)),
+                    ("end_line", Param::from(symbol.end_line)),
+                    ("signature", Param::from(symbol.signature.as_deref().unwrap_or(""))),
+                    ("visibility", Param::from(symbol.visibility.to_string())),
+                    ("is_async", Param::from(symbol.is_async)),
+                    ("is_partial", Param::from(symbol.is_partial)),
+                    (
+                        "decorators",
+                        string_list(symbol.decorators.iter().map(|d| &d.name)),
+                    ),
+                    ("is_static", Param::from(symbol.modifiers.is_static)),
+                    ("is_class_method", Param::from(symbol.modifiers.is_class_method)),
+                    ("is_abstract", Param::from(symbol.modifiers.is_abstract)),
+                    ("is_property", Param::from(symbol.modifiers.is_property)),
+                    ("is_declaration", Param::from(symbol.modifiers.is_declaration)),
+                    ("docstring", Param::from(symbol.docstring.as_deref().unwrap_or(""))),
+                    ("doc_params", doc_entries(&symbol.doc_sections.params)),
+                    (
+                        "doc_returns",
+                        Param::from(symbol.doc_sections.returns.as_deref().unwrap_or("")),
+                    ),
+                    ("doc_raises", doc_entries(&symbol.doc_sections.raises)),
+                    ("language", Param::from(symbol.language.to_string())),
+                ]));
+
+                // Parents are extracted from the same file
+                if let Some(parent_id) = &symbol.parent_id
+                    && let Some(parent_kind) = kinds.get(parent_id.as_str())
+                {
+                    rows.contains
+                        .entry((parent_kind.label(), label))
+                        .or_default()
+                        .push(Param::map([
+                            ("parent_id", Param::from(parent_id)),
+                            ("id", Param::from(&symbol.id)),
+                        ]));
+                }
+
+                if symbol.parent_id.is_none()
+                    && let Some(namespace) = &symbol.namespace
+                    && let Some(namespace_label) = namespace_labels.get(namespace.as_str())
+                {
+                    rows.namespace_symbols
+                        .entry((namespace_label, label))
+                        .or_default()
+                        .push(Param::map([
+                            ("namespace", Param::from(namespace)),
+                            ("id", Param::from(&symbol.id)),
+                            ("language", Param::from(&language)),
+                        ]));
+                }
+            }
+        }
+
+        rows
     }
+}
 
-    /// Execute a raw Cypher query
-    pub async fn query(&self, cypher: &str) -> GraphResult<Vec<Vec<FalkorValue>>> {
-        let mut graph = self.graph.lock().await;
+/// Build the statement linking call rows to the callee found by
+/// `callee_match`
+fn call_query(caller_label: Option<&str>, callee_match: &str) -> String {
+    format!(
+        r#"
+        UNWIND $rows AS row
+        MATCH (caller{} {{id: row.caller_id}})
+        {}
+        MERGE (caller)-[r:CALLS]->(callee)
+        SET r.call_site_line = row.line,
+            r.receiver = row.receiver,
+            r.confidence = row.confidence,
+            r.resolution = row.resolution
+        "#,
+        label_filter(caller_label),
+        callee_match
+    )
+}
 
-        let result = graph
-            .query(cypher)
-            .execute()
-            .await
-            .map_err(|e| GraphError::Query(e.to_string()))?;
+fn call_row(resolved: &ResolvedCall, callee: &str) -> Param {
+    let call = &resolved.call;
+    Param::map([
+        ("caller_id", Param::from(&call.caller_id)),
+        ("callee", Param::from(callee)),
+        ("line", Param::from(call.call_site_line)),
+        ("receiver", Param::from(call.receiver.as_deref().unwrap_or(""))),
+        ("confidence", Param::from(resolved.confidence)),
+        ("resolution", Param::from(resolved.resolution.as_str())),
+    ])
+}
 
-        // Collect results into a Vec
-        let rows: Vec<Vec<FalkorValue>> = result.data.collect();
-        Ok(rows)
+/// Label of a symbol known to the resolver
+fn label_of(resolver: &CallResolver, id: &str) -> Option<&'static str> {
+    resolver.get(id).map(|s| s.kind.label())
+}
+
+/// `:Label` when the label is known, so the id index can be used
+fn label_filter(label: Option<&str>) -> String {
+    label.map(|l| format!(":{}", l)).unwrap_or_default()
+}
+
+fn value_string(value: &FalkorValue) -> String {
+    match value {
+        FalkorValue::String(s) => s.clone(),
+        _ => String::new(),
     }
+}
 
-    /// Get the underlying graph handle for batch operations
-    pub fn graph(&self) -> Arc<Mutex<AsyncGraph>> {
-        self.graph.clone()
+/// Strings of a list value, such as a `collect()`
+fn string_values(value: &FalkorValue) -> Vec<String> {
+    match value {
+        FalkorValue::Array(values) => values
+            .iter()
+            .filter_map(|v| match v {
+                FalkorValue::String(s) => Some(s.clone()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
     }
+}
 
-    /// Get the graph name
-    pub fn name(&self) -> &str {
-        &self.graph_name
+fn value_u32(value: &FalkorValue) -> u32 {
+    match value {
+        FalkorValue::I64(n) => *n as u32,
+        _ => 0,
+    }
+}
+
+/// Build a list parameter from strings
+fn string_list<'a>(values: impl IntoIterator<Item = &'a String>) -> Param {
+    Param::List(values.into_iter().map(Param::from).collect())
+}
+
+/// Docstring entries as `name: description` strings, or the description
+/// alone for free text
+fn doc_entries(entries: &[DocEntry]) -> Param {
+    Param::List(
+        entries
+            .iter()
+            .map(|entry| match entry.name.as_str() {
+                "" => Param::from(&entry.description),
+                name => Param::from(format!("{}: {}", name, entry.description)),
+            })
+            .collect(),
+    )
+}
+
+/// Convert a `caller.id, callee name, receiver, line` row into a call
+fn row_to_call(row: &[FalkorValue]) -> Option<Call> {
+    match row {
+        [FalkorValue::String(caller_id), FalkorValue::String(callee_name), receiver, line] => {
+            Some(Call {
+                caller_id: caller_id.clone(),
+                callee_name: callee_name.clone(),
+                receiver: match receiver {
+                    FalkorValue::String(r) if !r.is_empty() => Some(r.clone()),
+                    _ => None,
+                },
+                call_site_line: match line {
+                    FalkorValue::I64(n) => *n as u32,
+                    _ => 0,
+                },
+            })
+        }
+        _ => None,
     }
 }
 
-/// Escape a string for use in a Cypher query
-fn escape_string(s: &str) -> String {
-    s.replace('\\', "\\\\")
-        .replace('\'', "\\'")
-        .replace('\n', "\\n")
-        .replace('\r', "\\r")
-        .replace('\t', "\\t")
+/// Row describing an import: the importing file, target, alias, items,
+/// line and resolution, with items and resolution as JSON, plus `extra`
+/// fields
+fn import_row<'a>(import: &Import, extra: impl IntoIterator<Item = (&'a str, Param)>) -> Param {
+    let items_json = serde_json::to_string(&import.items).unwrap_or_default();
+    let resolved_json = import
+        .resolved
+        .as_ref()
+        .and_then(|r| serde_json::to_string(r).ok())
+        .unwrap_or_default();
+    Param::map([
+        ("target", Param::from(&import.target)),
+        ("path", Param::from(import.source_file.to_string_lossy().to_string())),
+        ("alias", Param::from(import.alias.as_deref().unwrap_or(""))),
+        ("items", Param::from(items_json)),
+        ("line", Param::from(import.line)),
+        ("resolved", Param::from(resolved_json)),
+    ]
+    .into_iter()
+    .chain(extra))
+}
+
+/// Convert a `file path, target, alias, items, line, resolved` row into an
+/// import
+fn row_to_import(row: &[FalkorValue]) -> Option<Import> {
+    let [
+        FalkorValue::String(path),
+        FalkorValue::String(target),
+        alias,
+        items,
+        line,
+        resolved,
+    ] = row
+    else {
+        return None;
+    };
+    Some(Import {
+        source_file: PathBuf::from(path),
+        target: target.clone(),
+        alias: Some(value_string(alias)).filter(|a| !a.is_empty()),
+        items: serde_json::from_str(&value_string(items)).unwrap_or_default(),
+        line: value_u32(line),
+        resolved: serde_json::from_str(&value_string(resolved)).ok(),
+    })
+}
+
+/// Convert an `id, name, label, file_path` slice into a resolver entry
+fn symbol_entry(values: &[FalkorValue], parent_id: Option<String>) -> Option<SymbolEntry> {
+    match values {
+        [
+            FalkorValue::String(id),
+            FalkorValue::String(name),
+            FalkorValue::String(label),
+            FalkorValue::String(file_path),
+        ] => Some(SymbolEntry {
+            id: id.clone(),
+            name: name.clone(),
+            kind: SymbolKind::from_label(label)?,
+            file_path: PathBuf::from(file_path),
+            parent_id,
+        }),
+        _ => None,
+    }
 }
 
 /// Graph statistics
@@ -242,8 +1630,11 @@ pub struct GraphStats {
     pub function_count: usize,
     pub class_count: usize,
     pub struct_count: usize,
+    pub component_count: usize,
     pub calls_count: usize,
+    pub renders_count: usize,
     pub imports_count: usize,
+    pub namespace_count: usize,
 }
 
 impl std::fmt::Display for GraphStats {
@@ -253,8 +1644,60 @@ impl std::fmt::Display for GraphStats {
         writeln!(f, "  Functions: {}", self.function_count)?;
         writeln!(f, "  Classes:   {}", self.class_count)?;
         writeln!(f, "  Structs:   {}", self.struct_count)?;
+        writeln!(f, "  Components: {}", self.component_count)?;
         writeln!(f, "  Calls:     {}", self.calls_count)?;
+        writeln!(f, "  Renders:   {}", self.renders_count)?;
         writeln!(f, "  Imports:   {}", self.imports_count)?;
+        writeln!(f, "  Namespaces: {}", self.namespace_count)?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_rows_group_symbols_by_label() {
+        let symbol = |id: &str, kind, parent_id: Option<&str>| crate::Symbol {
+            id: id.to_string(),
+            name: id.to_string(),
+            qualified_name: id.to_string(),
+            namespace: None,
+            kind,
+            file_path: "a.py".into(),
+            start_line: 1,
+            end_line: 2,
+            byte_range: 0..0,
+            signature: None,
+            visibility: crate::Visibility::Public,
+            is_async: false,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: crate::Modifiers::default(),
+            docstring: None,
+            doc_sections: crate::DocSections::default(),
+            summary: None,
+            language: crate::Language::Python,
+            parent_id: parent_id.map(String::from),
+        };
+        let file_symbols = FileSymbols {
+            file_path: "a.py".into(),
+            symbols: vec![
+                symbol("Foo", SymbolKind::Class, None),
+                symbol("Foo.run", SymbolKind::Method, Some("Foo")),
+                symbol("Foo.stop", SymbolKind::Method, Some("Foo")),
+                symbol("main", SymbolKind::Function, None),
+            ],
+            ..Default::default()
+        };
+
+        let rows = NodeRows::from_files(&[file_symbols]);
+        assert_eq!(rows.files.len(), 1);
+        assert_eq!(rows.symbols["Class"].len(), 1);
+        assert_eq!(rows.symbols["Method"].len(), 2);
+        assert_eq!(rows.symbols["Function"].len(), 1);
+        assert_eq!(rows.contains[&("Class", "Method")].len(), 2);
+        assert_eq!(rows.contains.len(), 1);
+    }
+}
@@ -0,0 +1,338 @@
+//! Storage backend abstraction
+//!
+//! [`GraphStore`] covers everything the writer, the indexers and the CLI need
+//! from a graph: writing nodes and edges, replacing files, symbol lookup,
+//! call traversal and metadata. [`GraphSchema`] implements it on FalkorDB and
+//! [`EmbeddedStore`] keeps the graph in memory and persists it under
+//! `.kampus/`, so indexing works without any running services.
+
+use super::embedded::EmbeddedStore;
+use super::schema::{GraphSchema, GraphStats};
+use super::writer::PendingLinks;
+use super::GraphResult;
+use crate::resolver::{CallResolver, ResolvedCall, ResolvedReference, ResolvedRender};
+use crate::{Call, FileSymbols, Import, Inheritance, Language, SymbolKind, Visibility};
+use async_trait::async_trait;
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Symbol kinds returned by [`GraphStore::find_symbols`] when no kind is given
+pub const SEARCHABLE_KINDS: [SymbolKind; 17] = [
+    SymbolKind::Function,
+    SymbolKind::Class,
+    SymbolKind::Struct,
+    SymbolKind::Interface,
+    SymbolKind::Method,
+    SymbolKind::Trait,
+    SymbolKind::Enum,
+    SymbolKind::Component,
+    SymbolKind::Property,
+    SymbolKind::Union,
+    SymbolKind::TypeAlias,
+    SymbolKind::Macro,
+    SymbolKind::Module,
+    SymbolKind::Constant,
+    SymbolKind::Variable,
+    SymbolKind::Field,
+    SymbolKind::EnumVariant,
+];
+
+/// Operations shared by all graph storage backends
+#[async_trait]
+pub trait GraphStore: Send + Sync {
+    /// Prepare the store for writes (indexes, directories)
+    async fn initialize(&self) -> GraphResult<()>;
+
+    /// Drop all data
+    async fn clear(&self) -> GraphResult<()>;
+
+    /// Upsert file nodes and their symbols, including CONTAINS edges
+    async fn write_nodes(&self, files: &[FileSymbols]) -> GraphResult<()>;
+
+    /// Upsert the import edges of a set of files to the modules they name;
+    /// imports resolved to project files are linked by
+    /// [`GraphStore::write_import_links`]
+    async fn write_imports(&self, files: &[FileSymbols]) -> GraphResult<()>;
+
+    /// Upsert IMPORTS edges from files to the project files and symbols
+    /// their resolved imports point at
+    async fn write_import_links(&self, imports: &[Import]) -> GraphResult<()>;
+
+    /// Upsert CALLS edges; `resolver` knows the kind of every caller and
+    /// resolved callee
+    async fn write_calls(&self, calls: &[ResolvedCall], resolver: &CallResolver)
+        -> GraphResult<()>;
+
+    /// Upsert RENDERS edges between components
+    async fn write_renders(&self, renders: &[ResolvedRender]) -> GraphResult<()>;
+
+    /// Upsert DECORATED_BY and USES_TYPE edges; `resolver` knows the kind
+    /// of every referencing and referenced symbol
+    async fn write_references(
+        &self,
+        references: &[ResolvedReference],
+        resolver: &CallResolver,
+    ) -> GraphResult<()>;
+
+    /// Link children to every class-like symbol named by `parent_name`
+    async fn write_inheritance(
+        &self,
+        links: &[Inheritance],
+        resolver: &CallResolver,
+    ) -> GraphResult<()>;
+
+    /// Replace the IMPLEMENTS edges from Go types to the interfaces their
+    /// method sets satisfy, returning the number of edges
+    async fn write_implementations(&self) -> GraphResult<usize>;
+
+    /// Replace the DECLARES and DEFINES edges between C++ declarations and
+    /// their definitions, and attach out-of-line method definitions to their
+    /// class, returning the number of declaration/definition pairs
+    async fn write_definitions(&self) -> GraphResult<usize>;
+
+    /// Delete a file together with its symbols and their edges
+    async fn delete_file(&self, file_path: &str) -> GraphResult<()>;
+
+    /// Calls, inheritance, renders, references and imports from other files
+    /// that point into a file
+    async fn incoming_links(&self, file_path: &str) -> GraphResult<PendingLinks>;
+
+    /// Remove and return calls linked to placeholders for any of `names`
+    async fn take_unresolved_calls(&self, names: &BTreeSet<String>) -> GraphResult<Vec<Call>>;
+
+    /// Build a resolver holding the candidates for a set of pending links
+    async fn call_resolver(&self, links: &PendingLinks) -> GraphResult<CallResolver>;
+
+    /// Find symbols by name or qualified name pattern, ordered by qualified
+    /// name
+    async fn find_symbols(&self, query: &SymbolQuery) -> GraphResult<Vec<SymbolMatch>>;
+
+    /// Functions and methods calling, or called by, the functions or methods
+    /// named `function`, nearest first
+    async fn call_paths(
+        &self,
+        function: &str,
+        direction: CallDirection,
+        depth: u32,
+        limit: usize,
+    ) -> GraphResult<Vec<CallPathEntry>>;
+
+    /// All indexed files, ordered by path
+    async fn files(&self) -> GraphResult<Vec<FileEntry>>;
+
+    /// Node and edge counts
+    async fn stats(&self) -> GraphResult<GraphStats>;
+
+    async fn get_metadata(&self, key: &str) -> GraphResult<Option<String>>;
+
+    async fn set_metadata(&self, key: &str, value: &str) -> GraphResult<()>;
+
+    /// Persist pending changes; a no-op for backends that write through
+    async fn flush(&self) -> GraphResult<()> {
+        Ok(())
+    }
+}
+
+/// Which storage backend to use
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StoreBackend {
+    /// A FalkorDB server
+    #[default]
+    FalkorDb,
+    /// An in-process graph persisted under `.kampus/`
+    Embedded,
+}
+
+impl std::fmt::Display for StoreBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StoreBackend::FalkorDb => write!(f, "falkordb"),
+            StoreBackend::Embedded => write!(f, "embedded"),
+        }
+    }
+}
+
+impl std::str::FromStr for StoreBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "falkordb" | "falkor" => Ok(StoreBackend::FalkorDb),
+            "embedded" | "local" => Ok(StoreBackend::Embedded),
+            _ => Err(format!("Unknown backend: {}", s)),
+        }
+    }
+}
+
+/// Open a store
+///
+/// The embedded store lives in `<root>/.kampus/`; `db_uri` is only used by
+/// FalkorDB.
+pub async fn open_store(
+    backend: StoreBackend,
+    db_uri: Option<&str>,
+    graph_name: &str,
+    root: &Path,
+) -> GraphResult<Arc<dyn GraphStore>> {
+    match backend {
+        StoreBackend::FalkorDb => Ok(Arc::new(GraphSchema::connect(db_uri, graph_name).await?)),
+        StoreBackend::Embedded => Ok(Arc::new(EmbeddedStore::open(
+            root.join(EmbeddedStore::DIR),
+            graph_name,
+        )?)),
+    }
+}
+
+/// Parameters of a symbol search
+#[derive(Debug, Clone)]
+pub struct SymbolQuery {
+    pub pattern: NamePattern,
+    /// Restrict to one kind (None = any of [`SEARCHABLE_KINDS`])
+    pub kind: Option<SymbolKind>,
+    pub language: Option<Language>,
+    pub visibility: Option<Visibility>,
+    pub limit: usize,
+}
+
+/// A case-insensitive name pattern with `*` wildcards at either end
+///
+/// Patterns containing a scope separator (`::`, `.` or `/`) match qualified
+/// names, others match bare names.
+///
+/// Examples:
+///   "auth" -> exact match
+///   "*auth*" -> contains "auth"
+///   "auth*" -> starts with "auth"
+///   "*auth" -> ends with "auth"
+///   "crate::auth::*" -> qualified name starts with "crate::auth::"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NamePattern {
+    Any,
+    Exact(String),
+    Prefix(String),
+    Suffix(String),
+    Contains(String),
+}
+
+impl NamePattern {
+    pub fn parse(pattern: &str) -> Self {
+        let starts_with_wild = pattern.starts_with('*');
+        let ends_with_wild = pattern.ends_with('*');
+        let term = pattern.trim_matches('*').to_lowercase();
+
+        if term.is_empty() {
+            return NamePattern::Any;
+        }
+
+        match (starts_with_wild, ends_with_wild) {
+            (true, true) => NamePattern::Contains(term),
+            (true, false) => NamePattern::Suffix(term),
+            (false, true) => NamePattern::Prefix(term),
+            (false, false) => NamePattern::Exact(term),
+        }
+    }
+
+    /// The lowercased search term
+    pub fn term(&self) -> &str {
+        match self {
+            NamePattern::Any => "",
+            NamePattern::Exact(t)
+            | NamePattern::Prefix(t)
+            | NamePattern::Suffix(t)
+            | NamePattern::Contains(t) => t,
+        }
+    }
+
+    /// Whether the pattern applies to qualified rather than bare names
+    pub fn is_qualified(&self) -> bool {
+        let term = self.term();
+        term.contains("::") || term.contains('.') || term.contains('/') || term.contains('\\')
+    }
+
+    /// Match the pattern against a symbol's name or, for qualified
+    /// patterns, its qualified name
+    pub fn matches_symbol(&self, name: &str, qualified_name: &str) -> bool {
+        if self.is_qualified() {
+            self.matches(qualified_name)
+        } else {
+            self.matches(name)
+        }
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        match self {
+            NamePattern::Any => true,
+            NamePattern::Exact(t) => name == *t,
+            NamePattern::Prefix(t) => name.starts_with(t.as_str()),
+            NamePattern::Suffix(t) => name.ends_with(t.as_str()),
+            NamePattern::Contains(t) => name.contains(t.as_str()),
+        }
+    }
+}
+
+/// A symbol returned by a search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolMatch {
+    pub name: String,
+    pub qualified_name: String,
+    pub kind: SymbolKind,
+    pub file_path: String,
+    pub start_line: u32,
+}
+
+/// Which way to follow CALLS edges
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallDirection {
+    /// Functions that call the target
+    Callers,
+    /// Functions the target calls
+    Callees,
+}
+
+/// A function or method reached while following CALLS edges
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallPathEntry {
+    pub name: String,
+    pub file_path: String,
+    /// Number of calls between the target and this function
+    pub distance: u32,
+}
+
+/// An indexed file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEntry {
+    pub path: String,
+    pub language: String,
+    pub line_count: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_pattern() {
+        assert_eq!(NamePattern::parse("**"), NamePattern::Any);
+        assert!(NamePattern::parse("Auth").matches("auth"));
+        assert!(!NamePattern::parse("auth").matches("authenticate"));
+        assert!(NamePattern::parse("auth*").matches("Authenticate"));
+        assert!(NamePattern::parse("*user").matches("load_user"));
+        assert!(NamePattern::parse("*Prod*").matches("get_products"));
+        assert_eq!(NamePattern::parse("*Prod*").term(), "prod");
+
+        let qualified = NamePattern::parse("crate::auth::*");
+        assert!(qualified.is_qualified());
+        assert!(qualified.matches_symbol("login", "crate::auth::login"));
+        assert!(!qualified.matches_symbol("crate::auth::login", "crate::user::login"));
+        assert!(!NamePattern::parse("login").is_qualified());
+    }
+
+    #[test]
+    fn test_backend_from_str() {
+        assert_eq!("embedded".parse(), Ok(StoreBackend::Embedded));
+        assert_eq!("FalkorDB".parse(), Ok(StoreBackend::FalkorDb));
+        assert!("sqlite".parse::<StoreBackend>().is_err());
+    }
+}
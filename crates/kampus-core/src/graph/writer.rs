@@ -1,276 +1,366 @@
 //! Batch writer for graph database operations
-
-use super::{GraphError, GraphResult, GraphSchema};
-use crate::{Call, FileSymbols, Import, Inheritance, Symbol, SymbolKind};
-
-/// Batch size for database writes
-const BATCH_SIZE: usize = 1000;
-
-/// Writes symbols and relationships to the graph database in batches
+//!
+//! Drives a [`GraphStore`]: nodes for a batch of files are written first,
+//! then calls are resolved and linked in a single pass. Files can also be
+//! written in consecutive batches with [`GraphWriter::write_batch`], which
+//! spills their calls, inheritance, renders, references and resolved imports
+//! to a temporary file until [`GraphWriter::link_spilled`] links them one
+//! batch at a time.
+
+use super::{GraphError, GraphResult, GraphStore};
+use crate::resolver::{CallResolver, CallTarget, ResolvedCall, ResolvedReference, ResolvedRender};
+use crate::{Call, FileSymbols, Import, ImportTarget, Inheritance, Reference, Render};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Number of files written per batch
+pub const BATCH_SIZE: usize = 1000;
+
+/// Writes symbols and relationships to a graph store in batches
 pub struct GraphWriter {
-    schema: GraphSchema,
+    store: Arc<dyn GraphStore>,
 }
 
 impl GraphWriter {
-    pub fn new(schema: GraphSchema) -> Self {
-        Self { schema }
+    pub fn new(store: Arc<dyn GraphStore>) -> Self {
+        Self { store }
     }
 
-    /// Write a batch of file symbols to the database
+    /// Write a batch of file symbols to the store
+    ///
+    /// Nodes for every file are written before any calls or inheritance are
+    /// linked, so edges into files that come later in the batch are not lost.
     pub async fn write_files(&self, files: Vec<FileSymbols>) -> GraphResult<WriteStats> {
         let mut stats = WriteStats::default();
-
-        // Process in batches
-        for chunk in files.chunks(BATCH_SIZE) {
-            self.write_file_batch(chunk, &mut stats).await?;
+        let mut links = PendingLinks::default();
+        let mut resolver = CallResolver::new();
+
+        // Phase 1: file and symbol nodes
+        let mut files = files;
+        while !files.is_empty() {
+            let rest = files.split_off(files.len().min(BATCH_SIZE));
+            self.write_nodes(&files, &mut stats).await?;
+
+            let start = Instant::now();
+            for file_symbols in files {
+                resolver.add_file(&file_symbols);
+                links.add_file(file_symbols);
+            }
+            stats.resolve_time += start.elapsed();
+            files = rest;
         }
 
+        // Phase 2: cross-file links
+        self.link(&links, &resolver, &mut stats).await?;
+
         Ok(stats)
     }
 
-    /// Write a single file's symbols
-    pub async fn write_file(&self, file_symbols: &FileSymbols) -> GraphResult<()> {
-        let graph = self.schema.graph();
-        let mut graph = graph.lock().await;
-
-        let file_path = file_symbols.file_path.to_string_lossy().to_string();
-        let language = file_symbols
-            .language
-            .map(|l| l.to_string())
-            .unwrap_or_default();
-
-        // Create file node - embed values directly in query
-        let file_query = format!(
-            r#"
-            MERGE (f:File {{path: '{}'}})
-            SET f.language = '{}',
-                f.hash = '{}',
-                f.line_count = {},
-                f.last_indexed = timestamp()
-            "#,
-            escape_string(&file_path),
-            escape_string(&language),
-            escape_string(&file_symbols.content_hash),
-            file_symbols.line_count
-        );
-
-        graph
-            .query(&file_query)
-            .execute()
-            .await
-            .map_err(|e| GraphError::Query(e.to_string()))?;
+    /// Write the nodes of a batch of files and spill their relationships for
+    /// a later [`GraphWriter::link_spilled`]
+    ///
+    /// Nothing of the batch is kept in memory once this returns.
+    pub async fn write_batch(
+        &self,
+        files: Vec<FileSymbols>,
+        spill: &mut SpilledLinks,
+        stats: &mut WriteStats,
+    ) -> GraphResult<()> {
+        self.write_nodes(&files, stats).await?;
 
-        // Create symbol nodes
-        for symbol in &file_symbols.symbols {
-            self.write_symbol(&mut graph, symbol, &file_path).await?;
+        let mut links = PendingLinks::default();
+        for file_symbols in files {
+            links.add_file(file_symbols);
         }
+        spill.push(&links)
+    }
 
-        // Create import relationships
-        for import in &file_symbols.imports {
-            self.write_import(&mut graph, import).await?;
+    /// Link the relationships spilled by [`GraphWriter::write_batch`]
+    ///
+    /// Run this once every node is written. Each spilled batch is read back
+    /// and linked on its own with a resolver the store loads for just that
+    /// batch, so neither the pending links nor a symbol table for the whole
+    /// repository are held at once.
+    pub async fn link_spilled(
+        &self,
+        spill: SpilledLinks,
+        stats: &mut WriteStats,
+    ) -> GraphResult<()> {
+        for links in spill.into_batches()? {
+            let links = links?;
+            let start = Instant::now();
+            let resolver = self.store.call_resolver(&links).await?;
+            stats.resolve_time += start.elapsed();
+            self.link_relations(&links, &resolver, stats).await?;
         }
+        self.link_derived(stats).await
+    }
 
-        // Create call relationships
-        for call in &file_symbols.calls {
-            self.write_call(&mut graph, call).await?;
-        }
+    /// Write a single file's symbols and link its relationships
+    ///
+    /// Calls only resolve against the file's own symbols and inheritance only
+    /// against nodes already in the graph. Use [`GraphWriter::write_nodes`]
+    /// and [`GraphWriter::link`] when writing several files that may reference
+    /// each other.
+    pub async fn write_file(&self, file_symbols: &FileSymbols) -> GraphResult<WriteStats> {
+        let mut stats = WriteStats::default();
+        self.write_nodes(std::slice::from_ref(file_symbols), &mut stats)
+            .await?;
+
+        let mut resolver = CallResolver::new();
+        resolver.add_file(file_symbols);
+        let links = PendingLinks {
+            calls: file_symbols.calls.clone(),
+            inheritance: file_symbols.inheritance.clone(),
+            renders: file_symbols.renders.clone(),
+            references: file_symbols.references.clone(),
+            imports: project_imports(&file_symbols.imports).cloned().collect(),
+        };
+        self.link(&links, &resolver, &mut stats).await?;
 
-        // Create inheritance relationships
-        for inheritance in &file_symbols.inheritance {
-            self.write_inheritance(&mut graph, inheritance).await?;
-        }
+        Ok(stats)
+    }
 
-        Ok(())
+    /// Write the file node, its symbols and its imports without linking calls
+    /// or inheritance
+    pub async fn write_file_nodes(&self, file_symbols: &FileSymbols) -> GraphResult<WriteStats> {
+        let mut stats = WriteStats::default();
+        self.write_nodes(std::slice::from_ref(file_symbols), &mut stats)
+            .await?;
+        Ok(stats)
     }
 
-    async fn write_file_batch(
+    /// Write file nodes, symbol nodes with their CONTAINS edges, and imports
+    /// for a set of files without linking calls or inheritance
+    pub async fn write_nodes(
         &self,
         files: &[FileSymbols],
         stats: &mut WriteStats,
     ) -> GraphResult<()> {
+        let start = Instant::now();
+        self.store.write_nodes(files).await?;
+        stats.nodes_time += start.elapsed();
+
+        let start = Instant::now();
+        self.store.write_imports(files).await?;
+        stats.imports_time += start.elapsed();
+
         for file_symbols in files {
-            self.write_file(file_symbols).await?;
             stats.files_written += 1;
             stats.symbols_written += file_symbols.symbols.len();
             stats.imports_written += file_symbols.imports.len();
-            stats.calls_written += file_symbols.calls.len();
         }
+
         Ok(())
     }
 
-    async fn write_symbol(
+    /// Resolve calls, renders and references and link them, link imports to
+    /// the files and symbols they were resolved to, then link inheritance
+    /// against the symbols currently in the graph and recompute
+    /// the interfaces Go types implement and the definitions of C++
+    /// declarations
+    ///
+    /// Run this once all files of a batch have been written with
+    /// [`GraphWriter::write_nodes`]. The resolver must know every caller
+    /// and candidate callee or component referenced by `links`.
+    pub async fn link(
+        &self,
+        links: &PendingLinks,
+        resolver: &CallResolver,
+        stats: &mut WriteStats,
+    ) -> GraphResult<()> {
+        self.link_relations(links, resolver, stats).await?;
+        self.link_derived(stats).await
+    }
+
+    /// Resolve and link the calls, renders, references, imports and
+    /// inheritance in `links`
+    async fn link_relations(
         &self,
-        graph: &mut falkordb::AsyncGraph,
-        symbol: &Symbol,
-        file_path: &str,
+        links: &PendingLinks,
+        resolver: &CallResolver,
+        stats: &mut WriteStats,
     ) -> GraphResult<()> {
-        let label = symbol_kind_to_label(symbol.kind);
-        let visibility = format!("{:?}", symbol.visibility).to_lowercase();
-
-        let query = format!(
-            r#"
-            MERGE (s:{label} {{id: '{id}'}})
-            SET s.name = '{name}',
-                s.file_path = '{file_path}',
-                s.start_line = {start_line},
-                s.end_line = {end_line},
-                s.signature = '{signature}',
-                s.visibility = '{visibility}',
-                s.is_async = {is_async},
-                s.docstring = '{docstring}',
-                s.language = '{language}'
-            WITH s
-            MATCH (f:File {{path: '{file_path}'}})
-            MERGE (f)-[:CONTAINS]->(s)
-            "#,
-            label = label,
-            id = escape_string(&symbol.id),
-            name = escape_string(&symbol.name),
-            file_path = escape_string(file_path),
-            start_line = symbol.start_line,
-            end_line = symbol.end_line,
-            signature = escape_string(symbol.signature.as_deref().unwrap_or("")),
-            visibility = escape_string(&visibility),
-            is_async = symbol.is_async,
-            docstring = escape_string(symbol.docstring.as_deref().unwrap_or("")),
-            language = symbol.language.to_string()
+        debug!(
+            "Linking {} calls, {} inheritance relationships, {} renders and {} references \
+             against {} symbols",
+            links.calls.len(),
+            links.inheritance.len(),
+            links.renders.len(),
+            links.references.len(),
+            resolver.len()
         );
 
-        graph
-            .query(&query)
-            .execute()
-            .await
-            .map_err(|e| GraphError::Query(e.to_string()))?;
-
-        // If this symbol has a parent, create the CONTAINS relationship
-        if let Some(ref parent_id) = symbol.parent_id {
-            let parent_query = format!(
-                r#"
-                MATCH (p {{id: '{}'}})
-                MATCH (c {{id: '{}'}})
-                MERGE (p)-[:CONTAINS]->(c)
-                "#,
-                escape_string(parent_id),
-                escape_string(&symbol.id)
-            );
-
-            graph
-                .query(&parent_query)
-                .execute()
-                .await
-                .map_err(|e| GraphError::Query(e.to_string()))?;
+        let start = Instant::now();
+        let resolved: Vec<ResolvedCall> = links
+            .calls
+            .iter()
+            .flat_map(|call| resolver.resolve(call))
+            .collect();
+        stats.resolve_time += start.elapsed();
+
+        for call in &resolved {
+            match call.target {
+                CallTarget::Symbol(_) => stats.calls_written += 1,
+                CallTarget::Placeholder(_) => stats.calls_unresolved += 1,
+            }
         }
 
+        let start = Instant::now();
+        self.store.write_calls(&resolved, resolver).await?;
+        stats.calls_time += start.elapsed();
+
+        let start = Instant::now();
+        let renders: Vec<ResolvedRender> = links
+            .renders
+            .iter()
+            .flat_map(|render| resolver.resolve_render(render))
+            .collect();
+        stats.resolve_time += start.elapsed();
+        stats.renders_written += renders.len();
+
+        let start = Instant::now();
+        self.store.write_renders(&renders).await?;
+        stats.renders_time += start.elapsed();
+
+        let start = Instant::now();
+        let references: Vec<ResolvedReference> = links
+            .references
+            .iter()
+            .flat_map(|reference| resolver.resolve_reference(reference))
+            .collect();
+        stats.resolve_time += start.elapsed();
+        stats.references_written += references.len();
+
+        let start = Instant::now();
+        self.store.write_references(&references, resolver).await?;
+        stats.references_time += start.elapsed();
+
+        let start = Instant::now();
+        self.store.write_import_links(&links.imports).await?;
+        stats.imports_time += start.elapsed();
+
+        let start = Instant::now();
+        self.store
+            .write_inheritance(&links.inheritance, resolver)
+            .await?;
+        stats.inheritance_time += start.elapsed();
+
         Ok(())
     }
 
-    async fn write_import(
-        &self,
-        graph: &mut falkordb::AsyncGraph,
-        import: &Import,
-    ) -> GraphResult<()> {
-        let source_path = import.source_file.to_string_lossy();
-        let items_json = serde_json::to_string(&import.items).unwrap_or_default();
-
-        // Create a Module node for the import target and link to it
-        let query = format!(
-            r#"
-            MERGE (m:Module {{name: '{}'}})
-            SET m.is_external = true
-            WITH m
-            MATCH (f:File {{path: '{}'}})
-            MERGE (f)-[r:IMPORTS]->(m)
-            SET r.alias = '{}',
-                r.items = '{}',
-                r.line = {}
-            "#,
-            escape_string(&import.target),
-            escape_string(&source_path),
-            escape_string(import.alias.as_deref().unwrap_or("")),
-            escape_string(&items_json),
-            import.line
-        );
+    /// Recompute the interfaces Go types implement and the definitions of
+    /// C++ declarations over the whole graph
+    async fn link_derived(&self, stats: &mut WriteStats) -> GraphResult<()> {
+        let start = Instant::now();
+        stats.implementations_written = self.store.write_implementations().await?;
+        stats.implementations_time += start.elapsed();
 
-        graph
-            .query(&query)
-            .execute()
-            .await
-            .map_err(|e| GraphError::Query(e.to_string()))?;
+        let start = Instant::now();
+        stats.definitions_written = self.store.write_definitions().await?;
+        stats.definitions_time += start.elapsed();
 
         Ok(())
     }
 
-    async fn write_call(
-        &self,
-        graph: &mut falkordb::AsyncGraph,
-        call: &Call,
-    ) -> GraphResult<()> {
-        // Try to link to an existing function by name
-        let query = format!(
-            r#"
-            MATCH (caller {{id: '{}'}})
-            OPTIONAL MATCH (callee:Function {{name: '{}'}})
-            FOREACH (_ IN CASE WHEN callee IS NOT NULL THEN [1] ELSE [] END |
-                MERGE (caller)-[r:CALLS]->(callee)
-                SET r.call_site_line = {}
-            )
-            "#,
-            escape_string(&call.caller_id),
-            escape_string(&call.callee_name),
-            call.call_site_line
-        );
+    /// Get the store for direct operations
+    pub fn store(&self) -> &Arc<dyn GraphStore> {
+        &self.store
+    }
+}
 
-        graph
-            .query(&query)
-            .execute()
-            .await
-            .map_err(|e| GraphError::Query(e.to_string()))?;
+/// Calls, inheritance, render and reference relationships and resolved
+/// imports waiting to be linked
+///
+/// Collected while file nodes are written and resolved afterwards, once
+/// every callee, parent, component and imported file has a node to link to.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PendingLinks {
+    pub calls: Vec<Call>,
+    pub inheritance: Vec<Inheritance>,
+    pub renders: Vec<Render>,
+    pub references: Vec<Reference>,
+    /// Imports resolved to project files
+    pub imports: Vec<Import>,
+}
 
-        Ok(())
+impl PendingLinks {
+    /// Take the calls, inheritance, render and reference records and the
+    /// resolved imports of a parsed file
+    pub fn add_file(&mut self, file_symbols: FileSymbols) {
+        self.calls.extend(file_symbols.calls);
+        self.inheritance.extend(file_symbols.inheritance);
+        self.renders.extend(file_symbols.renders);
+        self.references.extend(file_symbols.references);
+        self.imports
+            .extend(project_imports(&file_symbols.imports).cloned());
     }
 
-    async fn write_inheritance(
-        &self,
-        graph: &mut falkordb::AsyncGraph,
-        inheritance: &Inheritance,
-    ) -> GraphResult<()> {
-        // Create INHERITS relationship
-        let query = format!(
-            r#"
-            MATCH (child {{id: '{}'}})
-            OPTIONAL MATCH (parent)
-            WHERE (parent:Class OR parent:Struct OR parent:Interface OR parent:Trait)
-              AND parent.name = '{}'
-            FOREACH (_ IN CASE WHEN parent IS NOT NULL THEN [1] ELSE [] END |
-                MERGE (child)-[:INHERITS]->(parent)
-            )
-            "#,
-            escape_string(&inheritance.child_id),
-            escape_string(&inheritance.parent_name)
-        );
+    /// Merge another set of pending links into this one
+    pub fn extend(&mut self, other: PendingLinks) {
+        self.calls.extend(other.calls);
+        self.inheritance.extend(other.inheritance);
+        self.renders.extend(other.renders);
+        self.references.extend(other.references);
+        self.imports.extend(other.imports);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+            && self.inheritance.is_empty()
+            && self.renders.is_empty()
+            && self.references.is_empty()
+            && self.imports.is_empty()
+    }
+}
 
-        graph
-            .query(&query)
-            .execute()
-            .await
-            .map_err(|e| GraphError::Query(e.to_string()))?;
+/// Pending links spilled to an anonymous temporary file, one line of JSON
+/// per batch
+///
+/// The file is removed by the OS once this is dropped.
+pub struct SpilledLinks {
+    file: BufWriter<File>,
+}
+
+impl SpilledLinks {
+    pub fn new() -> GraphResult<Self> {
+        Ok(Self {
+            file: BufWriter::new(tempfile::tempfile()?),
+        })
+    }
 
+    /// Append a batch of links
+    pub fn push(&mut self, links: &PendingLinks) -> GraphResult<()> {
+        if links.is_empty() {
+            return Ok(());
+        }
+        serde_json::to_writer(&mut self.file, links)
+            .map_err(|e| GraphError::Serialization(e.to_string()))?;
+        self.file.write_all(b"\n")?;
         Ok(())
     }
 
-    /// Get the schema for direct operations
-    pub fn schema(&self) -> &GraphSchema {
-        &self.schema
+    /// Read the batches back in the order they were pushed
+    pub fn into_batches(
+        self,
+    ) -> GraphResult<impl Iterator<Item = GraphResult<PendingLinks>>> {
+        let mut file = self.file.into_inner().map_err(|e| e.into_error())?;
+        file.seek(SeekFrom::Start(0))?;
+        Ok(BufReader::new(file).lines().map(|line| {
+            serde_json::from_str(&line?).map_err(|e| GraphError::Serialization(e.to_string()))
+        }))
     }
 }
 
-/// Escape a string for use in a Cypher query
-fn escape_string(s: &str) -> String {
-    s.replace('\\', "\\\\")
-        .replace('\'', "\\'")
-        .replace('\n', "\\n")
-        .replace('\r', "\\r")
-        .replace('\t', "\\t")
+/// Imports resolved to files in the project
+fn project_imports(imports: &[Import]) -> impl Iterator<Item = &Import> {
+    imports
+        .iter()
+        .filter(|i| matches!(i.resolved, Some(ImportTarget::Project { .. })))
 }
 
 /// Statistics from write operations
@@ -280,6 +370,29 @@ pub struct WriteStats {
     pub symbols_written: usize,
     pub imports_written: usize,
     pub calls_written: usize,
+    pub calls_unresolved: usize,
+    pub renders_written: usize,
+    pub references_written: usize,
+    pub implementations_written: usize,
+    pub definitions_written: usize,
+    /// Time spent writing file and symbol nodes
+    pub nodes_time: Duration,
+    /// Time spent writing import edges
+    pub imports_time: Duration,
+    /// Time spent resolving call targets
+    pub resolve_time: Duration,
+    /// Time spent writing call edges
+    pub calls_time: Duration,
+    /// Time spent writing inheritance edges
+    pub inheritance_time: Duration,
+    /// Time spent writing render edges
+    pub renders_time: Duration,
+    /// Time spent writing decorator and type reference edges
+    pub references_time: Duration,
+    /// Time spent matching Go types to interfaces
+    pub implementations_time: Duration,
+    /// Time spent pairing C++ declarations with their definitions
+    pub definitions_time: Duration,
 }
 
 impl std::fmt::Display for WriteStats {
@@ -289,22 +402,94 @@ impl std::fmt::Display for WriteStats {
         writeln!(f, "  Symbols: {}", self.symbols_written)?;
         writeln!(f, "  Imports: {}", self.imports_written)?;
         writeln!(f, "  Calls:   {}", self.calls_written)?;
+        writeln!(f, "  Unresolved: {}", self.calls_unresolved)?;
+        writeln!(f, "  Renders: {}", self.renders_written)?;
+        writeln!(f, "  References: {}", self.references_written)?;
+        writeln!(f, "  Implementations: {}", self.implementations_written)?;
+        writeln!(f, "  Definitions: {}", self.definitions_written)?;
+        writeln!(f, "Write Timings:")?;
+        writeln!(f, "  Nodes:       {:.2?}", self.nodes_time)?;
+        writeln!(f, "  Imports:     {:.2?}", self.imports_time)?;
+        writeln!(f, "  Resolution:  {:.2?}", self.resolve_time)?;
+        writeln!(f, "  Calls:       {:.2?}", self.calls_time)?;
+        writeln!(f, "  Inheritance: {:.2?}", self.inheritance_time)?;
+        writeln!(f, "  Renders:     {:.2?}", self.renders_time)?;
+        writeln!(f, "  References:  {:.2?}", self.references_time)?;
+        writeln!(f, "  Implementations: {:.2?}", self.implementations_time)?;
+        writeln!(f, "  Definitions: {:.2?}", self.definitions_time)?;
         Ok(())
     }
 }
 
-/// Convert SymbolKind to graph node label
-fn symbol_kind_to_label(kind: SymbolKind) -> &'static str {
-    match kind {
-        SymbolKind::Function => "Function",
-        SymbolKind::Class => "Class",
-        SymbolKind::Struct => "Struct",
-        SymbolKind::Interface => "Interface",
-        SymbolKind::Module => "Module",
-        SymbolKind::Method => "Method",
-        SymbolKind::Trait => "Trait",
-        SymbolKind::Enum => "Enum",
-        SymbolKind::Constant => "Constant",
-        SymbolKind::Variable => "Variable",
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_links_collects_file_relationships() {
+        let file_symbols = FileSymbols {
+            calls: vec![Call {
+                caller_id: "a.rs:main:1".to_string(),
+                callee_name: "helper".to_string(),
+                receiver: None,
+                call_site_line: 2,
+            }],
+            inheritance: vec![Inheritance {
+                child_id: "a.rs:Foo:5".to_string(),
+                parent_name: "Bar".to_string(),
+            }],
+            renders: vec![Render {
+                component_id: "App.tsx::App".to_string(),
+                element_name: "Header".to_string(),
+                receiver: None,
+                line: 3,
+            }],
+            references: vec![Reference {
+                kind: crate::ReferenceKind::UsesType,
+                source_id: "a.py::save".to_string(),
+                target_name: "User".to_string(),
+                receiver: None,
+                line: 7,
+            }],
+            ..Default::default()
+        };
+
+        let mut links = PendingLinks::default();
+        assert!(links.is_empty());
+
+        links.add_file(file_symbols);
+        assert_eq!(links.calls.len(), 1);
+        assert_eq!(links.inheritance.len(), 1);
+        assert_eq!(links.renders.len(), 1);
+        assert_eq!(links.references.len(), 1);
+    }
+
+    #[test]
+    fn test_spilled_links_read_back_in_order() {
+        let mut spill = SpilledLinks::new().unwrap();
+        for name in ["first", "second"] {
+            let links = PendingLinks {
+                calls: vec![Call {
+                    caller_id: "a.rs::main".to_string(),
+                    callee_name: name.to_string(),
+                    receiver: None,
+                    call_site_line: 2,
+                }],
+                ..Default::default()
+            };
+            spill.push(&links).unwrap();
+        }
+        spill.push(&PendingLinks::default()).unwrap();
+
+        let batches: Vec<PendingLinks> = spill
+            .into_batches()
+            .unwrap()
+            .collect::<GraphResult<_>>()
+            .unwrap();
+        let callees: Vec<&str> = batches
+            .iter()
+            .flat_map(|b| b.calls.iter().map(|c| c.callee_name.as_str()))
+            .collect();
+        assert_eq!(callees, vec!["first", "second"]);
     }
 }
@@ -1,20 +1,33 @@
 //! Full indexing pipeline
 //!
 //! Performs a complete index of the codebase using parallel processing.
+//! Files are parsed on a rayon pool and handed to the writer over a bounded
+//! channel, so writes overlap with parsing and at most `queue_capacity`
+//! parsed files wait in memory. Parsers block while the queue is full.
+//!
+//! The calls, inheritance, renders, references and resolved imports of each
+//! written batch are spilled to a temporary file. Once every node is written
+//! they are read back and linked a batch at a time against symbols loaded
+//! from the store, so memory stays bounded by the batch size rather than the
+//! size of the repository.
 
 use super::{IndexResult, IndexingStats};
 use crate::crawler::{Crawler, CrawlerConfig, SourceFile};
-use crate::graph::writer::GraphWriter;
-use crate::graph::GraphSchema;
+use crate::graph::writer::{GraphWriter, SpilledLinks, BATCH_SIZE};
+use crate::graph::{open_store, StoreBackend};
 use crate::parser::extractor::SymbolExtractor;
 use crate::parser::pool::ParserPool;
+use crate::parser::query::Extractors;
+use crate::resolver::imports::ImportResolver;
 use crate::{FileSymbols, Language};
 use rayon::prelude::*;
 use std::io::{self, Write};
 use std::path::Path;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::{mpsc, Arc};
-use std::time::Instant;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
 use tracing::{debug, warn};
 
 /// Configuration for full indexing
@@ -28,6 +41,10 @@ pub struct FullIndexConfig {
     pub threads: usize,
     /// Whether to clear existing data first
     pub clear_existing: bool,
+    /// Maximum number of parsed files waiting to be written
+    pub queue_capacity: usize,
+    /// Storage backend
+    pub backend: StoreBackend,
     /// FalkorDB connection URI
     pub db_uri: Option<String>,
     /// Graph name
@@ -41,6 +58,8 @@ impl Default for FullIndexConfig {
             languages: None,
             threads: num_cpus::get(),
             clear_existing: true,
+            queue_capacity: 2 * BATCH_SIZE,
+            backend: StoreBackend::default(),
             db_uri: None,
             graph_name: "kampus".to_string(),
         }
@@ -65,19 +84,21 @@ impl FullIndexer {
         println!("Indexing {:?}", self.config.root);
         println!("Using {} threads\n", self.config.threads);
 
-        // Connect to database
-        print!("Connecting to database...");
+        // Open the graph store
+        print!("Opening {} store...", self.config.backend);
         let _ = io::stdout().flush();
         let connect_start = Instant::now();
 
-        let schema = GraphSchema::connect(
+        let store = open_store(
+            self.config.backend,
             self.config.db_uri.as_deref(),
             &self.config.graph_name,
+            &self.config.root,
         )
         .await?;
 
         // Initialize schema (create indexes)
-        schema.initialize().await?;
+        store.initialize().await?;
         println!(" connected in {:.2?}", connect_start.elapsed());
 
         // Clear existing data if requested
@@ -85,10 +106,14 @@ impl FullIndexer {
             print!("Clearing existing data...");
             let _ = io::stdout().flush();
             let clear_start = Instant::now();
-            schema.clear().await?;
+            store.clear().await?;
             println!(" done in {:.2?}", clear_start.elapsed());
         }
 
+        // Runtime grammars must be registered before files are discovered
+        let extractors = Arc::new(Extractors::load(&self.config.root)?);
+        let imports = Arc::new(ImportResolver::load(&self.config.root));
+
         // Discover files
         print!("Discovering files...");
         let _ = io::stdout().flush();
@@ -110,14 +135,15 @@ impl FullIndexer {
             discover_start.elapsed()
         );
 
-        // Parse files in parallel
-        let parse_start = Instant::now();
+        // Parse in parallel and write as results arrive
+        let pipeline_start = Instant::now();
         let total_files = files.len();
-        print!("Parsing files... 0/{} (0%)", total_files);
+        print!("Indexing files... 0/{} (0%)", total_files);
         let _ = io::stdout().flush();
 
-        let (tx, rx) = mpsc::channel::<FileSymbols>();
-        let failed_count = Arc::new(AtomicUsize::new(0));
+        let capacity = self.config.queue_capacity.max(1);
+        let (tx, mut rx) = mpsc::channel::<FileSymbols>(capacity);
+        let counters = Arc::new(ParseCounters::default());
 
         // Configure rayon thread pool
         let pool = rayon::ThreadPoolBuilder::new()
@@ -126,80 +152,124 @@ impl FullIndexer {
             .unwrap();
 
         let root = self.config.root.clone();
-        let failed_count_clone = Arc::clone(&failed_count);
+        let parse_counters = Arc::clone(&counters);
 
         pool.spawn(move || {
-            files.par_iter().for_each_with(tx, |tx, file| {
-                match parse_file(file, &root) {
-                    Ok(symbols) => {
-                        let _ = tx.send(symbols);
-                    }
+            let counters = parse_counters;
+            // Stops early once the writer has gone away
+            let _ = files.par_iter().try_for_each_with(tx.clone(), |tx, file| {
+                let symbols = match parse_file(file, &root, &extractors, &imports) {
+                    Ok(symbols) => symbols,
                     Err(e) => {
                         warn!("Failed to parse {:?}: {}", file.path, e);
-                        failed_count_clone.fetch_add(1, Ordering::Relaxed);
+                        counters.failed.fetch_add(1, Ordering::Relaxed);
+                        return Ok(());
                     }
+                };
+                counters.parsed.fetch_add(1, Ordering::Relaxed);
+
+                match tx.try_send(symbols) {
+                    Ok(()) => Ok(()),
+                    Err(TrySendError::Full(symbols)) => {
+                        // The writer is behind; wait for room in the queue
+                        let wait_start = Instant::now();
+                        let sent = tx.blocking_send(symbols).map_err(|_| ());
+                        counters.waits.fetch_add(1, Ordering::Relaxed);
+                        counters
+                            .wait_nanos
+                            .fetch_add(nanos(wait_start.elapsed()), Ordering::Relaxed);
+                        sent
+                    }
+                    Err(TrySendError::Closed(_)) => Err(()),
                 }
             });
+            counters
+                .parse_nanos
+                .store(nanos(pipeline_start.elapsed()), Ordering::Relaxed);
+            // Closing the channel only now lets the writer see the parse time
+            drop(tx);
         });
 
-        // Collect parsed results with progress updates
-        let mut file_symbols: Vec<FileSymbols> = Vec::with_capacity(total_files);
+        let writer = GraphWriter::new(store);
+        let mut spill = SpilledLinks::new()?;
+        let mut batch: Vec<FileSymbols> = Vec::with_capacity(BATCH_SIZE);
+        let mut received = 0;
         let mut last_update = Instant::now();
 
-        for symbols in rx {
-            file_symbols.push(symbols);
+        while let Some(symbols) = rx.recv().await {
+            received += 1;
+            stats.peak_queue_depth = stats.peak_queue_depth.max(rx.len() + 1);
+            batch.push(symbols);
+
+            if batch.len() >= BATCH_SIZE {
+                let files = std::mem::replace(&mut batch, Vec::with_capacity(BATCH_SIZE));
+                writer
+                    .write_batch(files, &mut spill, &mut stats.write_stats)
+                    .await?;
+            }
 
             // Update progress every 100ms
             if last_update.elapsed().as_millis() >= 100 {
-                let done = file_symbols.len();
-                let percent = (done * 100) / total_files.max(1);
-                let elapsed = parse_start.elapsed().as_secs_f64();
-                let rate = done as f64 / elapsed.max(0.001);
-                let remaining = (total_files - done) as f64 / rate.max(0.001);
+                let parsed = counters.parsed.load(Ordering::Relaxed);
+                let written = stats.write_stats.files_written;
+                let percent = (received * 100) / total_files.max(1);
+                let elapsed = pipeline_start.elapsed().as_secs_f64();
+                let rate = received as f64 / elapsed.max(0.001);
+                let remaining = (total_files - received) as f64 / rate.max(0.001);
 
                 print!(
-                    "\rParsing files... {}/{} ({}%) - {:.1} files/sec, ~{:.1}s remaining    ",
-                    done, total_files, percent, rate, remaining
+                    "\rIndexing files... {}/{} ({}%) - {} parsed, {} written, {:.1} files/sec, ~{:.1}s remaining    ",
+                    received, total_files, percent, parsed, written, rate, remaining
                 );
                 let _ = io::stdout().flush();
                 last_update = Instant::now();
             }
         }
 
-        // Final progress update
-        let parse_elapsed = parse_start.elapsed();
-        stats.files_failed = failed_count.load(Ordering::Relaxed);
+        if !batch.is_empty() {
+            writer
+                .write_batch(batch, &mut spill, &mut stats.write_stats)
+                .await?;
+        }
+
+        stats.files_parsed = received;
+        stats.files_failed = counters.failed.load(Ordering::Relaxed);
+        stats.parse_time = Duration::from_nanos(counters.parse_nanos.load(Ordering::Relaxed));
+        stats.backpressure_waits = counters.waits.load(Ordering::Relaxed);
+        stats.backpressure_time = Duration::from_nanos(counters.wait_nanos.load(Ordering::Relaxed));
         println!(
-            "\rParsing files... {}/{} (100%) - completed in {:.2?}                    ",
-            file_symbols.len(), total_files, parse_elapsed
+            "\rIndexing files... {}/{} (100%) - parsed in {:.2?}, written in {:.2?}                    ",
+            received,
+            total_files,
+            stats.parse_time,
+            pipeline_start.elapsed()
         );
 
-        stats.files_parsed = file_symbols.len();
-
-        // Write to database
-        print!("Writing to database...");
+        // Link calls and inheritance now that every node exists
+        print!("Linking relationships...");
         let _ = io::stdout().flush();
-        let write_start = Instant::now();
-
-        let writer = GraphWriter::new(schema);
-        stats.write_stats = writer.write_files(file_symbols).await?;
+        let link_start = Instant::now();
+        writer
+            .link_spilled(spill, &mut stats.write_stats)
+            .await?;
 
         println!(
-            " {} symbols in {:.2?}",
-            stats.write_stats.symbols_written,
-            write_start.elapsed()
+            " {} calls in {:.2?}",
+            stats.write_stats.calls_written,
+            link_start.elapsed()
         );
 
         // Store the current git commit if in a git repo
-        if let Ok(git) = crate::git::GitDiff::open(&self.config.root) {
-            if let Ok(commit) = git.head_commit() {
-                writer
-                    .schema()
-                    .set_metadata("last_indexed_commit", &commit)
-                    .await?;
-                println!("Stored commit: {}", &commit[..12.min(commit.len())]);
-            }
+        if let Ok(git) = crate::git::GitDiff::open(&self.config.root)
+            && let Ok(commit) = git.head_commit()
+        {
+            writer
+                .store()
+                .set_metadata("last_indexed_commit", &commit)
+                .await?;
+            println!("Stored commit: {}", &commit[..12.min(commit.len())]);
         }
+        writer.store().flush().await?;
 
         stats.duration = start.elapsed();
         println!("\nIndexing complete in {:.2?}", stats.duration);
@@ -208,15 +278,36 @@ impl FullIndexer {
     }
 }
 
+/// Counters shared by the parse workers
+#[derive(Default)]
+struct ParseCounters {
+    parsed: AtomicUsize,
+    failed: AtomicUsize,
+    /// Sends that found the queue full
+    waits: AtomicUsize,
+    wait_nanos: AtomicU64,
+    /// Time until the last file was parsed
+    parse_nanos: AtomicU64,
+}
+
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
 /// Parse a single file and extract symbols
-fn parse_file(file: &SourceFile, root: &Path) -> IndexResult<FileSymbols> {
+fn parse_file(
+    file: &SourceFile,
+    root: &Path,
+    extractors: &Extractors,
+    imports: &ImportResolver,
+) -> IndexResult<FileSymbols> {
     debug!("Parsing {:?}", file.path);
 
     // Read file contents
     let source = std::fs::read(&file.path)?;
 
     // Parse with tree-sitter
-    let tree = ParserPool::parse(file.language, &source)?;
+    let tree = ParserPool::parse_file(file.language, &file.path, &source)?;
 
     // Extract symbols
     let relative_path = file
@@ -224,7 +315,14 @@ fn parse_file(file: &SourceFile, root: &Path) -> IndexResult<FileSymbols> {
         .strip_prefix(root)
         .unwrap_or(&file.path);
 
-    let file_symbols = SymbolExtractor::extract(&tree, &source, relative_path, file.language)?;
+    let mut file_symbols = SymbolExtractor::extract_with(
+        extractors,
+        &tree,
+        &source,
+        relative_path,
+        file.language,
+    )?;
+    imports.resolve(&mut file_symbols);
 
     Ok(file_symbols)
 }
@@ -238,5 +336,77 @@ mod tests {
         let config = FullIndexConfig::default();
         assert!(config.clear_existing);
         assert!(config.languages.is_none());
+        assert!(config.queue_capacity >= BATCH_SIZE);
+    }
+
+    #[tokio::test]
+    async fn test_parsers_wait_on_a_full_queue() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..200 {
+            let source = format!("def f{}():\n    return g{}()\n\ndef g{}():\n    pass\n", i, i, i);
+            std::fs::write(dir.path().join(format!("m{}.py", i)), source).unwrap();
+        }
+
+        let indexer = FullIndexer::new(FullIndexConfig {
+            root: dir.path().to_path_buf(),
+            threads: 4,
+            queue_capacity: 1,
+            backend: StoreBackend::Embedded,
+            ..Default::default()
+        });
+        let stats = indexer.run().await.unwrap();
+
+        assert_eq!(stats.files_parsed, 200);
+        assert_eq!(stats.write_stats.files_written, 200);
+        assert_eq!(stats.write_stats.calls_written, 200);
+        assert!(stats.backpressure_waits > 0);
+    }
+
+    #[tokio::test]
+    async fn test_calls_link_across_batches() {
+        let dir = tempfile::tempdir().unwrap();
+        let count = BATCH_SIZE + 1;
+        for i in 0..count {
+            let next = (i + 1) % count;
+            let source = format!(
+                "from m{} import g{}\n\ndef f{}():\n    return g{}()\n\ndef g{}():\n    pass\n",
+                next, next, i, next, i
+            );
+            std::fs::write(dir.path().join(format!("m{}.py", i)), source).unwrap();
+        }
+
+        let indexer = FullIndexer::new(FullIndexConfig {
+            root: dir.path().to_path_buf(),
+            threads: 4,
+            backend: StoreBackend::Embedded,
+            ..Default::default()
+        });
+        let stats = indexer.run().await.unwrap();
+
+        assert_eq!(stats.write_stats.files_written, count);
+        assert_eq!(stats.write_stats.calls_written, count);
+        assert_eq!(stats.write_stats.calls_unresolved, 0);
+    }
+
+    #[tokio::test]
+    async fn test_prototype_header_pairs_with_cpp_source() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("util.h"), "int add(int a, int b);\n").unwrap();
+        std::fs::write(
+            dir.path().join("util.cpp"),
+            "#include \"util.h\"\n\nint add(int a, int b) { return a + b; }\n",
+        )
+        .unwrap();
+
+        let indexer = FullIndexer::new(FullIndexConfig {
+            root: dir.path().to_path_buf(),
+            threads: 1,
+            backend: StoreBackend::Embedded,
+            ..Default::default()
+        });
+        let stats = indexer.run().await.unwrap();
+
+        assert_eq!(stats.write_stats.symbols_written, 2);
+        assert_eq!(stats.write_stats.definitions_written, 1);
     }
 }
@@ -3,12 +3,16 @@
 //! Only re-indexes files that have changed since the last index.
 
 use super::{IndexError, IndexResult, IndexingStats};
+use crate::crawler::{Crawler, CrawlerConfig};
 use crate::git::{ChangeKind, ChangedFile, GitDiff};
-use crate::graph::writer::GraphWriter;
-use crate::graph::GraphSchema;
+use crate::graph::writer::{GraphWriter, PendingLinks};
+use crate::graph::{open_store, StoreBackend};
 use crate::parser::extractor::SymbolExtractor;
+use crate::parser::query::Extractors;
 use crate::parser::pool::ParserPool;
-use crate::Language;
+use crate::resolver::imports::ImportResolver;
+use crate::{FileSymbols, Language};
+use std::collections::{BTreeSet, HashSet};
 use std::io::{self, Write};
 use std::path::Path;
 use std::time::Instant;
@@ -25,6 +29,8 @@ pub struct IncrementalConfig {
     pub since: Option<String>,
     /// Dry run (don't write to database)
     pub dry_run: bool,
+    /// Storage backend
+    pub backend: StoreBackend,
     /// FalkorDB connection URI
     pub db_uri: Option<String>,
     /// Graph name
@@ -38,6 +44,7 @@ impl Default for IncrementalConfig {
             languages: None,
             since: None,
             dry_run: false,
+            backend: StoreBackend::default(),
             db_uri: None,
             graph_name: "kampus".to_string(),
         }
@@ -64,14 +71,16 @@ impl IncrementalIndexer {
         // Open git repository
         let git = GitDiff::open(&self.config.root)?;
 
-        // Connect to database
-        print!("Connecting to database...");
+        // Open the graph store
+        print!("Opening {} store...", self.config.backend);
         let _ = io::stdout().flush();
         let connect_start = Instant::now();
 
-        let schema = GraphSchema::connect(
+        let store = open_store(
+            self.config.backend,
             self.config.db_uri.as_deref(),
             &self.config.graph_name,
+            &self.config.root,
         )
         .await?;
 
@@ -82,7 +91,7 @@ impl IncrementalIndexer {
             Some(ref_name) => ref_name.clone(),
             None => {
                 // Get last indexed commit from database
-                schema
+                store
                     .get_metadata("last_indexed_commit")
                     .await?
                     .ok_or_else(|| {
@@ -95,6 +104,10 @@ impl IncrementalIndexer {
 
         println!("Comparing against: {}...", &since[..12.min(since.len())]);
 
+        // Runtime grammars must be registered before changes are filtered
+        let extractors = Extractors::load(&self.config.root)?;
+        let imports = ImportResolver::load(&self.config.root);
+
         // Get changed files
         let changes = git.changes_since(&since)?;
         let changes = self.filter_changes(changes);
@@ -104,18 +117,22 @@ impl IncrementalIndexer {
 
         if self.config.dry_run {
             println!("Dry run - not writing to database:");
-            for change in &changes {
+            for (change, _) in &changes {
                 println!("  {:?}: {:?}", change.kind, change.path);
             }
             stats.duration = start.elapsed();
             return Ok(stats);
         }
 
-        // Process changes
-        let writer = GraphWriter::new(schema);
+        // Process changes. Nodes are written per file; calls and inheritance
+        // are linked in one pass at the end, together with the edges from
+        // unchanged files that pointed into the files being replaced.
+        let writer = GraphWriter::new(store);
         let total_changes = changes.len();
+        let mut links = PendingLinks::default();
+        let mut defined_names = BTreeSet::new();
 
-        for (i, change) in changes.into_iter().enumerate() {
+        for (i, (change, language)) in changes.into_iter().enumerate() {
             print!(
                 "\rProcessing {}/{} ({:.0}%)... ",
                 i + 1,
@@ -126,19 +143,28 @@ impl IncrementalIndexer {
 
             match change.kind {
                 ChangeKind::Deleted => {
+                    // Edges into the file are relinked, to placeholders if
+                    // nothing else defines their targets
                     let path_str = change.path.to_string_lossy();
-                    writer.schema().delete_file(&path_str).await?;
+                    links.extend(writer.store().incoming_links(&path_str).await?);
+                    writer.store().delete_file(&path_str).await?;
                 }
                 ChangeKind::Renamed => {
                     // Delete old path, add new path
                     if let Some(ref old_path) = change.old_path {
                         let old_path_str = old_path.to_string_lossy();
-                        writer.schema().delete_file(&old_path_str).await?;
+                        links.extend(writer.store().incoming_links(&old_path_str).await?);
+                        writer.store().delete_file(&old_path_str).await?;
                     }
 
                     // Parse and add the new path
-                    match self.parse_and_write(&change.path, &writer).await {
-                        Ok(_) => stats.files_parsed += 1,
+                    match self.parse_and_write(&change.path, language, &extractors, &imports, &writer, &mut stats).await {
+                        Ok(file_symbols) => {
+                            stats.files_parsed += 1;
+                            defined_names
+                                .extend(file_symbols.symbols.iter().map(|s| s.name.clone()));
+                            links.add_file(file_symbols);
+                        }
                         Err(e) => {
                             warn!("Failed to parse {:?}: {}", change.path, e);
                             stats.files_failed += 1;
@@ -149,12 +175,18 @@ impl IncrementalIndexer {
                     // Delete old data for modified files
                     if change.kind == ChangeKind::Modified {
                         let path_str = change.path.to_string_lossy();
-                        writer.schema().delete_file(&path_str).await?;
+                        links.extend(writer.store().incoming_links(&path_str).await?);
+                        writer.store().delete_file(&path_str).await?;
                     }
 
                     // Parse and add
-                    match self.parse_and_write(&change.path, &writer).await {
-                        Ok(_) => stats.files_parsed += 1,
+                    match self.parse_and_write(&change.path, language, &extractors, &imports, &writer, &mut stats).await {
+                        Ok(file_symbols) => {
+                            stats.files_parsed += 1;
+                            defined_names
+                                .extend(file_symbols.symbols.iter().map(|s| s.name.clone()));
+                            links.add_file(file_symbols);
+                        }
                         Err(e) => {
                             warn!("Failed to parse {:?}: {}", change.path, e);
                             stats.files_failed += 1;
@@ -164,6 +196,17 @@ impl IncrementalIndexer {
             }
         }
 
+        // Calls elsewhere that had no target may resolve to the new symbols
+        links
+            .calls
+            .extend(writer.store().take_unresolved_calls(&defined_names).await?);
+
+        // Link calls and inheritance now that every changed file is written
+        if !links.is_empty() {
+            let resolver = writer.store().call_resolver(&links).await?;
+            writer.link(&links, &resolver, &mut stats.write_stats).await?;
+        }
+
         if total_changes > 0 {
             println!("\rProcessing {}/{} (100%) - done                    ", total_changes, total_changes);
         }
@@ -171,9 +214,10 @@ impl IncrementalIndexer {
         // Update last indexed commit
         let new_commit = git.head_commit()?;
         writer
-            .schema()
+            .store()
             .set_metadata("last_indexed_commit", &new_commit)
             .await?;
+        writer.store().flush().await?;
         println!("Updated commit: {}", &new_commit[..12.min(new_commit.len())]);
 
         stats.duration = start.elapsed();
@@ -182,72 +226,81 @@ impl IncrementalIndexer {
         Ok(stats)
     }
 
-    /// Filter changes to only include supported languages
-    fn filter_changes(&self, changes: Vec<ChangedFile>) -> Vec<ChangedFile> {
+    /// Filter changes to only include supported languages, paired with the
+    /// language of each file
+    ///
+    /// Changed `.h` headers are classified against the headers the C++
+    /// sources of the whole tree include, collected only when there are any.
+    fn filter_changes(&self, changes: Vec<ChangedFile>) -> Vec<(ChangedFile, Language)> {
+        let has_headers = changes.iter().any(|change| {
+            change
+                .path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("h"))
+        });
+        let cpp_includes = if has_headers {
+            Crawler::new(CrawlerConfig {
+                root: self.config.root.clone(),
+                ..Default::default()
+            })
+            .cpp_includes()
+        } else {
+            HashSet::new()
+        };
+
         changes
             .into_iter()
-            .filter(|change| {
-                // Check if file has a supported extension
-                let ext = change
-                    .path
-                    .extension()
-                    .and_then(|e| e.to_str());
-
-                if let Some(ext) = ext {
-                    if let Some(lang) = Language::from_extension(ext) {
-                        // Filter by language if specified
-                        if let Some(ref languages) = self.config.languages {
-                            return languages.contains(&lang);
-                        }
-                        return true;
-                    }
+            .filter_map(|change| {
+                // Check if file is in a supported language
+                let lang =
+                    Language::from_path_with(&self.config.root.join(&change.path), &cpp_includes)?;
+                // Filter by language if specified
+                if let Some(ref languages) = self.config.languages
+                    && !languages.contains(&lang)
+                {
+                    return None;
                 }
-                false
+                Some((change, lang))
             })
             .collect()
     }
 
-    /// Parse a file and write to database
+    /// Parse a file and write its nodes, returning the parsed symbols so their
+    /// calls and inheritance can be linked later
     async fn parse_and_write(
         &self,
         path: &Path,
+        language: Language,
+        extractors: &Extractors,
+        imports: &ImportResolver,
         writer: &GraphWriter,
-    ) -> IndexResult<()> {
+        stats: &mut IndexingStats,
+    ) -> IndexResult<FileSymbols> {
         let full_path = self.config.root.join(path);
 
-        // Detect language
-        let ext = path
-            .extension()
-            .and_then(|e| e.to_str())
-            .ok_or_else(|| {
-                IndexError::Parser(crate::parser::pool::ParserError::UnsupportedLanguage(
-                    "Unknown extension".to_string(),
-                ))
-            })?;
-
-        let language = Language::from_extension(ext).ok_or_else(|| {
-            IndexError::Parser(crate::parser::pool::ParserError::UnsupportedLanguage(
-                ext.to_string(),
-            ))
-        })?;
-
         debug!("Parsing {:?} as {:?}", path, language);
 
         // Read and parse
+        let parse_start = Instant::now();
         let source = std::fs::read(&full_path)?;
-        let tree = ParserPool::parse(language, &source)?;
-        let file_symbols = SymbolExtractor::extract(&tree, &source, path, language)?;
+        let tree = ParserPool::parse_file(language, path, &source)?;
+        let mut file_symbols = SymbolExtractor::extract_with(extractors, &tree, &source, path, language)?;
+        imports.resolve(&mut file_symbols);
+        stats.parse_time += parse_start.elapsed();
 
         // Write to database
-        writer.write_file(&file_symbols).await?;
+        writer
+            .write_nodes(std::slice::from_ref(&file_symbols), &mut stats.write_stats)
+            .await?;
 
-        Ok(())
+        Ok(file_symbols)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::index::full::{FullIndexConfig, FullIndexer};
 
     #[test]
     fn test_incremental_config_default() {
@@ -255,4 +308,53 @@ mod tests {
         assert!(!config.dry_run);
         assert!(config.since.is_none());
     }
+
+    #[tokio::test]
+    async fn test_calls_into_a_deleted_file_become_placeholders() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("app.py"),
+            "from util import parse\n\ndef main():\n    parse()\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("util.py"), "def parse():\n    pass\n").unwrap();
+
+        let repo = git2::Repository::init(root).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_all(["*.py"], git2::IndexAddOption::DEFAULT, None).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "initial", &tree, &[])
+            .unwrap();
+
+        FullIndexer::new(FullIndexConfig {
+            root: root.to_path_buf(),
+            threads: 1,
+            backend: StoreBackend::Embedded,
+            ..Default::default()
+        })
+        .run()
+        .await
+        .unwrap();
+
+        std::fs::remove_file(root.join("util.py")).unwrap();
+        IncrementalIndexer::new(IncrementalConfig {
+            root: root.to_path_buf(),
+            backend: StoreBackend::Embedded,
+            ..Default::default()
+        })
+        .run()
+        .await
+        .unwrap();
+
+        let store = open_store(StoreBackend::Embedded, None, "kampus", root)
+            .await
+            .unwrap();
+        let names = BTreeSet::from(["parse".to_string()]);
+        let calls = store.take_unresolved_calls(&names).await.unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].caller_id, "app.py::main");
+    }
 }
@@ -42,6 +42,14 @@ pub struct IndexingStats {
     pub files_skipped: usize,
     /// Number of files that failed to parse
     pub files_failed: usize,
+    /// Time until the last file was parsed
+    pub parse_time: Duration,
+    /// Number of times a parser waited because the write queue was full
+    pub backpressure_waits: usize,
+    /// Total time parsers spent waiting on the write queue
+    pub backpressure_time: Duration,
+    /// Largest number of parsed files waiting to be written
+    pub peak_queue_depth: usize,
     /// Write statistics
     pub write_stats: WriteStats,
     /// Total duration
@@ -56,7 +64,15 @@ impl std::fmt::Display for IndexingStats {
         writeln!(f, "  Files skipped:    {}", self.files_skipped)?;
         writeln!(f, "  Files failed:     {}", self.files_failed)?;
         writeln!(f, "  Symbols written:  {}", self.write_stats.symbols_written)?;
+        writeln!(f, "  Parse time:       {:.2?}", self.parse_time)?;
+        writeln!(
+            f,
+            "  Backpressure:     {} waits ({:.2?})",
+            self.backpressure_waits, self.backpressure_time
+        )?;
+        writeln!(f, "  Peak queue:       {} files", self.peak_queue_depth)?;
         writeln!(f, "  Duration:         {:.2?}", self.duration)?;
+        write!(f, "\n{}", self.write_stats)?;
         Ok(())
     }
 }
@@ -8,45 +8,195 @@ pub mod git;
 pub mod graph;
 pub mod index;
 pub mod parser;
+pub mod resolver;
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::sync::RwLock;
 
 /// Supported programming languages
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+///
+/// Besides the built-in languages, grammars listed in
+/// `.kampus/grammars.toml` are loaded at runtime (see
+/// [`parser::grammars`]) and appear as [`Language::Custom`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Language {
     Python,
     Rust,
     JavaScript,
     TypeScript,
     Go,
+    C,
     Cpp,
+    Java,
+    CSharp,
+    Ruby,
+    Php,
+    /// A language with a runtime-loaded grammar, by lowercase name
+    Custom(&'static str),
+}
+
+/// A language registered at runtime
+struct CustomLanguage {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    scope_separator: &'static str,
+}
+
+/// Languages registered at runtime. Entries live for the whole process, so
+/// their names can be shared as `&'static str` and `Language` stays `Copy`.
+static CUSTOM_LANGUAGES: RwLock<Vec<CustomLanguage>> = RwLock::new(Vec::new());
+
+fn leak(s: &str) -> &'static str {
+    Box::leak(s.to_string().into_boxed_str())
 }
 
 impl Language {
+    /// Built-in languages
+    pub const BUILTIN: [Language; 11] = [
+        Language::Python,
+        Language::Rust,
+        Language::JavaScript,
+        Language::TypeScript,
+        Language::Go,
+        Language::C,
+        Language::Cpp,
+        Language::Java,
+        Language::CSharp,
+        Language::Ruby,
+        Language::Php,
+    ];
+
+    /// Register a runtime-loaded language and the file extensions it claims
+    ///
+    /// Registering a name again returns the language already registered, so
+    /// each name is leaked at most once. Only a name first seen in stored
+    /// data, which has no extensions yet, gets them filled in.
+    pub fn register(name: &str, extensions: &[String], scope_separator: &str) -> Language {
+        let name = name.to_lowercase();
+        let mut languages = CUSTOM_LANGUAGES.write().unwrap_or_else(|e| e.into_inner());
+        if let Some(language) = languages.iter_mut().find(|l| l.name == name) {
+            if language.extensions.is_empty() && !extensions.is_empty() {
+                let extensions: Vec<&'static str> =
+                    extensions.iter().map(|e| leak(&e.to_lowercase())).collect();
+                language.extensions = Box::leak(extensions.into_boxed_slice());
+                language.scope_separator = leak(scope_separator);
+            }
+            return Language::Custom(language.name);
+        }
+
+        let extensions: Vec<&'static str> =
+            extensions.iter().map(|e| leak(&e.to_lowercase())).collect();
+        let language = CustomLanguage {
+            name: leak(&name),
+            extensions: Box::leak(extensions.into_boxed_slice()),
+            scope_separator: leak(scope_separator),
+        };
+        let custom = Language::Custom(language.name);
+        languages.push(language);
+        custom
+    }
+
+    /// A runtime-loaded language by name, registering the name if needed
+    fn custom(name: &str) -> Language {
+        let name = name.to_lowercase();
+        if let Some(language) = Self::find_custom(|l| l.name == name) {
+            return language;
+        }
+        Self::register(&name, &[], ".")
+    }
+
+    fn find_custom(predicate: impl Fn(&CustomLanguage) -> bool) -> Option<Language> {
+        let languages = CUSTOM_LANGUAGES.read().unwrap_or_else(|e| e.into_inner());
+        languages
+            .iter()
+            .find(|l| predicate(l))
+            .map(|l| Language::Custom(l.name))
+    }
+
+    fn with_custom<T>(name: &str, f: impl Fn(&CustomLanguage) -> T) -> Option<T> {
+        let languages = CUSTOM_LANGUAGES.read().unwrap_or_else(|e| e.into_inner());
+        languages.iter().find(|l| l.name == name).map(f)
+    }
+
     /// Detect language from file extension
     pub fn from_extension(ext: &str) -> Option<Self> {
-        match ext.to_lowercase().as_str() {
+        let ext = ext.to_lowercase();
+        match ext.as_str() {
             "py" => Some(Language::Python),
             "rs" => Some(Language::Rust),
-            "js" | "mjs" | "cjs" => Some(Language::JavaScript),
+            "js" | "mjs" | "cjs" | "jsx" => Some(Language::JavaScript),
             "ts" | "tsx" => Some(Language::TypeScript),
             "go" => Some(Language::Go),
-            "cpp" | "cc" | "cxx" | "c++" | "hpp" | "hxx" | "h" => Some(Language::Cpp),
-            _ => None,
+            // .h headers may also be C++, see Language::from_path
+            "c" | "h" => Some(Language::C),
+            "cpp" | "cc" | "cxx" | "c++" | "hpp" | "hxx" | "hh" => Some(Language::Cpp),
+            "java" => Some(Language::Java),
+            "cs" => Some(Language::CSharp),
+            "rb" | "rake" => Some(Language::Ruby),
+            "php" => Some(Language::Php),
+            _ => Self::find_custom(|l| l.extensions.contains(&ext.as_str())),
         }
     }
 
+    /// Detect the language of a file from its extension, reading `.h`
+    /// headers to tell C from C++
+    ///
+    /// A header is C++ when it declares classes, namespaces, templates or
+    /// other constructs C does not have. Headers that cannot be read are C.
+    pub fn from_path(path: &std::path::Path) -> Option<Self> {
+        Self::from_path_with(path, &std::collections::HashSet::new())
+    }
+
+    /// Detect the language of a file like [`Language::from_path`], also
+    /// taking `.h` headers whose file name is in `cpp_includes` as C++
+    ///
+    /// `cpp_includes` holds the names of the headers C++ sources include, as
+    /// collected by [`crawler::Crawler::cpp_includes`].
+    pub fn from_path_with(
+        path: &std::path::Path,
+        cpp_includes: &std::collections::HashSet<String>,
+    ) -> Option<Self> {
+        let ext = path.extension()?.to_str()?;
+        let language = Self::from_extension(ext)?;
+        if language == Language::C
+            && ext.eq_ignore_ascii_case("h")
+            && (path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| cpp_includes.contains(n))
+                || std::fs::read(path).is_ok_and(|source| parser::languages::is_cpp_header(&source)))
+        {
+            return Some(Language::Cpp);
+        }
+        Some(language)
+    }
+
     /// Get all file extensions for this language
     pub fn extensions(&self) -> &'static [&'static str] {
         match self {
             Language::Python => &["py"],
             Language::Rust => &["rs"],
-            Language::JavaScript => &["js", "mjs", "cjs"],
+            Language::JavaScript => &["js", "mjs", "cjs", "jsx"],
             Language::TypeScript => &["ts", "tsx"],
             Language::Go => &["go"],
-            Language::Cpp => &["cpp", "cc", "cxx", "c++", "hpp", "hxx", "h"],
+            Language::C => &["c", "h"],
+            Language::Cpp => &["cpp", "cc", "cxx", "c++", "hpp", "hxx", "hh", "h"],
+            Language::Java => &["java"],
+            Language::CSharp => &["cs"],
+            Language::Ruby => &["rb", "rake"],
+            Language::Php => &["php"],
+            Language::Custom(name) => Self::with_custom(name, |l| l.extensions).unwrap_or(&[]),
+        }
+    }
+
+    /// Separator between the scopes of a qualified name
+    pub fn scope_separator(&self) -> &'static str {
+        match self {
+            Language::Rust | Language::Cpp | Language::Ruby => "::",
+            Language::Php => "\\",
+            Language::Custom(name) => Self::with_custom(name, |l| l.scope_separator).unwrap_or("."),
+            _ => ".",
         }
     }
 
@@ -58,7 +208,31 @@ impl Language {
             Language::JavaScript => "JavaScript",
             Language::TypeScript => "TypeScript",
             Language::Go => "Go",
+            Language::C => "C",
             Language::Cpp => "C++",
+            Language::Java => "Java",
+            Language::CSharp => "C#",
+            Language::Ruby => "Ruby",
+            Language::Php => "PHP",
+            Language::Custom(name) => name,
+        }
+    }
+
+    /// Lowercase identifier used in serialized data and query file names
+    pub fn id(&self) -> &'static str {
+        match self {
+            Language::Python => "python",
+            Language::Rust => "rust",
+            Language::JavaScript => "javascript",
+            Language::TypeScript => "typescript",
+            Language::Go => "go",
+            Language::C => "c",
+            Language::Cpp => "cpp",
+            Language::Java => "java",
+            Language::CSharp => "csharp",
+            Language::Ruby => "ruby",
+            Language::Php => "php",
+            Language::Custom(name) => name,
         }
     }
 }
@@ -72,27 +246,88 @@ impl std::fmt::Display for Language {
 impl std::str::FromStr for Language {
     type Err = String;
 
+    /// Parse a built-in language or a registered runtime-loaded one
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
+        let name = s.to_lowercase();
+        match name.as_str() {
             "python" | "py" => Ok(Language::Python),
             "rust" | "rs" => Ok(Language::Rust),
             "javascript" | "js" => Ok(Language::JavaScript),
             "typescript" | "ts" => Ok(Language::TypeScript),
             "go" => Ok(Language::Go),
+            "c" => Ok(Language::C),
             "cpp" | "c++" => Ok(Language::Cpp),
-            _ => Err(format!("Unknown language: {}", s)),
+            "java" => Ok(Language::Java),
+            "csharp" | "cs" | "c#" => Ok(Language::CSharp),
+            "ruby" | "rb" => Ok(Language::Ruby),
+            "php" => Ok(Language::Php),
+            _ => Self::find_custom(|l| l.name == name || l.extensions.contains(&name.as_str()))
+                .ok_or_else(|| format!("Unknown language: {}", s)),
         }
     }
 }
 
+impl Serialize for Language {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.id())
+    }
+}
+
+impl<'de> Deserialize<'de> for Language {
+    /// Unknown names are languages whose grammar is not loaded in this
+    /// process; they are kept so stored data survives a round trip
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(name
+            .parse()
+            .unwrap_or_else(|_| Language::custom(&name)))
+    }
+}
+
 /// Visibility of a symbol
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum Visibility {
+    /// Exported from its module or package
     #[default]
     Public,
     Private,
     Protected,
+    /// Visible within the crate or assembly (`pub(crate)`, C# `internal`)
+    Internal,
+    /// Visible within the parent module (`pub(super)`)
+    Super,
+    /// Visible within a given module (`pub(in path)`)
+    Restricted,
+}
+
+impl std::fmt::Display for Visibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Visibility::Public => write!(f, "public"),
+            Visibility::Private => write!(f, "private"),
+            Visibility::Protected => write!(f, "protected"),
+            Visibility::Internal => write!(f, "internal"),
+            Visibility::Super => write!(f, "super"),
+            Visibility::Restricted => write!(f, "restricted"),
+        }
+    }
+}
+
+impl std::str::FromStr for Visibility {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "public" | "pub" | "exported" => Ok(Visibility::Public),
+            "private" => Ok(Visibility::Private),
+            "protected" => Ok(Visibility::Protected),
+            "internal" | "crate" => Ok(Visibility::Internal),
+            "super" => Ok(Visibility::Super),
+            "restricted" => Ok(Visibility::Restricted),
+            _ => Err(format!("Unknown visibility: {}", s)),
+        }
+    }
 }
 
 /// Type of symbol
@@ -109,6 +344,71 @@ pub enum SymbolKind {
     Enum,
     Constant,
     Variable,
+    /// A React function or class component
+    Component,
+    /// A property with accessors, such as in C#
+    Property,
+    /// A C union
+    Union,
+    /// A type alias or C `typedef`
+    #[serde(rename = "type_alias")]
+    TypeAlias,
+    /// A macro, such as a C function-like `#define`
+    Macro,
+    /// A field of a struct
+    Field,
+    /// A variant of an enum
+    #[serde(rename = "enum_variant")]
+    EnumVariant,
+}
+
+impl SymbolKind {
+    /// Graph node label for this kind
+    pub fn label(&self) -> &'static str {
+        match self {
+            SymbolKind::Function => "Function",
+            SymbolKind::Class => "Class",
+            SymbolKind::Struct => "Struct",
+            SymbolKind::Interface => "Interface",
+            SymbolKind::Module => "Module",
+            SymbolKind::Method => "Method",
+            SymbolKind::Trait => "Trait",
+            SymbolKind::Enum => "Enum",
+            SymbolKind::Constant => "Constant",
+            SymbolKind::Variable => "Variable",
+            SymbolKind::Component => "Component",
+            SymbolKind::Property => "Property",
+            SymbolKind::Union => "Union",
+            SymbolKind::TypeAlias => "TypeAlias",
+            SymbolKind::Macro => "Macro",
+            SymbolKind::Field => "Field",
+            SymbolKind::EnumVariant => "EnumVariant",
+        }
+    }
+
+    /// Parse a graph node label back into a kind
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label {
+            "Function" => Some(SymbolKind::Function),
+            "Class" => Some(SymbolKind::Class),
+            "Struct" => Some(SymbolKind::Struct),
+            "Interface" => Some(SymbolKind::Interface),
+            "Module" => Some(SymbolKind::Module),
+            "Method" => Some(SymbolKind::Method),
+            "Trait" => Some(SymbolKind::Trait),
+            "Enum" => Some(SymbolKind::Enum),
+            "Constant" => Some(SymbolKind::Constant),
+            "Variable" => Some(SymbolKind::Variable),
+            "Component" => Some(SymbolKind::Component),
+            "Property" => Some(SymbolKind::Property),
+            "Union" => Some(SymbolKind::Union),
+            "TypeAlias" => Some(SymbolKind::TypeAlias),
+            "Macro" => Some(SymbolKind::Macro),
+            "Field" => Some(SymbolKind::Field),
+            "EnumVariant" => Some(SymbolKind::EnumVariant),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for SymbolKind {
@@ -124,17 +424,65 @@ impl std::fmt::Display for SymbolKind {
             SymbolKind::Enum => write!(f, "enum"),
             SymbolKind::Constant => write!(f, "constant"),
             SymbolKind::Variable => write!(f, "variable"),
+            SymbolKind::Component => write!(f, "component"),
+            SymbolKind::Property => write!(f, "property"),
+            SymbolKind::Union => write!(f, "union"),
+            SymbolKind::TypeAlias => write!(f, "type_alias"),
+            SymbolKind::Macro => write!(f, "macro"),
+            SymbolKind::Field => write!(f, "field"),
+            SymbolKind::EnumVariant => write!(f, "enum_variant"),
         }
     }
 }
 
+/// Kind of a container for files and symbols
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NamespaceKind {
+    /// Rust modules, C++ and TypeScript namespaces
+    Namespace,
+    /// Python and Go packages
+    Package,
+}
+
+impl NamespaceKind {
+    /// Graph node label for this kind
+    pub fn label(&self) -> &'static str {
+        match self {
+            NamespaceKind::Namespace => "Namespace",
+            NamespaceKind::Package => "Package",
+        }
+    }
+}
+
+/// A module, namespace or package
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Namespace {
+    /// Fully qualified name (`crate::graph`, `app.models`)
+    pub qualified_name: String,
+    /// Last segment of the qualified name
+    pub name: String,
+    pub kind: NamespaceKind,
+    pub language: Language,
+    /// Qualified name of the enclosing namespace, which has the same kind
+    pub parent: Option<String>,
+}
+
 /// A code symbol (function, class, etc.)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Symbol {
-    /// Unique identifier (file_path:name:start_line)
+    /// Stable identifier (`file_path::Scope.name`, see [`Symbol::generate_id`])
     pub id: String,
     /// Symbol name
     pub name: String,
+    /// Name qualified by the module, namespaces and types enclosing the
+    /// symbol (`crate::graph::GraphWriter::new`, `app.models.User.save`)
+    #[serde(default)]
+    pub qualified_name: String,
+    /// Qualified name of the innermost namespace or package containing the
+    /// symbol
+    #[serde(default)]
+    pub namespace: Option<String>,
     /// Kind of symbol
     pub kind: SymbolKind,
     /// File path where symbol is defined
@@ -143,14 +491,30 @@ pub struct Symbol {
     pub start_line: u32,
     /// Ending line number (1-indexed)
     pub end_line: u32,
+    /// Byte range of the definition in the source file
+    #[serde(default)]
+    pub byte_range: std::ops::Range<usize>,
     /// Function signature or declaration
     pub signature: Option<String>,
     /// Visibility
     pub visibility: Visibility,
     /// Whether the function is async
     pub is_async: bool,
-    /// Documentation string
+    /// Whether the declaration is one part of a type split across files,
+    /// such as a C# `partial` class; all parts share one symbol
+    #[serde(default)]
+    pub is_partial: bool,
+    /// Decorators applied to the definition, outermost first
+    #[serde(default)]
+    pub decorators: Vec<Decorator>,
+    /// Modifiers such as `static` or `abstract`
+    #[serde(default)]
+    pub modifiers: Modifiers,
+    /// Documentation string, without comment markers or quotes
     pub docstring: Option<String>,
+    /// Parameters, return value and errors described in the docstring
+    #[serde(default)]
+    pub doc_sections: DocSections,
     /// LLM-generated summary (Phase 2)
     pub summary: Option<String>,
     /// Language of the symbol
@@ -160,12 +524,82 @@ pub struct Symbol {
 }
 
 impl Symbol {
-    /// Create a unique ID for this symbol
-    pub fn generate_id(file_path: &std::path::Path, name: &str, start_line: u32) -> String {
+    /// Create the ID of a symbol from its path within the file
+    ///
+    /// `scoped_name` is the symbol name qualified by its enclosing scopes,
+    /// such as `Config.load` in Python or `Config::load` in Rust, with a
+    /// `#2`, `#3`... suffix for repeated definitions. The ID does not depend
+    /// on line numbers, so it survives edits elsewhere in the file.
+    pub fn generate_id(file_path: &std::path::Path, scoped_name: &str) -> String {
+        format!("{}::{}", file_path.display(), scoped_name)
+    }
+
+    /// Create the ID shared by all parts of a partial type, which does not
+    /// depend on the files declaring them
+    pub fn partial_id(language: Language, qualified_name: &str) -> String {
+        format!("partial:{}:{}", language.id(), qualified_name)
+    }
+
+    /// Create a temporary ID used by extractors until
+    /// [`SymbolExtractor`](parser::SymbolExtractor) assigns stable ones
+    pub fn provisional_id(file_path: &std::path::Path, name: &str, start_line: u32) -> String {
         format!("{}:{}:{}", file_path.display(), name, start_line)
     }
 }
 
+/// A decorator applied to a definition, such as `@app.route("/")` in Python
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Decorator {
+    /// Decorator expression without the `@` or arguments (`app.route`)
+    pub name: String,
+    /// Argument list as written, when the decorator is called (`("/")`)
+    pub arguments: Option<String>,
+    /// Line number of the decorator
+    pub line: u32,
+}
+
+/// Modifiers of a method or function beyond its visibility
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Modifiers {
+    /// Called on the type rather than an instance (`@staticmethod`)
+    pub is_static: bool,
+    /// Receives the type as its first argument (`@classmethod`)
+    pub is_class_method: bool,
+    /// Must be implemented by subtypes (`@abstractmethod`)
+    pub is_abstract: bool,
+    /// Accessed as an attribute rather than called (`@property`)
+    pub is_property: bool,
+    /// Declared without a body, such as a C++ prototype in a header
+    pub is_declaration: bool,
+}
+
+/// Sections of a docstring describing a function's interface
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DocSections {
+    /// `@param`, `Args:` or `# Arguments` entries
+    pub params: Vec<DocEntry>,
+    /// `@returns`, `Returns:` or `# Returns` text
+    pub returns: Option<String>,
+    /// `@throws`, `Raises:` or `# Errors` entries
+    pub raises: Vec<DocEntry>,
+}
+
+impl DocSections {
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty() && self.returns.is_none() && self.raises.is_empty()
+    }
+}
+
+/// A named entry of a docstring section
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocEntry {
+    /// Parameter or error type name, empty for free text
+    pub name: String,
+    pub description: String,
+}
+
 /// An import statement
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Import {
@@ -173,12 +607,46 @@ pub struct Import {
     pub source_file: PathBuf,
     /// Module or file being imported
     pub target: String,
-    /// Alias if any
+    /// Name the import is bound to when renamed: the module's for
+    /// `import x as y`, the single item's for `from x import y as z`
     pub alias: Option<String>,
     /// Specific items imported (for `from x import y, z`)
     pub items: Vec<String>,
     /// Line number of import
     pub line: u32,
+    /// Where the import points, when it could be resolved against the
+    /// project layout
+    #[serde(default)]
+    pub resolved: Option<ImportTarget>,
+}
+
+/// Where an import points once resolved against the project layout
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ImportTarget {
+    /// Files of the project: the imported module first, then the modules
+    /// named by the import's items
+    Project {
+        files: Vec<PathBuf>,
+        /// Symbols named by the import
+        symbols: Vec<ImportedSymbol>,
+    },
+    /// A crate or package from outside the project
+    External {
+        package: String,
+        /// Locked version, when the lock file pins exactly one
+        version: Option<String>,
+    },
+}
+
+/// A symbol named by an import
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportedSymbol {
+    /// Path as written in the import (`GraphWriter`, `writer::GraphWriter`)
+    pub name: String,
+    /// ID of the symbol in the file defining it
+    pub id: String,
+    pub file_path: PathBuf,
 }
 
 /// A function call reference
@@ -188,10 +656,68 @@ pub struct Call {
     pub caller_id: String,
     /// Name of called function
     pub callee_name: String,
+    /// Receiver or path qualifier (`self` in `self.run()`, `fmt` in `fmt.Println()`)
+    pub receiver: Option<String>,
     /// Line number where call occurs
     pub call_site_line: u32,
 }
 
+/// A JSX element rendered by a component
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Render {
+    /// ID of the rendering component
+    pub component_id: String,
+    /// Name of the rendered component (`Button` in `<Button />`)
+    pub element_name: String,
+    /// Object the element is a member of (`Icons` in `<Icons.Close />`)
+    pub receiver: Option<String>,
+    /// Line number of the element
+    pub line: u32,
+}
+
+/// Kind of a [`Reference`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferenceKind {
+    /// A definition names a decorator
+    DecoratedBy,
+    /// A function names a type in a parameter or return annotation
+    UsesType,
+}
+
+impl ReferenceKind {
+    /// Graph edge label for this kind
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReferenceKind::DecoratedBy => "DECORATED_BY",
+            ReferenceKind::UsesType => "USES_TYPE",
+        }
+    }
+
+    /// Parse a graph edge label back into a kind
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label {
+            "DECORATED_BY" => Some(ReferenceKind::DecoratedBy),
+            "USES_TYPE" => Some(ReferenceKind::UsesType),
+            _ => None,
+        }
+    }
+}
+
+/// A reference from a symbol to another symbol by name, other than a call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reference {
+    pub kind: ReferenceKind,
+    /// ID of the referencing symbol
+    pub source_id: String,
+    /// Name of the referenced symbol (`User` in `models.User`)
+    pub target_name: String,
+    /// Path qualifier of the name (`models` in `models.User`)
+    pub receiver: Option<String>,
+    /// Line number of the reference
+    pub line: u32,
+}
+
 /// An inheritance relationship
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Inheritance {
@@ -220,6 +746,14 @@ pub struct FileSymbols {
     pub calls: Vec<Call>,
     /// Inheritance relationships
     pub inheritance: Vec<Inheritance>,
+    /// Components rendered by components
+    pub renders: Vec<Render>,
+    /// Decorator and type references
+    pub references: Vec<Reference>,
+    /// Qualified name of the namespace or package the file belongs to
+    pub namespace: Option<String>,
+    /// Namespaces containing the file or declared in it, outermost first
+    pub namespaces: Vec<Namespace>,
 }
 
 /// Index statistics
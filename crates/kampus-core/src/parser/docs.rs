@@ -0,0 +1,458 @@
+//! Documentation comments
+//!
+//! Strips comment markers and string quotes from doc comments and
+//! docstrings, and splits out the parameter, return value and error
+//! sections written in JSDoc/Javadoc tags (`@param`), Sphinx fields
+//! (`:param x:`), Google style (`Args:`) or rustdoc headings
+//! (`# Arguments`).
+
+use crate::{DocEntry, DocSections};
+
+/// Line comment markers, longest first
+const LINE_MARKERS: [&str; 4] = ["///", "//!", "//", "#"];
+
+/// Clean a doc comment or docstring as written in the source
+///
+/// Block comments (`/** ... */`) lose their delimiters and leading `*`,
+/// line comments (`///`, `//`, `#`) their markers and string literals their
+/// quotes and common indentation.
+pub fn clean(text: &str) -> String {
+    let text = text.trim();
+    let lines: Vec<String> = if let Some(body) = text.strip_prefix("/*") {
+        let body = body
+            .trim_start_matches(['*', '!'])
+            .strip_suffix("*/")
+            .unwrap_or(body);
+        body.lines()
+            .map(|line| {
+                let line = line.trim_start();
+                let line = match line.strip_prefix('*') {
+                    Some(rest) => rest.strip_prefix(' ').unwrap_or(rest),
+                    None => line,
+                };
+                line.trim_end().to_string()
+            })
+            .collect()
+    } else if LINE_MARKERS.iter().any(|m| text.starts_with(m)) {
+        text.lines()
+            .map(|line| {
+                let line = line.trim();
+                let line = LINE_MARKERS
+                    .iter()
+                    .find_map(|m| line.strip_prefix(m))
+                    .unwrap_or(line);
+                line.strip_prefix(' ')
+                    .unwrap_or(line)
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    } else {
+        return clean_string(text);
+    };
+
+    trim_blank_lines(&lines)
+}
+
+/// Clean a string literal docstring the way Python's `inspect.cleandoc`
+/// does: quotes are removed, and the indentation shared by all lines after
+/// the first
+fn clean_string(text: &str) -> String {
+    let text = text.trim_start_matches(['r', 'R', 'u', 'U', 'b', 'B', 'f', 'F']);
+    let text = ["\"\"\"", "'''", "\"", "'", "`"]
+        .iter()
+        .find_map(|quote| text.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(text);
+
+    let mut lines = text.lines();
+    let first = lines.next().unwrap_or_default().trim();
+    let rest: Vec<&str> = lines.collect();
+    let indent = rest
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let lines: Vec<String> = std::iter::once(first.to_string())
+        .chain(rest.iter().map(|line| {
+            line.get(indent..)
+                .unwrap_or_default()
+                .trim_end()
+                .to_string()
+        }))
+        .collect();
+    trim_blank_lines(&lines)
+}
+
+fn trim_blank_lines(lines: &[String]) -> String {
+    let start = lines.iter().position(|l| !l.trim().is_empty());
+    let end = lines.iter().rposition(|l| !l.trim().is_empty());
+    match (start, end) {
+        (Some(start), Some(end)) => lines[start..=end].join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Section of a doc comment being read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Params,
+    Returns,
+    Raises,
+    /// A section with nothing to extract, such as `Examples`
+    Other,
+}
+
+/// Where continuation lines go
+#[derive(Debug, Clone, Copy)]
+enum Target {
+    None,
+    Param,
+    Returns,
+    Raise,
+}
+
+/// Parse the parameter, return value and error sections of a cleaned doc
+/// comment
+pub fn parse_sections(doc: &str) -> DocSections {
+    let mut sections = DocSections::default();
+    // The open heading section, with the indentation of a Google-style
+    // heading; rustdoc headings have none and last until the next heading
+    let mut section: Option<(Section, Option<usize>)> = None;
+    // Indentation of the entries of a Google-style section
+    let mut entry_indent: Option<usize> = None;
+    let mut target = Target::None;
+
+    for line in doc.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+
+        // A Google-style section ends at the first line not indented past
+        // its heading
+        if let Some((_, Some(heading_indent))) = section
+            && indent <= heading_indent
+        {
+            section = None;
+            target = Target::None;
+        }
+
+        if let Some(tag) = trimmed.strip_prefix('@') {
+            section = None;
+            target = parse_tag(tag, &mut sections);
+            continue;
+        }
+        if trimmed.starts_with(':')
+            && let Some(field) = parse_field(trimmed, &mut sections)
+        {
+            section = None;
+            target = field;
+            continue;
+        }
+        if let Some(heading) = trimmed.strip_prefix("# ") {
+            section = Some((heading_section(heading), None));
+            entry_indent = None;
+            target = Target::None;
+            continue;
+        }
+        if let Some(heading) = trimmed.strip_suffix(':')
+            && section.is_none()
+            && !heading.contains(": ")
+            && heading.chars().next().is_some_and(char::is_uppercase)
+        {
+            let kind = heading_section(heading);
+            if kind != Section::Other {
+                section = Some((kind, Some(indent)));
+                entry_indent = None;
+                target = Target::None;
+                continue;
+            }
+        }
+
+        match section {
+            Some((Section::Params | Section::Raises, style)) => {
+                let bullet = trimmed
+                    .strip_prefix("* ")
+                    .or_else(|| trimmed.strip_prefix("- "));
+                let starts_entry = match style {
+                    // Google style: entries share the first entry's indentation
+                    Some(_) => *entry_indent.get_or_insert(indent) == indent,
+                    None => bullet.is_some(),
+                };
+                if starts_entry || matches!(target, Target::None) {
+                    let entry = parse_entry(bullet.unwrap_or(trimmed));
+                    if section.is_some_and(|(s, _)| s == Section::Params) {
+                        sections.params.push(entry);
+                        target = Target::Param;
+                    } else {
+                        sections.raises.push(entry);
+                        target = Target::Raise;
+                    }
+                } else {
+                    append(&mut sections, target, trimmed);
+                }
+            }
+            Some((Section::Returns, _)) => {
+                target = Target::Returns;
+                append(&mut sections, target, trimmed);
+            }
+            Some((Section::Other, _)) => {}
+            None => append(&mut sections, target, trimmed),
+        }
+    }
+
+    sections
+}
+
+fn heading_section(heading: &str) -> Section {
+    match heading.trim().trim_end_matches(':') {
+        "Args" | "Arguments" | "Parameters" | "Params" | "Keyword Args" | "Keyword Arguments" => {
+            Section::Params
+        }
+        "Returns" | "Return" | "Yields" => Section::Returns,
+        "Raises" | "Throws" | "Exceptions" | "Errors" | "Panics" => Section::Raises,
+        _ => Section::Other,
+    }
+}
+
+/// Add a continuation line to the entry being read
+fn append(sections: &mut DocSections, target: Target, text: &str) {
+    let description = match target {
+        Target::None => return,
+        Target::Param => sections.params.last_mut().map(|e| &mut e.description),
+        Target::Raise => sections.raises.last_mut().map(|e| &mut e.description),
+        Target::Returns => Some(sections.returns.get_or_insert_with(String::new)),
+    };
+    if let Some(description) = description {
+        if !description.is_empty() {
+            description.push(' ');
+        }
+        description.push_str(text);
+    }
+}
+
+/// A JSDoc, Javadoc or PHPDoc tag, without the `@`
+fn parse_tag(tag: &str, sections: &mut DocSections) -> Target {
+    let (name, rest) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+    let (type_name, rest) = braced_type(rest.trim());
+    match name {
+        // @param {string} name - description, @param string $name description
+        "param" | "arg" | "argument" => {
+            let mut words = rest.splitn(2, char::is_whitespace);
+            let mut param = words.next().unwrap_or_default();
+            let mut description = words.next().unwrap_or_default().trim();
+            if type_name.is_none()
+                && !param.starts_with('$')
+                && let Some((name, rest)) = description.split_once(char::is_whitespace)
+                && name.starts_with('$')
+            {
+                param = name;
+                description = rest.trim();
+            }
+            let param = param.trim_matches(['[', ']']);
+            let param = param.split('=').next().unwrap_or(param);
+            sections.params.push(DocEntry {
+                name: param.to_string(),
+                description: strip_dash(description).to_string(),
+            });
+            Target::Param
+        }
+        "returns" | "return" => {
+            let description = strip_dash(rest);
+            sections.returns = Some(if description.is_empty() {
+                type_name.unwrap_or_default().to_string()
+            } else {
+                description.to_string()
+            });
+            Target::Returns
+        }
+        // @throws {Error} description, @throws IOException description
+        "throws" | "exception" | "raises" => {
+            let (error, description) = match type_name {
+                Some(error) => (error, rest),
+                None => rest.split_once(char::is_whitespace).unwrap_or((rest, "")),
+            };
+            sections.raises.push(DocEntry {
+                name: error.to_string(),
+                description: strip_dash(description.trim()).to_string(),
+            });
+            Target::Raise
+        }
+        _ => Target::None,
+    }
+}
+
+/// A Sphinx field list entry (`:param name: description`)
+fn parse_field(line: &str, sections: &mut DocSections) -> Option<Target> {
+    let (field, description) = line.strip_prefix(':')?.split_once(':')?;
+    let description = description.trim().to_string();
+    let mut words = field.split_whitespace();
+    let kind = words.next()?;
+    // The name comes last: `:param int count:`
+    let name = words.last().unwrap_or_default().to_string();
+    Some(match kind {
+        "param" | "parameter" | "arg" | "argument" | "key" | "keyword" => {
+            sections.params.push(DocEntry { name, description });
+            Target::Param
+        }
+        "returns" | "return" => {
+            sections.returns = Some(description);
+            Target::Returns
+        }
+        "raises" | "raise" | "except" | "exception" => {
+            sections.raises.push(DocEntry { name, description });
+            Target::Raise
+        }
+        _ => Target::None,
+    })
+}
+
+/// An entry of a parameter or error list: `` `name` - description``,
+/// `name (type): description`, `name: description`
+fn parse_entry(text: &str) -> DocEntry {
+    if let Some(rest) = text.strip_prefix('`')
+        && let Some((name, description)) = rest.split_once('`')
+    {
+        return DocEntry {
+            name: name.to_string(),
+            description: strip_dash(description.trim_start_matches(':').trim()).to_string(),
+        };
+    }
+
+    let head_end = text
+        .find(": ")
+        .or_else(|| text.strip_suffix(':').map(str::len));
+    match head_end {
+        Some(end) if !text[..end].trim().is_empty() => {
+            let head = &text[..end];
+            let name = head.split(" (").next().unwrap_or(head).trim();
+            DocEntry {
+                name: name.to_string(),
+                description: text[end + 1..].trim().to_string(),
+            }
+        }
+        _ => match text.split_once(" - ") {
+            Some((name, description)) if !name.contains(' ') => DocEntry {
+                name: name.to_string(),
+                description: description.trim().to_string(),
+            },
+            // Free text, such as a rustdoc `# Errors` paragraph
+            _ => DocEntry {
+                name: String::new(),
+                description: text.to_string(),
+            },
+        },
+    }
+}
+
+/// Split a leading `{Type}` off a tag
+fn braced_type(text: &str) -> (Option<&str>, &str) {
+    if let Some(rest) = text.strip_prefix('{')
+        && let Some((type_name, rest)) = rest.split_once('}')
+    {
+        (Some(type_name.trim()), rest.trim())
+    } else {
+        (None, text)
+    }
+}
+
+fn strip_dash(text: &str) -> &str {
+    text.strip_prefix('-').map_or(text, str::trim_start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(entries: &[DocEntry]) -> Vec<(&str, &str)> {
+        entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.description.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_comment_markers() {
+        assert_eq!(
+            clean("/// Loads the config\n/// from disk"),
+            "Loads the config\nfrom disk"
+        );
+        assert_eq!(
+            clean("/**\n * A service.\n *\n * @param x the x\n */"),
+            "A service.\n\n@param x the x"
+        );
+        assert_eq!(clean("# Ruby comment"), "Ruby comment");
+        assert_eq!(
+            clean("r\"\"\"Summary.\n\n    Args:\n        x: the x\n    \"\"\""),
+            "Summary.\n\nArgs:\n    x: the x"
+        );
+    }
+
+    #[test]
+    fn test_jsdoc_sections() {
+        let sections = parse_sections(
+            "Save a user.\n@param {User} user - The user\n  to save\n@param {boolean} [force=false] Overwrite\n@returns {Promise<void>} Resolves when saved\n@throws {ValidationError} If invalid",
+        );
+        assert_eq!(
+            entries(&sections.params),
+            [("user", "The user to save"), ("force", "Overwrite")]
+        );
+        assert_eq!(sections.returns.as_deref(), Some("Resolves when saved"));
+        assert_eq!(
+            entries(&sections.raises),
+            [("ValidationError", "If invalid")]
+        );
+
+        let php = parse_sections("@param string $name The name\n@return bool");
+        assert_eq!(entries(&php.params), [("$name", "The name")]);
+        assert_eq!(php.returns.as_deref(), Some("bool"));
+    }
+
+    #[test]
+    fn test_google_and_sphinx_sections() {
+        let sections = parse_sections(
+            "Fetch rows.\n\nArgs:\n    table (str): Table name.\n    limit: Maximum rows,\n        or None.\n\nReturns:\n    list: The rows.\n\nRaises:\n    KeyError: If the table is missing.\n\nExample text.",
+        );
+        assert_eq!(
+            entries(&sections.params),
+            [
+                ("table", "Table name."),
+                ("limit", "Maximum rows, or None.")
+            ]
+        );
+        assert_eq!(sections.returns.as_deref(), Some("list: The rows."));
+        assert_eq!(
+            entries(&sections.raises),
+            [("KeyError", "If the table is missing.")]
+        );
+
+        let sphinx = parse_sections(
+            ":param int count: How many\n:returns: The total\n:raises ValueError: If negative",
+        );
+        assert_eq!(entries(&sphinx.params), [("count", "How many")]);
+        assert_eq!(sphinx.returns.as_deref(), Some("The total"));
+        assert_eq!(entries(&sphinx.raises), [("ValueError", "If negative")]);
+    }
+
+    #[test]
+    fn test_rustdoc_sections() {
+        let sections = parse_sections(
+            "Open a store.\n\n# Arguments\n\n* `dir` - Directory holding\n  the graph\n* `name` - Graph name\n\n# Errors\n\nFails when the file is corrupt.\n\n# Examples\n\n```\nopen(dir, name)\n```",
+        );
+        assert_eq!(
+            entries(&sections.params),
+            [
+                ("dir", "Directory holding the graph"),
+                ("name", "Graph name")
+            ]
+        );
+        assert_eq!(
+            entries(&sections.raises),
+            [("", "Fails when the file is corrupt.")]
+        );
+        assert_eq!(sections.returns, None);
+    }
+}
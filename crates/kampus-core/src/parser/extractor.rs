@@ -1,10 +1,17 @@
 //! Symbol extraction from tree-sitter ASTs
 
 use crate::parser::languages::{
-    CppExtractor, GoExtractor, JavaScriptExtractor, PythonExtractor, RustExtractor,
-    TypeScriptExtractor,
+    CExtractor, CSharpExtractor, CppExtractor, GoExtractor, JavaExtractor, JavaScriptExtractor,
+    PhpExtractor, PythonExtractor, RubyExtractor, RustExtractor, TypeScriptExtractor,
 };
-use crate::{Call, FileSymbols, Import, Inheritance, Language, Symbol};
+use crate::parser::docs;
+use crate::parser::pool::Grammar;
+use crate::parser::query::Extractors;
+use crate::{
+    Call, FileSymbols, Import, Inheritance, Language, Namespace, NamespaceKind, Reference, Render,
+    Symbol, SymbolKind,
+};
+use std::collections::HashMap;
 use std::path::Path;
 use thiserror::Error;
 use tree_sitter::Tree;
@@ -15,6 +22,12 @@ pub enum ExtractorError {
     UnsupportedLanguage(String),
     #[error("Extraction failed: {0}")]
     ExtractionFailed(String),
+    #[error("Invalid query for {0}: {1}")]
+    InvalidQuery(String, String),
+    #[error("Grammar error: {0}")]
+    Grammar(#[from] crate::parser::grammars::GrammarError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 /// Trait for language-specific symbol extraction
@@ -35,7 +48,8 @@ pub trait LanguageExtractor: Send + Sync {
         file_path: &Path,
     ) -> Vec<Import>;
 
-    /// Extract function calls within functions
+    /// Extract function calls within functions, each attributed to the
+    /// innermost enclosing function or method
     fn extract_calls(
         &self,
         tree: &Tree,
@@ -50,25 +64,93 @@ pub trait LanguageExtractor: Send + Sync {
         source: &[u8],
         symbols: &[Symbol],
     ) -> Vec<Inheritance>;
+
+    /// Extract the components rendered by each component, for languages
+    /// with JSX
+    fn extract_renders(&self, _tree: &Tree, _source: &[u8], _symbols: &[Symbol]) -> Vec<Render> {
+        Vec::new()
+    }
+
+    /// Extract references to decorators and types, for languages that have
+    /// them
+    fn extract_references(
+        &self,
+        _tree: &Tree,
+        _source: &[u8],
+        _symbols: &[Symbol],
+    ) -> Vec<Reference> {
+        Vec::new()
+    }
+
+    /// Name of the scope a node opens for the definitions nested in it
+    /// (classes, modules, impl blocks, functions), used to build symbol IDs
+    fn scope_name(&self, node: tree_sitter::Node, source: &[u8]) -> Option<String>;
+
+    /// Scope of a definition that lives outside the body of its type, such
+    /// as a Go method's receiver type or the class of a C++ `Shape::area`
+    /// definition; it nests inside the scopes enclosing the definition
+    fn receiver_scope(&self, _node: tree_sitter::Node, _source: &[u8]) -> Option<String> {
+        None
+    }
+
+    /// Kind of namespace a scope node declares, such as a Rust `mod` block
+    /// or a C++ namespace
+    fn namespace_kind(&self, _node: tree_sitter::Node) -> Option<NamespaceKind> {
+        None
+    }
+
+    /// Segments of the qualified name of the module a file defines, such as
+    /// `["crate", "graph"]` for `src/graph/mod.rs`; empty for languages where
+    /// files are not part of a namespace
+    fn module_path(&self, _tree: &Tree, _source: &[u8], _file_path: &Path) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Kind of the namespaces in [`LanguageExtractor::module_path`]
+    fn module_kind(&self) -> NamespaceKind {
+        NamespaceKind::Namespace
+    }
 }
 
 /// Main extractor that delegates to language-specific extractors
 pub struct SymbolExtractor;
 
 impl SymbolExtractor {
-    /// Extract all symbols and relationships from a file
+    /// Extract all symbols and relationships from a file with the built-in
+    /// extractors
     pub fn extract(
         tree: &Tree,
         source: &[u8],
         file_path: &Path,
         language: Language,
     ) -> Result<FileSymbols, ExtractorError> {
-        let extractor = Self::get_extractor(language)?;
+        Self::extract_with(&Extractors::default(), tree, source, file_path, language)
+    }
 
-        let symbols = extractor.extract_symbols(tree, source, file_path);
+    /// Extract all symbols and relationships from a file, using the
+    /// project's query files where it has them
+    pub fn extract_with(
+        extractors: &Extractors,
+        tree: &Tree,
+        source: &[u8],
+        file_path: &Path,
+        language: Language,
+    ) -> Result<FileSymbols, ExtractorError> {
+        let extractor = extractors.get(language, Grammar::for_file(language, file_path))?;
+
+        let mut symbols = extractor.extract_symbols(tree, source, file_path);
+        for symbol in &mut symbols {
+            if let Some(docstring) = &symbol.docstring {
+                symbol.doc_sections = docs::parse_sections(docstring);
+            }
+        }
+        let (namespace, namespaces) =
+            Self::assign_names(extractor.as_ref(), tree, source, file_path, language, &mut symbols);
         let imports = extractor.extract_imports(tree, source, file_path);
         let calls = extractor.extract_calls(tree, source, &symbols);
         let inheritance = extractor.extract_inheritance(tree, source, &symbols);
+        let renders = extractor.extract_renders(tree, source, &symbols);
+        let references = extractor.extract_references(tree, source, &symbols);
 
         let content_hash = Self::compute_hash(source);
         let line_count = source.iter().filter(|&&b| b == b'\n').count() as u32 + 1;
@@ -82,18 +164,136 @@ impl SymbolExtractor {
             imports,
             calls,
             inheritance,
+            renders,
+            references,
+            namespace,
+            namespaces,
         })
     }
 
-    fn get_extractor(language: Language) -> Result<Box<dyn LanguageExtractor>, ExtractorError> {
-        match language {
-            Language::Python => Ok(Box::new(PythonExtractor)),
-            Language::Rust => Ok(Box::new(RustExtractor)),
-            Language::JavaScript => Ok(Box::new(JavaScriptExtractor)),
-            Language::TypeScript => Ok(Box::new(TypeScriptExtractor)),
-            Language::Go => Ok(Box::new(GoExtractor)),
-            Language::Cpp => Ok(Box::new(CppExtractor)),
+    /// Replace the provisional IDs of `symbols` with IDs built from their
+    /// scope path, point `parent_id` at the new IDs and fill in qualified
+    /// names
+    ///
+    /// Returns the namespace of the file and every namespace containing the
+    /// file or declared in it.
+    fn assign_names(
+        extractor: &dyn LanguageExtractor,
+        tree: &Tree,
+        source: &[u8],
+        file_path: &Path,
+        language: Language,
+        symbols: &mut [Symbol],
+    ) -> (Option<String>, Vec<Namespace>) {
+        let root = tree.root_node();
+        let separator = language.scope_separator();
+        let module_path = extractor.module_path(tree, source, file_path);
+
+        let mut namespaces = Vec::new();
+        let mut file_namespace = None;
+        for depth in 1..=module_path.len() {
+            let namespace = Namespace {
+                qualified_name: module_path[..depth].join(separator),
+                name: module_path[depth - 1]
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+                kind: extractor.module_kind(),
+                language,
+                parent: file_namespace.take(),
+            };
+            file_namespace = Some(namespace.qualified_name.clone());
+            namespaces.push(namespace);
         }
+
+        let mut occurrences: HashMap<String, usize> = HashMap::new();
+        let mut renamed: HashMap<String, String> = HashMap::new();
+
+        for symbol in symbols.iter_mut() {
+            let range = symbol.byte_range.clone();
+            // Enclosing scopes, innermost first, with the kind of namespace
+            // they declare
+            let mut scopes: Vec<(String, Option<NamespaceKind>)> = Vec::new();
+
+            if let Some(mut definition) = root.descendant_for_byte_range(range.start, range.end) {
+                // Climb to the outermost node inside the definition's range
+                while let Some(parent) = definition.parent()
+                    && parent.start_byte() >= range.start
+                    && parent.end_byte() <= range.end
+                {
+                    definition = parent;
+                }
+
+                scopes.extend(
+                    extractor
+                        .receiver_scope(definition, source)
+                        .map(|name| (name, None)),
+                );
+
+                let mut ancestor = definition.parent();
+                while let Some(node) = ancestor {
+                    if let Some(name) = extractor.scope_name(node, source) {
+                        scopes.push((name, extractor.namespace_kind(node)));
+                    }
+                    ancestor = node.parent();
+                }
+            }
+            scopes.reverse();
+
+            // Namespaces declared in the file nest inside the file's own
+            let mut qualified: Vec<&str> = module_path.iter().map(String::as_str).collect();
+            symbol.namespace = file_namespace.clone();
+            for (name, kind) in &scopes {
+                qualified.push(name);
+                if let Some(kind) = kind {
+                    let qualified_name = qualified.join(separator);
+                    if !namespaces.iter().any(|n| n.qualified_name == qualified_name) {
+                        namespaces.push(Namespace {
+                            qualified_name: qualified_name.clone(),
+                            name: name.clone(),
+                            kind: *kind,
+                            language,
+                            parent: symbol.namespace.clone(),
+                        });
+                    }
+                    symbol.namespace = Some(qualified_name);
+                }
+            }
+            qualified.push(&symbol.name);
+            symbol.qualified_name = qualified.join(separator);
+
+            // Repeated definitions (overloads, conditional redefinitions)
+            // are numbered in source order
+            let mut scoped: Vec<&str> = scopes.iter().map(|(name, _)| name.as_str()).collect();
+            scoped.push(&symbol.name);
+            let scoped_name = scoped.join(separator);
+            let count = occurrences.entry(scoped_name.clone()).or_default();
+            *count += 1;
+            let scoped_name = if *count > 1 {
+                format!("{}#{}", scoped_name, count)
+            } else {
+                scoped_name
+            };
+
+            // The parts of a partial type share one symbol across files
+            let id = if symbol.is_partial {
+                Symbol::partial_id(language, &symbol.qualified_name)
+            } else {
+                Symbol::generate_id(file_path, &scoped_name)
+            };
+            renamed.insert(std::mem::replace(&mut symbol.id, id.clone()), id);
+        }
+
+        for symbol in symbols.iter_mut() {
+            if let Some(parent_id) = symbol.parent_id.as_mut()
+                && let Some(id) = renamed.get(parent_id)
+            {
+                *parent_id = id.clone();
+            }
+        }
+
+        (file_namespace, namespaces)
     }
 
     fn compute_hash(source: &[u8]) -> String {
@@ -104,6 +304,25 @@ impl SymbolExtractor {
     }
 }
 
+/// The hand-written extractor for a language; runtime-loaded languages only
+/// have query extractors
+pub(crate) fn builtin_extractor(language: Language) -> Option<Box<dyn LanguageExtractor>> {
+    match language {
+        Language::Python => Some(Box::new(PythonExtractor)),
+        Language::Rust => Some(Box::new(RustExtractor)),
+        Language::JavaScript => Some(Box::new(JavaScriptExtractor)),
+        Language::TypeScript => Some(Box::new(TypeScriptExtractor)),
+        Language::Go => Some(Box::new(GoExtractor)),
+        Language::C => Some(Box::new(CExtractor)),
+        Language::Cpp => Some(Box::new(CppExtractor)),
+        Language::Java => Some(Box::new(JavaExtractor)),
+        Language::CSharp => Some(Box::new(CSharpExtractor)),
+        Language::Ruby => Some(Box::new(RubyExtractor)),
+        Language::Php => Some(Box::new(PhpExtractor)),
+        Language::Custom(_) => None,
+    }
+}
+
 /// Helper to get text from a node
 pub fn node_text<'a>(node: tree_sitter::Node<'a>, source: &'a [u8]) -> &'a str {
     node.utf8_text(source).unwrap_or("")
@@ -115,12 +334,22 @@ pub fn find_child<'a>(
     kind: &str,
 ) -> Option<tree_sitter::Node<'a>> {
     let mut cursor = node.walk();
-    for child in node.children(&mut cursor) {
-        if child.kind() == kind {
-            return Some(child);
-        }
-    }
-    None
+    node.children(&mut cursor).find(|child| child.kind() == kind)
+}
+
+/// Helper to check whether a node sits directly in the `body_kind` body of
+/// an `owner_kind` node, such as a method in a class body
+pub fn is_member_of(node: tree_sitter::Node, body_kind: &str, owner_kind: &str) -> bool {
+    node.parent()
+        .filter(|body| body.kind() == body_kind)
+        .and_then(|body| body.parent())
+        .is_some_and(|owner| owner.kind() == owner_kind)
+}
+
+/// Helper to get the text of a node's field
+pub fn field_text(node: tree_sitter::Node, field: &str, source: &[u8]) -> Option<String> {
+    node.child_by_field_name(field)
+        .map(|n| node_text(n, source).to_string())
 }
 
 /// Helper to find all children of a specific kind
@@ -149,17 +378,835 @@ pub fn find_all_nodes<'a>(
     }
 }
 
-/// Helper to get docstring from preceding comment/string
+/// Collect calls in a single walk of the tree, attributing each one to the
+/// innermost function, method or component whose byte range encloses it
+///
+/// `callee` maps a node of one of `call_kinds` to the callee name and
+/// receiver, or `None` to skip it. Calls outside any function are dropped.
+pub fn extract_scoped_calls<'a>(
+    root: tree_sitter::Node<'a>,
+    symbols: &[Symbol],
+    call_kinds: &[&str],
+    mut callee: impl FnMut(tree_sitter::Node<'a>) -> Option<(String, Option<String>)>,
+) -> Vec<Call> {
+    let scope_kinds = [SymbolKind::Function, SymbolKind::Method, SymbolKind::Component];
+    walk_scoped(root, symbols, &scope_kinds, call_kinds, |caller, node| {
+        let (callee_name, receiver) = callee(node)?;
+        (!callee_name.is_empty()).then(|| Call {
+            caller_id: caller.id.clone(),
+            callee_name,
+            receiver,
+            call_site_line: node.start_position().row as u32 + 1,
+        })
+    })
+}
+
+/// Visit every node of one of `node_kinds` in a single walk of the tree,
+/// passing it to `visit` with the innermost symbol of one of `scope_kinds`
+/// whose byte range encloses it
+///
+/// Nodes outside any such symbol are skipped.
+pub fn walk_scoped<'a, T>(
+    root: tree_sitter::Node<'a>,
+    symbols: &[Symbol],
+    scope_kinds: &[SymbolKind],
+    node_kinds: &[&str],
+    mut visit: impl FnMut(&Symbol, tree_sitter::Node<'a>) -> Option<T>,
+) -> Vec<T> {
+    // Outer scopes sort before the scopes nested in them
+    let mut scopes: Vec<&Symbol> = symbols
+        .iter()
+        .filter(|s| scope_kinds.contains(&s.kind))
+        .collect();
+    scopes.sort_by_key(|s| (s.byte_range.start, std::cmp::Reverse(s.byte_range.end)));
+
+    let mut results = Vec::new();
+    let mut pending = scopes.into_iter().peekable();
+    let mut enclosing: Vec<&Symbol> = Vec::new();
+    let mut cursor = root.walk();
+
+    // Pre-order traversal visits nodes in increasing start byte
+    loop {
+        let node = cursor.node();
+        if node_kinds.contains(&node.kind()) {
+            let offset = node.start_byte();
+            while let Some(scope) = pending.next_if(|s| s.byte_range.start <= offset) {
+                while enclosing
+                    .last()
+                    .is_some_and(|s| s.byte_range.end <= scope.byte_range.start)
+                {
+                    enclosing.pop();
+                }
+                enclosing.push(scope);
+            }
+            while enclosing.last().is_some_and(|s| s.byte_range.end <= offset) {
+                enclosing.pop();
+            }
+
+            if let Some(scope) = enclosing.last()
+                && let Some(result) = visit(scope, node)
+            {
+                results.push(result);
+            }
+        }
+
+        if cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return results;
+            }
+        }
+    }
+}
+
+/// Doc comment preceding a node, with comment markers stripped
+///
+/// Contiguous comments are joined, so runs of `///` or `//` lines read as one
+/// block. Attributes between the comments and the node (`#[derive(...)]`)
+/// are skipped, and a comment trailing code on its own line is left to that
+/// code.
 pub fn extract_docstring<'a>(
     node: tree_sitter::Node<'a>,
     source: &'a [u8],
     comment_kinds: &[&str],
 ) -> Option<String> {
-    // Look for preceding sibling that is a comment
-    if let Some(prev) = node.prev_sibling() {
-        if comment_kinds.contains(&prev.kind()) {
-            return Some(node_text(prev, source).to_string());
+    let mut comments = Vec::new();
+    let mut next_row = node.start_position().row;
+    let mut sibling = node.prev_sibling();
+    while let Some(prev) = sibling {
+        sibling = prev.prev_sibling();
+        if prev.kind() == "attribute_item" {
+            next_row = prev.start_position().row;
+            continue;
+        }
+        // A blank line separates the comment from the node
+        if !comment_kinds.contains(&prev.kind()) || last_row(prev) + 1 < next_row {
+            break;
         }
+        if let Some(before) = sibling
+            && !comment_kinds.contains(&before.kind())
+            && last_row(before) == prev.start_position().row
+        {
+            break;
+        }
+        comments.push(docs::clean(node_text(prev, source)));
+        next_row = prev.start_position().row;
+    }
+
+    comments.reverse();
+    let docstring = comments.join("\n");
+    (!docstring.trim().is_empty()).then(|| docstring.trim().to_string())
+}
+
+/// Last row holding text of a node; line comments can end at the start of
+/// the next row
+fn last_row(node: tree_sitter::Node) -> usize {
+    let end = node.end_position();
+    if end.column == 0 && end.row > node.start_position().row {
+        end.row - 1
+    } else {
+        end.row
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::languages::fixtures::{callers_of, extract, imports};
+    use crate::parser::pool::ParserPool;
+    use crate::{ReferenceKind, Visibility};
+
+    fn ids(file: &FileSymbols) -> Vec<&str> {
+        file.symbols.iter().map(|s| s.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_ids_survive_unrelated_edits() {
+        let source = "class Config:\n    def load(self):\n        pass\n\ndef main():\n    pass\n";
+        let before = extract(Language::Python, source);
+        let after = extract(Language::Python, &format!("import os\n\n{}", source));
+
+        assert_eq!(ids(&before), ["test::main", "test::Config", "test::Config.load"]);
+        assert_eq!(ids(&before), ids(&after));
+
+        let method = &after.symbols[2];
+        assert_eq!(method.start_line, 4);
+        assert_eq!(method.parent_id.as_deref(), Some("test::Config"));
+    }
+
+    #[test]
+    fn test_ids_qualified_by_scope() {
+        let file = extract(
+            Language::Rust,
+            "struct Foo;\nimpl Foo { fn new() -> Self { Foo } }\nimpl Default for Foo { fn default() -> Self { Foo::new() } }\nmod inner { fn new() {} }\n",
+        );
+        let rust_ids = ids(&file);
+        assert!(rust_ids.contains(&"test::Foo::new"));
+        assert!(rust_ids.contains(&"test::<Foo as Default>::default"));
+        assert!(rust_ids.contains(&"test::inner::new"));
+        assert_eq!(file.symbols.len(), 5);
+
+        let file = extract(Language::Go, "package p\nfunc (s *Stack) Push() {}\nfunc Push() {}\n");
+        assert_eq!(ids(&file), ["test::Push", "test::Stack.Push"]);
+    }
+
+    #[test]
+    fn test_qualified_names_and_namespaces() {
+        let source = "mod inner { pub struct Writer; impl Writer { fn flush(&self) {} } }\n";
+        let tree = ParserPool::parse(Language::Rust, source.as_bytes()).unwrap();
+        let file = SymbolExtractor::extract(
+            &tree,
+            source.as_bytes(),
+            Path::new("crates/core/src/graph/writer.rs"),
+            Language::Rust,
+        )
+        .unwrap();
+
+        let flush = file.symbols.iter().find(|s| s.name == "flush").unwrap();
+        assert_eq!(flush.qualified_name, "crate::graph::writer::inner::Writer::flush");
+        assert_eq!(flush.namespace.as_deref(), Some("crate::graph::writer::inner"));
+        assert_eq!(file.namespace.as_deref(), Some("crate::graph::writer"));
+
+        let namespaces: Vec<(&str, Option<&str>)> = file
+            .namespaces
+            .iter()
+            .map(|n| (n.qualified_name.as_str(), n.parent.as_deref()))
+            .collect();
+        assert_eq!(
+            namespaces,
+            [
+                ("crate", None),
+                ("crate::graph", Some("crate")),
+                ("crate::graph::writer", Some("crate::graph")),
+                ("crate::graph::writer::inner", Some("crate::graph::writer")),
+            ]
+        );
+
+        let file = extract(
+            Language::Cpp,
+            "namespace a { namespace b { class C { void run(); }; } }\n",
+        );
+        let class = file.symbols.iter().find(|s| s.name == "C").unwrap();
+        assert_eq!(class.qualified_name, "a::b::C");
+        assert_eq!(class.namespace.as_deref(), Some("a::b"));
+    }
+
+    #[test]
+    fn test_components_and_renders() {
+        let source = r#"
+import React, { memo } from "react";
+
+export function App({ user }: Props) {
+    const title = formatTitle(user);
+    return <Layout><Header title={title} /><Icons.Logo /><div /></Layout>;
+}
+
+const Header = ({ title }: { title: string }) => <h1>{title}</h1>;
+const Row = memo(function Row() { return <li />; });
+function formatTitle(user: User): string { return user.name; }
+
+class Layout extends React.Component<Props> {
+    render() { return <main>{this.props.children}</main>; }
+}
+"#;
+        let tree =
+            ParserPool::parse_file(Language::TypeScript, Path::new("App.tsx"), source.as_bytes())
+                .unwrap();
+        assert!(!tree.root_node().has_error());
+        let file = SymbolExtractor::extract(
+            &tree,
+            source.as_bytes(),
+            Path::new("App.tsx"),
+            Language::TypeScript,
+        )
+        .unwrap();
+
+        let kind = |name: &str| file.symbols.iter().find(|s| s.name == name).unwrap().kind;
+        assert_eq!(kind("App"), SymbolKind::Component);
+        assert_eq!(kind("Header"), SymbolKind::Component);
+        assert_eq!(kind("Row"), SymbolKind::Component);
+        assert_eq!(kind("Layout"), SymbolKind::Component);
+        assert_eq!(kind("formatTitle"), SymbolKind::Function);
+
+        let renders: Vec<(&str, Option<&str>)> = file
+            .renders
+            .iter()
+            .map(|r| (r.element_name.as_str(), r.receiver.as_deref()))
+            .collect();
+        assert_eq!(renders, [("Layout", None), ("Header", None), ("Logo", Some("Icons"))]);
+        assert!(file.renders.iter().all(|r| r.component_id == "App.tsx::App"));
+        assert_eq!(callers_of(&file, "formatTitle"), ["App"]);
+
+        let source = "class Page extends Component { render() { return <Nav />; } }\n";
+        let file = extract(Language::JavaScript, source);
+        assert_eq!(file.symbols[0].kind, SymbolKind::Component);
+        assert_eq!(file.renders[0].element_name, "Nav");
+    }
+
+    #[test]
+    fn test_repeated_definitions_numbered() {
+        let file = extract(Language::Python, "def run():\n    pass\n\ndef run():\n    pass\n");
+        assert_eq!(ids(&file), ["test::run", "test::run#2"]);
+    }
+
+    #[test]
+    fn test_calls_attributed_to_innermost_function() {
+        let file = extract(
+            Language::Python,
+            "def outer():\n    def inner():\n        helper()\n    inner()\n    other()\n\nsetup()\n",
+        );
+
+        assert_eq!(callers_of(&file, "helper"), ["inner"]);
+        assert_eq!(callers_of(&file, "inner"), ["outer"]);
+        assert_eq!(callers_of(&file, "other"), ["outer"]);
+        assert!(callers_of(&file, "setup").is_empty());
+    }
+
+    #[test]
+    fn test_calls_on_shared_line() {
+        let file = extract(
+            Language::Rust,
+            "fn a() { b(); } fn b() { c(); }\nimpl S { fn run(&self) { self.step(); } }\n",
+        );
+
+        assert_eq!(callers_of(&file, "b"), ["a"]);
+        assert_eq!(callers_of(&file, "c"), ["b"]);
+        assert_eq!(callers_of(&file, "step"), ["run"]);
+        assert_eq!(file.calls.len(), 3);
+    }
+
+    #[test]
+    fn test_python_decorators_and_annotations() {
+        let source = r#"import abc
+from typing import Optional
+
+@dataclass(frozen=True)
+class Account(abc.ABC):
+    @property
+    def owner(self) -> "models.User":
+        return self._owner
+
+    @owner.setter
+    def owner(self, value: User) -> None:
+        self._owner = value
+
+    @staticmethod
+    def parse(raw: str) -> Optional[Account]:
+        pass
+
+    @classmethod
+    def empty(cls) -> Account:
+        pass
+
+    @abc.abstractmethod
+    def close(self, reason: Reason = None):
+        pass
+
+@app.route("/users", methods=["GET"])
+def list_users(query: Query, limit: int = 10) -> list[User]:
+    pass
+"#;
+        let file = extract(Language::Python, source);
+
+        let symbol = |id: &str| file.symbols.iter().find(|s| s.id == id).unwrap();
+        assert_eq!(
+            ids(&file),
+            [
+                "test::list_users",
+                "test::Account",
+                "test::Account.owner",
+                "test::Account.owner#2",
+                "test::Account.parse",
+                "test::Account.empty",
+                "test::Account.close",
+            ]
+        );
+
+        let account = symbol("test::Account");
+        assert_eq!(account.decorators[0].name, "dataclass");
+        assert_eq!(account.decorators[0].arguments.as_deref(), Some("(frozen=True)"));
+        assert_eq!(account.decorators[0].line, 4);
+
+        assert!(symbol("test::Account.owner").modifiers.is_property);
+        assert!(symbol("test::Account.owner#2").modifiers.is_property);
+        assert!(symbol("test::Account.parse").modifiers.is_static);
+        assert!(symbol("test::Account.empty").modifiers.is_class_method);
+        let close = symbol("test::Account.close");
+        assert!(close.modifiers.is_abstract && !close.modifiers.is_static);
+        assert_eq!(symbol("test::list_users").decorators[0].name, "app.route");
+
+        let references = |kind: ReferenceKind, id: &str| -> Vec<(Option<&str>, &str)> {
+            file.references
+                .iter()
+                .filter(|r| r.kind == kind && r.source_id == id)
+                .map(|r| (r.receiver.as_deref(), r.target_name.as_str()))
+                .collect()
+        };
+        assert_eq!(
+            references(ReferenceKind::DecoratedBy, "test::list_users"),
+            [(Some("app"), "route")]
+        );
+        assert_eq!(
+            references(ReferenceKind::DecoratedBy, "test::Account.owner#2"),
+            [(Some("owner"), "setter")]
+        );
+        assert_eq!(
+            references(ReferenceKind::UsesType, "test::Account.owner"),
+            [(Some("models"), "User")]
+        );
+        assert_eq!(
+            references(ReferenceKind::UsesType, "test::Account.parse"),
+            [(None, "str"), (None, "Optional"), (None, "Account")]
+        );
+        assert_eq!(
+            references(ReferenceKind::UsesType, "test::list_users"),
+            [(None, "Query"), (None, "int"), (None, "list"), (None, "User")]
+        );
+        assert_eq!(references(ReferenceKind::UsesType, "test::Account.close"), [(None, "Reason")]);
+    }
+
+    #[test]
+    fn test_rust_items() {
+        let source = r#"macro_rules! square {
+    ($x:expr) => { $x * $x };
+}
+
+pub const LIMIT: u32 = square!(4);
+static mut COUNTER: u64 = 0;
+pub type Result<T> = std::result::Result<T, Error>;
+
+pub mod config {}
+
+pub struct Point<T> {
+    /// Horizontal position
+    pub x: T,
+    y: T,
+}
+
+pub enum Shape {
+    Circle { radius: f64 },
+    Empty,
+}
+
+pub trait Store {
+    type Key;
+    const NAME: &'static str;
+}
+
+impl<T> Point<T> {
+    const ORIGIN: u8 = 0;
+
+    fn area(&self) -> u32 {
+        println!("{}", LIMIT);
+        tracing::debug!("area");
+        square!(2)
+    }
+}
+"#;
+        let file = extract(Language::Rust, source);
+
+        let symbol = |name: &str| file.symbols.iter().find(|s| s.name == name).unwrap();
+        let kinds: Vec<(&str, SymbolKind)> = [
+            "square", "LIMIT", "COUNTER", "Result", "config", "x", "y", "Circle", "Empty", "Key",
+            "NAME", "ORIGIN",
+        ]
+        .iter()
+        .map(|name| (*name, symbol(name).kind))
+        .collect();
+        assert_eq!(
+            kinds,
+            [
+                ("square", SymbolKind::Macro),
+                ("LIMIT", SymbolKind::Constant),
+                ("COUNTER", SymbolKind::Variable),
+                ("Result", SymbolKind::TypeAlias),
+                ("config", SymbolKind::Module),
+                ("x", SymbolKind::Field),
+                ("y", SymbolKind::Field),
+                ("Circle", SymbolKind::EnumVariant),
+                ("Empty", SymbolKind::EnumVariant),
+                ("Key", SymbolKind::TypeAlias),
+                ("NAME", SymbolKind::Constant),
+                ("ORIGIN", SymbolKind::Constant),
+            ]
+        );
+
+        let x = symbol("x");
+        assert_eq!(x.qualified_name, "crate::Point::x");
+        assert_eq!(x.parent_id.as_deref(), Some("test::Point"));
+        assert_eq!(x.docstring.as_deref(), Some("Horizontal position"));
+        assert_eq!(x.visibility, Visibility::Public);
+        assert_eq!(symbol("y").visibility, Visibility::Private);
+        assert_eq!(symbol("Empty").visibility, Visibility::Public);
+        assert_eq!(symbol("NAME").parent_id.as_deref(), Some("test::Store"));
+        assert_eq!(symbol("NAME").visibility, Visibility::Public);
+        // Impls of generic types belong to the type
+        assert_eq!(symbol("ORIGIN").parent_id.as_deref(), Some("test::Point"));
+        assert_eq!(symbol("area").parent_id.as_deref(), Some("test::Point"));
+        assert_eq!(symbol("square").signature.as_deref(), Some("macro_rules! square"));
+
+        let calls: Vec<(&str, Option<&str>)> = file
+            .calls
+            .iter()
+            .map(|c| (c.callee_name.as_str(), c.receiver.as_deref()))
+            .collect();
+        assert_eq!(calls, [("println", None), ("debug", Some("tracing")), ("square", None)]);
+    }
+
+    #[test]
+    fn test_doc_comments() {
+        let symbol = |language: Language, source: &str, name: &str| -> Symbol {
+            let file = extract(language, source);
+            file.symbols.into_iter().find(|s| s.name == name).unwrap()
+        };
+
+        let source = r#"// Licensed under MIT
+
+/// Opens the store.
+///
+/// # Errors
+///
+/// Fails when the file is corrupt.
+#[derive(Debug)]
+pub struct Store;
+
+const X: u8 = 1; // not a doc
+fn bare() {}
+"#;
+        let store = symbol(Language::Rust, source, "Store");
+        assert_eq!(
+            store.docstring.as_deref(),
+            Some("Opens the store.\n\n# Errors\n\nFails when the file is corrupt.")
+        );
+        assert_eq!(store.doc_sections.raises[0].description, "Fails when the file is corrupt.");
+        assert_eq!(symbol(Language::Rust, source, "bare").docstring, None);
+
+        let source = "package main\n\n// Run starts the server\n// and blocks.\nfunc Run() {}\n";
+        assert_eq!(
+            symbol(Language::Go, source, "Run").docstring.as_deref(),
+            Some("Run starts the server\nand blocks.")
+        );
+
+        let source = r#"/**
+ * Save a user.
+ * @param {User} user - The user
+ * @returns {boolean} Whether it was saved
+ */
+function save(user) {}
+"#;
+        let save = symbol(Language::TypeScript, source, "save");
+        assert_eq!(save.doc_sections.params[0].name, "user");
+        assert_eq!(save.doc_sections.returns.as_deref(), Some("Whether it was saved"));
+
+        let source = r#"def fetch(table):
+    # comment before the docstring
+    """Fetch rows.
+
+    Args:
+        table: Table name.
+
+    Raises:
+        KeyError: If missing.
+    """
+    return []
+"#;
+        let fetch = symbol(Language::Python, source, "fetch");
+        assert!(fetch.docstring.as_deref().unwrap().starts_with("Fetch rows.\n\nArgs:\n    table"));
+        assert_eq!(fetch.doc_sections.params[0].description, "Table name.");
+        assert_eq!(fetch.doc_sections.raises[0].name, "KeyError");
+    }
+
+    #[test]
+    fn test_visibility() {
+        let visibility = |language: Language, source: &str| -> Vec<(String, Visibility)> {
+            extract(language, source)
+                .symbols
+                .into_iter()
+                .map(|s| (s.name, s.visibility))
+                .collect()
+        };
+        let (public, private, protected) =
+            (Visibility::Public, Visibility::Private, Visibility::Protected);
+
+        let source = "pub fn a() {}\npub(crate) fn b() {}\npub(super) fn c() {}\npub(in crate::x) fn d() {}\npub(self) fn e() {}\nfn f() {}\n";
+        let rust = visibility(Language::Rust, source);
+        let expected = [
+            ("a", public),
+            ("b", Visibility::Internal),
+            ("c", Visibility::Super),
+            ("d", Visibility::Restricted),
+            ("e", private),
+            ("f", private),
+        ];
+        assert_eq!(rust, expected.map(|(n, v)| (n.to_string(), v)));
+
+        let go = visibility(Language::Go, "package p\nfunc Run() {}\nfunc run() {}\n");
+        assert_eq!(go, [("Run".to_string(), public), ("run".to_string(), private)]);
+
+        let source = r#"__all__ = ["load"]
+
+def load():
+    def inner():
+        pass
+
+def dump():
+    pass
+
+class _Cache:
+    def __init__(self):
+        pass
+
+    def _evict(self):
+        pass
+
+    def __key(self):
+        pass
+"#;
+        let python = visibility(Language::Python, source);
+        let expected = [
+            ("load", public),
+            ("inner", private),
+            ("dump", private),
+            ("_Cache", private),
+            ("__init__", public),
+            ("_evict", protected),
+            ("__key", private),
+        ];
+        assert_eq!(python, expected.map(|(n, v)| (n.to_string(), v)));
+
+        let source = r#"export function save() {}
+function helper() {}
+const format = () => {};
+export { format };
+export default class Store {
+    #cache() {}
+    private load() {}
+    protected flush() {}
+    get() {}
+}
+"#;
+        let typescript = visibility(Language::TypeScript, source);
+        let find = |name: &str| typescript.iter().find(|(n, _)| n == name).unwrap().1;
+        assert_eq!(find("save"), public);
+        assert_eq!(find("helper"), private);
+        assert_eq!(find("format"), public);
+        assert_eq!(find("Store"), public);
+        assert_eq!(find("#cache"), private);
+        assert_eq!(find("load"), private);
+        assert_eq!(find("flush"), protected);
+        assert_eq!(find("get"), public);
+
+        // Scripts without imports or exports define globals
+        let javascript = visibility(Language::JavaScript, "function main() {}\n");
+        assert_eq!(javascript, [("main".to_string(), public)]);
+        let source = "function main() {}\nfunction api() {}\nmodule.exports = { api };\n";
+        let javascript = visibility(Language::JavaScript, source);
+        assert_eq!(
+            javascript,
+            [("main".to_string(), private), ("api".to_string(), public)]
+        );
+
+        let source = r#"class Widget {
+    void init();
+public:
+    void draw() const;
+    int size() { return 0; }
+protected:
+    virtual void paint();
+};
+struct Point { int norm(); private: void reset(); };
+static void local() {}
+namespace { void hidden() {} }
+"#;
+        let cpp = visibility(Language::Cpp, source);
+        let find = |name: &str| cpp.iter().find(|(n, _)| n == name).unwrap().1;
+        assert_eq!(find("init"), private);
+        assert_eq!(find("draw"), public);
+        assert_eq!(find("size"), public);
+        assert_eq!(find("paint"), protected);
+        assert_eq!(find("norm"), public);
+        assert_eq!(find("reset"), private);
+        assert_eq!(find("local"), private);
+        assert_eq!(find("hidden"), private);
+    }
+
+    #[test]
+    fn test_go_interfaces() {
+        let source = r#"package store
+
+type Reader interface {
+	// Read fills p
+	Read(p []byte) (n int, err error)
+}
+
+type Celsius float64
+
+func (c Celsius) Read(p []byte) (int, error) { return 0, nil }
+"#;
+        let file = extract(Language::Go, source);
+        let summary: Vec<(&str, SymbolKind, Option<&str>)> = file
+            .symbols
+            .iter()
+            .map(|s| (s.qualified_name.as_str(), s.kind, s.parent_id.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("store.Celsius.Read", SymbolKind::Method, None),
+                ("store.Reader", SymbolKind::Interface, None),
+                (
+                    "store.Reader.Read",
+                    SymbolKind::Method,
+                    Some("test::Reader")
+                ),
+                ("store.Celsius", SymbolKind::TypeAlias, None),
+            ]
+        );
+
+        let read = &file.symbols[2];
+        assert_eq!(
+            read.signature.as_deref(),
+            Some("Read(p []byte) (n int, err error)")
+        );
+        assert_eq!(read.docstring.as_deref(), Some("Read fills p"));
+        assert!(read.modifiers.is_abstract);
+
+        let file = extract(
+            Language::Go,
+            "package store
+
+type ReadCloser interface {
+	Reader
+	io.Closer
+	Flush() error
+}
+",
+        );
+        let embeds: Vec<(&str, &str)> = file
+            .inheritance
+            .iter()
+            .map(|i| (i.child_id.as_str(), i.parent_name.as_str()))
+            .collect();
+        assert_eq!(
+            embeds,
+            [("test::ReadCloser", "Reader"), ("test::ReadCloser", "Closer")]
+        );
+    }
+
+    #[test]
+    fn test_go_signatures_with_empty_type_parameters() {
+        let source = "package log\n\nfunc (l *Logger) Print(v interface{}, o struct{}) error {\n\treturn nil\n}\n\nfunc Any(v interface{}) {}\n";
+        let file = extract(Language::Go, source);
+        let signatures: Vec<&str> = file
+            .symbols
+            .iter()
+            .filter_map(|s| s.signature.as_deref())
+            .collect();
+        assert_eq!(
+            signatures,
+            [
+                "func Any(v interface{})",
+                "func (l *Logger) Print(v interface{}, o struct{}) error",
+            ]
+        );
+        assert_eq!(
+            crate::resolver::implements::arity(signatures[1], "Print"),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn test_python_renamed_imports() {
+        let source = "import numpy as np\nfrom .helpers import h as hh, g, k as kk\nfrom .util import f as ff\n";
+        let file = extract(Language::Python, source);
+        assert_eq!(
+            imports(&file),
+            [
+                ("numpy", Some("np"), vec![]),
+                (".helpers", None, vec!["g"]),
+                (".helpers", Some("hh"), vec!["h"]),
+                (".helpers", Some("kk"), vec!["k"]),
+                (".util", Some("ff"), vec!["f"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_typescript_reexports() {
+        let source = "import Button, { SIZES as sizes } from './Button';\nimport { useState, useEffect as effect } from 'react';\nexport { Card as Panel } from './card';\nexport * from './theme';\nexport * as icons from './icons';\n";
+        let file = extract(Language::TypeScript, source);
+        assert_eq!(
+            imports(&file),
+            [
+                ("./Button", Some("Button"), vec!["default"]),
+                ("./Button", Some("sizes"), vec!["SIZES"]),
+                ("react", None, vec!["useState"]),
+                ("react", Some("effect"), vec!["useEffect"]),
+                ("./card", None, vec!["Card"]),
+                ("./theme", None, vec!["*"]),
+                ("./icons", Some("icons"), vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cpp_templates_and_out_of_line_definitions() {
+        let source = r#"namespace geo {
+template <typename T>
+class Box {
+public:
+    Box();
+    virtual T get() const = 0;
+    template <typename V> void visit(V& v);
+    static Box* make();
+};
+
+int free_fn(int a);
+}
+
+template <typename T>
+T geo::Box<T>::get() const { return T(); }
+int geo::free_fn(int a) { return a; }
+void local() { int helper(int); }
+"#;
+        let file = extract(Language::Cpp, source);
+        let summary: Vec<(&str, SymbolKind, Option<&str>, bool)> = file
+            .symbols
+            .iter()
+            .map(|s| {
+                (
+                    s.id.as_str(),
+                    s.kind,
+                    s.parent_id.as_deref(),
+                    s.modifiers.is_declaration,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("test::geo::Box", SymbolKind::Class, None, false),
+                ("test::geo::Box::Box", SymbolKind::Method, Some("test::geo::Box"), true),
+                ("test::geo::Box::get", SymbolKind::Method, Some("test::geo::Box"), true),
+                ("test::geo::Box::visit", SymbolKind::Method, Some("test::geo::Box"), true),
+                ("test::geo::Box::make", SymbolKind::Method, Some("test::geo::Box"), true),
+                ("test::geo::free_fn", SymbolKind::Function, None, true),
+                ("test::geo::Box::get#2", SymbolKind::Method, Some("test::geo::Box"), false),
+                ("test::geo::free_fn#2", SymbolKind::Function, None, false),
+                ("test::local", SymbolKind::Function, None, false),
+            ]
+        );
+
+        let signature = |i: usize| file.symbols[i].signature.as_deref();
+        assert_eq!(signature(0), Some("template <typename T> class Box {"));
+        assert_eq!(signature(3), Some("template <typename V> void visit(V& v)"));
+        assert_eq!(
+            signature(6),
+            Some("template <typename T> T geo::Box<T>::get() const")
+        );
+        assert!(file.symbols[2].modifiers.is_abstract);
+        assert!(file.symbols[4].modifiers.is_static);
     }
-    None
 }
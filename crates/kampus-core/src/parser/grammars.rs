@@ -0,0 +1,239 @@
+//! Tree-sitter grammars loaded from shared libraries at runtime
+//!
+//! Languages beyond the built-in ones are listed in `.kampus/grammars.toml`:
+//!
+//! ```toml
+//! [[grammar]]
+//! name = "kotlin"
+//! library = "/usr/local/lib/libtree-sitter-kotlin.so"
+//! extensions = ["kt", "kts"]
+//! # Optional: defaults to .kampus/queries/kotlin.scm
+//! query = "tools/kotlin-tags.scm"
+//! # Optional: defaults to tree_sitter_<name>
+//! symbol = "tree_sitter_kotlin"
+//! # Optional: defaults to "."
+//! scope_separator = "."
+//! ```
+//!
+//! Each grammar becomes a [`Language::Custom`] whose files are extracted by a
+//! [`QueryExtractor`](super::QueryExtractor) using the grammar's query.
+
+use crate::Language;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use thiserror::Error;
+
+/// Grammar configuration file, relative to the project root
+pub const GRAMMARS_FILE: &str = ".kampus/grammars.toml";
+
+#[derive(Error, Debug)]
+pub enum GrammarError {
+    #[error("Invalid {GRAMMARS_FILE}: {0}")]
+    Config(#[from] toml::de::Error),
+    #[error("Grammar {0} conflicts with a built-in language")]
+    Builtin(String),
+    #[error("Failed to load grammar {0} from {1:?}: {2}")]
+    Library(String, PathBuf, libloading::Error),
+    #[error("Grammar {0} has ABI version {1}, expected {2} to {3}")]
+    IncompatibleVersion(String, usize, usize, usize),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// One `[[grammar]]` entry
+#[derive(Debug, Clone, Deserialize)]
+pub struct GrammarConfig {
+    /// Language name, used by `--language` and the graph `language` property
+    pub name: String,
+    /// Path to the shared library, relative to the project root or absolute
+    pub library: PathBuf,
+    /// File extensions handled by the grammar, without the dot
+    pub extensions: Vec<String>,
+    /// Query file driving extraction
+    #[serde(default)]
+    pub query: Option<PathBuf>,
+    /// Exported function returning the grammar
+    #[serde(default)]
+    pub symbol: Option<String>,
+    /// Separator between the scopes of a qualified name
+    #[serde(default)]
+    pub scope_separator: Option<String>,
+}
+
+impl GrammarConfig {
+    /// Exported function returning the grammar
+    pub fn symbol(&self) -> String {
+        self.symbol
+            .clone()
+            .unwrap_or_else(|| format!("tree_sitter_{}", self.name.to_lowercase().replace('-', "_")))
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct GrammarsFile {
+    #[serde(default, rename = "grammar")]
+    grammars: Vec<GrammarConfig>,
+}
+
+/// Grammars loaded so far, by language name. Libraries are never unloaded,
+/// since parsers and trees keep pointers into them.
+static LOADED: RwLock<Vec<(&'static str, tree_sitter::Language)>> = RwLock::new(Vec::new());
+
+/// Read the grammar entries of a project, if it has any
+pub fn read_config(root: &Path) -> Result<Vec<GrammarConfig>, GrammarError> {
+    let path = root.join(GRAMMARS_FILE);
+    if !path.is_file() {
+        return Ok(Vec::new());
+    }
+    let file: GrammarsFile = toml::from_str(&std::fs::read_to_string(path)?)?;
+    Ok(file.grammars)
+}
+
+/// Register the languages of a project's grammars without loading them, so
+/// that their names and extensions are recognised
+pub fn register(root: &Path) -> Result<Vec<(Language, GrammarConfig)>, GrammarError> {
+    read_config(root)?
+        .into_iter()
+        .map(|config| {
+            if Language::BUILTIN.iter().any(|l| config.name.parse() == Ok(*l)) {
+                return Err(GrammarError::Builtin(config.name));
+            }
+            let language = Language::register(
+                &config.name,
+                &config.extensions,
+                config.scope_separator.as_deref().unwrap_or("."),
+            );
+            Ok((language, config))
+        })
+        .collect()
+}
+
+/// Register and load the grammars of a project
+pub fn load(root: &Path) -> Result<Vec<(Language, GrammarConfig)>, GrammarError> {
+    let grammars = register(root)?;
+    for (language, config) in &grammars {
+        let Language::Custom(name) = *language else {
+            continue;
+        };
+        if tree_sitter_language(name).is_some() {
+            continue;
+        }
+
+        let path = root.join(&config.library);
+        let grammar = load_library(&path, &config.symbol())
+            .map_err(|e| GrammarError::Library(config.name.clone(), path.clone(), e))?;
+
+        let version = grammar.abi_version();
+        if !(tree_sitter::MIN_COMPATIBLE_LANGUAGE_VERSION..=tree_sitter::LANGUAGE_VERSION)
+            .contains(&version)
+        {
+            return Err(GrammarError::IncompatibleVersion(
+                config.name.clone(),
+                version,
+                tree_sitter::MIN_COMPATIBLE_LANGUAGE_VERSION,
+                tree_sitter::LANGUAGE_VERSION,
+            ));
+        }
+
+        LOADED
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((name, grammar));
+    }
+    Ok(grammars)
+}
+
+fn load_library(path: &Path, symbol: &str) -> Result<tree_sitter::Language, libloading::Error> {
+    // SAFETY: the library is trusted project configuration and the symbol is
+    // a tree-sitter grammar constructor taking no arguments
+    unsafe {
+        let library = libloading::Library::new(path)?;
+        let constructor: libloading::Symbol<unsafe extern "C" fn() -> *const ()> =
+            library.get(symbol.as_bytes())?;
+        let language = tree_sitter_language::LanguageFn::from_raw(*constructor);
+        // Keep the library loaded for the rest of the process
+        std::mem::forget(library);
+        Ok(tree_sitter::Language::new(language))
+    }
+}
+
+/// The loaded grammar of a runtime language
+pub(crate) fn tree_sitter_language(name: &str) -> Option<tree_sitter::Language> {
+    LOADED
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|(loaded, _)| *loaded == name)
+        .map(|(_, language)| language.clone())
+}
+
+/// Query sources for the loaded grammars that name a query file
+pub(crate) fn queries(
+    root: &Path,
+    grammars: &[(Language, GrammarConfig)],
+) -> Result<HashMap<Language, String>, GrammarError> {
+    let mut queries = HashMap::new();
+    for (language, config) in grammars {
+        if let Some(query) = &config.query {
+            queries.insert(*language, std::fs::read_to_string(root.join(query))?);
+        }
+    }
+    Ok(queries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_grammars() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".kampus")).unwrap();
+        std::fs::write(
+            dir.path().join(GRAMMARS_FILE),
+            "[[grammar]]\nname = \"Kotlin\"\nlibrary = \"grammars/kotlin.so\"\nextensions = [\"kt\", \"kts\"]\n",
+        )
+        .unwrap();
+
+        let grammars = register(dir.path()).unwrap();
+        let kotlin = grammars[0].0;
+        assert_eq!(kotlin, Language::Custom("kotlin"));
+        assert_eq!(grammars[0].1.symbol(), "tree_sitter_kotlin");
+        assert_eq!(Language::from_extension("kts"), Some(kotlin));
+        assert_eq!("kotlin".parse(), Ok(kotlin));
+        assert_eq!(serde_json::to_string(&kotlin).unwrap(), "\"kotlin\"");
+        assert_eq!(serde_json::from_str::<Language>("\"kotlin\"").unwrap(), kotlin);
+
+        // The library does not exist
+        assert!(matches!(load(dir.path()), Err(GrammarError::Library(..))));
+
+        std::fs::write(
+            dir.path().join(GRAMMARS_FILE),
+            "[[grammar]]\nname = \"python\"\nlibrary = \"python.so\"\nextensions = [\"py\"]\n",
+        )
+        .unwrap();
+        assert!(matches!(register(dir.path()), Err(GrammarError::Builtin(_))));
+    }
+
+    #[test]
+    fn test_register_again_reuses_the_language() {
+        // A name first seen in stored data gets its extensions later
+        let zig = serde_json::from_str::<Language>("\"zig\"").unwrap();
+        assert_eq!(Language::from_extension("zig"), None);
+
+        let extensions = vec!["zig".to_string()];
+        assert_eq!(Language::register("Zig", &extensions, "."), zig);
+        assert_eq!(Language::from_extension("zig"), Some(zig));
+
+        let leaked = |name: &str| {
+            let languages = crate::CUSTOM_LANGUAGES.read().unwrap();
+            let language = languages.iter().find(|l| l.name == name).unwrap();
+            (language.name.as_ptr(), language.extensions.as_ptr())
+        };
+        let before = leaked("zig");
+        assert_eq!(Language::register("zig", &extensions, "."), zig);
+        assert_eq!(leaked("zig"), before);
+    }
+}
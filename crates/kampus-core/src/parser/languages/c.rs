@@ -0,0 +1,372 @@
+//! C language extractor
+
+use crate::parser::extractor::{
+    extract_docstring, extract_scoped_calls, field_text, find_all_nodes, find_child, node_text,
+    LanguageExtractor,
+};
+use crate::parser::ParserPool;
+use crate::{
+    Call, DocSections, Import, Inheritance, Language, Modifiers, Symbol, SymbolKind, Visibility,
+};
+use std::path::Path;
+use tree_sitter::{Node, Tree};
+
+/// Node kinds of the C++ grammar that C has no counterpart for
+const CPP_ONLY_KINDS: [&str; 9] = [
+    "class_specifier",
+    "namespace_definition",
+    "template_declaration",
+    "access_specifier",
+    "using_declaration",
+    "alias_declaration",
+    "qualified_identifier",
+    "reference_declarator",
+    "operator_name",
+];
+
+/// Extensions of C++ source files that may include a `.h` header
+const CPP_SOURCE_EXTENSIONS: [&str; 4] = ["cpp", "cc", "cxx", "c++"];
+
+pub struct CExtractor;
+
+impl LanguageExtractor for CExtractor {
+    fn extract_symbols(&self, tree: &Tree, source: &[u8], file_path: &Path) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+        let root = tree.root_node();
+
+        // Function definitions
+        let mut func_nodes = Vec::new();
+        find_all_nodes(root, "function_definition", &mut func_nodes);
+        for node in func_nodes {
+            let Some(name) = node
+                .child_by_field_name("declarator")
+                .and_then(|d| declarator_name(d, source))
+            else {
+                continue;
+            };
+            let mut symbol = self.symbol(node, source, file_path, name, SymbolKind::Function);
+            symbol.signature = Some(signature(node, source));
+            if storage_class(node, source) == Some("static") {
+                symbol.visibility = Visibility::Private;
+            }
+            symbols.push(symbol);
+        }
+
+        // Struct, union and enum definitions; references such as
+        // `struct point *p` have no body
+        let mut type_nodes = Vec::new();
+        find_all_nodes(root, "struct_specifier", &mut type_nodes);
+        find_all_nodes(root, "union_specifier", &mut type_nodes);
+        find_all_nodes(root, "enum_specifier", &mut type_nodes);
+        for node in type_nodes {
+            if node.child_by_field_name("body").is_none() {
+                continue;
+            }
+            let Some(name) = field_text(node, "name", source) else {
+                continue;
+            };
+            symbols.push(self.symbol(node, source, file_path, name, specifier_kind(node)));
+        }
+
+        // Typedefs; `typedef struct { ... } point;` defines the struct itself
+        let mut typedef_nodes = Vec::new();
+        find_all_nodes(root, "type_definition", &mut typedef_nodes);
+        for node in typedef_nodes {
+            let kind = node
+                .child_by_field_name("type")
+                .filter(|t| {
+                    t.child_by_field_name("body").is_some() && t.child_by_field_name("name").is_none()
+                })
+                .map_or(SymbolKind::TypeAlias, specifier_kind);
+
+            let mut cursor = node.walk();
+            for declarator in node.children_by_field_name("declarator", &mut cursor) {
+                if let Some(name) = declarator_name(declarator, source) {
+                    symbols.push(self.symbol(node, source, file_path, name, kind));
+                }
+            }
+        }
+
+        // Function-like macros
+        let mut macro_nodes = Vec::new();
+        find_all_nodes(root, "preproc_function_def", &mut macro_nodes);
+        for node in macro_nodes {
+            let Some(name) = field_text(node, "name", source) else {
+                continue;
+            };
+            let mut symbol = self.symbol(node, source, file_path, name, SymbolKind::Macro);
+            symbol.signature = Some(format!(
+                "#define {}{}",
+                symbol.name,
+                field_text(node, "parameters", source).unwrap_or_default()
+            ));
+            symbols.push(symbol);
+        }
+
+        symbols
+    }
+
+    fn extract_imports(&self, tree: &Tree, source: &[u8], file_path: &Path) -> Vec<Import> {
+        let mut include_nodes = Vec::new();
+        find_all_nodes(tree.root_node(), "preproc_include", &mut include_nodes);
+
+        // #include "local.h", #include <stdio.h>
+        include_nodes
+            .into_iter()
+            .filter_map(|node| {
+                let path = node.child_by_field_name("path")?;
+                if !matches!(path.kind(), "string_literal" | "system_lib_string") {
+                    return None;
+                }
+                Some(Import {
+                    source_file: file_path.to_path_buf(),
+                    target: node_text(path, source)
+                        .trim_matches(|c| matches!(c, '"' | '<' | '>'))
+                        .to_string(),
+                    alias: None,
+                    items: Vec::new(),
+                    line: node.start_position().row as u32 + 1,
+                    resolved: None,
+                })
+            })
+            .collect()
+    }
+
+    fn extract_calls(&self, tree: &Tree, source: &[u8], symbols: &[Symbol]) -> Vec<Call> {
+        extract_scoped_calls(tree.root_node(), symbols, &["call_expression"], |call_node| {
+            let function = call_node.child_by_field_name("function")?;
+            match function.kind() {
+                // run(), including function-like macros
+                "identifier" => Some((node_text(function, source).to_string(), None)),
+                // ops->run(), ops.run() through function pointers
+                "field_expression" => Some((
+                    field_text(function, "field", source)?,
+                    field_text(function, "argument", source),
+                )),
+                _ => None,
+            }
+        })
+    }
+
+    fn extract_inheritance(
+        &self,
+        _tree: &Tree,
+        _source: &[u8],
+        _symbols: &[Symbol],
+    ) -> Vec<Inheritance> {
+        Vec::new()
+    }
+
+    /// Struct, union and enum tags share one file-wide scope with functions,
+    /// even when declared inside another struct
+    fn scope_name(&self, _node: Node, _source: &[u8]) -> Option<String> {
+        None
+    }
+}
+
+impl CExtractor {
+    fn symbol(
+        &self,
+        node: Node,
+        source: &[u8],
+        file_path: &Path,
+        name: String,
+        kind: SymbolKind,
+    ) -> Symbol {
+        let start_line = node.start_position().row as u32 + 1;
+        let end_line = node.end_position().row as u32 + 1;
+
+        let signature = source
+            .get(node.start_byte()..node.end_byte())
+            .and_then(|bytes| std::str::from_utf8(bytes).ok())
+            .and_then(|s| s.lines().next())
+            .map(|s| s.trim_end_matches('{').trim().to_string());
+
+        // Comments precede the declaration a struct is defined in
+        let documented = node
+            .parent()
+            .filter(|p| matches!(p.kind(), "declaration" | "type_definition"))
+            .unwrap_or(node);
+        let docstring = extract_docstring(documented, source, &["comment"]);
+
+        Symbol {
+            id: Symbol::provisional_id(file_path, &name, start_line),
+            name,
+            qualified_name: String::new(),
+            namespace: None,
+            kind,
+            file_path: file_path.to_path_buf(),
+            start_line,
+            end_line,
+            byte_range: node.start_byte()..node.end_byte(),
+            signature,
+            visibility: Visibility::Public,
+            is_async: false,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers::default(),
+            docstring,
+            doc_sections: DocSections::default(),
+            summary: None,
+            language: Language::C,
+            parent_id: None,
+        }
+    }
+}
+
+/// Whether a `.h` header is C++ rather than C
+///
+/// The header is parsed with the C++ grammar and checked for constructs C
+/// does not have, such as classes, namespaces and templates.
+pub(crate) fn is_cpp_header(source: &[u8]) -> bool {
+    let Ok(tree) = ParserPool::parse(Language::Cpp, source) else {
+        return false;
+    };
+
+    let mut cursor = tree.walk();
+    loop {
+        if CPP_ONLY_KINDS.contains(&cursor.node().kind()) {
+            return true;
+        }
+        if cursor.goto_first_child() || cursor.goto_next_sibling() {
+            continue;
+        }
+        loop {
+            if !cursor.goto_parent() {
+                return false;
+            }
+            if cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+/// Whether a file is a C++ source that may include a `.h` header
+pub(crate) fn is_cpp_source(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| CPP_SOURCE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// File names of the headers a source includes: `util.h` for both
+/// `#include "util.h"` and `#include "../include/util.h"`
+///
+/// A header of plain prototypes reads as C, but when it declares the
+/// functions of a `.cpp` file it has to be C++ for the two to be paired.
+pub(crate) fn included_headers(source: &str) -> impl Iterator<Item = &str> {
+    source.lines().filter_map(|line| {
+        let rest = line.trim_start().strip_prefix('#')?;
+        let included = rest.trim_start().strip_prefix("include")?;
+        let included = included.trim().trim_matches(|c| matches!(c, '"' | '<' | '>'));
+        included.rsplit('/').next()
+    })
+}
+
+/// Name declared by a declarator, through pointers, arrays, parentheses and
+/// function parameters: `*(*handlers[4])(int)` declares `handlers`
+fn declarator_name(node: Node, source: &[u8]) -> Option<String> {
+    match node.kind() {
+        "identifier" | "type_identifier" | "field_identifier" | "primitive_type" => {
+            Some(node_text(node, source).to_string())
+        }
+        "parenthesized_declarator" => declarator_name(node.named_child(0)?, source),
+        _ => declarator_name(node.child_by_field_name("declarator")?, source),
+    }
+}
+
+/// Symbol kind of a struct, union or enum specifier
+fn specifier_kind(node: Node) -> SymbolKind {
+    match node.kind() {
+        "union_specifier" => SymbolKind::Union,
+        "enum_specifier" => SymbolKind::Enum,
+        _ => SymbolKind::Struct,
+    }
+}
+
+/// Storage class of a declaration (`static`, `extern`...)
+fn storage_class<'a>(node: Node<'a>, source: &'a [u8]) -> Option<&'a str> {
+    find_child(node, "storage_class_specifier").map(|n| node_text(n, source))
+}
+
+/// The function definition up to its body, on one line
+fn signature(node: Node, source: &[u8]) -> String {
+    let end = node
+        .child_by_field_name("body")
+        .map_or(node.end_byte(), |body| body.start_byte());
+    let text = source
+        .get(node.start_byte()..end)
+        .and_then(|bytes| std::str::from_utf8(bytes).ok())
+        .unwrap_or_default();
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::languages::fixtures::{callers_of, extract, imports, symbol};
+    use crate::{Language, SymbolKind, Visibility};
+
+    #[test]
+    fn test_c_declarations() {
+        let source = r#"#include <stdio.h>
+#include "list.h"
+
+#define MAX(a, b) ((a) > (b) ? (a) : (b))
+#define BUFFER_SIZE 64
+
+/* A node in the list */
+struct node {
+    struct node *next;
+    int value;
+};
+
+typedef struct { int x, y; } point_t;
+typedef union value { int i; float f; } value_t;
+typedef int (*compare_fn)(const void *, const void *);
+enum color { RED, GREEN };
+
+static int helper(struct node *n) { return MAX(n->value, 0); }
+
+char *describe(point_t p, struct ops *ops) {
+    ops->log(helper(NULL));
+    printf("%d", p.x);
+    return NULL;
+}
+"#;
+        let file = extract(Language::C, source);
+
+        let kinds: Vec<(&str, SymbolKind)> =
+            file.symbols.iter().map(|s| (s.name.as_str(), s.kind)).collect();
+        for expected in [
+            ("helper", SymbolKind::Function),
+            ("describe", SymbolKind::Function),
+            ("node", SymbolKind::Struct),
+            ("value", SymbolKind::Union),
+            ("color", SymbolKind::Enum),
+            ("point_t", SymbolKind::Struct),
+            ("value_t", SymbolKind::TypeAlias),
+            ("compare_fn", SymbolKind::TypeAlias),
+            ("MAX", SymbolKind::Macro),
+        ] {
+            assert!(kinds.contains(&expected), "missing {:?} in {:?}", expected, kinds);
+        }
+        assert_eq!(file.symbols.len(), 9);
+
+        assert_eq!(symbol(&file, "helper").visibility, Visibility::Private);
+        assert_eq!(symbol(&file, "describe").visibility, Visibility::Public);
+        assert_eq!(
+            symbol(&file, "describe").signature.as_deref(),
+            Some("char *describe(point_t p, struct ops *ops)")
+        );
+        assert_eq!(symbol(&file, "MAX").signature.as_deref(), Some("#define MAX(a, b)"));
+        assert_eq!(symbol(&file, "node").docstring.as_deref(), Some("A node in the list"));
+        assert_eq!(symbol(&file, "node").id, "test::node");
+
+        assert_eq!(imports(&file), [("stdio.h", None, vec![]), ("list.h", None, vec![])]);
+
+        assert_eq!(callers_of(&file, "MAX"), ["helper"]);
+        assert_eq!(callers_of(&file, "helper"), ["describe"]);
+        assert_eq!(callers_of(&file, "log"), ["describe"]);
+        assert_eq!(callers_of(&file, "printf"), ["describe"]);
+    }
+}
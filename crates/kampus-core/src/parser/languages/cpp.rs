@@ -1,11 +1,16 @@
 //! C++ language extractor
 
 use crate::parser::extractor::{
-    extract_docstring, find_all_nodes, find_child, node_text, LanguageExtractor,
+    extract_docstring, extract_scoped_calls, field_text, find_all_nodes, find_child, node_text,
+    LanguageExtractor,
 };
-use crate::{Call, Import, Inheritance, Language, Symbol, SymbolKind, Visibility};
+use crate::{
+    Call, DocSections, Import, Inheritance, Language, Modifiers, NamespaceKind, Symbol, SymbolKind,
+    Visibility,
+};
+use std::collections::HashSet;
 use std::path::Path;
-use tree_sitter::Tree;
+use tree_sitter::{Node, Tree};
 
 pub struct CppExtractor;
 
@@ -14,78 +19,96 @@ impl LanguageExtractor for CppExtractor {
         let mut symbols = Vec::new();
         let root = tree.root_node();
 
-        // Function definitions
-        let mut func_nodes = Vec::new();
-        find_all_nodes(root, "function_definition", &mut func_nodes);
-        for node in func_nodes {
-            if let Some(symbol) = self.extract_function(node, source, file_path, None) {
-                symbols.push(symbol);
-            }
-        }
-
-        // Class/struct definitions
+        // Class/struct definitions; forward declarations have no body
         let mut class_nodes = Vec::new();
         find_all_nodes(root, "class_specifier", &mut class_nodes);
         find_all_nodes(root, "struct_specifier", &mut class_nodes);
         for node in class_nodes {
+            let Some(body) = node.child_by_field_name("body") else {
+                continue;
+            };
             if let Some(class_symbol) = self.extract_class(node, source, file_path) {
                 let class_id = class_symbol.id.clone();
-                let is_struct = node.kind() == "struct_specifier";
                 symbols.push(class_symbol);
 
-                // Extract methods from class body
-                if let Some(body) = find_child(node, "field_declaration_list") {
-                    // Track current access specifier
-                    let mut current_visibility = if is_struct {
-                        Visibility::Public
-                    } else {
-                        Visibility::Private
-                    };
-
-                    let mut cursor = body.walk();
-                    for child in body.children(&mut cursor) {
-                        match child.kind() {
-                            "access_specifier" => {
-                                let text = node_text(child, source).to_lowercase();
-                                current_visibility = if text.contains("public") {
-                                    Visibility::Public
-                                } else if text.contains("protected") {
-                                    Visibility::Protected
-                                } else {
-                                    Visibility::Private
-                                };
-                            }
-                            "function_definition" => {
-                                if let Some(mut method) =
-                                    self.extract_function(child, source, file_path, Some(&class_id))
-                                {
-                                    method.visibility = current_visibility;
-                                    method.kind = SymbolKind::Method;
-                                    symbols.push(method);
-                                }
+                // Extract methods from class body, including member templates
+                let mut cursor = body.walk();
+                for child in body.named_children(&mut cursor) {
+                    let member = untemplated(child);
+                    match member.kind() {
+                        "function_definition" => {
+                            if let Some(mut method) =
+                                self.extract_function(member, source, file_path, Some(&class_id))
+                            {
+                                method.kind = SymbolKind::Method;
+                                symbols.push(method);
                             }
-                            "declaration" => {
-                                // Method declaration (not definition)
-                                if let Some(declarator) = find_child(child, "function_declarator") {
-                                    if let Some(mut method) = self.extract_method_declaration(
-                                        child,
-                                        declarator,
-                                        source,
-                                        file_path,
-                                        &class_id,
-                                    ) {
-                                        method.visibility = current_visibility;
-                                        symbols.push(method);
-                                    }
-                                }
+                        }
+                        "declaration" | "field_declaration" => {
+                            // Method declaration (not definition)
+                            if let Some(mut method) =
+                                self.extract_declaration(member, source, file_path, Some(&class_id))
+                            {
+                                method.kind = SymbolKind::Method;
+                                symbols.push(method);
                             }
-                            _ => {}
                         }
+                        _ => {}
                     }
                 }
             }
         }
 
+        // Namespaces declared in the file, to tell `ns::function` from
+        // `Class::method` definitions
+        let mut namespace_nodes = Vec::new();
+        find_all_nodes(root, "namespace_definition", &mut namespace_nodes);
+        let namespaces: HashSet<&str> = namespace_nodes
+            .iter()
+            .filter_map(|n| n.child_by_field_name("name"))
+            .flat_map(|name| node_text(name, source).split("::"))
+            .collect();
+
+        // Function definitions and prototypes outside class bodies
+        let mut func_nodes = Vec::new();
+        find_all_nodes(root, "function_definition", &mut func_nodes);
+        find_all_nodes(root, "declaration", &mut func_nodes);
+        func_nodes.sort_by_key(|n| n.start_byte());
+        for node in func_nodes {
+            // Methods defined in a class body are extracted with their class,
+            // and prototypes inside functions are not part of the API
+            if is_nested(node) {
+                continue;
+            }
+            let symbol = if node.kind() == "function_definition" {
+                self.extract_function(node, source, file_path, None)
+            } else {
+                self.extract_declaration(node, source, file_path, None)
+            };
+            let Some(mut symbol) = symbol else {
+                continue;
+            };
+
+            // Out-of-line member definitions: double Shape::area() const
+            let qualifier = function_declarator(node)
+                .and_then(|declarator| function_name(declarator, source))
+                .and_then(|(qualifier, _)| qualifier);
+            if let Some(qualifier) = qualifier {
+                let owner = qualifier.rsplit("::").next().unwrap_or(&qualifier);
+                if !namespaces.contains(owner) {
+                    symbol.kind = SymbolKind::Method;
+                    symbol.parent_id = symbols
+                        .iter()
+                        .find(|s| {
+                            matches!(s.kind, SymbolKind::Class | SymbolKind::Struct)
+                                && s.name == owner
+                        })
+                        .map(|class| class.id.clone());
+                }
+            }
+            symbols.push(symbol);
+        }
+
         // Enum definitions
         let mut enum_nodes = Vec::new();
         find_all_nodes(root, "enum_specifier", &mut enum_nodes);
@@ -125,6 +148,7 @@ impl LanguageExtractor for CppExtractor {
                     alias: None,
                     items: Vec::new(),
                     line: node.start_position().row as u32 + 1,
+                    resolved: None,
                 });
             }
         }
@@ -146,6 +170,7 @@ impl LanguageExtractor for CppExtractor {
                     alias: None,
                     items: Vec::new(),
                     line: node.start_position().row as u32 + 1,
+                    resolved: None,
                 });
             }
         }
@@ -154,51 +179,43 @@ impl LanguageExtractor for CppExtractor {
     }
 
     fn extract_calls(&self, tree: &Tree, source: &[u8], symbols: &[Symbol]) -> Vec<Call> {
-        let mut calls = Vec::new();
-        let root = tree.root_node();
-
-        for symbol in symbols.iter().filter(|s| {
-            s.kind == SymbolKind::Function || s.kind == SymbolKind::Method
-        }) {
-            let mut call_nodes = Vec::new();
-            find_all_nodes(root, "call_expression", &mut call_nodes);
-
-            for call_node in call_nodes {
-                let call_line = call_node.start_position().row as u32 + 1;
-                if call_line >= symbol.start_line && call_line <= symbol.end_line {
-                    if let Some(func) = call_node.child(0) {
-                        let callee_name = match func.kind() {
-                            "identifier" => node_text(func, source).to_string(),
-                            "field_expression" => {
-                                // obj.method() or obj->method()
-                                find_child(func, "field_identifier")
-                                    .map(|n| node_text(n, source).to_string())
-                                    .unwrap_or_default()
-                            }
-                            "qualified_identifier" => {
-                                // Namespace::function()
-                                node_text(func, source).to_string()
-                            }
-                            "template_function" => {
-                                find_child(func, "identifier")
-                                    .map(|n| node_text(n, source).to_string())
-                                    .unwrap_or_default()
-                            }
-                            _ => continue,
-                        };
-                        if !callee_name.is_empty() {
-                            calls.push(Call {
-                                caller_id: symbol.id.clone(),
-                                callee_name,
-                                call_site_line: call_line,
-                            });
+        extract_scoped_calls(tree.root_node(), symbols, &["call_expression"], |call_node| {
+            let func = call_node.child(0)?;
+            let (callee_name, receiver) = match func.kind() {
+                "identifier" => (node_text(func, source).to_string(), None),
+                "field_expression" => {
+                    // obj.method() or obj->method()
+                    let name = find_child(func, "field_identifier")
+                        .map(|n| node_text(n, source).to_string())
+                        .unwrap_or_default();
+                    let receiver = func
+                        .child_by_field_name("argument")
+                        .map(|n| node_text(n, source).to_string());
+                    (name, receiver)
+                }
+                "qualified_identifier" => {
+                    // Namespace::function() or Class::method()
+                    let text = node_text(func, source);
+                    match text.rsplit_once("::") {
+                        Some((scope, name)) => {
+                            let scope = scope.trim();
+                            let receiver =
+                                (!scope.is_empty()).then(|| scope.to_string());
+                            (name.trim().to_string(), receiver)
                         }
+                        None => (text.to_string(), None),
                     }
                 }
-            }
-        }
-
-        calls
+                "template_function" => {
+                    let name = find_child(func, "identifier")
+                        .map(|n| node_text(n, source).to_string())
+                        .unwrap_or_default();
+                    (name, None)
+                }
+                _ => return None,
+            };
+            Some((callee_name, receiver))
+        })
     }
 
     fn extract_inheritance(
@@ -241,6 +258,31 @@ impl LanguageExtractor for CppExtractor {
 
         inheritance
     }
+
+    fn scope_name(&self, node: tree_sitter::Node, source: &[u8]) -> Option<String> {
+        match node.kind() {
+            "namespace_definition" | "class_specifier" | "struct_specifier" => {
+                field_text(node, "name", source)
+            }
+            "function_definition" => {
+                let (qualifier, name) = function_name(function_declarator(node)?, source)?;
+                Some(match qualifier {
+                    Some(qualifier) => format!("{}::{}", qualifier, name),
+                    None => name,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// `double Shape::area()` is defined in `Shape`
+    fn receiver_scope(&self, node: tree_sitter::Node, source: &[u8]) -> Option<String> {
+        function_name(function_declarator(node)?, source)?.0
+    }
+
+    fn namespace_kind(&self, node: tree_sitter::Node) -> Option<NamespaceKind> {
+        (node.kind() == "namespace_definition").then_some(NamespaceKind::Namespace)
+    }
 }
 
 impl CppExtractor {
@@ -252,10 +294,7 @@ impl CppExtractor {
         parent_id: Option<&str>,
     ) -> Option<Symbol> {
         // Get the declarator which contains the function name
-        let declarator = find_child(node, "function_declarator")
-            .or_else(|| find_child(node, "pointer_declarator"))?;
-
-        let name = self.extract_function_name(declarator, source)?;
+        let (_, name) = function_name(function_declarator(node)?, source)?;
 
         let start_line = node.start_position().row as u32 + 1;
         let end_line = node.end_position().row as u32 + 1;
@@ -269,9 +308,10 @@ impl CppExtractor {
                 } else {
                     s.lines().next().unwrap_or("").to_string()
                 }
-            });
+            })
+            .map(|s| with_template(node, source, s));
 
-        let docstring = extract_docstring(node, source, &["comment"]);
+        let docstring = extract_docstring(outer(node), source, &["comment"]);
 
         let kind = if parent_id.is_some() {
             SymbolKind::Method
@@ -280,73 +320,86 @@ impl CppExtractor {
         };
 
         Some(Symbol {
-            id: Symbol::generate_id(file_path, &name, start_line),
+            id: Symbol::provisional_id(file_path, &name, start_line),
             name,
+            qualified_name: String::new(),
+            namespace: None,
             kind,
             file_path: file_path.to_path_buf(),
             start_line,
             end_line,
+            byte_range: node.start_byte()..node.end_byte(),
             signature,
-            visibility: Visibility::Public,
+            visibility: visibility(node, source),
             is_async: false,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers {
+                is_static: parent_id.is_some() && is_static(node, source),
+                ..Modifiers::default()
+            },
             docstring,
+            doc_sections: DocSections::default(),
             summary: None,
             language: Language::Cpp,
             parent_id: parent_id.map(String::from),
         })
     }
 
-    fn extract_function_name(&self, declarator: tree_sitter::Node, source: &[u8]) -> Option<String> {
-        // Direct identifier
-        if let Some(id) = find_child(declarator, "identifier") {
-            return Some(node_text(id, source).to_string());
-        }
-
-        // Qualified identifier (Class::method)
-        if let Some(qualified) = find_child(declarator, "qualified_identifier") {
-            return Some(node_text(qualified, source).to_string());
-        }
-
-        // Destructor
-        if let Some(destructor) = find_child(declarator, "destructor_name") {
-            return Some(node_text(destructor, source).to_string());
-        }
-
-        // Nested declarator
-        if let Some(nested) = find_child(declarator, "function_declarator") {
-            return self.extract_function_name(nested, source);
-        }
-
-        None
-    }
-
-    fn extract_method_declaration(
+    /// A function or method declared without a body, such as a prototype in
+    /// a header or a member function declared in its class
+    fn extract_declaration(
         &self,
-        _declaration: tree_sitter::Node,
-        declarator: tree_sitter::Node,
+        node: tree_sitter::Node,
         source: &[u8],
         file_path: &Path,
-        parent_id: &str,
+        parent_id: Option<&str>,
     ) -> Option<Symbol> {
-        let name = self.extract_function_name(declarator, source)?;
+        let declarator = function_declarator(node)?;
+        let (_, name) = function_name(declarator, source)?;
+
+        let start_line = node.start_position().row as u32 + 1;
+        let end_line = node.end_position().row as u32 + 1;
+
+        let signature = node_text(node, source).trim_end_matches(';').trim().to_string();
+        let is_pure_virtual = node
+            .child_by_field_name("default_value")
+            .is_some_and(|value| node_text(value, source) == "0");
 
-        let start_line = declarator.start_position().row as u32 + 1;
-        let end_line = declarator.end_position().row as u32 + 1;
+        let docstring = extract_docstring(outer(node), source, &["comment"]);
+
+        let kind = if parent_id.is_some() {
+            SymbolKind::Method
+        } else {
+            SymbolKind::Function
+        };
 
         Some(Symbol {
-            id: Symbol::generate_id(file_path, &name, start_line),
+            id: Symbol::provisional_id(file_path, &name, start_line),
             name,
-            kind: SymbolKind::Method,
+            qualified_name: String::new(),
+            namespace: None,
+            kind,
             file_path: file_path.to_path_buf(),
             start_line,
             end_line,
-            signature: None,
-            visibility: Visibility::Private,
+            byte_range: node.start_byte()..node.end_byte(),
+            signature: Some(with_template(node, source, signature)),
+            visibility: visibility(node, source),
             is_async: false,
-            docstring: None,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers {
+                is_static: parent_id.is_some() && is_static(node, source),
+                is_abstract: is_pure_virtual,
+                is_declaration: true,
+                ..Modifiers::default()
+            },
+            docstring,
+            doc_sections: DocSections::default(),
             summary: None,
             language: Language::Cpp,
-            parent_id: Some(parent_id.to_string()),
+            parent_id: parent_id.map(String::from),
         })
     }
 
@@ -372,21 +425,28 @@ impl CppExtractor {
             .get(node.start_byte()..node.end_byte())
             .and_then(|bytes| std::str::from_utf8(bytes).ok())
             .and_then(|s| s.lines().next())
-            .map(|s| s.to_string());
+            .map(|s| with_template(node, source, s.to_string()));
 
-        let docstring = extract_docstring(node, source, &["comment"]);
+        let docstring = extract_docstring(outer(node), source, &["comment"]);
 
         Some(Symbol {
-            id: Symbol::generate_id(file_path, &name, start_line),
+            id: Symbol::provisional_id(file_path, &name, start_line),
             name,
+            qualified_name: String::new(),
+            namespace: None,
             kind,
             file_path: file_path.to_path_buf(),
             start_line,
             end_line,
+            byte_range: node.start_byte()..node.end_byte(),
             signature,
-            visibility: Visibility::Public,
+            visibility: visibility(node, source),
             is_async: false,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers::default(),
             docstring,
+            doc_sections: DocSections::default(),
             summary: None,
             language: Language::Cpp,
             parent_id: None,
@@ -414,19 +474,167 @@ impl CppExtractor {
         let docstring = extract_docstring(node, source, &["comment"]);
 
         Some(Symbol {
-            id: Symbol::generate_id(file_path, &name, start_line),
+            id: Symbol::provisional_id(file_path, &name, start_line),
             name,
+            qualified_name: String::new(),
+            namespace: None,
             kind: SymbolKind::Enum,
             file_path: file_path.to_path_buf(),
             start_line,
             end_line,
+            byte_range: node.start_byte()..node.end_byte(),
             signature,
-            visibility: Visibility::Public,
+            visibility: visibility(node, source),
             is_async: false,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers::default(),
             docstring,
+            doc_sections: DocSections::default(),
             summary: None,
             language: Language::Cpp,
             parent_id: None,
         })
     }
 }
+
+/// Visibility of a declaration
+///
+/// Members of a class body take the access of the nearest preceding
+/// `public:`, `protected:` or `private:` label, or the default of the class
+/// (`private` for `class`, `public` for `struct` and `union`). Declarations
+/// with internal linkage, in an anonymous namespace or declared `static`
+/// outside a class, are private.
+fn visibility(node: Node, source: &[u8]) -> Visibility {
+    // Nested types are declared by a field declaration, member templates by
+    // a template declaration
+    let member = node
+        .parent()
+        .filter(|p| matches!(p.kind(), "field_declaration" | "template_declaration"))
+        .unwrap_or(node);
+    if let Some(body) = member.parent().filter(|p| p.kind() == "field_declaration_list") {
+        let mut sibling = member.prev_sibling();
+        while let Some(prev) = sibling {
+            if prev.kind() == "access_specifier" {
+                return match node_text(prev, source).trim_end_matches(':').trim() {
+                    "public" => Visibility::Public,
+                    "protected" => Visibility::Protected,
+                    _ => Visibility::Private,
+                };
+            }
+            sibling = prev.prev_sibling();
+        }
+        return match body.parent().map(|p| p.kind()) {
+            Some("class_specifier") => Visibility::Private,
+            _ => Visibility::Public,
+        };
+    }
+
+    let is_static = is_static(node, source);
+    let mut ancestor = node.parent();
+    while let Some(parent) = ancestor {
+        if parent.kind() == "namespace_definition" && parent.child_by_field_name("name").is_none() {
+            return Visibility::Private;
+        }
+        ancestor = parent.parent();
+    }
+    if is_static {
+        Visibility::Private
+    } else {
+        Visibility::Public
+    }
+}
+
+/// Whether a declaration is declared `static`
+fn is_static(node: Node, source: &[u8]) -> bool {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .any(|c| c.kind() == "storage_class_specifier" && node_text(c, source) == "static")
+}
+
+/// Whether a node is declared in a class body or inside a function
+fn is_nested(node: Node) -> bool {
+    let mut ancestor = node.parent();
+    while let Some(parent) = ancestor {
+        if matches!(parent.kind(), "field_declaration_list" | "compound_statement") {
+            return true;
+        }
+        ancestor = parent.parent();
+    }
+    false
+}
+
+/// The template declaration wrapping a node, or the node itself
+fn outer(node: Node) -> Node {
+    node.parent()
+        .filter(|p| p.kind() == "template_declaration")
+        .unwrap_or(node)
+}
+
+/// A member template declared in a class body, or the member itself
+fn untemplated(node: Node) -> Node {
+    if node.kind() != "template_declaration" {
+        return node;
+    }
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .find(|c| {
+            matches!(
+                c.kind(),
+                "function_definition" | "declaration" | "field_declaration"
+            )
+        })
+        .unwrap_or(node)
+}
+
+/// Prefix a signature with the template parameters of its declaration
+/// (`template <typename T> T max(T a, T b)`)
+fn with_template(node: Node, source: &[u8], signature: String) -> String {
+    let parameters = node
+        .parent()
+        .filter(|p| p.kind() == "template_declaration")
+        .and_then(|p| p.child_by_field_name("parameters"));
+    match parameters {
+        Some(parameters) => format!("template {} {}", node_text(parameters, source), signature),
+        None => signature,
+    }
+}
+
+/// The function declarator of a definition or declaration, looking through
+/// pointer and reference return types
+fn function_declarator(node: Node) -> Option<Node> {
+    let mut declarator = node.child_by_field_name("declarator")?;
+    loop {
+        match declarator.kind() {
+            "function_declarator" => return Some(declarator),
+            "pointer_declarator" => declarator = declarator.child_by_field_name("declarator")?,
+            "reference_declarator" => declarator = declarator.named_child(0)?,
+            _ => return None,
+        }
+    }
+}
+
+/// Qualifier and name of a function declarator: `(Some("geo::Shape"),
+/// "area")` for `geo::Shape::area()`, dropping template arguments of the
+/// qualifier (`Box<T>::get` is in `Box`)
+fn function_name(declarator: Node, source: &[u8]) -> Option<(Option<String>, String)> {
+    let mut name = declarator.child_by_field_name("declarator")?;
+    let mut scopes = Vec::new();
+    while name.kind() == "qualified_identifier" {
+        if let Some(scope) = name.child_by_field_name("scope") {
+            let scope = match scope.kind() {
+                "template_type" => scope.child_by_field_name("name").unwrap_or(scope),
+                _ => scope,
+            };
+            scopes.push(node_text(scope, source));
+        }
+        name = name.child_by_field_name("name")?;
+    }
+    let name = match name.kind() {
+        "identifier" | "field_identifier" | "destructor_name" | "operator_name" => name,
+        "template_function" => name.child_by_field_name("name")?,
+        _ => return None,
+    };
+    let qualifier = (!scopes.is_empty()).then(|| scopes.join("::"));
+    Some((qualifier, node_text(name, source).to_string()))
+}
@@ -0,0 +1,418 @@
+//! C# language extractor
+
+use crate::parser::extractor::{
+    extract_docstring, extract_scoped_calls, field_text, find_child, node_text, LanguageExtractor,
+};
+use crate::{
+    Call, DocSections, Import, Inheritance, Language, Modifiers, NamespaceKind, Symbol, SymbolKind,
+    Visibility,
+};
+use std::path::Path;
+use tree_sitter::{Node, Tree};
+
+/// Declarations of classes and class-like types
+const TYPE_DECLARATIONS: [&str; 5] = [
+    "class_declaration",
+    "struct_declaration",
+    "record_declaration",
+    "interface_declaration",
+    "enum_declaration",
+];
+
+/// Declarations of methods, constructors and properties
+const MEMBER_DECLARATIONS: [&str; 3] = [
+    "method_declaration",
+    "constructor_declaration",
+    "property_declaration",
+];
+
+pub struct CSharpExtractor;
+
+impl LanguageExtractor for CSharpExtractor {
+    fn extract_symbols(&self, tree: &Tree, source: &[u8], file_path: &Path) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+        self.extract_declarations(tree.root_node(), source, file_path, None, &mut symbols);
+        symbols
+    }
+
+    fn extract_imports(&self, tree: &Tree, source: &[u8], file_path: &Path) -> Vec<Import> {
+        let mut imports = Vec::new();
+        let mut pending = vec![tree.root_node()];
+
+        // using directives sit at the top of the file or of a namespace
+        while let Some(node) = pending.pop() {
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                match child.kind() {
+                    "using_directive" => imports.extend(self.extract_using(child, source, file_path)),
+                    "namespace_declaration" => pending.extend(child.child_by_field_name("body")),
+                    _ => {}
+                }
+            }
+        }
+
+        imports.sort_by_key(|i| i.line);
+        imports
+    }
+
+    fn extract_calls(&self, tree: &Tree, source: &[u8], symbols: &[Symbol]) -> Vec<Call> {
+        extract_scoped_calls(
+            tree.root_node(),
+            symbols,
+            &["invocation_expression", "object_creation_expression"],
+            |call_node| {
+                if call_node.kind() == "object_creation_expression" {
+                    // new Foo<T>() calls the constructor of Foo
+                    let created = call_node.child_by_field_name("type")?;
+                    return Some((type_name(created, source), None));
+                }
+
+                let function = call_node.child_by_field_name("function")?;
+                match function.kind() {
+                    // Run(), Run<T>()
+                    "identifier" | "generic_name" => Some((type_name(function, source), None)),
+                    // obj.Run(), Type.Run<T>()
+                    "member_access_expression" => Some((
+                        type_name(function.child_by_field_name("name")?, source),
+                        field_text(function, "expression", source),
+                    )),
+                    _ => None,
+                }
+            },
+        )
+    }
+
+    fn extract_inheritance(
+        &self,
+        tree: &Tree,
+        source: &[u8],
+        symbols: &[Symbol],
+    ) -> Vec<Inheritance> {
+        let mut inheritance = Vec::new();
+
+        for symbol in symbols {
+            if !matches!(
+                symbol.kind,
+                SymbolKind::Class | SymbolKind::Struct | SymbolKind::Interface
+            ) {
+                continue;
+            }
+            let Some(node) = tree
+                .root_node()
+                .descendant_for_byte_range(symbol.byte_range.start, symbol.byte_range.end)
+            else {
+                continue;
+            };
+
+            // class Foo : Base, IComparable<Foo>; record Bar(int X) : Base(X)
+            let Some(bases) = find_child(node, "base_list") else {
+                continue;
+            };
+            let mut cursor = bases.walk();
+            for base in bases.named_children(&mut cursor) {
+                let base = match base.kind() {
+                    "primary_constructor_base_type" => match base.named_child(0) {
+                        Some(base) => base,
+                        None => continue,
+                    },
+                    "argument_list" => continue,
+                    _ => base,
+                };
+                inheritance.push(Inheritance {
+                    child_id: symbol.id.clone(),
+                    parent_name: type_name(base, source),
+                });
+            }
+        }
+
+        inheritance
+    }
+
+    fn scope_name(&self, node: Node, source: &[u8]) -> Option<String> {
+        let kind = node.kind();
+        if kind == "namespace_declaration"
+            || TYPE_DECLARATIONS.contains(&kind)
+            || MEMBER_DECLARATIONS.contains(&kind)
+        {
+            field_text(node, "name", source)
+        } else {
+            None
+        }
+    }
+
+    fn namespace_kind(&self, node: Node) -> Option<NamespaceKind> {
+        (node.kind() == "namespace_declaration").then_some(NamespaceKind::Namespace)
+    }
+
+    /// A file-scoped namespace (`namespace App.Models;`) applies to the whole
+    /// file
+    fn module_path(&self, tree: &Tree, source: &[u8], _file_path: &Path) -> Vec<String> {
+        find_child(tree.root_node(), "file_scoped_namespace_declaration")
+            .and_then(|namespace| field_text(namespace, "name", source))
+            .map(|name| name.split('.').map(|s| s.trim().to_string()).collect())
+            .unwrap_or_default()
+    }
+}
+
+impl CSharpExtractor {
+    /// Extract the types and members declared directly in `node`, recursing
+    /// into namespaces and type bodies
+    fn extract_declarations(
+        &self,
+        node: Node,
+        source: &[u8],
+        file_path: &Path,
+        parent: Option<(&str, &str)>,
+        symbols: &mut Vec<Symbol>,
+    ) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            let kind = child.kind();
+            if TYPE_DECLARATIONS.contains(&kind) {
+                let Some(symbol) = self.extract_type(child, source, file_path, parent) else {
+                    continue;
+                };
+                let id = symbol.id.clone();
+                symbols.push(symbol);
+                if let Some(body) = child.child_by_field_name("body") {
+                    self.extract_declarations(body, source, file_path, Some((&id, kind)), symbols);
+                }
+            } else if MEMBER_DECLARATIONS.contains(&kind) {
+                symbols.extend(self.extract_member(child, source, file_path, parent));
+            } else if kind == "namespace_declaration" {
+                if let Some(body) = child.child_by_field_name("body") {
+                    self.extract_declarations(body, source, file_path, None, symbols);
+                }
+            } else if kind == "declaration_list" {
+                self.extract_declarations(child, source, file_path, parent, symbols);
+            }
+        }
+    }
+
+    fn extract_type(
+        &self,
+        node: Node,
+        source: &[u8],
+        file_path: &Path,
+        parent: Option<(&str, &str)>,
+    ) -> Option<Symbol> {
+        let name = field_text(node, "name", source)?;
+        let kind = match node.kind() {
+            "struct_declaration" => SymbolKind::Struct,
+            "interface_declaration" => SymbolKind::Interface,
+            "enum_declaration" => SymbolKind::Enum,
+            // record struct Point(int X, int Y)
+            "record_declaration" if find_child(node, "struct").is_some() => SymbolKind::Struct,
+            _ => SymbolKind::Class,
+        };
+
+        let mut symbol = self.symbol(node, source, file_path, name, kind, parent);
+        symbol.is_partial = modifiers(node, source).contains(&"partial");
+        Some(symbol)
+    }
+
+    fn extract_member(
+        &self,
+        node: Node,
+        source: &[u8],
+        file_path: &Path,
+        parent: Option<(&str, &str)>,
+    ) -> Option<Symbol> {
+        let name = field_text(node, "name", source)?;
+        let kind = if node.kind() == "property_declaration" {
+            SymbolKind::Property
+        } else {
+            SymbolKind::Method
+        };
+
+        let mut symbol = self.symbol(node, source, file_path, name, kind, parent);
+        symbol.is_async = modifiers(node, source).contains(&"async");
+        Some(symbol)
+    }
+
+    fn symbol(
+        &self,
+        node: Node,
+        source: &[u8],
+        file_path: &Path,
+        name: String,
+        kind: SymbolKind,
+        parent: Option<(&str, &str)>,
+    ) -> Symbol {
+        let start_line = node.start_position().row as u32 + 1;
+        let end_line = node.end_position().row as u32 + 1;
+
+        let docstring = extract_docstring(node, source, &["comment"]);
+
+        Symbol {
+            id: Symbol::provisional_id(file_path, &name, start_line),
+            name,
+            qualified_name: String::new(),
+            namespace: None,
+            kind,
+            file_path: file_path.to_path_buf(),
+            start_line,
+            end_line,
+            byte_range: node.start_byte()..node.end_byte(),
+            signature: Some(signature(node, source)),
+            visibility: visibility(node, source, parent.map(|(_, kind)| kind)),
+            is_async: false,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers::default(),
+            docstring,
+            doc_sections: DocSections::default(),
+            summary: None,
+            language: Language::CSharp,
+            parent_id: parent.map(|(id, _)| id.to_string()),
+        }
+    }
+
+    fn extract_using(&self, node: Node, source: &[u8], file_path: &Path) -> Option<Import> {
+        // using System.Text; using static System.Math; using Json = System.Text.Json;
+        let alias = node.child_by_field_name("name");
+        let mut cursor = node.walk();
+        let target = node
+            .named_children(&mut cursor)
+            .filter(|child| Some(*child) != alias)
+            .last()?;
+
+        Some(Import {
+            source_file: file_path.to_path_buf(),
+            target: node_text(target, source).to_string(),
+            alias: alias.map(|a| node_text(a, source).to_string()),
+            items: Vec::new(),
+            line: node.start_position().row as u32 + 1,
+            resolved: None,
+        })
+    }
+}
+
+/// Modifier keywords of a declaration (`public`, `static`, `partial`...)
+fn modifiers<'a>(node: Node<'a>, source: &'a [u8]) -> Vec<&'a str> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .filter(|child| child.kind() == "modifier")
+        .map(|modifier| node_text(modifier, source))
+        .collect()
+}
+
+/// The declaration up to its body or accessors, with attributes and
+/// modifiers, on one line
+fn signature(node: Node, source: &[u8]) -> String {
+    let end = node
+        .child_by_field_name("body")
+        .or_else(|| node.child_by_field_name("accessors"))
+        .map_or(node.end_byte(), |body| body.start_byte());
+    let text = source
+        .get(node.start_byte()..end)
+        .and_then(|bytes| std::str::from_utf8(bytes).ok())
+        .unwrap_or_default();
+    text.trim_end_matches(';')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Visibility from the access modifiers of a declaration
+///
+/// Interface members are public unless declared otherwise. Types declared
+/// outside another type default to `internal`, members to `private`.
+fn visibility(node: Node, source: &[u8], parent_kind: Option<&str>) -> Visibility {
+    let modifiers = modifiers(node, source);
+    if modifiers.contains(&"public") {
+        Visibility::Public
+    } else if modifiers.contains(&"protected") {
+        Visibility::Protected
+    } else if modifiers.contains(&"private") {
+        Visibility::Private
+    } else if modifiers.contains(&"internal") {
+        Visibility::Internal
+    } else if parent_kind == Some("interface_declaration") {
+        Visibility::Public
+    } else if parent_kind.is_none() {
+        Visibility::Internal
+    } else {
+        Visibility::Private
+    }
+}
+
+/// Name of a type without namespace qualifiers or type arguments:
+/// `System.Collections.Generic.List<int>` is `List`
+fn type_name(node: Node, source: &[u8]) -> String {
+    match node.kind() {
+        "generic_name" => find_child(node, "identifier")
+            .map(|n| node_text(n, source).to_string())
+            .unwrap_or_default(),
+        "qualified_name" => node
+            .child_by_field_name("name")
+            .map(|name| type_name(name, source))
+            .unwrap_or_default(),
+        _ => node_text(node, source).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::languages::fixtures::{callers_of, extract_at, imports, parents, symbol};
+    use crate::{Language, Symbol, SymbolKind, Visibility};
+
+    #[test]
+    fn test_csharp_declarations() {
+        let source = r#"using System;
+using Json = System.Text.Json;
+
+namespace Shop.Models;
+
+/// An order
+public partial class Order : Entity, IComparable<Order>
+{
+    public int Id { get; set; }
+
+    public Order(int id) { Id = id; Log("created"); }
+
+    public async Task<decimal> Total() => tax.Apply(new Calculator().Sum<decimal>());
+
+    interface IShipping { void Ship(); }
+}
+
+internal record struct Point(int X, int Y);
+"#;
+        let file = extract_at(Language::CSharp, "Models/Order.cs", source);
+
+        let order = symbol(&file, "Shop.Models.Order");
+        assert_eq!(order.kind, SymbolKind::Class);
+        assert!(order.is_partial);
+        assert_eq!(order.id, Symbol::partial_id(Language::CSharp, "Shop.Models.Order"));
+        assert_eq!(order.docstring.as_deref(), Some("An order"));
+        assert_eq!(
+            order.signature.as_deref(),
+            Some("public partial class Order : Entity, IComparable<Order>")
+        );
+
+        let id = symbol(&file, "Shop.Models.Order.Id");
+        assert_eq!(id.kind, SymbolKind::Property);
+        assert_eq!(id.id, "Models/Order.cs::Order.Id");
+        assert_eq!(id.parent_id.as_ref(), Some(&order.id));
+        assert_eq!(symbol(&file, "Shop.Models.Order.Order").kind, SymbolKind::Method);
+        assert!(symbol(&file, "Shop.Models.Order.Total").is_async);
+        assert_eq!(symbol(&file, "Shop.Models.Order.IShipping").visibility, Visibility::Private);
+        assert_eq!(symbol(&file, "Shop.Models.Order.IShipping.Ship").visibility, Visibility::Public);
+        let point = symbol(&file, "Shop.Models.Point");
+        assert_eq!(point.kind, SymbolKind::Struct);
+        assert!(!point.is_partial);
+
+        assert_eq!(file.namespace.as_deref(), Some("Shop.Models"));
+        assert_eq!(
+            imports(&file),
+            [("System", None, vec![]), ("System.Text.Json", Some("Json"), vec![])]
+        );
+
+        assert_eq!(parents(&file), ["Entity", "IComparable"]);
+
+        assert_eq!(callers_of(&file, "Log"), ["Order"]);
+        assert_eq!(callers_of(&file, "Apply"), ["Total"]);
+        assert_eq!(callers_of(&file, "Calculator"), ["Total"]);
+        assert_eq!(callers_of(&file, "Sum"), ["Total"]);
+    }
+}
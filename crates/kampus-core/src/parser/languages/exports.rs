@@ -0,0 +1,250 @@
+//! Module exports shared by the JavaScript and TypeScript extractors
+//!
+//! Declarations exported with `export`, listed in `export { ... }` or
+//! assigned to `module.exports` are public; other declarations of a module
+//! are private to it. Files without imports or exports are scripts, whose
+//! top-level declarations are globals.
+//!
+//! `export ... from` statements also import the module they re-export from.
+//! `import` statements are read here too, the same way for both languages.
+
+use crate::parser::extractor::{field_text, find_all_nodes, find_child, node_text};
+use crate::{Import, Symbol, SymbolKind, Visibility};
+use std::collections::HashSet;
+use std::path::Path;
+use tree_sitter::Node;
+
+/// Names a file exports by reference rather than at their declaration
+struct Exports {
+    names: HashSet<String>,
+    is_module: bool,
+}
+
+/// Set the visibility of the declarations in `symbols` from the file's
+/// exports; methods keep their member visibility
+pub fn apply(root: Node, source: &[u8], symbols: &mut [Symbol]) {
+    let exports = Exports::of(root, source);
+    for symbol in symbols.iter_mut().filter(|s| s.kind != SymbolKind::Method) {
+        let range = symbol.byte_range.clone();
+        if let Some(node) = root.descendant_for_byte_range(range.start, range.end) {
+            symbol.visibility = exports.visibility(node, &symbol.name);
+        }
+    }
+}
+
+/// Imports made by an `import` statement
+///
+/// Names imported as they are share one import. The default import and
+/// each renamed specifier get one of their own, whose alias is the local
+/// name and whose item is the name imported, `default` for the default
+/// export: `import Button, { SIZES as sizes } from './Button'` makes
+/// `default` as `Button` and `SIZES` as `sizes`.
+pub fn imports(statement: Node, source: &[u8], file_path: &Path) -> Vec<Import> {
+    let target = statement
+        .child_by_field_name("source")
+        .map(|n| string_value(n, source))
+        .unwrap_or_default();
+
+    let mut items = Vec::new();
+    let mut renamed = Vec::new();
+    if let Some(clause) = find_child(statement, "import_clause") {
+        if let Some(default) = find_child(clause, "identifier") {
+            renamed.push(("default".to_string(), node_text(default, source).to_string()));
+        }
+        if let Some(named) = find_child(clause, "named_imports") {
+            let mut cursor = named.walk();
+            for specifier in named.named_children(&mut cursor) {
+                if specifier.kind() != "import_specifier" {
+                    continue;
+                }
+                let Some(name) = field_text(specifier, "name", source) else {
+                    continue;
+                };
+                match field_text(specifier, "alias", source) {
+                    Some(alias) => renamed.push((name, alias)),
+                    None => items.push(name),
+                }
+            }
+        }
+    }
+
+    let line = statement.start_position().row as u32 + 1;
+    let import = |alias, items| Import {
+        source_file: file_path.to_path_buf(),
+        target: target.clone(),
+        alias,
+        items,
+        line,
+        resolved: None,
+    };
+    let mut imports = Vec::new();
+    if !items.is_empty() || renamed.is_empty() {
+        imports.push(import(None, items));
+    }
+    for (item, alias) in renamed {
+        imports.push(import(Some(alias), vec![item]));
+    }
+    imports
+}
+
+/// Imports made by `export { a, b } from 'x'` and `export * from 'x'`,
+/// with the names re-exported as items, `*` for all of them, and the
+/// namespace of `export * as ns from 'x'` as alias
+pub fn reexports(root: Node, source: &[u8], file_path: &Path) -> Vec<Import> {
+    let mut imports = Vec::new();
+    let mut cursor = root.walk();
+    for statement in root.named_children(&mut cursor) {
+        if statement.kind() != "export_statement" {
+            continue;
+        }
+        let Some(target) = statement.child_by_field_name("source") else {
+            continue;
+        };
+
+        let mut items = Vec::new();
+        let mut alias = None;
+        if let Some(clause) = find_child(statement, "export_clause") {
+            let mut cursor = clause.walk();
+            for specifier in clause.named_children(&mut cursor) {
+                items.extend(field_text(specifier, "name", source));
+            }
+        } else if let Some(namespace) = find_child(statement, "namespace_export") {
+            alias = namespace
+                .named_child(0)
+                .map(|n| string_value(n, source));
+        } else {
+            items.push("*".to_string());
+        }
+
+        imports.push(Import {
+            source_file: file_path.to_path_buf(),
+            target: string_value(target, source),
+            alias,
+            items,
+            line: statement.start_position().row as u32 + 1,
+            resolved: None,
+        });
+    }
+    imports
+}
+
+/// Text of a string literal without its quotes
+pub fn string_value(node: Node, source: &[u8]) -> String {
+    node_text(node, source)
+        .trim_matches(|c| c == '"' || c == '\'' || c == '`')
+        .to_string()
+}
+
+/// Visibility of a class member: `private` and `protected` modifiers and
+/// `#private` names
+pub fn member_visibility(node: Node, source: &[u8]) -> Visibility {
+    if find_child(node, "private_property_identifier").is_some() {
+        return Visibility::Private;
+    }
+    match find_child(node, "accessibility_modifier").map(|m| node_text(m, source)) {
+        Some("private") => Visibility::Private,
+        Some("protected") => Visibility::Protected,
+        _ => Visibility::Public,
+    }
+}
+
+impl Exports {
+    fn of(root: Node, source: &[u8]) -> Self {
+        let mut names = HashSet::new();
+        let mut is_module = false;
+
+        let mut cursor = root.walk();
+        for statement in root.named_children(&mut cursor) {
+            match statement.kind() {
+                "import_statement" => is_module = true,
+                "export_statement" => {
+                    is_module = true;
+                    // export { a, b as c }
+                    if let Some(clause) = find_child(statement, "export_clause") {
+                        let mut cursor = clause.walk();
+                        for specifier in clause.named_children(&mut cursor) {
+                            names.extend(field_text(specifier, "name", source));
+                        }
+                    }
+                    // export default name
+                    if let Some(value) = statement.child_by_field_name("value")
+                        && value.kind() == "identifier"
+                    {
+                        names.insert(node_text(value, source).to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // module.exports = { a, b: c }, exports.name = value
+        let mut assignments = Vec::new();
+        find_all_nodes(root, "assignment_expression", &mut assignments);
+        for assignment in assignments {
+            let (Some(left), Some(right)) = (
+                assignment.child_by_field_name("left"),
+                assignment.child_by_field_name("right"),
+            ) else {
+                continue;
+            };
+            let target = node_text(left, source);
+            if target == "module.exports" {
+                is_module = true;
+                names.extend(exported_values(right, source));
+            } else if let Some(name) = target
+                .strip_prefix("module.exports.")
+                .or_else(|| target.strip_prefix("exports."))
+            {
+                is_module = true;
+                names.insert(name.to_string());
+                names.extend(exported_values(right, source));
+            }
+        }
+
+        Exports { names, is_module }
+    }
+
+    /// Visibility of a function, class or type declared at `node`
+    fn visibility(&self, node: Node, name: &str) -> Visibility {
+        // Climb from an arrow function to its `const` statement
+        let mut statement = node;
+        while let Some(parent) = statement.parent()
+            && matches!(
+                parent.kind(),
+                "variable_declarator" | "lexical_declaration" | "variable_declaration"
+            )
+        {
+            statement = parent;
+        }
+
+        match statement.parent().map(|p| p.kind()) {
+            Some("export_statement") => Visibility::Public,
+            Some("program") if !self.is_module || self.names.contains(name) => Visibility::Public,
+            _ => Visibility::Private,
+        }
+    }
+}
+
+/// Local names exported by an assignment to `module.exports`
+fn exported_values(value: Node, source: &[u8]) -> Vec<String> {
+    match value.kind() {
+        "identifier" => vec![node_text(value, source).to_string()],
+        "object" => {
+            let mut cursor = value.walk();
+            value
+                .named_children(&mut cursor)
+                .filter_map(|property| match property.kind() {
+                    "shorthand_property_identifier" => {
+                        Some(node_text(property, source).to_string())
+                    }
+                    "pair" => property
+                        .child_by_field_name("value")
+                        .filter(|v| v.kind() == "identifier")
+                        .map(|v| node_text(v, source).to_string()),
+                    _ => None,
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
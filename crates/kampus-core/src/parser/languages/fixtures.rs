@@ -0,0 +1,56 @@
+//! Helpers shared by the extractor fixture tests
+
+use crate::parser::extractor::SymbolExtractor;
+use crate::parser::pool::ParserPool;
+use crate::{FileSymbols, Language, Symbol};
+use std::path::Path;
+
+/// Parse and extract `source` as a file named `test`
+pub(crate) fn extract(language: Language, source: &str) -> FileSymbols {
+    extract_at(language, "test", source)
+}
+
+/// Parse and extract `source` as the file at `path`
+pub(crate) fn extract_at(language: Language, path: &str, source: &str) -> FileSymbols {
+    let tree = ParserPool::parse_file(language, Path::new(path), source.as_bytes()).unwrap();
+    SymbolExtractor::extract(&tree, source.as_bytes(), Path::new(path), language).unwrap()
+}
+
+/// The symbol with the given qualified name
+pub(crate) fn symbol<'a>(file: &'a FileSymbols, qualified_name: &str) -> &'a Symbol {
+    file.symbols
+        .iter()
+        .find(|s| s.qualified_name == qualified_name)
+        .unwrap_or_else(|| panic!("no symbol {}", qualified_name))
+}
+
+/// Names of the symbols that call `callee`
+pub(crate) fn callers_of<'a>(file: &'a FileSymbols, callee: &str) -> Vec<&'a str> {
+    file.calls
+        .iter()
+        .filter(|c| c.callee_name == callee)
+        .map(|c| {
+            let caller = file.symbols.iter().find(|s| s.id == c.caller_id).unwrap();
+            caller.name.as_str()
+        })
+        .collect()
+}
+
+/// Target, alias and items of each import
+pub(crate) fn imports(file: &FileSymbols) -> Vec<(&str, Option<&str>, Vec<&str>)> {
+    file.imports
+        .iter()
+        .map(|i| {
+            let items = i.items.iter().map(String::as_str).collect();
+            (i.target.as_str(), i.alias.as_deref(), items)
+        })
+        .collect()
+}
+
+/// Parent names of each inheritance record
+pub(crate) fn parents(file: &FileSymbols) -> Vec<&str> {
+    file.inheritance
+        .iter()
+        .map(|i| i.parent_name.as_str())
+        .collect()
+}
@@ -1,9 +1,13 @@
 //! Go language extractor
 
 use crate::parser::extractor::{
-    extract_docstring, find_all_nodes, find_child, node_text, LanguageExtractor,
+    extract_docstring, extract_scoped_calls, field_text, find_all_nodes, find_child, node_text,
+    LanguageExtractor,
+};
+use crate::{
+    Call, DocSections, Import, Inheritance, Language, Modifiers, NamespaceKind, Symbol, SymbolKind,
+    Visibility,
 };
-use crate::{Call, Import, Inheritance, Language, Symbol, SymbolKind, Visibility};
 use std::path::Path;
 use tree_sitter::Tree;
 
@@ -38,10 +42,12 @@ impl LanguageExtractor for GoExtractor {
         for node in type_nodes {
             let mut cursor = node.walk();
             for child in node.children(&mut cursor) {
-                if child.kind() == "type_spec" {
-                    if let Some(symbol) = self.extract_type_spec(child, source, file_path) {
-                        symbols.push(symbol);
-                    }
+                if child.kind() == "type_spec"
+                    && let Some(symbol) = self.extract_type_spec(child, source, file_path)
+                {
+                    let methods = self.extract_interface_methods(child, source, file_path, &symbol);
+                    symbols.push(symbol);
+                    symbols.extend(methods);
                 }
             }
         }
@@ -69,6 +75,7 @@ impl LanguageExtractor for GoExtractor {
                     alias: None,
                     items: Vec::new(),
                     line: node.start_position().row as u32 + 1,
+                    resolved: None,
                 });
             }
 
@@ -76,25 +83,26 @@ impl LanguageExtractor for GoExtractor {
             if let Some(spec_list) = find_child(node, "import_spec_list") {
                 let mut cursor = spec_list.walk();
                 for child in spec_list.children(&mut cursor) {
-                    if child.kind() == "import_spec" {
-                        if let Some(path) = find_child(child, "interpreted_string_literal") {
-                            let target = node_text(path, source)
-                                .trim_matches('"')
-                                .to_string();
-
-                            let alias = find_child(child, "package_identifier")
-                                .or_else(|| find_child(child, "dot"))
-                                .or_else(|| find_child(child, "blank_identifier"))
-                                .map(|n| node_text(n, source).to_string());
-
-                            imports.push(Import {
-                                source_file: file_path.to_path_buf(),
-                                target,
-                                alias,
-                                items: Vec::new(),
-                                line: child.start_position().row as u32 + 1,
-                            });
-                        }
+                    if child.kind() == "import_spec"
+                        && let Some(path) = find_child(child, "interpreted_string_literal")
+                    {
+                        let target = node_text(path, source)
+                            .trim_matches('"')
+                            .to_string();
+
+                        let alias = find_child(child, "package_identifier")
+                            .or_else(|| find_child(child, "dot"))
+                            .or_else(|| find_child(child, "blank_identifier"))
+                            .map(|n| node_text(n, source).to_string());
+
+                        imports.push(Import {
+                            source_file: file_path.to_path_buf(),
+                            target,
+                            alias,
+                            items: Vec::new(),
+                            line: child.start_position().row as u32 + 1,
+                            resolved: None,
+                        });
                     }
                 }
             }
@@ -104,42 +112,24 @@ impl LanguageExtractor for GoExtractor {
     }
 
     fn extract_calls(&self, tree: &Tree, source: &[u8], symbols: &[Symbol]) -> Vec<Call> {
-        let mut calls = Vec::new();
-        let root = tree.root_node();
-
-        for symbol in symbols.iter().filter(|s| {
-            s.kind == SymbolKind::Function || s.kind == SymbolKind::Method
-        }) {
-            let mut call_nodes = Vec::new();
-            find_all_nodes(root, "call_expression", &mut call_nodes);
-
-            for call_node in call_nodes {
-                let call_line = call_node.start_position().row as u32 + 1;
-                if call_line >= symbol.start_line && call_line <= symbol.end_line {
-                    if let Some(func) = call_node.child(0) {
-                        let callee_name = match func.kind() {
-                            "identifier" => node_text(func, source).to_string(),
-                            "selector_expression" => {
-                                // obj.Method() or pkg.Function()
-                                find_child(func, "field_identifier")
-                                    .map(|n| node_text(n, source).to_string())
-                                    .unwrap_or_default()
-                            }
-                            _ => continue,
-                        };
-                        if !callee_name.is_empty() {
-                            calls.push(Call {
-                                caller_id: symbol.id.clone(),
-                                callee_name,
-                                call_site_line: call_line,
-                            });
-                        }
-                    }
+        extract_scoped_calls(tree.root_node(), symbols, &["call_expression"], |call_node| {
+            let func = call_node.child(0)?;
+            let (callee_name, receiver) = match func.kind() {
+                "identifier" => (node_text(func, source).to_string(), None),
+                "selector_expression" => {
+                    // obj.Method() or pkg.Function()
+                    let name = find_child(func, "field_identifier")
+                        .map(|n| node_text(n, source).to_string())
+                        .unwrap_or_default();
+                    let receiver = func
+                        .child_by_field_name("operand")
+                        .map(|n| node_text(n, source).to_string());
+                    (name, receiver)
                 }
-            }
-        }
-
-        calls
+                _ => return None,
+            };
+            Some((callee_name, receiver))
+        })
     }
 
     fn extract_inheritance(
@@ -161,45 +151,50 @@ impl LanguageExtractor for GoExtractor {
                 .unwrap_or("");
 
             // Check for struct type with embedded fields
-            if let Some(struct_type) = find_child(node, "struct_type") {
-                if let Some(field_list) = find_child(struct_type, "field_declaration_list") {
-                    let mut cursor = field_list.walk();
-                    for field in field_list.children(&mut cursor) {
-                        if field.kind() == "field_declaration" {
-                            // Embedded field has no name, just a type
-                            let has_name = find_child(field, "field_identifier").is_some();
-                            if !has_name {
-                                if let Some(embedded_type) = find_child(field, "type_identifier") {
-                                    if let Some(symbol) = symbols
-                                        .iter()
-                                        .find(|s| s.kind == SymbolKind::Struct && s.name == type_name)
-                                    {
-                                        inheritance.push(Inheritance {
-                                            child_id: symbol.id.clone(),
-                                            parent_name: node_text(embedded_type, source).to_string(),
-                                        });
-                                    }
-                                }
-                            }
+            if let Some(struct_type) = find_child(node, "struct_type")
+                && let Some(field_list) = find_child(struct_type, "field_declaration_list")
+            {
+                let mut cursor = field_list.walk();
+                for field in field_list.children(&mut cursor) {
+                    if field.kind() == "field_declaration" {
+                        // Embedded field has no name, just a type
+                        let has_name = find_child(field, "field_identifier").is_some();
+                        if !has_name
+                            && let Some(embedded_type) = find_child(field, "type_identifier")
+                            && let Some(symbol) = symbols
+                                .iter()
+                                .find(|s| s.kind == SymbolKind::Struct && s.name == type_name)
+                        {
+                            inheritance.push(Inheritance {
+                                child_id: symbol.id.clone(),
+                                parent_name: node_text(embedded_type, source).to_string(),
+                            });
                         }
                     }
                 }
             }
 
-            // Check for interface embedding
+            // Check for interface embedding: `Reader` or `io.Reader`
             if let Some(interface_type) = find_child(node, "interface_type") {
                 let mut cursor = interface_type.walk();
                 for child in interface_type.children(&mut cursor) {
-                    if child.kind() == "type_identifier" {
-                        if let Some(symbol) = symbols
+                    let embedded = match child.kind() {
+                        "type_elem" => child.named_child(0).and_then(|t| match t.kind() {
+                            "type_identifier" => Some(t),
+                            "qualified_type" => t.child_by_field_name("name"),
+                            _ => None,
+                        }),
+                        _ => None,
+                    };
+                    if let Some(embedded) = embedded
+                        && let Some(symbol) = symbols
                             .iter()
                             .find(|s| s.kind == SymbolKind::Interface && s.name == type_name)
-                        {
-                            inheritance.push(Inheritance {
-                                child_id: symbol.id.clone(),
-                                parent_name: node_text(child, source).to_string(),
-                            });
-                        }
+                    {
+                        inheritance.push(Inheritance {
+                            child_id: symbol.id.clone(),
+                            parent_name: node_text(embedded, source).to_string(),
+                        });
                     }
                 }
             }
@@ -207,6 +202,56 @@ impl LanguageExtractor for GoExtractor {
 
         inheritance
     }
+
+    fn scope_name(&self, node: tree_sitter::Node, source: &[u8]) -> Option<String> {
+        match node.kind() {
+            "function_declaration" | "method_declaration" | "type_spec" => {
+                field_text(node, "name", source)
+            }
+            _ => None,
+        }
+    }
+
+    /// Go packages are named by their directory, or by the package clause
+    /// for files at the root
+    fn module_path(&self, tree: &Tree, source: &[u8], file_path: &Path) -> Vec<String> {
+        let dir = file_path
+            .parent()
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default();
+        if !dir.is_empty() {
+            return vec![dir];
+        }
+
+        find_child(tree.root_node(), "package_clause")
+            .and_then(|clause| find_child(clause, "package_identifier"))
+            .map(|name| vec![node_text(name, source).to_string()])
+            .unwrap_or_default()
+    }
+
+    fn module_kind(&self) -> NamespaceKind {
+        NamespaceKind::Package
+    }
+
+    fn receiver_scope(&self, node: tree_sitter::Node, source: &[u8]) -> Option<String> {
+        if node.kind() != "method_declaration" {
+            return None;
+        }
+        let receiver = node.child_by_field_name("receiver")?;
+        let mut cursor = receiver.walk();
+        let param = receiver
+            .children(&mut cursor)
+            .find(|c| c.kind() == "parameter_declaration")?;
+
+        // Strip pointers and type arguments: (s *Stack[T]) -> Stack
+        let mut receiver_type = param.child_by_field_name("type")?;
+        while matches!(receiver_type.kind(), "pointer_type" | "generic_type") {
+            receiver_type = receiver_type
+                .child_by_field_name("type")
+                .or_else(|| receiver_type.named_child(0))?;
+        }
+        Some(node_text(receiver_type, source).to_string())
+    }
 }
 
 impl GoExtractor {
@@ -222,37 +267,30 @@ impl GoExtractor {
         let start_line = node.start_position().row as u32 + 1;
         let end_line = node.end_position().row as u32 + 1;
 
-        // Go visibility: uppercase = public, lowercase = private
-        let visibility = if name.chars().next().map(|c| c.is_uppercase()).unwrap_or(false) {
-            Visibility::Public
-        } else {
-            Visibility::Private
-        };
+        let visibility = visibility(&name);
 
-        let signature = source
-            .get(node.start_byte()..node.end_byte())
-            .and_then(|bytes| std::str::from_utf8(bytes).ok())
-            .map(|s| {
-                if let Some(idx) = s.find('{') {
-                    s[..idx].trim().to_string()
-                } else {
-                    s.lines().next().unwrap_or("").to_string()
-                }
-            });
+        let signature = Some(signature(node, source));
 
         let docstring = extract_docstring(node, source, &["comment"]);
 
         Some(Symbol {
-            id: Symbol::generate_id(file_path, &name, start_line),
+            id: Symbol::provisional_id(file_path, &name, start_line),
             name,
+            qualified_name: String::new(),
+            namespace: None,
             kind: SymbolKind::Function,
             file_path: file_path.to_path_buf(),
             start_line,
             end_line,
+            byte_range: node.start_byte()..node.end_byte(),
             signature,
             visibility,
             is_async: false, // Go uses goroutines, not async/await
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers::default(),
             docstring,
+            doc_sections: DocSections::default(),
             summary: None,
             language: Language::Go,
             parent_id: None,
@@ -272,11 +310,7 @@ impl GoExtractor {
         let start_line = node.start_position().row as u32 + 1;
         let end_line = node.end_position().row as u32 + 1;
 
-        let visibility = if name.chars().next().map(|c| c.is_uppercase()).unwrap_or(false) {
-            Visibility::Public
-        } else {
-            Visibility::Private
-        };
+        let visibility = visibility(&name);
 
         // Get receiver type
         let receiver_type = find_child(node, "parameter_list")
@@ -296,34 +330,34 @@ impl GoExtractor {
         let parent_id = receiver_type.as_ref().and_then(|rt| {
             existing_symbols
                 .iter()
-                .find(|s| s.kind == SymbolKind::Struct && s.name == *rt)
+                .find(|s| {
+                    matches!(s.kind, SymbolKind::Struct | SymbolKind::TypeAlias) && s.name == *rt
+                })
                 .map(|s| s.id.clone())
         });
 
-        let signature = source
-            .get(node.start_byte()..node.end_byte())
-            .and_then(|bytes| std::str::from_utf8(bytes).ok())
-            .map(|s| {
-                if let Some(idx) = s.find('{') {
-                    s[..idx].trim().to_string()
-                } else {
-                    s.lines().next().unwrap_or("").to_string()
-                }
-            });
+        let signature = Some(signature(node, source));
 
         let docstring = extract_docstring(node, source, &["comment"]);
 
         Some(Symbol {
-            id: Symbol::generate_id(file_path, &name, start_line),
+            id: Symbol::provisional_id(file_path, &name, start_line),
             name,
+            qualified_name: String::new(),
+            namespace: None,
             kind: SymbolKind::Method,
             file_path: file_path.to_path_buf(),
             start_line,
             end_line,
+            byte_range: node.start_byte()..node.end_byte(),
             signature,
             visibility,
             is_async: false,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers::default(),
             docstring,
+            doc_sections: DocSections::default(),
             summary: None,
             language: Language::Go,
             parent_id,
@@ -342,20 +376,16 @@ impl GoExtractor {
         let start_line = node.start_position().row as u32 + 1;
         let end_line = node.end_position().row as u32 + 1;
 
-        let visibility = if name.chars().next().map(|c| c.is_uppercase()).unwrap_or(false) {
-            Visibility::Public
-        } else {
-            Visibility::Private
-        };
+        let visibility = visibility(&name);
 
-        // Determine if struct or interface
+        // Determine if struct, interface or another named type
         let kind = if find_child(node, "struct_type").is_some() {
             SymbolKind::Struct
         } else if find_child(node, "interface_type").is_some() {
             SymbolKind::Interface
         } else {
-            // Type alias
-            SymbolKind::Interface
+            // type Celsius float64
+            SymbolKind::TypeAlias
         };
 
         let signature = source
@@ -364,25 +394,102 @@ impl GoExtractor {
             .and_then(|s| s.lines().next())
             .map(|s| s.to_string());
 
-        let docstring = node
-            .prev_sibling()
-            .filter(|n| n.kind() == "comment")
-            .map(|n| node_text(n, source).to_string());
+        let docstring = extract_docstring(node, source, &["comment"]);
 
         Some(Symbol {
-            id: Symbol::generate_id(file_path, &name, start_line),
+            id: Symbol::provisional_id(file_path, &name, start_line),
             name,
+            qualified_name: String::new(),
+            namespace: None,
             kind,
             file_path: file_path.to_path_buf(),
             start_line,
             end_line,
+            byte_range: node.start_byte()..node.end_byte(),
             signature,
             visibility,
             is_async: false,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers::default(),
             docstring,
+            doc_sections: DocSections::default(),
             summary: None,
             language: Language::Go,
             parent_id: None,
         })
     }
+
+    /// Methods an interface declares, as methods of the interface
+    fn extract_interface_methods(
+        &self,
+        node: tree_sitter::Node,
+        source: &[u8],
+        file_path: &Path,
+        interface: &Symbol,
+    ) -> Vec<Symbol> {
+        let Some(interface_type) = find_child(node, "interface_type") else {
+            return Vec::new();
+        };
+
+        let mut cursor = interface_type.walk();
+        interface_type
+            .children(&mut cursor)
+            .filter(|child| child.kind() == "method_elem")
+            .filter_map(|elem| {
+                let name = field_text(elem, "name", source)?;
+                let start_line = elem.start_position().row as u32 + 1;
+                Some(Symbol {
+                    id: Symbol::provisional_id(file_path, &name, start_line),
+                    visibility: visibility(&name),
+                    name,
+                    qualified_name: String::new(),
+                    namespace: None,
+                    kind: SymbolKind::Method,
+                    file_path: file_path.to_path_buf(),
+                    start_line,
+                    end_line: elem.end_position().row as u32 + 1,
+                    byte_range: elem.start_byte()..elem.end_byte(),
+                    signature: Some(node_text(elem, source).to_string()),
+                    is_async: false,
+                    is_partial: false,
+                    decorators: Vec::new(),
+                    modifiers: Modifiers {
+                        is_abstract: true,
+                        ..Modifiers::default()
+                    },
+                    docstring: extract_docstring(elem, source, &["comment"]),
+                    doc_sections: DocSections::default(),
+                    summary: None,
+                    language: Language::Go,
+                    parent_id: Some(interface.id.clone()),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Names starting with an upper-case letter are exported from their package
+fn visibility(name: &str) -> Visibility {
+    if name.chars().next().is_some_and(char::is_uppercase) {
+        Visibility::Public
+    } else {
+        Visibility::Private
+    }
+}
+
+/// The function or method declaration up to its body
+///
+/// Cut at the body node rather than the first `{`, which may open an
+/// `interface{}` or `struct{}` parameter type.
+fn signature(node: tree_sitter::Node, source: &[u8]) -> String {
+    let end = node
+        .child_by_field_name("body")
+        .map_or(node.end_byte(), |body| body.start_byte());
+    source
+        .get(node.start_byte()..end)
+        .and_then(|bytes| std::str::from_utf8(bytes).ok())
+        .unwrap_or_default()
+        .trim()
+        .to_string()
 }
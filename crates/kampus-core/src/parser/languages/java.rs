@@ -0,0 +1,401 @@
+//! Java language extractor
+
+use crate::parser::extractor::{
+    extract_docstring, extract_scoped_calls, field_text, find_child, node_text, LanguageExtractor,
+};
+use crate::{
+    Call, DocSections, Import, Inheritance, Language, Modifiers, NamespaceKind, Symbol, SymbolKind,
+    Visibility,
+};
+use std::path::Path;
+use tree_sitter::{Node, Tree};
+
+/// Declarations of classes and class-like types
+const TYPE_DECLARATIONS: [&str; 5] = [
+    "class_declaration",
+    "interface_declaration",
+    "enum_declaration",
+    "record_declaration",
+    "annotation_type_declaration",
+];
+
+/// Declarations of methods and constructors
+const METHOD_DECLARATIONS: [&str; 3] = [
+    "method_declaration",
+    "constructor_declaration",
+    "compact_constructor_declaration",
+];
+
+pub struct JavaExtractor;
+
+impl LanguageExtractor for JavaExtractor {
+    fn extract_symbols(&self, tree: &Tree, source: &[u8], file_path: &Path) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+        self.extract_declarations(tree.root_node(), source, file_path, None, &mut symbols);
+        symbols
+    }
+
+    fn extract_imports(&self, tree: &Tree, source: &[u8], file_path: &Path) -> Vec<Import> {
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        root.children(&mut cursor)
+            .filter(|node| node.kind() == "import_declaration")
+            .filter_map(|node| {
+                // import com.example.Foo; import com.example.*; import static ...
+                let path = find_child(node, "scoped_identifier")
+                    .or_else(|| find_child(node, "identifier"))?;
+                let mut target = node_text(path, source).to_string();
+                if find_child(node, "asterisk").is_some() {
+                    target.push_str(".*");
+                }
+                Some(Import {
+                    source_file: file_path.to_path_buf(),
+                    target,
+                    alias: None,
+                    items: Vec::new(),
+                    line: node.start_position().row as u32 + 1,
+                    resolved: None,
+                })
+            })
+            .collect()
+    }
+
+    fn extract_calls(&self, tree: &Tree, source: &[u8], symbols: &[Symbol]) -> Vec<Call> {
+        extract_scoped_calls(
+            tree.root_node(),
+            symbols,
+            &["method_invocation", "object_creation_expression"],
+            |call_node| match call_node.kind() {
+                // obj.method(), Type.method(), method()
+                "method_invocation" => Some((
+                    field_text(call_node, "name", source)?,
+                    field_text(call_node, "object", source),
+                )),
+                // new Foo<>() calls the constructor of Foo
+                _ => Some((type_name(call_node.child_by_field_name("type")?, source), None)),
+            },
+        )
+    }
+
+    fn extract_inheritance(
+        &self,
+        tree: &Tree,
+        source: &[u8],
+        symbols: &[Symbol],
+    ) -> Vec<Inheritance> {
+        let mut inheritance = Vec::new();
+
+        for symbol in symbols {
+            if !matches!(
+                symbol.kind,
+                SymbolKind::Class | SymbolKind::Interface | SymbolKind::Enum
+            ) {
+                continue;
+            }
+            let Some(node) = tree
+                .root_node()
+                .descendant_for_byte_range(symbol.byte_range.start, symbol.byte_range.end)
+            else {
+                continue;
+            };
+
+            // extends Base, implements A, B, interface extends A, B
+            let mut parents = Vec::new();
+            if let Some(superclass) = node.child_by_field_name("superclass")
+                && let Some(parent) = superclass.named_child(0)
+            {
+                parents.push(parent);
+            }
+            let lists = [
+                node.child_by_field_name("interfaces"),
+                find_child(node, "extends_interfaces"),
+            ];
+            for list in lists.into_iter().flatten() {
+                if let Some(types) = find_child(list, "type_list") {
+                    let mut cursor = types.walk();
+                    parents.extend(types.named_children(&mut cursor));
+                }
+            }
+
+            inheritance.extend(parents.into_iter().map(|parent| Inheritance {
+                child_id: symbol.id.clone(),
+                parent_name: type_name(parent, source),
+            }));
+        }
+
+        inheritance
+    }
+
+    fn scope_name(&self, node: Node, source: &[u8]) -> Option<String> {
+        if TYPE_DECLARATIONS.contains(&node.kind()) || METHOD_DECLARATIONS.contains(&node.kind()) {
+            field_text(node, "name", source)
+        } else {
+            None
+        }
+    }
+
+    /// Java files belong to the package in their `package` declaration
+    fn module_path(&self, tree: &Tree, source: &[u8], _file_path: &Path) -> Vec<String> {
+        let root = tree.root_node();
+        find_child(root, "package_declaration")
+            .and_then(|package| {
+                find_child(package, "scoped_identifier").or_else(|| find_child(package, "identifier"))
+            })
+            .map(|name| node_text(name, source).split('.').map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    fn module_kind(&self) -> NamespaceKind {
+        NamespaceKind::Package
+    }
+}
+
+impl JavaExtractor {
+    /// Extract the types and methods declared directly in `node`, recursing
+    /// into type bodies
+    fn extract_declarations(
+        &self,
+        node: Node,
+        source: &[u8],
+        file_path: &Path,
+        parent: Option<(&str, &str)>,
+        symbols: &mut Vec<Symbol>,
+    ) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            let kind = child.kind();
+            if TYPE_DECLARATIONS.contains(&kind) {
+                let Some(symbol) = self.extract_type(child, source, file_path, parent) else {
+                    continue;
+                };
+                let (id, declaration) = (symbol.id.clone(), kind);
+                symbols.push(symbol);
+                if let Some(body) = child.child_by_field_name("body") {
+                    // Enum members follow the constants
+                    let body = find_child(body, "enum_body_declarations").unwrap_or(body);
+                    self.extract_declarations(
+                        body,
+                        source,
+                        file_path,
+                        Some((&id, declaration)),
+                        symbols,
+                    );
+                }
+            } else if METHOD_DECLARATIONS.contains(&kind) {
+                symbols.extend(self.extract_method(child, source, file_path, parent));
+            } else if !matches!(kind, "block" | "constructor_body") {
+                // Types nested in other declarations, such as local classes
+                // in a static initializer, are not members
+                self.extract_declarations(child, source, file_path, parent, symbols);
+            }
+        }
+    }
+
+    fn extract_type(
+        &self,
+        node: Node,
+        source: &[u8],
+        file_path: &Path,
+        parent: Option<(&str, &str)>,
+    ) -> Option<Symbol> {
+        let name = field_text(node, "name", source)?;
+        let kind = match node.kind() {
+            "interface_declaration" | "annotation_type_declaration" => SymbolKind::Interface,
+            "enum_declaration" => SymbolKind::Enum,
+            _ => SymbolKind::Class,
+        };
+
+        Some(self.symbol(node, source, file_path, name, kind, parent))
+    }
+
+    fn extract_method(
+        &self,
+        node: Node,
+        source: &[u8],
+        file_path: &Path,
+        parent: Option<(&str, &str)>,
+    ) -> Option<Symbol> {
+        let name = field_text(node, "name", source)?;
+        Some(self.symbol(node, source, file_path, name, SymbolKind::Method, parent))
+    }
+
+    fn symbol(
+        &self,
+        node: Node,
+        source: &[u8],
+        file_path: &Path,
+        name: String,
+        kind: SymbolKind,
+        parent: Option<(&str, &str)>,
+    ) -> Symbol {
+        let start_line = node.start_position().row as u32 + 1;
+        let end_line = node.end_position().row as u32 + 1;
+
+        let docstring = extract_docstring(node, source, &["block_comment", "line_comment"]);
+
+        Symbol {
+            id: Symbol::provisional_id(file_path, &name, start_line),
+            name,
+            qualified_name: String::new(),
+            namespace: None,
+            kind,
+            file_path: file_path.to_path_buf(),
+            start_line,
+            end_line,
+            byte_range: node.start_byte()..node.end_byte(),
+            signature: Some(signature(node, source)),
+            visibility: visibility(node, source, parent.map(|(_, kind)| kind)),
+            is_async: false,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers::default(),
+            docstring,
+            doc_sections: DocSections::default(),
+            summary: None,
+            language: Language::Java,
+            parent_id: parent.map(|(id, _)| id.to_string()),
+        }
+    }
+}
+
+/// The declaration up to its body, with annotations and modifiers, on one
+/// line: `@Override public String toString()`
+fn signature(node: Node, source: &[u8]) -> String {
+    let end = node
+        .child_by_field_name("body")
+        .map_or(node.end_byte(), |body| body.start_byte());
+    let text = source
+        .get(node.start_byte()..end)
+        .and_then(|bytes| std::str::from_utf8(bytes).ok())
+        .unwrap_or_default();
+    text.trim_end_matches(';')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Visibility from the modifiers of a declaration
+///
+/// Members of interfaces and annotation types are public unless declared
+/// private. Package-private declarations are private.
+fn visibility(node: Node, source: &[u8], parent_kind: Option<&str>) -> Visibility {
+    let mut cursor = node.walk();
+    let keywords: Vec<&str> = find_child(node, "modifiers")
+        .map(|modifiers| {
+            modifiers
+                .children(&mut cursor)
+                .map(|m| node_text(m, source))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if keywords.contains(&"public") {
+        Visibility::Public
+    } else if keywords.contains(&"protected") {
+        Visibility::Protected
+    } else if keywords.contains(&"private") {
+        Visibility::Private
+    } else if matches!(
+        parent_kind,
+        Some("interface_declaration" | "annotation_type_declaration")
+    ) {
+        Visibility::Public
+    } else {
+        Visibility::Private
+    }
+}
+
+/// Name of a type without package qualifiers or type arguments:
+/// `java.util.List<String>` is `List`
+fn type_name(node: Node, source: &[u8]) -> String {
+    match node.kind() {
+        "generic_type" => node
+            .named_child(0)
+            .map(|base| type_name(base, source))
+            .unwrap_or_default(),
+        "scoped_type_identifier" => {
+            let mut cursor = node.walk();
+            node.named_children(&mut cursor)
+                .filter(|n| n.kind() == "type_identifier")
+                .last()
+                .map(|n| node_text(n, source).to_string())
+                .unwrap_or_default()
+        }
+        _ => node_text(node, source).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::languages::fixtures::{callers_of, extract_at, imports, parents, symbol};
+    use crate::{Language, SymbolKind, Visibility};
+
+    #[test]
+    fn test_java_declarations() {
+        let source = r#"package com.example.app;
+
+import java.util.List;
+import static java.util.Collections.*;
+
+/** A service */
+@Service
+public class UserService extends BaseService implements Repository<User>, AutoCloseable {
+    private final List<User> users;
+
+    public UserService() { super(); load(); }
+
+    @Override
+    protected void close() { users.clear(); new Audit().log("closed"); }
+
+    void load() {}
+
+    public record Page(int number) implements Comparable<Page> {}
+
+    enum State implements Runnable { ON, OFF; public void run() { State.valueOf("ON"); } }
+}
+
+interface Repository<T> extends Iterable<T> { T find(String id); }
+"#;
+        let file = extract_at(
+            Language::Java,
+            "src/main/java/com/example/app/UserService.java",
+            source,
+        );
+
+        let service = symbol(&file, "com.example.app.UserService");
+        assert_eq!(service.kind, SymbolKind::Class);
+        assert_eq!(service.visibility, Visibility::Public);
+        assert_eq!(service.docstring.as_deref(), Some("A service"));
+        assert!(service.signature.as_deref().unwrap().starts_with("@Service public class UserService"));
+
+        let constructor = symbol(&file, "com.example.app.UserService.UserService");
+        assert_eq!(constructor.kind, SymbolKind::Method);
+        assert_eq!(constructor.parent_id.as_ref(), Some(&service.id));
+
+        let close = symbol(&file, "com.example.app.UserService.close");
+        assert_eq!(close.visibility, Visibility::Protected);
+        assert_eq!(close.signature.as_deref(), Some("@Override protected void close()"));
+        assert_eq!(symbol(&file, "com.example.app.UserService.load").visibility, Visibility::Private);
+        assert_eq!(symbol(&file, "com.example.app.UserService.Page").kind, SymbolKind::Class);
+        assert_eq!(symbol(&file, "com.example.app.UserService.State").kind, SymbolKind::Enum);
+        assert_eq!(symbol(&file, "com.example.app.UserService.State.run").kind, SymbolKind::Method);
+        let find = symbol(&file, "com.example.app.Repository.find");
+        assert_eq!(find.visibility, Visibility::Public);
+
+        assert_eq!(file.namespace.as_deref(), Some("com.example.app"));
+        assert_eq!(
+            imports(&file),
+            [("java.util.List", None, vec![]), ("java.util.Collections.*", None, vec![])]
+        );
+
+        assert_eq!(
+            parents(&file),
+            ["BaseService", "Repository", "AutoCloseable", "Comparable", "Runnable", "Iterable"]
+        );
+
+        assert_eq!(callers_of(&file, "load"), ["UserService"]);
+        assert_eq!(callers_of(&file, "Audit"), ["close"]);
+        assert_eq!(callers_of(&file, "log"), ["close"]);
+        assert_eq!(callers_of(&file, "valueOf"), ["run"]);
+    }
+}
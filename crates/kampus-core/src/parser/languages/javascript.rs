@@ -1,9 +1,14 @@
 //! JavaScript language extractor
 
+use super::{exports, jsx};
 use crate::parser::extractor::{
-    extract_docstring, find_all_nodes, find_child, find_children, node_text, LanguageExtractor,
+    extract_docstring, extract_scoped_calls, field_text, find_all_nodes, find_child, find_children,
+    node_text, LanguageExtractor,
+};
+use crate::{
+    Call, DocSections, Import, Inheritance, Language, Modifiers, Render, Symbol, SymbolKind,
+    Visibility,
 };
-use crate::{Call, Import, Inheritance, Language, Symbol, SymbolKind, Visibility};
 use std::path::Path;
 use tree_sitter::Tree;
 
@@ -29,14 +34,14 @@ impl LanguageExtractor for JavaScriptExtractor {
         find_all_nodes(root, "variable_declaration", &mut var_nodes);
         for node in var_nodes {
             for declarator in find_children(node, "variable_declarator") {
-                if let Some(value) = find_child(declarator, "arrow_function") {
-                    if let Some(name) = find_child(declarator, "identifier") {
-                        if let Some(symbol) =
-                            self.extract_arrow_function(value, name, source, file_path)
-                        {
-                            symbols.push(symbol);
-                        }
-                    }
+                if let Some(value) = declarator.child_by_field_name("value")
+                    && (value.kind() == "arrow_function"
+                        || jsx::unwrap_component(value, source).is_some())
+                    && let Some(name) = find_child(declarator, "identifier")
+                    && let Some(symbol) =
+                        self.extract_arrow_function(value, name, source, file_path)
+                {
+                    symbols.push(symbol);
                 }
             }
         }
@@ -62,6 +67,7 @@ impl LanguageExtractor for JavaScriptExtractor {
             }
         }
 
+        exports::apply(root, source, &mut symbols);
         symbols
     }
 
@@ -73,68 +79,34 @@ impl LanguageExtractor for JavaScriptExtractor {
         let mut import_nodes = Vec::new();
         find_all_nodes(root, "import_statement", &mut import_nodes);
         for node in import_nodes {
-            let target = find_child(node, "string")
-                .map(|n| {
-                    node_text(n, source)
-                        .trim_matches('"')
-                        .trim_matches('\'')
-                        .to_string()
-                })
-                .unwrap_or_default();
-
-            let mut items = Vec::new();
-
-            // Named imports: import { a, b } from 'x'
-            if let Some(clause) = find_child(node, "import_clause") {
-                if let Some(named) = find_child(clause, "named_imports") {
-                    let mut cursor = named.walk();
-                    for specifier in named.children(&mut cursor) {
-                        if specifier.kind() == "import_specifier" {
-                            if let Some(name) = find_child(specifier, "identifier") {
-                                items.push(node_text(name, source).to_string());
-                            }
-                        }
-                    }
-                }
-
-                // Default import: import x from 'y'
-                if let Some(default) = find_child(clause, "identifier") {
-                    items.push(node_text(default, source).to_string());
-                }
-            }
-
-            imports.push(Import {
-                source_file: file_path.to_path_buf(),
-                target,
-                alias: None,
-                items,
-                line: node.start_position().row as u32 + 1,
-            });
+            imports.extend(exports::imports(node, source, file_path));
         }
 
+        // export { a } from 'x', export * from 'x'
+        imports.extend(exports::reexports(root, source, file_path));
+
         // CommonJS require
         let mut call_nodes = Vec::new();
         find_all_nodes(root, "call_expression", &mut call_nodes);
         for node in call_nodes {
-            if let Some(func) = node.child(0) {
-                if node_text(func, source) == "require" {
-                    if let Some(args) = find_child(node, "arguments") {
-                        if let Some(arg) = args.child(1) {
-                            // Skip opening paren
-                            let target = node_text(arg, source)
-                                .trim_matches('"')
-                                .trim_matches('\'')
-                                .to_string();
-                            imports.push(Import {
-                                source_file: file_path.to_path_buf(),
-                                target,
-                                alias: None,
-                                items: Vec::new(),
-                                line: node.start_position().row as u32 + 1,
-                            });
-                        }
-                    }
-                }
+            if let Some(func) = node.child(0)
+                && node_text(func, source) == "require"
+                && let Some(args) = find_child(node, "arguments")
+                && let Some(arg) = args.child(1)
+            {
+                // Skip opening paren
+                let target = node_text(arg, source)
+                    .trim_matches('"')
+                    .trim_matches('\'')
+                    .to_string();
+                imports.push(Import {
+                    source_file: file_path.to_path_buf(),
+                    target,
+                    alias: None,
+                    items: Vec::new(),
+                    line: node.start_position().row as u32 + 1,
+                    resolved: None,
+                });
             }
         }
 
@@ -142,41 +114,23 @@ impl LanguageExtractor for JavaScriptExtractor {
     }
 
     fn extract_calls(&self, tree: &Tree, source: &[u8], symbols: &[Symbol]) -> Vec<Call> {
-        let mut calls = Vec::new();
-        let root = tree.root_node();
-
-        for symbol in symbols.iter().filter(|s| {
-            s.kind == SymbolKind::Function || s.kind == SymbolKind::Method
-        }) {
-            let mut call_nodes = Vec::new();
-            find_all_nodes(root, "call_expression", &mut call_nodes);
-
-            for call_node in call_nodes {
-                let call_line = call_node.start_position().row as u32 + 1;
-                if call_line >= symbol.start_line && call_line <= symbol.end_line {
-                    if let Some(func) = call_node.child(0) {
-                        let callee_name = match func.kind() {
-                            "identifier" => node_text(func, source).to_string(),
-                            "member_expression" => {
-                                find_child(func, "property_identifier")
-                                    .map(|n| node_text(n, source).to_string())
-                                    .unwrap_or_default()
-                            }
-                            _ => continue,
-                        };
-                        if !callee_name.is_empty() && callee_name != "require" {
-                            calls.push(Call {
-                                caller_id: symbol.id.clone(),
-                                callee_name,
-                                call_site_line: call_line,
-                            });
-                        }
-                    }
+        extract_scoped_calls(tree.root_node(), symbols, &["call_expression"], |call_node| {
+            let func = call_node.child(0)?;
+            let (callee_name, receiver) = match func.kind() {
+                "identifier" => (node_text(func, source).to_string(), None),
+                "member_expression" => {
+                    let name = find_child(func, "property_identifier")
+                        .map(|n| node_text(n, source).to_string())
+                        .unwrap_or_default();
+                    let receiver = func
+                        .child_by_field_name("object")
+                        .map(|n| node_text(n, source).to_string());
+                    (name, receiver)
                 }
-            }
-        }
-
-        calls
+                _ => return None,
+            };
+            (callee_name != "require").then_some((callee_name, receiver))
+        })
     }
 
     fn extract_inheritance(
@@ -196,24 +150,46 @@ impl LanguageExtractor for JavaScriptExtractor {
                 .map(|n| node_text(n, source))
                 .unwrap_or("");
 
-            if let Some(heritage) = find_child(node, "class_heritage") {
-                if let Some(extends) = find_child(heritage, "identifier") {
-                    let parent_name = node_text(extends, source).to_string();
-                    if let Some(symbol) = symbols
-                        .iter()
-                        .find(|s| s.kind == SymbolKind::Class && s.name == class_name)
-                    {
-                        inheritance.push(Inheritance {
-                            child_id: symbol.id.clone(),
-                            parent_name,
-                        });
-                    }
+            if let Some(heritage) = find_child(node, "class_heritage")
+                && let Some(extends) = find_child(heritage, "identifier")
+            {
+                let parent_name = node_text(extends, source).to_string();
+                if let Some(symbol) = symbols
+                    .iter()
+                    .find(|s| {
+                        matches!(s.kind, SymbolKind::Class | SymbolKind::Component)
+                            && s.name == class_name
+                    })
+                {
+                    inheritance.push(Inheritance {
+                        child_id: symbol.id.clone(),
+                        parent_name,
+                    });
                 }
             }
         }
 
         inheritance
     }
+
+    fn extract_renders(&self, tree: &Tree, source: &[u8], symbols: &[Symbol]) -> Vec<Render> {
+        jsx::extract_renders(tree, source, symbols)
+    }
+
+    fn scope_name(&self, node: tree_sitter::Node, source: &[u8]) -> Option<String> {
+        match node.kind() {
+            "class_declaration" | "function_declaration" | "method_definition" => {
+                field_text(node, "name", source)
+            }
+            "variable_declarator" => {
+                let value = node.child_by_field_name("value")?;
+                (matches!(value.kind(), "arrow_function" | "function_expression")
+                    || jsx::unwrap_component(value, source).is_some())
+                .then(|| field_text(node, "name", source))?
+            }
+            _ => None,
+        }
+    }
 }
 
 impl JavaScriptExtractor {
@@ -244,18 +220,30 @@ impl JavaScriptExtractor {
             });
 
         let docstring = extract_docstring(node, source, &["comment"]);
+        let kind = if jsx::is_function_component(&name, node) {
+            SymbolKind::Component
+        } else {
+            SymbolKind::Function
+        };
 
         Some(Symbol {
-            id: Symbol::generate_id(file_path, &name, start_line),
+            id: Symbol::provisional_id(file_path, &name, start_line),
             name,
-            kind: SymbolKind::Function,
+            qualified_name: String::new(),
+            namespace: None,
+            kind,
             file_path: file_path.to_path_buf(),
             start_line,
             end_line,
+            byte_range: node.start_byte()..node.end_byte(),
             signature,
             visibility: Visibility::Public,
             is_async,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers::default(),
             docstring,
+            doc_sections: DocSections::default(),
             summary: None,
             language: Language::JavaScript,
             parent_id: None,
@@ -275,6 +263,11 @@ impl JavaScriptExtractor {
         let end_line = node.end_position().row as u32 + 1;
 
         let is_async = node_text(node, source).trim_start().starts_with("async");
+        let kind = if jsx::is_function_component(&name, node) {
+            SymbolKind::Component
+        } else {
+            SymbolKind::Function
+        };
 
         let signature = Some(format!(
             "const {} = {}",
@@ -293,16 +286,23 @@ impl JavaScriptExtractor {
         ));
 
         Some(Symbol {
-            id: Symbol::generate_id(file_path, &name, start_line),
+            id: Symbol::provisional_id(file_path, &name, start_line),
             name,
-            kind: SymbolKind::Function,
+            qualified_name: String::new(),
+            namespace: None,
+            kind,
             file_path: file_path.to_path_buf(),
             start_line,
             end_line,
+            byte_range: name_node.start_byte()..node.end_byte(),
             signature,
             visibility: Visibility::Public,
             is_async,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers::default(),
             docstring: None,
+            doc_sections: DocSections::default(),
             summary: None,
             language: Language::JavaScript,
             parent_id: None,
@@ -328,18 +328,30 @@ impl JavaScriptExtractor {
             .map(|s| s.trim_end_matches('{').trim().to_string());
 
         let docstring = extract_docstring(node, source, &["comment"]);
+        let kind = if jsx::is_class_component(node, source) {
+            SymbolKind::Component
+        } else {
+            SymbolKind::Class
+        };
 
         Some(Symbol {
-            id: Symbol::generate_id(file_path, &name, start_line),
+            id: Symbol::provisional_id(file_path, &name, start_line),
             name,
-            kind: SymbolKind::Class,
+            qualified_name: String::new(),
+            namespace: None,
+            kind,
             file_path: file_path.to_path_buf(),
             start_line,
             end_line,
+            byte_range: node.start_byte()..node.end_byte(),
             signature,
             visibility: Visibility::Public,
             is_async: false,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers::default(),
             docstring,
+            doc_sections: DocSections::default(),
             summary: None,
             language: Language::JavaScript,
             parent_id: None,
@@ -353,7 +365,8 @@ impl JavaScriptExtractor {
         file_path: &Path,
         parent_id: &str,
     ) -> Option<Symbol> {
-        let name_node = find_child(node, "property_identifier")?;
+        let name_node = find_child(node, "property_identifier")
+            .or_else(|| find_child(node, "private_property_identifier"))?;
         let name = node_text(name_node, source).to_string();
 
         let start_line = node.start_position().row as u32 + 1;
@@ -372,23 +385,26 @@ impl JavaScriptExtractor {
                 }
             });
 
-        let visibility = if name.starts_with('_') {
-            Visibility::Private
-        } else {
-            Visibility::Public
-        };
+        let visibility = exports::member_visibility(node, source);
 
         Some(Symbol {
-            id: Symbol::generate_id(file_path, &name, start_line),
+            id: Symbol::provisional_id(file_path, &name, start_line),
             name,
+            qualified_name: String::new(),
+            namespace: None,
             kind: SymbolKind::Method,
             file_path: file_path.to_path_buf(),
             start_line,
             end_line,
+            byte_range: node.start_byte()..node.end_byte(),
             signature,
             visibility,
             is_async,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers::default(),
             docstring: None,
+            doc_sections: DocSections::default(),
             summary: None,
             language: Language::JavaScript,
             parent_id: Some(parent_id.to_string()),
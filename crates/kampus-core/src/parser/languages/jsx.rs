@@ -0,0 +1,120 @@
+//! JSX support shared by the JavaScript and TypeScript extractors
+//!
+//! Recognises React function and class components and the components their
+//! JSX renders.
+
+use crate::parser::extractor::{field_text, find_child, node_text, walk_scoped};
+use crate::{Render, Symbol, SymbolKind};
+use tree_sitter::{Node, Tree};
+
+/// Base classes of class components (`React.Component`, `PureComponent`)
+const COMPONENT_BASES: [&str; 2] = ["Component", "PureComponent"];
+
+/// Calls wrapping a function component (`memo(...)`, `React.forwardRef(...)`)
+const COMPONENT_WRAPPERS: [&str; 2] = ["memo", "forwardRef"];
+
+/// Whether a name follows the React convention for components
+fn is_component_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+}
+
+/// Whether a subtree contains a JSX element
+fn contains_jsx(node: Node) -> bool {
+    if matches!(node.kind(), "jsx_element" | "jsx_self_closing_element") {
+        return true;
+    }
+    let mut cursor = node.walk();
+    node.children(&mut cursor).any(contains_jsx)
+}
+
+/// Whether a function, arrow function or wrapped function named `name` is a
+/// function component: a capitalised name and a body that returns JSX
+pub fn is_function_component(name: &str, node: Node) -> bool {
+    is_component_name(name) && contains_jsx(node)
+}
+
+/// Whether a class declaration is a class component: it extends a React
+/// component class or its `render` method returns JSX
+pub fn is_class_component(node: Node, source: &[u8]) -> bool {
+    let Some(name) = field_text(node, "name", source) else {
+        return false;
+    };
+    if !is_component_name(&name) {
+        return false;
+    }
+
+    if let Some(heritage) = find_child(node, "class_heritage") {
+        let base = node_text(heritage, source)
+            .trim_start_matches("extends")
+            .trim_start()
+            .split(|c: char| c == '<' || c == '(' || c == '{' || c.is_whitespace())
+            .next()
+            .unwrap_or("");
+        if COMPONENT_BASES.contains(&base.rsplit('.').next().unwrap_or(base)) {
+            return true;
+        }
+    }
+
+    let Some(body) = node.child_by_field_name("body") else {
+        return false;
+    };
+    let mut cursor = body.walk();
+    body.children(&mut cursor).any(|member| {
+        member.kind() == "method_definition"
+            && field_text(member, "name", source).as_deref() == Some("render")
+            && contains_jsx(member)
+    })
+}
+
+/// The function inside a component wrapper call such as
+/// `memo(function Row() {...})` or `React.forwardRef((props, ref) => ...)`
+pub fn unwrap_component<'a>(node: Node<'a>, source: &[u8]) -> Option<Node<'a>> {
+    if node.kind() != "call_expression" {
+        return None;
+    }
+    let function = node_text(node.child_by_field_name("function")?, source);
+    if !COMPONENT_WRAPPERS.contains(&function.rsplit('.').next().unwrap_or(function)) {
+        return None;
+    }
+
+    let argument = node.child_by_field_name("arguments")?.named_child(0)?;
+    match argument.kind() {
+        "arrow_function" | "function_expression" | "function" => Some(argument),
+        _ => unwrap_component(argument, source),
+    }
+}
+
+/// Collect the components rendered by each component, attributed to the
+/// innermost component enclosing the JSX element
+///
+/// Lowercase elements are intrinsic (`<div>`) and skipped; member elements
+/// such as `<Icons.Close />` are always components.
+pub fn extract_renders(tree: &Tree, source: &[u8], symbols: &[Symbol]) -> Vec<Render> {
+    walk_scoped(
+        tree.root_node(),
+        symbols,
+        &[SymbolKind::Component],
+        &["jsx_opening_element", "jsx_self_closing_element"],
+        |component, element| {
+            let name = element.child_by_field_name("name")?;
+            let (element_name, receiver) = match name.kind() {
+                "identifier" => {
+                    let name = node_text(name, source);
+                    (is_component_name(name).then(|| name.to_string())?, None)
+                }
+                "member_expression" => (
+                    field_text(name, "property", source)?,
+                    field_text(name, "object", source),
+                ),
+                _ => return None,
+            };
+
+            Some(Render {
+                component_id: component.id.clone(),
+                element_name,
+                receiver,
+                line: element.start_position().row as u32 + 1,
+            })
+        },
+    )
+}
@@ -1,15 +1,30 @@
 //! Language-specific symbol extractors
 
+mod c;
 mod cpp;
+mod csharp;
+pub(crate) mod exports;
+#[cfg(test)]
+pub(crate) mod fixtures;
 mod go;
+mod java;
 mod javascript;
+mod jsx;
+mod php;
 mod python;
+mod ruby;
 mod rust;
 mod typescript;
 
+pub use c::CExtractor;
+pub(crate) use c::{included_headers, is_cpp_header, is_cpp_source};
 pub use cpp::CppExtractor;
+pub use csharp::CSharpExtractor;
 pub use go::GoExtractor;
+pub use java::JavaExtractor;
 pub use javascript::JavaScriptExtractor;
+pub use php::PhpExtractor;
 pub use python::PythonExtractor;
+pub use ruby::RubyExtractor;
 pub use rust::RustExtractor;
 pub use typescript::TypeScriptExtractor;
@@ -0,0 +1,418 @@
+//! PHP language extractor
+
+use crate::parser::extractor::{
+    extract_docstring, extract_scoped_calls, field_text, find_child, node_text, LanguageExtractor,
+};
+use crate::{
+    Call, DocSections, Import, Inheritance, Language, Modifiers, NamespaceKind, Symbol, SymbolKind,
+    Visibility,
+};
+use std::path::Path;
+use tree_sitter::{Node, Tree};
+
+/// Declarations of classes and class-like types
+const TYPE_DECLARATIONS: [&str; 4] = [
+    "class_declaration",
+    "interface_declaration",
+    "trait_declaration",
+    "enum_declaration",
+];
+
+pub struct PhpExtractor;
+
+impl LanguageExtractor for PhpExtractor {
+    fn extract_symbols(&self, tree: &Tree, source: &[u8], file_path: &Path) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+        self.extract_declarations(tree.root_node(), source, file_path, None, &mut symbols);
+        symbols
+    }
+
+    fn extract_imports(&self, tree: &Tree, source: &[u8], file_path: &Path) -> Vec<Import> {
+        let mut imports = Vec::new();
+        let mut pending = vec![tree.root_node()];
+
+        // use statements sit at the top of the file or of a namespace block
+        while let Some(node) = pending.pop() {
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                match child.kind() {
+                    "namespace_use_declaration" => {
+                        imports.extend(self.extract_use(child, source, file_path))
+                    }
+                    "namespace_definition" => pending.extend(child.child_by_field_name("body")),
+                    _ => {}
+                }
+            }
+        }
+
+        imports.sort_by_key(|i| i.line);
+        imports
+    }
+
+    fn extract_calls(&self, tree: &Tree, source: &[u8], symbols: &[Symbol]) -> Vec<Call> {
+        extract_scoped_calls(
+            tree.root_node(),
+            symbols,
+            &[
+                "function_call_expression",
+                "member_call_expression",
+                "nullsafe_member_call_expression",
+                "scoped_call_expression",
+                "object_creation_expression",
+            ],
+            |call_node| match call_node.kind() {
+                // helper(), \App\helper()
+                "function_call_expression" => {
+                    let function = call_node.child_by_field_name("function")?;
+                    matches!(function.kind(), "name" | "qualified_name")
+                        .then(|| (type_name(function, source), None))
+                }
+                // $this->save(), $user?->save()
+                "member_call_expression" | "nullsafe_member_call_expression" => Some((
+                    field_text(call_node, "name", source)?,
+                    field_text(call_node, "object", source),
+                )),
+                // User::find(), parent::__construct()
+                "scoped_call_expression" => Some((
+                    field_text(call_node, "name", source)?,
+                    field_text(call_node, "scope", source),
+                )),
+                // new User() calls the constructor of User
+                _ => {
+                    let mut cursor = call_node.walk();
+                    let created = call_node
+                        .named_children(&mut cursor)
+                        .find(|n| matches!(n.kind(), "name" | "qualified_name"))?;
+                    Some((type_name(created, source), None))
+                }
+            },
+        )
+    }
+
+    fn extract_inheritance(
+        &self,
+        tree: &Tree,
+        source: &[u8],
+        symbols: &[Symbol],
+    ) -> Vec<Inheritance> {
+        let mut inheritance = Vec::new();
+
+        for symbol in symbols {
+            if !matches!(
+                symbol.kind,
+                SymbolKind::Class | SymbolKind::Interface | SymbolKind::Trait | SymbolKind::Enum
+            ) {
+                continue;
+            }
+            let Some(node) = tree
+                .root_node()
+                .descendant_for_byte_range(symbol.byte_range.start, symbol.byte_range.end)
+            else {
+                continue;
+            };
+
+            // extends Base, implements A, B, and traits used in the body
+            let mut lists: Vec<Node> = ["base_clause", "class_interface_clause"]
+                .into_iter()
+                .filter_map(|kind| find_child(node, kind))
+                .collect();
+            if let Some(body) = node.child_by_field_name("body") {
+                let mut cursor = body.walk();
+                lists.extend(
+                    body.named_children(&mut cursor)
+                        .filter(|n| n.kind() == "use_declaration"),
+                );
+            }
+
+            for list in lists {
+                let mut cursor = list.walk();
+                for parent in list.named_children(&mut cursor) {
+                    if matches!(parent.kind(), "name" | "qualified_name") {
+                        inheritance.push(Inheritance {
+                            child_id: symbol.id.clone(),
+                            parent_name: type_name(parent, source),
+                        });
+                    }
+                }
+            }
+        }
+
+        inheritance
+    }
+
+    fn scope_name(&self, node: Node, source: &[u8]) -> Option<String> {
+        let kind = node.kind();
+        if TYPE_DECLARATIONS.contains(&kind)
+            || matches!(kind, "method_declaration" | "function_definition")
+            || (kind == "namespace_definition" && node.child_by_field_name("body").is_some())
+        {
+            field_text(node, "name", source)
+        } else {
+            None
+        }
+    }
+
+    fn namespace_kind(&self, node: Node) -> Option<NamespaceKind> {
+        (node.kind() == "namespace_definition").then_some(NamespaceKind::Namespace)
+    }
+
+    /// A `namespace App\Models;` statement applies to the rest of the file
+    fn module_path(&self, tree: &Tree, source: &[u8], _file_path: &Path) -> Vec<String> {
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        root.named_children(&mut cursor)
+            .find(|n| n.kind() == "namespace_definition" && n.child_by_field_name("body").is_none())
+            .and_then(|namespace| field_text(namespace, "name", source))
+            .map(|name| name.split('\\').map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+}
+
+impl PhpExtractor {
+    /// Extract the types, methods and functions declared directly in `node`,
+    /// recursing into namespace blocks and type bodies
+    fn extract_declarations(
+        &self,
+        node: Node,
+        source: &[u8],
+        file_path: &Path,
+        parent: Option<&str>,
+        symbols: &mut Vec<Symbol>,
+    ) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            let kind = child.kind();
+            if TYPE_DECLARATIONS.contains(&kind) {
+                let Some(name) = field_text(child, "name", source) else {
+                    continue;
+                };
+                let symbol_kind = match kind {
+                    "interface_declaration" => SymbolKind::Interface,
+                    "trait_declaration" => SymbolKind::Trait,
+                    "enum_declaration" => SymbolKind::Enum,
+                    _ => SymbolKind::Class,
+                };
+                let symbol = self.symbol(child, source, file_path, name, symbol_kind, None);
+                let id = symbol.id.clone();
+                symbols.push(symbol);
+                if let Some(body) = child.child_by_field_name("body") {
+                    self.extract_declarations(body, source, file_path, Some(&id), symbols);
+                }
+            } else if kind == "method_declaration" || kind == "function_definition" {
+                let Some(name) = field_text(child, "name", source) else {
+                    continue;
+                };
+                let symbol_kind = if parent.is_some() {
+                    SymbolKind::Method
+                } else {
+                    SymbolKind::Function
+                };
+                symbols.push(self.symbol(child, source, file_path, name, symbol_kind, parent));
+            } else if kind == "namespace_definition" {
+                if let Some(body) = child.child_by_field_name("body") {
+                    self.extract_declarations(body, source, file_path, None, symbols);
+                }
+            } else if kind == "if_statement" && parent.is_none() {
+                // Functions declared conditionally, as in
+                // `if (!function_exists('helper')) { function helper() {} }`
+                if let Some(body) = child.child_by_field_name("body") {
+                    self.extract_declarations(body, source, file_path, None, symbols);
+                }
+            } else if kind == "compound_statement" && parent.is_none() {
+                self.extract_declarations(child, source, file_path, None, symbols);
+            }
+        }
+    }
+
+    fn symbol(
+        &self,
+        node: Node,
+        source: &[u8],
+        file_path: &Path,
+        name: String,
+        kind: SymbolKind,
+        parent: Option<&str>,
+    ) -> Symbol {
+        let start_line = node.start_position().row as u32 + 1;
+        let end_line = node.end_position().row as u32 + 1;
+
+        let docstring = extract_docstring(node, source, &["comment"]);
+
+        Symbol {
+            id: Symbol::provisional_id(file_path, &name, start_line),
+            name,
+            qualified_name: String::new(),
+            namespace: None,
+            kind,
+            file_path: file_path.to_path_buf(),
+            start_line,
+            end_line,
+            byte_range: node.start_byte()..node.end_byte(),
+            signature: Some(signature(node, source)),
+            visibility: visibility(node, source),
+            is_async: false,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers::default(),
+            docstring,
+            doc_sections: DocSections::default(),
+            summary: None,
+            language: Language::Php,
+            parent_id: parent.map(String::from),
+        }
+    }
+
+    fn extract_use(&self, node: Node, source: &[u8], file_path: &Path) -> Vec<Import> {
+        // use App\Models\User; use App\Models\{User, Post as Article};
+        let prefix = find_child(node, "namespace_name").map(|n| node_text(n, source));
+        let clauses = match node.child_by_field_name("body") {
+            Some(group) => {
+                let mut cursor = group.walk();
+                group.named_children(&mut cursor).collect::<Vec<_>>()
+            }
+            None => {
+                let mut cursor = node.walk();
+                node.named_children(&mut cursor).collect()
+            }
+        };
+
+        clauses
+            .into_iter()
+            .filter(|clause| clause.kind() == "namespace_use_clause")
+            .filter_map(|clause| {
+                let alias = clause.child_by_field_name("alias");
+                let mut cursor = clause.walk();
+                let path = clause
+                    .named_children(&mut cursor)
+                    .find(|n| Some(*n) != alias && matches!(n.kind(), "name" | "qualified_name"))?;
+                let path = node_text(path, source).trim_start_matches('\\');
+                let target = match prefix {
+                    Some(prefix) => format!("{}\\{}", prefix.trim_start_matches('\\'), path),
+                    None => path.to_string(),
+                };
+                Some(Import {
+                    source_file: file_path.to_path_buf(),
+                    target,
+                    alias: alias.map(|a| node_text(a, source).to_string()),
+                    items: Vec::new(),
+                    line: clause.start_position().row as u32 + 1,
+                    resolved: None,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The declaration up to its body, on one line
+fn signature(node: Node, source: &[u8]) -> String {
+    let end = node
+        .child_by_field_name("body")
+        .map_or(node.end_byte(), |body| body.start_byte());
+    let text = source
+        .get(node.start_byte()..end)
+        .and_then(|bytes| std::str::from_utf8(bytes).ok())
+        .unwrap_or_default();
+    text.trim_end_matches(';')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Visibility from the modifiers of a declaration; members without one are
+/// public
+fn visibility(node: Node, source: &[u8]) -> Visibility {
+    match find_child(node, "visibility_modifier").map(|m| node_text(m, source)) {
+        Some("private") => Visibility::Private,
+        Some("protected") => Visibility::Protected,
+        _ => Visibility::Public,
+    }
+}
+
+/// Name of a type or function without its namespace: `\App\Models\User` is
+/// `User`
+fn type_name(node: Node, source: &[u8]) -> String {
+    node_text(node, source)
+        .rsplit('\\')
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::languages::fixtures::{callers_of, extract, imports, parents, symbol};
+    use crate::{Language, SymbolKind, Visibility};
+
+    #[test]
+    fn test_php_declarations() {
+        let source = r#"<?php
+namespace App\Models;
+
+use Illuminate\Database\Eloquent\Model;
+use App\Contracts\{Billable, Auditable as Audit};
+
+/** A customer */
+final class Customer extends Model implements Billable, \JsonSerializable
+{
+    use HasFactory, Notifiable;
+
+    public function charge(int $amount): bool
+    {
+        $invoice = new Invoice($amount);
+        return $this->gateway()->pay($invoice) && Log::info("charged");
+    }
+
+    private function gateway() { return resolve_gateway(); }
+}
+
+trait Notifiable { protected function notify() {} }
+
+interface Chargeable extends Billable {}
+
+function helper() { return \App\format_money(1); }
+"#;
+        let file = extract(Language::Php, source);
+
+        let customer = symbol(&file, "App\\Models\\Customer");
+        assert_eq!(customer.kind, SymbolKind::Class);
+        assert_eq!(customer.docstring.as_deref(), Some("A customer"));
+        assert_eq!(
+            customer.signature.as_deref(),
+            Some("final class Customer extends Model implements Billable, \\JsonSerializable")
+        );
+
+        let charge = symbol(&file, "App\\Models\\Customer\\charge");
+        assert_eq!(charge.kind, SymbolKind::Method);
+        assert_eq!(charge.visibility, Visibility::Public);
+        assert_eq!(charge.parent_id.as_ref(), Some(&customer.id));
+        assert_eq!(symbol(&file, "App\\Models\\Customer\\gateway").visibility, Visibility::Private);
+        assert_eq!(symbol(&file, "App\\Models\\Notifiable").kind, SymbolKind::Trait);
+        assert_eq!(
+            symbol(&file, "App\\Models\\Notifiable\\notify").visibility,
+            Visibility::Protected
+        );
+        assert_eq!(symbol(&file, "App\\Models\\Chargeable").kind, SymbolKind::Interface);
+        assert_eq!(symbol(&file, "App\\Models\\helper").kind, SymbolKind::Function);
+        assert_eq!(file.namespace.as_deref(), Some("App\\Models"));
+
+        assert_eq!(
+            imports(&file),
+            [
+                ("Illuminate\\Database\\Eloquent\\Model", None, vec![]),
+                ("App\\Contracts\\Billable", None, vec![]),
+                ("App\\Contracts\\Auditable", Some("Audit"), vec![]),
+            ]
+        );
+
+        assert_eq!(
+            parents(&file),
+            ["Model", "Billable", "JsonSerializable", "HasFactory", "Notifiable", "Billable"]
+        );
+
+        assert_eq!(callers_of(&file, "Invoice"), ["charge"]);
+        assert_eq!(callers_of(&file, "pay"), ["charge"]);
+        assert_eq!(callers_of(&file, "info"), ["charge"]);
+        assert_eq!(callers_of(&file, "resolve_gateway"), ["gateway"]);
+        assert_eq!(callers_of(&file, "format_money"), ["helper"]);
+    }
+}
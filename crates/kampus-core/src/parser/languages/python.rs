@@ -1,11 +1,17 @@
 //! Python language extractor
 
+use crate::parser::docs;
 use crate::parser::extractor::{
-    find_all_nodes, find_child, find_children, node_text, LanguageExtractor,
+    extract_scoped_calls, field_text, find_all_nodes, find_child, find_children, is_member_of,
+    node_text, LanguageExtractor,
 };
-use crate::{Call, Import, Inheritance, Language, Symbol, SymbolKind, Visibility};
+use crate::{
+    Call, Decorator, DocSections, Import, Inheritance, Language, Modifiers, NamespaceKind,
+    Reference, ReferenceKind, Symbol, SymbolKind, Visibility,
+};
+use std::collections::HashSet;
 use std::path::Path;
-use tree_sitter::Tree;
+use tree_sitter::{Node, Tree};
 
 pub struct PythonExtractor;
 
@@ -14,10 +20,13 @@ impl LanguageExtractor for PythonExtractor {
         let mut symbols = Vec::new();
         let root = tree.root_node();
 
-        // Extract top-level functions
+        // Extract functions (methods are extracted with their class)
         let mut func_nodes = Vec::new();
         find_all_nodes(root, "function_definition", &mut func_nodes);
         for node in func_nodes {
+            if is_member_of(decorated(node), "block", "class_definition") {
+                continue;
+            }
             if let Some(symbol) = self.extract_function(node, source, file_path, None) {
                 symbols.push(symbol);
             }
@@ -31,11 +40,18 @@ impl LanguageExtractor for PythonExtractor {
                 let class_id = class_symbol.id.clone();
                 symbols.push(class_symbol);
 
-                // Extract methods from class body
+                // Extract methods from class body, with their decorators
                 if let Some(body) = find_child(node, "block") {
-                    for child in find_children(body, "function_definition") {
-                        if let Some(method) =
-                            self.extract_function(child, source, file_path, Some(&class_id))
+                    let mut cursor = body.walk();
+                    for child in body.named_children(&mut cursor) {
+                        let definition = match child.kind() {
+                            "decorated_definition" => child.child_by_field_name("definition"),
+                            _ => Some(child),
+                        };
+                        if let Some(definition) = definition
+                            && definition.kind() == "function_definition"
+                            && let Some(method) =
+                                self.extract_function(definition, source, file_path, Some(&class_id))
                         {
                             symbols.push(method);
                         }
@@ -44,6 +60,22 @@ impl LanguageExtractor for PythonExtractor {
             }
         }
 
+        // A module's __all__ lists the names it exports
+        if let Some(exports) = dunder_all(root, source) {
+            for symbol in &mut symbols {
+                let range = symbol.byte_range.clone();
+                if let Some(node) = root.descendant_for_byte_range(range.start, range.end)
+                    && enclosing_scope(node).is_none()
+                {
+                    symbol.visibility = if exports.contains(&symbol.name) {
+                        Visibility::Public
+                    } else {
+                        Visibility::Private
+                    };
+                }
+            }
+        }
+
         symbols
     }
 
@@ -51,90 +83,100 @@ impl LanguageExtractor for PythonExtractor {
         let mut imports = Vec::new();
         let root = tree.root_node();
 
-        // import x, y
+        // import x, y.z as w
         let mut import_nodes = Vec::new();
         find_all_nodes(root, "import_statement", &mut import_nodes);
         for node in import_nodes {
-            for name_node in find_children(node, "dotted_name") {
-                let target = node_text(name_node, source).to_string();
-                let alias = find_child(node, "aliased_import")
-                    .and_then(|n| find_child(n, "identifier"))
-                    .map(|n| node_text(n, source).to_string());
+            let mut cursor = node.walk();
+            for name_node in node.children_by_field_name("name", &mut cursor) {
+                let (target, alias) = match name_node.kind() {
+                    "aliased_import" => (
+                        field_text(name_node, "name", source).unwrap_or_default(),
+                        field_text(name_node, "alias", source),
+                    ),
+                    _ => (node_text(name_node, source).to_string(), None),
+                };
                 imports.push(Import {
                     source_file: file_path.to_path_buf(),
                     target,
                     alias,
                     items: Vec::new(),
                     line: node.start_position().row as u32 + 1,
+                    resolved: None,
                 });
             }
         }
 
-        // from x import y, z
+        // from x import y, z as w; from . import *
         let mut from_nodes = Vec::new();
         find_all_nodes(root, "import_from_statement", &mut from_nodes);
         for node in from_nodes {
-            let target = find_child(node, "dotted_name")
-                .or_else(|| find_child(node, "relative_import"))
-                .map(|n| node_text(n, source).to_string())
-                .unwrap_or_default();
-
-            let items: Vec<String> = find_children(node, "dotted_name")
-                .into_iter()
-                .skip(1) // Skip the module name
-                .map(|n| node_text(n, source).to_string())
-                .collect();
-
-            imports.push(Import {
-                source_file: file_path.to_path_buf(),
-                target,
-                alias: None,
-                items,
-                line: node.start_position().row as u32 + 1,
-            });
+            let target = field_text(node, "module_name", source).unwrap_or_default();
+            let line = node.start_position().row as u32 + 1;
+
+            // Each renamed item gets an import of its own, whose alias is
+            // the name the item is bound to
+            let mut items = Vec::new();
+            let mut renamed = Vec::new();
+            let mut cursor = node.walk();
+            for name_node in node.children_by_field_name("name", &mut cursor) {
+                match name_node.kind() {
+                    "aliased_import" => renamed.extend(
+                        field_text(name_node, "name", source)
+                            .map(|item| (item, field_text(name_node, "alias", source))),
+                    ),
+                    _ => items.push(node_text(name_node, source).to_string()),
+                }
+            }
+            if find_child(node, "wildcard_import").is_some() {
+                items.push("*".to_string());
+            }
+
+            if !items.is_empty() || renamed.is_empty() {
+                imports.push(Import {
+                    source_file: file_path.to_path_buf(),
+                    target: target.clone(),
+                    alias: None,
+                    items,
+                    line,
+                    resolved: None,
+                });
+            }
+            for (item, alias) in renamed {
+                imports.push(Import {
+                    source_file: file_path.to_path_buf(),
+                    target: target.clone(),
+                    alias,
+                    items: vec![item],
+                    line,
+                    resolved: None,
+                });
+            }
         }
 
         imports
     }
 
     fn extract_calls(&self, tree: &Tree, source: &[u8], symbols: &[Symbol]) -> Vec<Call> {
-        let mut calls = Vec::new();
-        let root = tree.root_node();
-
-        // Build a map of function bodies
-        for symbol in symbols.iter().filter(|s| {
-            s.kind == SymbolKind::Function || s.kind == SymbolKind::Method
-        }) {
-            let mut call_nodes = Vec::new();
-            find_all_nodes(root, "call", &mut call_nodes);
-
-            for call_node in call_nodes {
-                let call_line = call_node.start_position().row as u32 + 1;
-                if call_line >= symbol.start_line && call_line <= symbol.end_line {
-                    if let Some(func) = call_node.child(0) {
-                        let callee_name = match func.kind() {
-                            "identifier" => node_text(func, source).to_string(),
-                            "attribute" => {
-                                // obj.method() - get the method name
-                                find_child(func, "identifier")
-                                    .map(|n| node_text(n, source).to_string())
-                                    .unwrap_or_default()
-                            }
-                            _ => continue,
-                        };
-                        if !callee_name.is_empty() {
-                            calls.push(Call {
-                                caller_id: symbol.id.clone(),
-                                callee_name,
-                                call_site_line: call_line,
-                            });
-                        }
-                    }
+        extract_scoped_calls(tree.root_node(), symbols, &["call"], |call_node| {
+            let func = call_node.child(0)?;
+            let (callee_name, receiver) = match func.kind() {
+                "identifier" => (node_text(func, source).to_string(), None),
+                "attribute" => {
+                    // obj.method() - get the method name
+                    let name = func
+                        .child_by_field_name("attribute")
+                        .map(|n| node_text(n, source).to_string())
+                        .unwrap_or_default();
+                    let receiver = func
+                        .child_by_field_name("object")
+                        .map(|n| node_text(n, source).to_string());
+                    (name, receiver)
                 }
-            }
-        }
-
-        calls
+                _ => return None,
+            };
+            Some((callee_name, receiver))
+        })
     }
 
     fn extract_inheritance(
@@ -178,6 +220,98 @@ impl LanguageExtractor for PythonExtractor {
 
         inheritance
     }
+
+    fn extract_references(
+        &self,
+        tree: &Tree,
+        source: &[u8],
+        symbols: &[Symbol],
+    ) -> Vec<Reference> {
+        let mut references = Vec::new();
+
+        for symbol in symbols {
+            // @app.route("/") names route, qualified by app
+            for decorator in &symbol.decorators {
+                let (receiver, name) = split_qualified(&decorator.name);
+                references.push(Reference {
+                    kind: ReferenceKind::DecoratedBy,
+                    source_id: symbol.id.clone(),
+                    target_name: name.to_string(),
+                    receiver: receiver.map(String::from),
+                    line: decorator.line,
+                });
+            }
+
+            if !matches!(symbol.kind, SymbolKind::Function | SymbolKind::Method) {
+                continue;
+            }
+            let Some(node) = tree
+                .root_node()
+                .descendant_for_byte_range(symbol.byte_range.start, symbol.byte_range.end)
+            else {
+                continue;
+            };
+
+            // def save(user: User, *, audit: Optional[Log] = None) -> "Result"
+            let mut annotations = Vec::new();
+            if let Some(parameters) = node.child_by_field_name("parameters") {
+                let mut cursor = parameters.walk();
+                annotations.extend(
+                    parameters
+                        .named_children(&mut cursor)
+                        .filter_map(|p| p.child_by_field_name("type")),
+                );
+            }
+            annotations.extend(node.child_by_field_name("return_type"));
+
+            let mut seen = HashSet::new();
+            for annotation in annotations {
+                let mut names = Vec::new();
+                type_names(annotation, source, &mut names);
+                for (receiver, name) in names {
+                    if seen.insert((receiver.clone(), name.clone())) {
+                        references.push(Reference {
+                            kind: ReferenceKind::UsesType,
+                            source_id: symbol.id.clone(),
+                            target_name: name,
+                            receiver,
+                            line: annotation.start_position().row as u32 + 1,
+                        });
+                    }
+                }
+            }
+        }
+
+        references
+    }
+
+    fn scope_name(&self, node: tree_sitter::Node, source: &[u8]) -> Option<String> {
+        match node.kind() {
+            "class_definition" | "function_definition" => field_text(node, "name", source),
+            _ => None,
+        }
+    }
+
+    fn module_path(&self, _tree: &Tree, _source: &[u8], file_path: &Path) -> Vec<String> {
+        let mut path: Vec<String> = file_path
+            .with_extension("")
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+
+        // src/ layout: src/app/models.py is app.models
+        if path.len() > 1 && path[0] == "src" {
+            path.remove(0);
+        }
+        if path.last().is_some_and(|m| m == "__init__") {
+            path.pop();
+        }
+        path
+    }
+
+    fn module_kind(&self) -> NamespaceKind {
+        NamespaceKind::Package
+    }
 }
 
 impl PythonExtractor {
@@ -214,45 +348,31 @@ impl PythonExtractor {
             .and_then(|s| s.lines().next())
             .map(|s| s.trim_end_matches(':').to_string());
 
-        // Extract docstring (first string in body)
-        let docstring = find_child(node, "block")
-            .and_then(|body| {
-                let mut cursor = body.walk();
-                body.children(&mut cursor)
-                    .find(|c| c.kind() == "expression_statement")
-            })
-            .and_then(|expr| find_child(expr, "string"))
-            .map(|s| {
-                let text = node_text(s, source);
-                // Strip triple quotes
-                text.trim_start_matches("\"\"\"")
-                    .trim_start_matches("'''")
-                    .trim_end_matches("\"\"\"")
-                    .trim_end_matches("'''")
-                    .trim()
-                    .to_string()
-            });
-
-        // Visibility based on name convention
-        let visibility = if name.starts_with("__") && !name.ends_with("__") {
-            Visibility::Private
-        } else if name.starts_with('_') {
-            Visibility::Protected
-        } else {
-            Visibility::Public
-        };
+        let docstring = body_docstring(node, source);
+
+        let decorators = decorators(node, source);
+        let modifiers = modifiers(&decorators);
+
+        let visibility = visibility(node, &name);
 
         Some(Symbol {
-            id: Symbol::generate_id(file_path, &name, start_line),
+            id: Symbol::provisional_id(file_path, &name, start_line),
             name,
+            qualified_name: String::new(),
+            namespace: None,
             kind,
             file_path: file_path.to_path_buf(),
             start_line,
             end_line,
+            byte_range: node.start_byte()..node.end_byte(),
             signature,
             visibility,
             is_async,
+            is_partial: false,
+            decorators,
+            modifiers,
             docstring,
+            doc_sections: DocSections::default(),
             summary: None,
             language: Language::Python,
             parent_id: parent_id.map(String::from),
@@ -278,44 +398,203 @@ impl PythonExtractor {
             .and_then(|s| s.lines().next())
             .map(|s| s.trim_end_matches(':').to_string());
 
-        // Extract docstring
-        let docstring = find_child(node, "block")
-            .and_then(|body| {
-                let mut cursor = body.walk();
-                body.children(&mut cursor)
-                    .find(|c| c.kind() == "expression_statement")
-            })
-            .and_then(|expr| find_child(expr, "string"))
-            .map(|s| {
-                let text = node_text(s, source);
-                text.trim_start_matches("\"\"\"")
-                    .trim_start_matches("'''")
-                    .trim_end_matches("\"\"\"")
-                    .trim_end_matches("'''")
-                    .trim()
-                    .to_string()
-            });
-
-        let visibility = if name.starts_with('_') {
-            Visibility::Private
-        } else {
-            Visibility::Public
-        };
+        let docstring = body_docstring(node, source);
+
+        let visibility = visibility(node, &name);
 
         Some(Symbol {
-            id: Symbol::generate_id(file_path, &name, start_line),
+            id: Symbol::provisional_id(file_path, &name, start_line),
             name,
+            qualified_name: String::new(),
+            namespace: None,
             kind: SymbolKind::Class,
             file_path: file_path.to_path_buf(),
             start_line,
             end_line,
+            byte_range: node.start_byte()..node.end_byte(),
             signature,
             visibility,
             is_async: false,
+            is_partial: false,
+            decorators: decorators(node, source),
+            modifiers: Modifiers::default(),
             docstring,
+            doc_sections: DocSections::default(),
             summary: None,
             language: Language::Python,
             parent_id: None,
         })
     }
 }
+
+/// The `decorated_definition` wrapping a decorated function or class, or the
+/// definition itself
+fn decorated(node: Node) -> Node {
+    node.parent()
+        .filter(|p| p.kind() == "decorated_definition")
+        .unwrap_or(node)
+}
+
+/// Kind of the innermost function or class definition enclosing a
+/// definition
+fn enclosing_scope(node: Node) -> Option<&'static str> {
+    let mut ancestor = node.parent();
+    while let Some(parent) = ancestor {
+        match parent.kind() {
+            "function_definition" => return Some("function_definition"),
+            "class_definition" => return Some("class_definition"),
+            _ => ancestor = parent.parent(),
+        }
+    }
+    None
+}
+
+/// Visibility by naming convention
+///
+/// `__name` members are private to their class and `_name` members
+/// protected, while `_name` definitions at module level are private to the
+/// module. Functions local to another function are private.
+fn visibility(node: Node, name: &str) -> Visibility {
+    match enclosing_scope(node) {
+        Some("function_definition") => Visibility::Private,
+        _ if name.starts_with("__") && name.ends_with("__") => Visibility::Public,
+        Some(_) if name.starts_with("__") => Visibility::Private,
+        Some(_) if name.starts_with('_') => Visibility::Protected,
+        None if name.starts_with('_') => Visibility::Private,
+        _ => Visibility::Public,
+    }
+}
+
+/// Names listed in a module-level `__all__ = [...]`, including later
+/// `__all__ += [...]` additions
+fn dunder_all(root: Node, source: &[u8]) -> Option<HashSet<String>> {
+    let mut exports: Option<HashSet<String>> = None;
+    let mut cursor = root.walk();
+    for statement in root.named_children(&mut cursor) {
+        let Some(assignment) = statement
+            .named_child(0)
+            .filter(|_| statement.kind() == "expression_statement")
+            .filter(|a| matches!(a.kind(), "assignment" | "augmented_assignment"))
+        else {
+            continue;
+        };
+        if field_text(assignment, "left", source).as_deref() != Some("__all__") {
+            continue;
+        }
+        let Some(names) = assignment
+            .child_by_field_name("right")
+            .filter(|r| matches!(r.kind(), "list" | "tuple"))
+        else {
+            continue;
+        };
+        let exports = exports.get_or_insert_default();
+        let mut cursor = names.walk();
+        for name in names.named_children(&mut cursor) {
+            if name.kind() == "string" {
+                exports.insert(docs::clean(node_text(name, source)));
+            }
+        }
+    }
+    exports
+}
+
+/// String literal opening the body of a function or class definition
+fn body_docstring(node: Node, source: &[u8]) -> Option<String> {
+    let body = node.child_by_field_name("body")?;
+    let mut cursor = body.walk();
+    let first = body.named_children(&mut cursor).find(|c| c.kind() != "comment")?;
+    if first.kind() != "expression_statement" {
+        return None;
+    }
+    let string = first.named_child(0).filter(|s| s.kind() == "string")?;
+    Some(docs::clean(node_text(string, source))).filter(|doc| !doc.is_empty())
+}
+
+/// Decorators applied to a function or class definition, outermost first
+fn decorators(node: Node, source: &[u8]) -> Vec<Decorator> {
+    let outer = decorated(node);
+    if outer == node {
+        return Vec::new();
+    }
+
+    find_children(outer, "decorator")
+        .into_iter()
+        .filter_map(|decorator| {
+            let expression = decorator.named_child(0)?;
+            // @lru_cache(maxsize=None) calls the decorator factory
+            let (name, arguments) = match expression.kind() {
+                "call" => (
+                    expression.child_by_field_name("function")?,
+                    field_text(expression, "arguments", source),
+                ),
+                _ => (expression, None),
+            };
+            Some(Decorator {
+                name: node_text(name, source).to_string(),
+                arguments,
+                line: decorator.start_position().row as u32 + 1,
+            })
+        })
+        .collect()
+}
+
+/// Modifiers set by the built-in decorators, also when qualified as in
+/// `@abc.abstractmethod`
+fn modifiers(decorators: &[Decorator]) -> Modifiers {
+    let mut modifiers = Modifiers::default();
+    for decorator in decorators {
+        match split_qualified(&decorator.name).1 {
+            "staticmethod" => modifiers.is_static = true,
+            "classmethod" => modifiers.is_class_method = true,
+            "abstractmethod" => modifiers.is_abstract = true,
+            "abstractproperty" => {
+                modifiers.is_abstract = true;
+                modifiers.is_property = true;
+            }
+            // @name.setter and friends redefine a property
+            "property" | "cached_property" | "getter" | "setter" | "deleter" => {
+                modifiers.is_property = true
+            }
+            _ => {}
+        }
+    }
+    modifiers
+}
+
+/// Split `models.User` into its qualifier and last segment
+fn split_qualified(name: &str) -> (Option<&str>, &str) {
+    match name.rsplit_once('.') {
+        Some((receiver, name)) => (Some(receiver), name),
+        None => (None, name),
+    }
+}
+
+/// Names a type annotation refers to, with their qualifiers: `User` and
+/// `Optional` in `Optional[User]`, `models.User` in `"models.User"`
+fn type_names(node: Node, source: &[u8], names: &mut Vec<(Option<String>, String)>) {
+    match node.kind() {
+        "identifier" => names.push((None, node_text(node, source).to_string())),
+        "attribute" => {
+            let (receiver, name) = split_qualified(node_text(node, source));
+            names.push((receiver.map(String::from), name.to_string()));
+        }
+        // Forward references
+        "string" => {
+            let text = node_text(node, source).trim_matches(|c| c == '"' || c == '\'');
+            if !text.is_empty()
+                && text
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+            {
+                let (receiver, name) = split_qualified(text);
+                names.push((receiver.map(String::from), name.to_string()));
+            }
+        }
+        _ => {
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                type_names(child, source, names);
+            }
+        }
+    }
+}
@@ -0,0 +1,392 @@
+//! Ruby language extractor
+
+use crate::parser::extractor::{
+    extract_docstring, extract_scoped_calls, field_text, find_all_nodes, node_text,
+    LanguageExtractor,
+};
+use crate::{
+    Call, DocSections, Import, Inheritance, Language, Modifiers, Symbol, SymbolKind, Visibility,
+};
+use std::path::Path;
+use tree_sitter::{Node, Tree};
+
+/// Calls in a class or module body that mix a module in
+const MIXIN_METHODS: [&str; 3] = ["include", "extend", "prepend"];
+
+pub struct RubyExtractor;
+
+impl LanguageExtractor for RubyExtractor {
+    fn extract_symbols(&self, tree: &Tree, source: &[u8], file_path: &Path) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+        self.extract_body(tree.root_node(), source, file_path, None, &mut symbols);
+        symbols
+    }
+
+    fn extract_imports(&self, tree: &Tree, source: &[u8], file_path: &Path) -> Vec<Import> {
+        let mut call_nodes = Vec::new();
+        find_all_nodes(tree.root_node(), "call", &mut call_nodes);
+
+        // require "json", require_relative "models/user"
+        call_nodes
+            .into_iter()
+            .filter_map(|node| {
+                if node.child_by_field_name("receiver").is_some() {
+                    return None;
+                }
+                let method = field_text(node, "method", source)?;
+                if method != "require" && method != "require_relative" {
+                    return None;
+                }
+                let path = node.child_by_field_name("arguments")?.named_child(0)?;
+                if path.kind() != "string" {
+                    return None;
+                }
+                let path = node_text(path, source).trim_matches(|c| c == '"' || c == '\'');
+                let target = if method == "require_relative" && !path.starts_with('.') {
+                    format!("./{}", path)
+                } else {
+                    path.to_string()
+                };
+                Some(Import {
+                    source_file: file_path.to_path_buf(),
+                    target,
+                    alias: None,
+                    items: Vec::new(),
+                    line: node.start_position().row as u32 + 1,
+                    resolved: None,
+                })
+            })
+            .collect()
+    }
+
+    fn extract_calls(&self, tree: &Tree, source: &[u8], symbols: &[Symbol]) -> Vec<Call> {
+        // Calls without arguments or receiver parse as bare identifiers,
+        // which cannot be told apart from local variables
+        extract_scoped_calls(tree.root_node(), symbols, &["call"], |call_node| {
+            Some((
+                field_text(call_node, "method", source)?,
+                field_text(call_node, "receiver", source),
+            ))
+        })
+    }
+
+    fn extract_inheritance(
+        &self,
+        tree: &Tree,
+        source: &[u8],
+        symbols: &[Symbol],
+    ) -> Vec<Inheritance> {
+        let mut inheritance = Vec::new();
+
+        for symbol in symbols {
+            if !matches!(symbol.kind, SymbolKind::Class | SymbolKind::Module) {
+                continue;
+            }
+            let Some(node) = tree
+                .root_node()
+                .descendant_for_byte_range(symbol.byte_range.start, symbol.byte_range.end)
+            else {
+                continue;
+            };
+
+            // class Admin < User
+            let mut parents = Vec::new();
+            if let Some(superclass) = node.child_by_field_name("superclass")
+                && let Some(parent) = superclass.named_child(0)
+            {
+                parents.push(parent);
+            }
+
+            // include Comparable, extend ClassMethods, prepend Logging
+            if let Some(body) = node.child_by_field_name("body") {
+                let mut cursor = body.walk();
+                for statement in body.named_children(&mut cursor) {
+                    if statement.kind() != "call"
+                        || statement.child_by_field_name("receiver").is_some()
+                        || !field_text(statement, "method", source)
+                            .is_some_and(|m| MIXIN_METHODS.contains(&m.as_str()))
+                    {
+                        continue;
+                    }
+                    if let Some(arguments) = statement.child_by_field_name("arguments") {
+                        let mut cursor = arguments.walk();
+                        parents.extend(arguments.named_children(&mut cursor));
+                    }
+                }
+            }
+
+            inheritance.extend(
+                parents
+                    .into_iter()
+                    .filter(|p| matches!(p.kind(), "constant" | "scope_resolution"))
+                    .map(|parent| Inheritance {
+                        child_id: symbol.id.clone(),
+                        parent_name: constant_name(parent, source),
+                    }),
+            );
+        }
+
+        inheritance
+    }
+
+    fn scope_name(&self, node: Node, source: &[u8]) -> Option<String> {
+        match node.kind() {
+            "class" | "module" | "method" | "singleton_method" => field_text(node, "name", source),
+            _ => None,
+        }
+    }
+
+    /// `class Admin::User` is defined in `Admin`
+    fn receiver_scope(&self, node: Node, source: &[u8]) -> Option<String> {
+        if !matches!(node.kind(), "class" | "module") {
+            return None;
+        }
+        let name = node
+            .child_by_field_name("name")
+            .filter(|name| name.kind() == "scope_resolution")?;
+        field_text(name, "scope", source)
+    }
+}
+
+impl RubyExtractor {
+    /// Extract the modules, classes and methods defined in a body, tracking
+    /// `private`/`protected`/`public` sections
+    fn extract_body(
+        &self,
+        node: Node,
+        source: &[u8],
+        file_path: &Path,
+        parent: Option<&str>,
+        symbols: &mut Vec<Symbol>,
+    ) {
+        let mut visibility = Visibility::Public;
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            match child.kind() {
+                "class" | "module" => {
+                    let Some(name) = child.child_by_field_name("name") else {
+                        continue;
+                    };
+                    let kind = if child.kind() == "class" {
+                        SymbolKind::Class
+                    } else {
+                        SymbolKind::Module
+                    };
+                    let name = constant_name(name, source);
+                    let symbol = self.symbol(child, source, file_path, name, kind, parent);
+                    let id = symbol.id.clone();
+                    symbols.push(symbol);
+                    if let Some(body) = child.child_by_field_name("body") {
+                        self.extract_body(body, source, file_path, Some(&id), symbols);
+                    }
+                }
+                "method" | "singleton_method" => {
+                    if let Some(mut method) = self.extract_method(child, source, file_path, parent) {
+                        method.visibility = visibility;
+                        symbols.push(method);
+                    }
+                }
+                // class << self
+                "singleton_class" => {
+                    if let Some(body) = child.child_by_field_name("body") {
+                        self.extract_body(body, source, file_path, parent, symbols);
+                    }
+                }
+                // A bare `private` applies to the methods that follow
+                "identifier" => match node_text(child, source) {
+                    "public" => visibility = Visibility::Public,
+                    "protected" => visibility = Visibility::Protected,
+                    "private" => visibility = Visibility::Private,
+                    _ => {}
+                },
+                // private def helper ... end
+                "call" if child.child_by_field_name("receiver").is_none() => {
+                    let modifier = match field_text(child, "method", source).as_deref() {
+                        Some("public") => Visibility::Public,
+                        Some("protected") => Visibility::Protected,
+                        Some("private") => Visibility::Private,
+                        _ => continue,
+                    };
+                    let Some(arguments) = child.child_by_field_name("arguments") else {
+                        continue;
+                    };
+                    let mut cursor = arguments.walk();
+                    for argument in arguments.named_children(&mut cursor) {
+                        if matches!(argument.kind(), "method" | "singleton_method")
+                            && let Some(mut method) =
+                                self.extract_method(argument, source, file_path, parent)
+                        {
+                            method.visibility = modifier;
+                            symbols.push(method);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn extract_method(
+        &self,
+        node: Node,
+        source: &[u8],
+        file_path: &Path,
+        parent: Option<&str>,
+    ) -> Option<Symbol> {
+        let name = field_text(node, "name", source)?;
+        let kind = if parent.is_some() {
+            SymbolKind::Method
+        } else {
+            SymbolKind::Function
+        };
+        Some(self.symbol(node, source, file_path, name, kind, parent))
+    }
+
+    fn symbol(
+        &self,
+        node: Node,
+        source: &[u8],
+        file_path: &Path,
+        name: String,
+        kind: SymbolKind,
+        parent: Option<&str>,
+    ) -> Symbol {
+        let start_line = node.start_position().row as u32 + 1;
+        let end_line = node.end_position().row as u32 + 1;
+
+        // The first line: `def self.find(id)`, `class Admin < User`
+        let signature = source
+            .get(node.start_byte()..node.end_byte())
+            .and_then(|bytes| std::str::from_utf8(bytes).ok())
+            .and_then(|s| s.lines().next())
+            .map(|s| s.trim().to_string());
+
+        // Comments before the first statement of a body precede the body
+        // itself
+        let documented = match node.prev_sibling() {
+            None => node.parent().filter(|p| p.kind() == "body_statement").unwrap_or(node),
+            Some(_) => node,
+        };
+        let docstring = extract_docstring(documented, source, &["comment"]);
+
+        Symbol {
+            id: Symbol::provisional_id(file_path, &name, start_line),
+            name,
+            qualified_name: String::new(),
+            namespace: None,
+            kind,
+            file_path: file_path.to_path_buf(),
+            start_line,
+            end_line,
+            byte_range: node.start_byte()..node.end_byte(),
+            signature,
+            visibility: Visibility::Public,
+            is_async: false,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers::default(),
+            docstring,
+            doc_sections: DocSections::default(),
+            summary: None,
+            language: Language::Ruby,
+            parent_id: parent.map(String::from),
+        }
+    }
+}
+
+/// Last segment of a constant path: `Admin::User` is `User`
+fn constant_name(node: Node, source: &[u8]) -> String {
+    match node.kind() {
+        "scope_resolution" => field_text(node, "name", source).unwrap_or_default(),
+        _ => node_text(node, source).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::languages::fixtures::{callers_of, extract, imports, symbol};
+    use crate::{Language, SymbolKind, Visibility};
+
+    #[test]
+    fn test_ruby_declarations() {
+        let source = r#"require "json"
+require_relative "concerns/auditable"
+
+module Billing
+  # An invoice
+  class Invoice < ApplicationRecord
+    include Auditable
+    extend Billing::Lookup
+
+    def self.find_open(id)
+      where(id: id).first
+    end
+
+    def total
+      lines.sum(&:amount)
+    end
+
+    private
+
+    def recalculate
+      Tax.apply(self)
+    end
+
+    class << self
+      def build(attrs) = new(attrs)
+    end
+  end
+end
+
+class Billing::Refund < Invoice
+end
+
+def helper(x) = puts(x)
+"#;
+        let file = extract(Language::Ruby, source);
+
+        assert_eq!(symbol(&file, "Billing").kind, SymbolKind::Module);
+        let invoice = symbol(&file, "Billing::Invoice");
+        assert_eq!(invoice.kind, SymbolKind::Class);
+        assert_eq!(invoice.docstring.as_deref(), Some("An invoice"));
+        assert_eq!(invoice.signature.as_deref(), Some("class Invoice < ApplicationRecord"));
+
+        let find_open = symbol(&file, "Billing::Invoice::find_open");
+        assert_eq!(find_open.kind, SymbolKind::Method);
+        assert_eq!(find_open.parent_id.as_ref(), Some(&invoice.id));
+        assert_eq!(find_open.signature.as_deref(), Some("def self.find_open(id)"));
+        assert_eq!(symbol(&file, "Billing::Invoice::total").visibility, Visibility::Public);
+        assert_eq!(symbol(&file, "Billing::Invoice::recalculate").visibility, Visibility::Private);
+        assert_eq!(symbol(&file, "Billing::Invoice::build").kind, SymbolKind::Method);
+        assert_eq!(symbol(&file, "Billing::Refund").kind, SymbolKind::Class);
+        assert_eq!(symbol(&file, "helper").kind, SymbolKind::Function);
+
+        assert_eq!(
+            imports(&file),
+            [("json", None, vec![]), ("./concerns/auditable", None, vec![])]
+        );
+
+        let parents: Vec<(&str, &str)> = file
+            .inheritance
+            .iter()
+            .map(|i| {
+                let child = file.symbols.iter().find(|s| s.id == i.child_id).unwrap();
+                (child.name.as_str(), i.parent_name.as_str())
+            })
+            .collect();
+        assert_eq!(
+            parents,
+            [
+                ("Invoice", "ApplicationRecord"),
+                ("Invoice", "Auditable"),
+                ("Invoice", "Lookup"),
+                ("Refund", "Invoice"),
+            ]
+        );
+
+        assert_eq!(callers_of(&file, "where"), ["find_open"]);
+        assert_eq!(callers_of(&file, "sum"), ["total"]);
+        assert_eq!(callers_of(&file, "apply"), ["recalculate"]);
+        assert_eq!(callers_of(&file, "puts"), ["helper"]);
+    }
+}
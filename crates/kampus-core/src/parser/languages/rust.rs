@@ -1,11 +1,15 @@
 //! Rust language extractor
 
 use crate::parser::extractor::{
-    extract_docstring, find_all_nodes, find_child, find_children, node_text, LanguageExtractor,
+    extract_docstring, extract_scoped_calls, field_text, find_all_nodes, find_child, find_children,
+    is_member_of, node_text, LanguageExtractor,
+};
+use crate::{
+    Call, DocSections, Import, Inheritance, Language, Modifiers, NamespaceKind, Symbol, SymbolKind,
+    Visibility,
 };
-use crate::{Call, Import, Inheritance, Language, Symbol, SymbolKind, Visibility};
 use std::path::Path;
-use tree_sitter::Tree;
+use tree_sitter::{Node, Tree};
 
 pub struct RustExtractor;
 
@@ -14,10 +18,13 @@ impl LanguageExtractor for RustExtractor {
         let mut symbols = Vec::new();
         let root = tree.root_node();
 
-        // Extract functions
+        // Extract functions (impl methods are extracted below)
         let mut func_nodes = Vec::new();
         find_all_nodes(root, "function_item", &mut func_nodes);
         for node in func_nodes {
+            if is_member_of(node, "declaration_list", "impl_item") {
+                continue;
+            }
             if let Some(symbol) = self.extract_function(node, source, file_path, None) {
                 symbols.push(symbol);
             }
@@ -28,7 +35,16 @@ impl LanguageExtractor for RustExtractor {
         find_all_nodes(root, "struct_item", &mut struct_nodes);
         for node in struct_nodes {
             if let Some(symbol) = self.extract_struct(node, source, file_path) {
+                let fields = self.extract_members(
+                    node,
+                    "field_declaration",
+                    SymbolKind::Field,
+                    &symbol,
+                    source,
+                    file_path,
+                );
                 symbols.push(symbol);
+                symbols.extend(fields);
             }
         }
 
@@ -37,7 +53,16 @@ impl LanguageExtractor for RustExtractor {
         find_all_nodes(root, "enum_item", &mut enum_nodes);
         for node in enum_nodes {
             if let Some(symbol) = self.extract_enum(node, source, file_path) {
+                let variants = self.extract_members(
+                    node,
+                    "enum_variant",
+                    SymbolKind::EnumVariant,
+                    &symbol,
+                    source,
+                    file_path,
+                );
                 symbols.push(symbol);
+                symbols.extend(variants);
             }
         }
 
@@ -54,25 +79,13 @@ impl LanguageExtractor for RustExtractor {
         let mut impl_nodes = Vec::new();
         find_all_nodes(root, "impl_item", &mut impl_nodes);
         for node in impl_nodes {
-            // Get the type being implemented
-            let type_name = find_child(node, "type_identifier")
-                .or_else(|| find_child(node, "generic_type"))
-                .map(|n| node_text(n, source));
-
             if let Some(body) = find_child(node, "declaration_list") {
                 for func in find_children(body, "function_item") {
                     if let Some(mut method) = self.extract_function(func, source, file_path, None) {
                         method.kind = SymbolKind::Method;
-                        if let Some(type_name) = type_name {
-                            // Try to find parent symbol
-                            let parent_id = symbols
-                                .iter()
-                                .find(|s| {
-                                    (s.kind == SymbolKind::Struct || s.kind == SymbolKind::Enum)
-                                        && s.name == type_name
-                                })
-                                .map(|s| s.id.clone());
-                            method.parent_id = parent_id;
+                        method.parent_id = self.owner(func, source, &symbols).map(|s| s.id.clone());
+                        if in_trait_impl(func) {
+                            method.visibility = Visibility::Public;
                         }
                         symbols.push(method);
                     }
@@ -80,6 +93,55 @@ impl LanguageExtractor for RustExtractor {
             }
         }
 
+        // Modules
+        let mut mod_nodes = Vec::new();
+        find_all_nodes(root, "mod_item", &mut mod_nodes);
+        for node in mod_nodes {
+            if let Some(name) = field_text(node, "name", source) {
+                symbols.push(self.symbol(node, source, file_path, name, SymbolKind::Module));
+            }
+        }
+
+        // macro_rules! definitions
+        let mut macro_nodes = Vec::new();
+        find_all_nodes(root, "macro_definition", &mut macro_nodes);
+        for node in macro_nodes {
+            if let Some(name) = field_text(node, "name", source) {
+                let mut symbol = self.symbol(node, source, file_path, name, SymbolKind::Macro);
+                symbol.signature = Some(format!("macro_rules! {}", symbol.name));
+                symbols.push(symbol);
+            }
+        }
+
+        // Constants, statics and type aliases, including the associated
+        // consts and types of traits and impls
+        let items = [
+            ("const_item", SymbolKind::Constant),
+            ("static_item", SymbolKind::Variable),
+            ("type_item", SymbolKind::TypeAlias),
+            ("associated_type", SymbolKind::TypeAlias),
+        ];
+        for (node_kind, kind) in items {
+            let mut item_nodes = Vec::new();
+            find_all_nodes(root, node_kind, &mut item_nodes);
+            for node in item_nodes {
+                let Some(name) = field_text(node, "name", source) else {
+                    continue;
+                };
+                let mut symbol = self.symbol(node, source, file_path, name, kind);
+                if let Some(owner) = self.owner(node, source, &symbols) {
+                    symbol.parent_id = Some(owner.id.clone());
+                    if owner.kind == SymbolKind::Trait {
+                        symbol.visibility = owner.visibility;
+                    }
+                }
+                if in_trait_impl(node) {
+                    symbol.visibility = Visibility::Public;
+                }
+                symbols.push(symbol);
+            }
+        }
+
         symbols
     }
 
@@ -92,14 +154,18 @@ impl LanguageExtractor for RustExtractor {
         find_all_nodes(root, "use_declaration", &mut use_nodes);
 
         for node in use_nodes {
-            if let Some(use_clause) = node.child(1) {
+            if let Some(use_clause) = node.child_by_field_name("argument") {
                 let (target, items) = self.parse_use_clause(use_clause, source);
+                let alias = (use_clause.kind() == "use_as_clause")
+                    .then(|| field_text(use_clause, "alias", source))
+                    .flatten();
                 imports.push(Import {
                     source_file: file_path.to_path_buf(),
                     target,
-                    alias: None,
+                    alias,
                     items,
                     line: node.start_position().row as u32 + 1,
+                    resolved: None,
                 });
             }
         }
@@ -115,6 +181,7 @@ impl LanguageExtractor for RustExtractor {
                     alias: None,
                     items: Vec::new(),
                     line: node.start_position().row as u32 + 1,
+                    resolved: None,
                 });
             }
         }
@@ -123,46 +190,40 @@ impl LanguageExtractor for RustExtractor {
     }
 
     fn extract_calls(&self, tree: &Tree, source: &[u8], symbols: &[Symbol]) -> Vec<Call> {
-        let mut calls = Vec::new();
-        let root = tree.root_node();
-
-        for symbol in symbols.iter().filter(|s| {
-            s.kind == SymbolKind::Function || s.kind == SymbolKind::Method
-        }) {
-            let mut call_nodes = Vec::new();
-            find_all_nodes(root, "call_expression", &mut call_nodes);
-
-            for call_node in call_nodes {
-                let call_line = call_node.start_position().row as u32 + 1;
-                if call_line >= symbol.start_line && call_line <= symbol.end_line {
-                    if let Some(func) = call_node.child(0) {
-                        let callee_name = match func.kind() {
-                            "identifier" => node_text(func, source).to_string(),
-                            "field_expression" => {
-                                // obj.method()
-                                find_child(func, "field_identifier")
-                                    .map(|n| node_text(n, source).to_string())
-                                    .unwrap_or_default()
-                            }
-                            "scoped_identifier" => {
-                                // Module::function()
-                                node_text(func, source).to_string()
-                            }
-                            _ => continue,
-                        };
-                        if !callee_name.is_empty() {
-                            calls.push(Call {
-                                caller_id: symbol.id.clone(),
-                                callee_name,
-                                call_site_line: call_line,
-                            });
-                        }
-                    }
+        let call_kinds = ["call_expression", "macro_invocation"];
+        extract_scoped_calls(tree.root_node(), symbols, &call_kinds, |call_node| {
+            // println!(...), tracing::debug!(...)
+            let func = match call_node.kind() {
+                "macro_invocation" => call_node.child_by_field_name("macro")?,
+                _ => call_node.child(0)?,
+            };
+            let (callee_name, receiver) = match func.kind() {
+                "identifier" => (node_text(func, source).to_string(), None),
+                "field_expression" => {
+                    // obj.method()
+                    let name = find_child(func, "field_identifier")
+                        .map(|n| node_text(n, source).to_string())
+                        .unwrap_or_default();
+                    let receiver = func
+                        .child_by_field_name("value")
+                        .map(|n| node_text(n, source).to_string());
+                    (name, receiver)
                 }
-            }
-        }
-
-        calls
+                "scoped_identifier" => {
+                    // Module::function() or Type::new()
+                    let name = func
+                        .child_by_field_name("name")
+                        .map(|n| node_text(n, source).to_string())
+                        .unwrap_or_default();
+                    let path = func
+                        .child_by_field_name("path")
+                        .map(|n| node_text(n, source).to_string());
+                    (name, path)
+                }
+                _ => return None,
+            };
+            Some((callee_name, receiver))
+        })
     }
 
     fn extract_inheritance(
@@ -200,21 +261,188 @@ impl LanguageExtractor for RustExtractor {
                 }
             }
 
-            if let (Some(type_name), Some(trait_name)) = (type_name, trait_name) {
-                if let Some(symbol) = symbols.iter().find(|s| s.name == type_name) {
-                    inheritance.push(Inheritance {
-                        child_id: symbol.id.clone(),
-                        parent_name: trait_name,
-                    });
-                }
+            if let (Some(type_name), Some(trait_name)) = (type_name, trait_name)
+                && let Some(symbol) = symbols.iter().find(|s| {
+                    matches!(s.kind, SymbolKind::Struct | SymbolKind::Enum) && s.name == type_name
+                })
+            {
+                inheritance.push(Inheritance {
+                    child_id: symbol.id.clone(),
+                    parent_name: trait_name,
+                });
             }
         }
 
         inheritance
     }
+
+    fn scope_name(&self, node: tree_sitter::Node, source: &[u8]) -> Option<String> {
+        match node.kind() {
+            "mod_item" | "trait_item" | "function_item" | "struct_item" | "enum_item" => {
+                field_text(node, "name", source)
+            }
+            "impl_item" => {
+                let self_type = self.impl_type_name(node.child_by_field_name("type")?, source);
+                match node.child_by_field_name("trait") {
+                    Some(trait_node) => Some(format!(
+                        "<{} as {}>",
+                        self_type,
+                        self.impl_type_name(trait_node, source)
+                    )),
+                    None => Some(self_type),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn namespace_kind(&self, node: tree_sitter::Node) -> Option<NamespaceKind> {
+        (node.kind() == "mod_item").then_some(NamespaceKind::Namespace)
+    }
+
+    fn module_path(&self, _tree: &Tree, _source: &[u8], file_path: &Path) -> Vec<String> {
+        let components: Vec<String> = file_path
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+
+        // Modules are laid out below the crate's src/ directory; anything
+        // else (tests, examples, binaries) is a crate root of its own
+        let mut path = vec!["crate".to_string()];
+        let Some(src) = components.iter().rposition(|c| c == "src") else {
+            return path;
+        };
+        let modules = &components[src + 1..];
+        if modules.first().is_some_and(|c| c == "bin") {
+            return path;
+        }
+
+        for (i, component) in modules.iter().enumerate() {
+            if i + 1 < modules.len() {
+                path.push(component.clone());
+                continue;
+            }
+            let stem = component.strip_suffix(".rs").unwrap_or(component);
+            if !matches!(stem, "lib" | "main" | "mod") {
+                path.push(stem.to_string());
+            }
+        }
+        path
+    }
 }
 
 impl RustExtractor {
+    /// Name of an impl's type or trait without type arguments
+    fn impl_type_name(&self, node: tree_sitter::Node, source: &[u8]) -> String {
+        let node = match node.kind() {
+            "generic_type" => node.child_by_field_name("type").unwrap_or(node),
+            _ => node,
+        };
+        node_text(node, source).to_string()
+    }
+
+    /// The trait, or the struct or enum of the impl, an item is declared in
+    fn owner<'s>(
+        &self,
+        node: tree_sitter::Node,
+        source: &[u8],
+        symbols: &'s [Symbol],
+    ) -> Option<&'s Symbol> {
+        let container = node
+            .parent()
+            .filter(|p| p.kind() == "declaration_list")?
+            .parent()?;
+        match container.kind() {
+            "trait_item" => symbols.iter().find(|s| {
+                s.kind == SymbolKind::Trait
+                    && s.byte_range == (container.start_byte()..container.end_byte())
+            }),
+            "impl_item" => {
+                let type_name = self.impl_type_name(container.child_by_field_name("type")?, source);
+                symbols.iter().find(|s| {
+                    matches!(s.kind, SymbolKind::Struct | SymbolKind::Enum) && s.name == type_name
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Fields of a struct or variants of an enum
+    fn extract_members(
+        &self,
+        node: tree_sitter::Node,
+        member_kind: &str,
+        kind: SymbolKind,
+        parent: &Symbol,
+        source: &[u8],
+        file_path: &Path,
+    ) -> Vec<Symbol> {
+        // Tuple struct fields have no names
+        let Some(body) = node.child_by_field_name("body") else {
+            return Vec::new();
+        };
+        find_children(body, member_kind)
+            .into_iter()
+            .filter_map(|member| {
+                let name = field_text(member, "name", source)?;
+                let mut symbol = self.symbol(member, source, file_path, name, kind);
+                symbol.parent_id = Some(parent.id.clone());
+                // Variants are as visible as their enum
+                if kind == SymbolKind::EnumVariant {
+                    symbol.visibility = parent.visibility;
+                }
+                Some(symbol)
+            })
+            .collect()
+    }
+
+    /// A symbol whose signature is its first line
+    fn symbol(
+        &self,
+        node: tree_sitter::Node,
+        source: &[u8],
+        file_path: &Path,
+        name: String,
+        kind: SymbolKind,
+    ) -> Symbol {
+        let start_line = node.start_position().row as u32 + 1;
+        let end_line = node.end_position().row as u32 + 1;
+
+        let visibility = visibility(node, source);
+
+        let signature = node_text(node, source).lines().next().map(|s| {
+            s.trim()
+                .trim_end_matches([',', ';', '{'])
+                .trim_end()
+                .to_string()
+        });
+
+        let docstring = extract_docstring(node, source, &["line_comment", "block_comment"]);
+
+        Symbol {
+            id: Symbol::provisional_id(file_path, &name, start_line),
+            name,
+            qualified_name: String::new(),
+            namespace: None,
+            kind,
+            file_path: file_path.to_path_buf(),
+            start_line,
+            end_line,
+            byte_range: node.start_byte()..node.end_byte(),
+            signature,
+            visibility,
+            is_async: false,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers::default(),
+            docstring,
+            doc_sections: DocSections::default(),
+            summary: None,
+            language: Language::Rust,
+            parent_id: None,
+        }
+    }
+
     fn extract_function(
         &self,
         node: tree_sitter::Node,
@@ -228,12 +456,7 @@ impl RustExtractor {
         let start_line = node.start_position().row as u32 + 1;
         let end_line = node.end_position().row as u32 + 1;
 
-        // Check for pub visibility
-        let visibility = if node_text(node, source).trim_start().starts_with("pub") {
-            Visibility::Public
-        } else {
-            Visibility::Private
-        };
+        let visibility = visibility(node, source);
 
         // Check if async
         let is_async = node_text(node, source).contains("async fn");
@@ -251,20 +474,26 @@ impl RustExtractor {
             });
 
         // Extract doc comment
-        let docstring = extract_docstring(node, source, &["line_comment", "block_comment"])
-            .map(|s| s.trim_start_matches("///").trim_start_matches("//!").trim().to_string());
+        let docstring = extract_docstring(node, source, &["line_comment", "block_comment"]);
 
         Some(Symbol {
-            id: Symbol::generate_id(file_path, &name, start_line),
+            id: Symbol::provisional_id(file_path, &name, start_line),
             name,
+            qualified_name: String::new(),
+            namespace: None,
             kind: SymbolKind::Function,
             file_path: file_path.to_path_buf(),
             start_line,
             end_line,
+            byte_range: node.start_byte()..node.end_byte(),
             signature,
             visibility,
             is_async,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers::default(),
             docstring,
+            doc_sections: DocSections::default(),
             summary: None,
             language: Language::Rust,
             parent_id: None,
@@ -283,11 +512,7 @@ impl RustExtractor {
         let start_line = node.start_position().row as u32 + 1;
         let end_line = node.end_position().row as u32 + 1;
 
-        let visibility = if node_text(node, source).trim_start().starts_with("pub") {
-            Visibility::Public
-        } else {
-            Visibility::Private
-        };
+        let visibility = visibility(node, source);
 
         let signature = source
             .get(node.start_byte()..node.end_byte())
@@ -295,20 +520,26 @@ impl RustExtractor {
             .and_then(|s| s.lines().next())
             .map(|s| s.to_string());
 
-        let docstring = extract_docstring(node, source, &["line_comment", "block_comment"])
-            .map(|s| s.trim_start_matches("///").trim().to_string());
+        let docstring = extract_docstring(node, source, &["line_comment", "block_comment"]);
 
         Some(Symbol {
-            id: Symbol::generate_id(file_path, &name, start_line),
+            id: Symbol::provisional_id(file_path, &name, start_line),
             name,
+            qualified_name: String::new(),
+            namespace: None,
             kind: SymbolKind::Struct,
             file_path: file_path.to_path_buf(),
             start_line,
             end_line,
+            byte_range: node.start_byte()..node.end_byte(),
             signature,
             visibility,
             is_async: false,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers::default(),
             docstring,
+            doc_sections: DocSections::default(),
             summary: None,
             language: Language::Rust,
             parent_id: None,
@@ -327,11 +558,7 @@ impl RustExtractor {
         let start_line = node.start_position().row as u32 + 1;
         let end_line = node.end_position().row as u32 + 1;
 
-        let visibility = if node_text(node, source).trim_start().starts_with("pub") {
-            Visibility::Public
-        } else {
-            Visibility::Private
-        };
+        let visibility = visibility(node, source);
 
         let signature = source
             .get(node.start_byte()..node.end_byte())
@@ -339,20 +566,26 @@ impl RustExtractor {
             .and_then(|s| s.lines().next())
             .map(|s| s.to_string());
 
-        let docstring = extract_docstring(node, source, &["line_comment", "block_comment"])
-            .map(|s| s.trim_start_matches("///").trim().to_string());
+        let docstring = extract_docstring(node, source, &["line_comment", "block_comment"]);
 
         Some(Symbol {
-            id: Symbol::generate_id(file_path, &name, start_line),
+            id: Symbol::provisional_id(file_path, &name, start_line),
             name,
+            qualified_name: String::new(),
+            namespace: None,
             kind: SymbolKind::Enum,
             file_path: file_path.to_path_buf(),
             start_line,
             end_line,
+            byte_range: node.start_byte()..node.end_byte(),
             signature,
             visibility,
             is_async: false,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers::default(),
             docstring,
+            doc_sections: DocSections::default(),
             summary: None,
             language: Language::Rust,
             parent_id: None,
@@ -371,11 +604,7 @@ impl RustExtractor {
         let start_line = node.start_position().row as u32 + 1;
         let end_line = node.end_position().row as u32 + 1;
 
-        let visibility = if node_text(node, source).trim_start().starts_with("pub") {
-            Visibility::Public
-        } else {
-            Visibility::Private
-        };
+        let visibility = visibility(node, source);
 
         let signature = source
             .get(node.start_byte()..node.end_byte())
@@ -383,20 +612,26 @@ impl RustExtractor {
             .and_then(|s| s.lines().next())
             .map(|s| s.to_string());
 
-        let docstring = extract_docstring(node, source, &["line_comment", "block_comment"])
-            .map(|s| s.trim_start_matches("///").trim().to_string());
+        let docstring = extract_docstring(node, source, &["line_comment", "block_comment"]);
 
         Some(Symbol {
-            id: Symbol::generate_id(file_path, &name, start_line),
+            id: Symbol::provisional_id(file_path, &name, start_line),
             name,
+            qualified_name: String::new(),
+            namespace: None,
             kind: SymbolKind::Trait,
             file_path: file_path.to_path_buf(),
             start_line,
             end_line,
+            byte_range: node.start_byte()..node.end_byte(),
             signature,
             visibility,
             is_async: false,
+            is_partial: false,
+            decorators: Vec::new(),
+            modifiers: Modifiers::default(),
             docstring,
+            doc_sections: DocSections::default(),
             summary: None,
             language: Language::Rust,
             parent_id: None,
@@ -409,26 +644,21 @@ impl RustExtractor {
                 (node_text(node, source).to_string(), Vec::new())
             }
             "use_as_clause" => {
-                let path = node.child(0).map(|n| node_text(n, source)).unwrap_or("");
-                (path.to_string(), Vec::new())
+                let path = field_text(node, "path", source).unwrap_or_default();
+                (path, Vec::new())
             }
             "scoped_use_list" => {
-                let path = find_child(node, "scoped_identifier")
-                    .or_else(|| find_child(node, "identifier"))
-                    .map(|n| node_text(n, source))
-                    .unwrap_or("");
-
-                let items = find_child(node, "use_list")
-                    .map(|list| {
-                        let mut cursor = list.walk();
-                        list.children(&mut cursor)
-                            .filter(|c| c.kind() == "identifier" || c.kind() == "scoped_identifier")
-                            .map(|n| node_text(n, source).to_string())
-                            .collect()
-                    })
-                    .unwrap_or_default();
-
-                (path.to_string(), items)
+                let path = field_text(node, "path", source).unwrap_or_default();
+                let mut items = Vec::new();
+                if let Some(list) = node.child_by_field_name("list") {
+                    use_list_items(list, "", source, &mut items);
+                }
+                (path, items)
+            }
+            "use_list" => {
+                let mut items = Vec::new();
+                use_list_items(node, "", source, &mut items);
+                (String::new(), items)
             }
             "use_wildcard" => {
                 let path = node.child(0).map(|n| node_text(n, source)).unwrap_or("");
@@ -438,3 +668,63 @@ impl RustExtractor {
         }
     }
 }
+
+/// Paths named by a use list, relative to the list's prefix, with nested
+/// lists flattened (`{writer::{GraphWriter, BATCH_SIZE}, Param}` names
+/// `writer::GraphWriter`, `writer::BATCH_SIZE` and `Param`)
+fn use_list_items(list: Node, prefix: &str, source: &[u8], items: &mut Vec<String>) {
+    let mut cursor = list.walk();
+    for child in list.named_children(&mut cursor) {
+        match child.kind() {
+            "scoped_use_list" => {
+                let path = field_text(child, "path", source).unwrap_or_default();
+                if let Some(nested) = child.child_by_field_name("list") {
+                    use_list_items(nested, &format!("{}{}::", prefix, path), source, items);
+                }
+            }
+            "use_as_clause" => {
+                if let Some(path) = field_text(child, "path", source) {
+                    items.push(format!("{}{}", prefix, path));
+                }
+            }
+            "line_comment" | "block_comment" => {}
+            _ => items.push(format!("{}{}", prefix, node_text(child, source))),
+        }
+    }
+}
+
+/// Visibility of an item: `pub`, `pub(crate)`, `pub(super)`, `pub(self)` or
+/// `pub(in path)`
+fn visibility(node: Node, source: &[u8]) -> Visibility {
+    let Some(modifier) = find_child(node, "visibility_modifier") else {
+        return Visibility::Private;
+    };
+    let text = node_text(modifier, source);
+    // `crate fn` is the unstable spelling of `pub(crate) fn`
+    if text == "crate" {
+        return Visibility::Internal;
+    }
+    let Some(scope) = text
+        .strip_prefix("pub")
+        .map(str::trim)
+        .and_then(|s| s.strip_prefix('('))
+        .and_then(|s| s.strip_suffix(')'))
+    else {
+        return Visibility::Public;
+    };
+    let scope = scope.trim();
+    match scope.strip_prefix("in ").map(str::trim).unwrap_or(scope) {
+        "crate" => Visibility::Internal,
+        "super" => Visibility::Super,
+        "self" => Visibility::Private,
+        _ => Visibility::Restricted,
+    }
+}
+
+/// Items of an `impl Trait for Type` block are as visible as the trait's
+/// items, which are all public
+fn in_trait_impl(node: Node) -> bool {
+    node.parent()
+        .and_then(|body| body.parent())
+        .is_some_and(|p| p.kind() == "impl_item" && p.child_by_field_name("trait").is_some())
+}
@@ -2,9 +2,13 @@
 //!
 //! Provides thread-safe parser pool and language-specific extractors.
 
+pub mod docs;
 pub mod extractor;
+pub mod grammars;
 pub mod languages;
 pub mod pool;
+pub mod query;
 
 pub use extractor::SymbolExtractor;
-pub use pool::ParserPool;
+pub use pool::{Grammar, ParserPool};
+pub use query::{Extractors, QueryExtractor};
@@ -2,7 +2,9 @@
 
 use crate::Language;
 use std::cell::RefCell;
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use std::path::Path;
 use thiserror::Error;
 use tree_sitter::Parser;
 
@@ -16,26 +18,59 @@ pub enum ParserError {
     UnsupportedLanguage(String),
 }
 
-// Thread-local storage for parsers (one per language per thread)
+/// A tree-sitter grammar
+///
+/// Most languages have exactly one. TypeScript files containing JSX need the
+/// separate TSX grammar, since `<T>expr` casts and JSX elements conflict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Grammar {
+    Language(Language),
+    Tsx,
+}
+
+impl Grammar {
+    /// Grammar for a file of `language`, picked by the file's extension
+    pub fn for_file(language: Language, path: &Path) -> Self {
+        let is_tsx = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("tsx"));
+        match language {
+            Language::TypeScript if is_tsx => Grammar::Tsx,
+            _ => Grammar::Language(language),
+        }
+    }
+}
+
+impl std::fmt::Display for Grammar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Grammar::Language(language) => write!(f, "{}", language),
+            Grammar::Tsx => write!(f, "TSX"),
+        }
+    }
+}
+
+// Thread-local storage for parsers (one per grammar per thread)
 thread_local! {
-    static PARSERS: RefCell<HashMap<Language, Parser>> = RefCell::new(HashMap::new());
+    static PARSERS: RefCell<HashMap<Grammar, Parser>> = RefCell::new(HashMap::new());
 }
 
 /// Pool of tree-sitter parsers optimized for parallel processing
 pub struct ParserPool;
 
 impl ParserPool {
-    /// Get or create a parser for the given language
-    pub fn get_parser(language: Language) -> Result<(), ParserError> {
+    /// Get or create a parser for the given grammar
+    pub fn get_parser(grammar: Grammar) -> Result<(), ParserError> {
         PARSERS.with(|parsers| {
             let mut parsers = parsers.borrow_mut();
-            if !parsers.contains_key(&language) {
+            if let Entry::Vacant(entry) = parsers.entry(grammar) {
                 let mut parser = Parser::new();
-                let ts_language = Self::get_tree_sitter_language(language)?;
+                let ts_language = Self::tree_sitter_language(grammar)?;
                 parser
                     .set_language(&ts_language)
                     .map_err(|e| ParserError::SetLanguage(e.to_string()))?;
-                parsers.insert(language, parser);
+                entry.insert(parser);
             }
             Ok(())
         })
@@ -46,30 +81,58 @@ impl ParserPool {
         language: Language,
         source: &[u8],
     ) -> Result<tree_sitter::Tree, ParserError> {
-        Self::get_parser(language)?;
+        Self::parse_grammar(Grammar::Language(language), source)
+    }
+
+    /// Parse a file's source with the grammar its extension calls for
+    pub fn parse_file(
+        language: Language,
+        path: &Path,
+        source: &[u8],
+    ) -> Result<tree_sitter::Tree, ParserError> {
+        Self::parse_grammar(Grammar::for_file(language, path), source)
+    }
+
+    /// Parse source code with a specific grammar
+    pub fn parse_grammar(
+        grammar: Grammar,
+        source: &[u8],
+    ) -> Result<tree_sitter::Tree, ParserError> {
+        Self::get_parser(grammar)?;
 
         PARSERS.with(|parsers| {
             let mut parsers = parsers.borrow_mut();
             let parser = parsers
-                .get_mut(&language)
-                .ok_or_else(|| ParserError::UnsupportedLanguage(language.to_string()))?;
+                .get_mut(&grammar)
+                .ok_or_else(|| ParserError::UnsupportedLanguage(grammar.to_string()))?;
             parser
                 .parse(source, None)
                 .ok_or_else(|| ParserError::Parse("Parser returned None".to_string()))
         })
     }
 
-    /// Get the tree-sitter language for a Language enum
-    fn get_tree_sitter_language(
-        language: Language,
+    /// Get the tree-sitter language for a grammar
+    pub(crate) fn tree_sitter_language(
+        grammar: Grammar,
     ) -> Result<tree_sitter::Language, ParserError> {
+        let language = match grammar {
+            Grammar::Language(language) => language,
+            Grammar::Tsx => return Ok(tree_sitter_typescript::LANGUAGE_TSX.into()),
+        };
         match language {
             Language::Python => Ok(tree_sitter_python::LANGUAGE.into()),
             Language::Rust => Ok(tree_sitter_rust::LANGUAGE.into()),
             Language::JavaScript => Ok(tree_sitter_javascript::LANGUAGE.into()),
             Language::TypeScript => Ok(tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()),
             Language::Go => Ok(tree_sitter_go::LANGUAGE.into()),
+            Language::C => Ok(tree_sitter_c::LANGUAGE.into()),
             Language::Cpp => Ok(tree_sitter_cpp::LANGUAGE.into()),
+            Language::Java => Ok(tree_sitter_java::LANGUAGE.into()),
+            Language::CSharp => Ok(tree_sitter_c_sharp::LANGUAGE.into()),
+            Language::Ruby => Ok(tree_sitter_ruby::LANGUAGE.into()),
+            Language::Php => Ok(tree_sitter_php::LANGUAGE_PHP.into()),
+            Language::Custom(name) => super::grammars::tree_sitter_language(name)
+                .ok_or_else(|| ParserError::UnsupportedLanguage(name.to_string())),
         }
     }
 }
@@ -98,4 +161,49 @@ mod tests {
         let tree = ParserPool::parse(Language::JavaScript, source).unwrap();
         assert!(tree.root_node().child_count() > 0);
     }
+
+    #[test]
+    fn test_parse_java() {
+        let source = b"package app; public class Hello { void run() {} }";
+        let tree = ParserPool::parse(Language::Java, source).unwrap();
+        assert!(!tree.root_node().has_error());
+    }
+
+    #[test]
+    fn test_parse_csharp() {
+        let source = b"namespace App; public partial class Hello { public int Count { get; set; } }";
+        let tree = ParserPool::parse(Language::CSharp, source).unwrap();
+        assert!(!tree.root_node().has_error());
+    }
+
+    #[test]
+    fn test_parse_c() {
+        let source = b"#include <stdio.h>\nstatic int main(void) { return 0; }";
+        let tree = ParserPool::parse(Language::C, source).unwrap();
+        assert!(!tree.root_node().has_error());
+    }
+
+    #[test]
+    fn test_parse_ruby() {
+        let source = b"module App\n  class Hello < Base\n    def run = puts(1)\n  end\nend\n";
+        let tree = ParserPool::parse(Language::Ruby, source).unwrap();
+        assert!(!tree.root_node().has_error());
+    }
+
+    #[test]
+    fn test_parse_php() {
+        let source = b"<?php\nnamespace App;\nfinal class Hello { public function run(): void {} }\n";
+        let tree = ParserPool::parse(Language::Php, source).unwrap();
+        assert!(!tree.root_node().has_error());
+    }
+
+    #[test]
+    fn test_parse_tsx() {
+        let source = b"const App = (): JSX.Element => <div className=\"app\"><Header /></div>;";
+        let tree = ParserPool::parse_file(Language::TypeScript, Path::new("App.tsx"), source).unwrap();
+        assert!(!tree.root_node().has_error());
+
+        let tree = ParserPool::parse(Language::TypeScript, source).unwrap();
+        assert!(tree.root_node().has_error());
+    }
 }
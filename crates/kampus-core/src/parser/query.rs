@@ -0,0 +1,555 @@
+//! Extraction driven by tree-sitter queries
+//!
+//! [`QueryExtractor`] finds symbols and relationships with a tags-style
+//! query instead of hand-written node walking. Captures follow the
+//! tree-sitter tags conventions, with a few additions:
+//!
+//! - `@definition.<kind>` marks a definition (`function`, `method`, `class`,
+//!   `struct`, `interface`, `trait`, `enum`, `module`, `constant`,
+//!   `variable`, `component`, `property`, `union`, `type_alias` or `macro`),
+//!   named by the `@name` capture of the same pattern and documented by an
+//!   optional `@doc` capture
+//! - `@reference.call` marks a call, with the callee in `@name` and an
+//!   optional `@receiver`
+//! - `@reference.import` marks an import, with the imported path in `@name`
+//! - `@reference.inherits` marks the name of a base type, inherited by the
+//!   innermost class-like definition around it
+//!
+//! Queries for the built-in languages are bundled from `queries/`. A project
+//! can replace them with `.kampus/queries/<language>.scm`, or extend them
+//! when that file starts with a `; extends` line.
+
+use crate::parser::docs;
+use crate::parser::extractor::{
+    builtin_extractor, node_text, walk_scoped, ExtractorError, LanguageExtractor,
+};
+use crate::parser::grammars;
+use crate::parser::pool::{Grammar, ParserPool};
+use crate::{
+    Call, DocSections, Import, Inheritance, Language, Modifiers, NamespaceKind, Reference, Render,
+    Symbol, SymbolKind, Visibility,
+};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Node, Query, QueryCursor, Tree};
+
+/// Directory, relative to the project root, holding query files
+pub const QUERY_DIR: &str = ".kampus/queries";
+
+/// First line of a query file that extends the bundled query
+const EXTENDS_MARKER: &str = "; extends";
+
+/// Symbol kinds that other definitions are members of
+const TYPE_KINDS: [SymbolKind; 6] = [
+    SymbolKind::Class,
+    SymbolKind::Struct,
+    SymbolKind::Interface,
+    SymbolKind::Trait,
+    SymbolKind::Enum,
+    SymbolKind::Component,
+];
+
+/// Bundled query for a built-in language
+pub fn bundled_query(language: Language) -> Option<&'static str> {
+    match language {
+        Language::Python => Some(include_str!("../../queries/python.scm")),
+        Language::Rust => Some(include_str!("../../queries/rust.scm")),
+        Language::JavaScript => Some(include_str!("../../queries/javascript.scm")),
+        Language::TypeScript => Some(include_str!("../../queries/typescript.scm")),
+        Language::Go => Some(include_str!("../../queries/go.scm")),
+        Language::C => Some(include_str!("../../queries/c.scm")),
+        Language::Cpp => Some(include_str!("../../queries/cpp.scm")),
+        Language::Java => Some(include_str!("../../queries/java.scm")),
+        Language::CSharp => Some(include_str!("../../queries/csharp.scm")),
+        Language::Ruby => Some(include_str!("../../queries/ruby.scm")),
+        Language::Php => Some(include_str!("../../queries/php.scm")),
+        Language::Custom(_) => None,
+    }
+}
+
+/// Grammars a language's files may be parsed with
+fn grammars(language: Language) -> Vec<Grammar> {
+    match language {
+        Language::TypeScript => vec![Grammar::Language(language), Grammar::Tsx],
+        _ => vec![Grammar::Language(language)],
+    }
+}
+
+/// Compile a query for a grammar
+pub fn compile_query(grammar: Grammar, source: &str) -> Result<Query, ExtractorError> {
+    let language = ParserPool::tree_sitter_language(grammar)
+        .map_err(|e| ExtractorError::InvalidQuery(grammar.to_string(), e.to_string()))?;
+    Query::new(&language, source)
+        .map_err(|e| ExtractorError::InvalidQuery(grammar.to_string(), e.to_string()))
+}
+
+/// The extractor to use for each language
+///
+/// Languages without project query files use their hand-written extractor.
+/// Queries are compiled once when loaded and shared between threads.
+#[derive(Default, Clone)]
+pub struct Extractors {
+    queries: HashMap<Grammar, Arc<Query>>,
+}
+
+impl Extractors {
+    /// Load the project's runtime grammars (see [`grammars`]) and the query
+    /// files in `<root>/.kampus/queries`
+    ///
+    /// Every runtime grammar needs a query, either named in its config entry
+    /// or in `.kampus/queries/<name>.scm`, which takes precedence.
+    pub fn load(root: &Path) -> Result<Self, ExtractorError> {
+        let mut extractors = Self::default();
+        let grammars = grammars::load(root)?;
+        for (language, source) in grammars::queries(root, &grammars)? {
+            extractors.add_query(language, &source)?;
+        }
+
+        let dir = root.join(QUERY_DIR);
+        let mut paths: Vec<_> = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.extension().is_some_and(|e| e == "scm"))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        paths.sort();
+
+        for path in paths {
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+            let Ok(language) = stem.parse::<Language>() else {
+                tracing::warn!("Ignoring query file for unknown language: {:?}", path);
+                continue;
+            };
+            let source = std::fs::read_to_string(&path)?;
+            extractors.add_query(language, &source)?;
+        }
+
+        for (language, _) in &grammars {
+            if !extractors.queries.contains_key(&Grammar::Language(*language)) {
+                return Err(ExtractorError::InvalidQuery(
+                    language.to_string(),
+                    format!("no query file, expected {}/{}.scm", QUERY_DIR, language.id()),
+                ));
+            }
+        }
+
+        Ok(extractors)
+    }
+
+    /// Use a query for `language`, replacing the hand-written extractor
+    ///
+    /// A query starting with `; extends` is appended to the bundled one.
+    pub fn add_query(&mut self, language: Language, source: &str) -> Result<(), ExtractorError> {
+        let source = if source.trim_start().starts_with(EXTENDS_MARKER) {
+            format!("{}\n{}", bundled_query(language).unwrap_or_default(), source)
+        } else {
+            source.to_string()
+        };
+
+        for grammar in grammars(language) {
+            self.queries
+                .insert(grammar, Arc::new(compile_query(grammar, &source)?));
+        }
+        Ok(())
+    }
+
+    /// The extractor for a file of `language` parsed with `grammar`
+    pub fn get(
+        &self,
+        language: Language,
+        grammar: Grammar,
+    ) -> Result<Box<dyn LanguageExtractor>, ExtractorError> {
+        match self.queries.get(&grammar) {
+            Some(query) => Ok(Box::new(QueryExtractor::new(language, Arc::clone(query)))),
+            None => builtin_extractor(language)
+                .ok_or_else(|| ExtractorError::UnsupportedLanguage(language.to_string())),
+        }
+    }
+}
+
+/// A definition found by the query
+struct Definition<'a> {
+    kind: SymbolKind,
+    node: Node<'a>,
+    name: String,
+    doc: Option<String>,
+}
+
+/// Extractor driven by a tree-sitter query
+///
+/// Scope names are collected while extracting symbols, so an instance
+/// serves a single file. Module paths and namespaces come from the language's
+/// hand-written extractor.
+pub struct QueryExtractor {
+    language: Language,
+    query: Arc<Query>,
+    /// Hand-written extractor of a built-in language
+    builtin: Option<Box<dyn LanguageExtractor>>,
+    /// Names of the definitions in the current file by byte range
+    scopes: Mutex<HashMap<(usize, usize), String>>,
+}
+
+impl QueryExtractor {
+    pub fn new(language: Language, query: Arc<Query>) -> Self {
+        Self {
+            language,
+            query,
+            builtin: builtin_extractor(language),
+            scopes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// An extractor using the bundled query for `grammar`
+    pub fn bundled(language: Language, grammar: Grammar) -> Result<Self, ExtractorError> {
+        let source = bundled_query(language)
+            .ok_or_else(|| ExtractorError::UnsupportedLanguage(language.to_string()))?;
+        let query = compile_query(grammar, source)?;
+        Ok(Self::new(language, Arc::new(query)))
+    }
+
+    /// Run the query and pass each match's captures to `visit` by name
+    fn for_each_match<'a>(
+        &self,
+        tree: &'a Tree,
+        source: &[u8],
+        mut visit: impl FnMut(&HashMap<&str, Node<'a>>),
+    ) {
+        let names = self.query.capture_names();
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(&self.query, tree.root_node(), source);
+        while let Some(m) = matches.next() {
+            let captures: HashMap<&str, Node<'a>> = m
+                .captures
+                .iter()
+                .map(|c| (names[c.index as usize], c.node))
+                .collect();
+            visit(&captures);
+        }
+    }
+
+    /// Definitions in source order, outer definitions first
+    fn definitions<'a>(&self, tree: &'a Tree, source: &[u8]) -> Vec<Definition<'a>> {
+        let mut definitions: Vec<Definition<'a>> = Vec::new();
+        self.for_each_match(tree, source, |captures| {
+            for (capture, node) in captures {
+                if let Some(kind) = capture.strip_prefix("definition.").and_then(definition_kind)
+                    && let Some(name) = captures.get("name")
+                {
+                    definitions.push(Definition {
+                        kind,
+                        node: *node,
+                        name: node_text(*name, source).to_string(),
+                        doc: captures
+                            .get("doc")
+                            .map(|n| docs::clean(node_text(*n, source))),
+                    });
+                }
+            }
+        });
+
+        // Several patterns may match the same definition, such as a method
+        // that is also a function; the more specific kind wins
+        definitions.sort_by_key(|d| {
+            (
+                d.node.start_byte(),
+                std::cmp::Reverse(d.node.end_byte()),
+                d.kind == SymbolKind::Function,
+            )
+        });
+        definitions.dedup_by(|a, b| a.node == b.node && a.name == b.name);
+        definitions
+    }
+}
+
+fn definition_kind(kind: &str) -> Option<SymbolKind> {
+    match kind {
+        "function" => Some(SymbolKind::Function),
+        "method" => Some(SymbolKind::Method),
+        "class" => Some(SymbolKind::Class),
+        "struct" => Some(SymbolKind::Struct),
+        "interface" => Some(SymbolKind::Interface),
+        "trait" => Some(SymbolKind::Trait),
+        "enum" => Some(SymbolKind::Enum),
+        "module" => Some(SymbolKind::Module),
+        "constant" => Some(SymbolKind::Constant),
+        "variable" => Some(SymbolKind::Variable),
+        "component" => Some(SymbolKind::Component),
+        "property" => Some(SymbolKind::Property),
+        "union" => Some(SymbolKind::Union),
+        "type_alias" => Some(SymbolKind::TypeAlias),
+        "macro" => Some(SymbolKind::Macro),
+        "field" => Some(SymbolKind::Field),
+        "enum_variant" => Some(SymbolKind::EnumVariant),
+        _ => None,
+    }
+}
+
+/// Innermost symbol of one of `kinds` whose byte range encloses `node`
+fn enclosing<'s>(symbols: &'s [Symbol], kinds: &[SymbolKind], node: Node) -> Option<&'s Symbol> {
+    symbols
+        .iter()
+        .filter(|s| {
+            kinds.contains(&s.kind)
+                && s.byte_range.start <= node.start_byte()
+                && node.end_byte() <= s.byte_range.end
+                && s.byte_range != (node.start_byte()..node.end_byte())
+        })
+        .min_by_key(|s| s.byte_range.len())
+}
+
+fn unquote(text: &str) -> &str {
+    text.trim_matches(|c| matches!(c, '"' | '\'' | '`' | '<' | '>'))
+}
+
+impl LanguageExtractor for QueryExtractor {
+    fn extract_symbols(&self, tree: &Tree, source: &[u8], file_path: &Path) -> Vec<Symbol> {
+        let definitions = self.definitions(tree, source);
+        let mut scopes = self.scopes.lock().unwrap_or_else(|e| e.into_inner());
+        scopes.clear();
+
+        let mut symbols: Vec<Symbol> = Vec::with_capacity(definitions.len());
+        for definition in definitions {
+            let node = definition.node;
+            let start_line = node.start_position().row as u32 + 1;
+            let text = node_text(node, source);
+            let signature = text
+                .lines()
+                .next()
+                .map(|line| line.trim().trim_end_matches(['{', ':']).trim_end().to_string());
+
+            let parent_id = enclosing(&symbols, &TYPE_KINDS, node).map(|s| s.id.clone());
+            scopes.insert((node.start_byte(), node.end_byte()), definition.name.clone());
+
+            symbols.push(Symbol {
+                id: Symbol::provisional_id(file_path, &definition.name, start_line),
+                name: definition.name,
+                qualified_name: String::new(),
+                namespace: None,
+                kind: definition.kind,
+                file_path: file_path.to_path_buf(),
+                start_line,
+                end_line: node.end_position().row as u32 + 1,
+                byte_range: node.start_byte()..node.end_byte(),
+                signature,
+                visibility: Visibility::Public,
+                is_async: text.trim_start().starts_with("async"),
+                is_partial: false,
+                decorators: Vec::new(),
+                modifiers: Modifiers::default(),
+                docstring: definition.doc,
+                doc_sections: DocSections::default(),
+                summary: None,
+                language: self.language,
+                parent_id,
+            });
+        }
+
+        symbols
+    }
+
+    fn extract_imports(&self, tree: &Tree, source: &[u8], file_path: &Path) -> Vec<Import> {
+        let mut imports = Vec::new();
+        self.for_each_match(tree, source, |captures| {
+            if captures.contains_key("reference.import")
+                && let Some(name) = captures.get("name")
+            {
+                imports.push(Import {
+                    source_file: file_path.to_path_buf(),
+                    target: unquote(node_text(*name, source)).to_string(),
+                    alias: None,
+                    items: Vec::new(),
+                    line: name.start_position().row as u32 + 1,
+                    resolved: None,
+                });
+            }
+        });
+        imports
+    }
+
+    fn extract_calls(&self, tree: &Tree, source: &[u8], symbols: &[Symbol]) -> Vec<Call> {
+        // Callee and receiver by call node, attributed in one walk
+        let mut references: HashMap<usize, (String, Option<String>)> = HashMap::new();
+        let mut kinds: Vec<&str> = Vec::new();
+        self.for_each_match(tree, source, |captures| {
+            if let Some(call) = captures.get("reference.call")
+                && let Some(name) = captures.get("name")
+            {
+                let receiver = captures
+                    .get("receiver")
+                    .map(|n| node_text(*n, source).to_string());
+                references.insert(call.id(), (node_text(*name, source).to_string(), receiver));
+                if !kinds.contains(&call.kind()) {
+                    kinds.push(call.kind());
+                }
+            }
+        });
+
+        let scope_kinds = [SymbolKind::Function, SymbolKind::Method, SymbolKind::Component];
+        walk_scoped(tree.root_node(), symbols, &scope_kinds, &kinds, |caller, node| {
+            let (callee_name, receiver) = references.get(&node.id())?.clone();
+            Some(Call {
+                caller_id: caller.id.clone(),
+                callee_name,
+                receiver,
+                call_site_line: node.start_position().row as u32 + 1,
+            })
+        })
+    }
+
+    fn extract_inheritance(
+        &self,
+        tree: &Tree,
+        source: &[u8],
+        symbols: &[Symbol],
+    ) -> Vec<Inheritance> {
+        let mut inheritance = Vec::new();
+        self.for_each_match(tree, source, |captures| {
+            if let Some(parent) = captures.get("reference.inherits")
+                && let Some(child) = enclosing(symbols, &TYPE_KINDS, *parent)
+            {
+                inheritance.push(Inheritance {
+                    child_id: child.id.clone(),
+                    parent_name: node_text(*parent, source).to_string(),
+                });
+            }
+        });
+        inheritance
+    }
+
+    fn extract_renders(&self, tree: &Tree, source: &[u8], symbols: &[Symbol]) -> Vec<Render> {
+        self.builtin
+            .as_ref()
+            .map_or_else(Vec::new, |b| b.extract_renders(tree, source, symbols))
+    }
+
+    fn extract_references(
+        &self,
+        tree: &Tree,
+        source: &[u8],
+        symbols: &[Symbol],
+    ) -> Vec<Reference> {
+        self.builtin
+            .as_ref()
+            .map_or_else(Vec::new, |b| b.extract_references(tree, source, symbols))
+    }
+
+    fn scope_name(&self, node: Node, source: &[u8]) -> Option<String> {
+        let scopes = self.scopes.lock().unwrap_or_else(|e| e.into_inner());
+        scopes
+            .get(&(node.start_byte(), node.end_byte()))
+            .cloned()
+            .or_else(|| self.builtin.as_ref()?.scope_name(node, source))
+    }
+
+    fn receiver_scope(&self, node: Node, source: &[u8]) -> Option<String> {
+        self.builtin.as_ref()?.receiver_scope(node, source)
+    }
+
+    fn namespace_kind(&self, node: Node) -> Option<NamespaceKind> {
+        self.builtin.as_ref()?.namespace_kind(node)
+    }
+
+    fn module_path(&self, tree: &Tree, source: &[u8], file_path: &Path) -> Vec<String> {
+        self.builtin
+            .as_ref()
+            .map_or_else(Vec::new, |b| b.module_path(tree, source, file_path))
+    }
+
+    fn module_kind(&self) -> NamespaceKind {
+        self.builtin
+            .as_ref()
+            .map_or(NamespaceKind::Namespace, |b| b.module_kind())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::SymbolExtractor;
+
+    #[test]
+    fn test_bundled_queries_compile() {
+        for language in Language::BUILTIN {
+            for grammar in grammars(language) {
+                QueryExtractor::bundled(language, grammar).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_bundled_query_matches_hand_written_extractor() {
+        let source = "import os\n\nclass Config(Base):\n    def load(self):\n        return os.path.join(self.root)\n\ndef main():\n    \"\"\"Entry point\"\"\"\n    Config().load()\n";
+        let tree = ParserPool::parse(Language::Python, source.as_bytes()).unwrap();
+        let mut extractors = Extractors::default();
+        extractors
+            .add_query(Language::Python, bundled_query(Language::Python).unwrap())
+            .unwrap();
+        let file = SymbolExtractor::extract_with(
+            &extractors,
+            &tree,
+            source.as_bytes(),
+            Path::new("app/config.py"),
+            Language::Python,
+        )
+        .unwrap();
+        let builtin =
+            SymbolExtractor::extract(&tree, source.as_bytes(), Path::new("app/config.py"), Language::Python)
+                .unwrap();
+
+        let ids = |file: &crate::FileSymbols| {
+            let mut ids: Vec<String> = file.symbols.iter().map(|s| s.id.clone()).collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(ids(&file), ids(&builtin));
+        assert_eq!(file.symbols.iter().find(|s| s.name == "Config").unwrap().qualified_name, "app.config.Config");
+        assert_eq!(file.imports[0].target, "os");
+        assert_eq!(file.inheritance[0].parent_name, "Base");
+
+        let main = file.symbols.iter().find(|s| s.name == "main").unwrap();
+        assert_eq!(main.docstring.as_deref(), Some("Entry point"));
+
+        let mut calls: Vec<(&str, &str)> = file
+            .calls
+            .iter()
+            .map(|c| {
+                let caller = file.symbols.iter().find(|s| s.id == c.caller_id).unwrap();
+                (caller.name.as_str(), c.callee_name.as_str())
+            })
+            .collect();
+        calls.sort();
+        assert_eq!(calls, [("load", "join"), ("main", "Config"), ("main", "load")]);
+    }
+
+    #[test]
+    fn test_project_query_extends_bundled() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(QUERY_DIR)).unwrap();
+        std::fs::write(
+            dir.path().join(QUERY_DIR).join("python.scm"),
+            "; extends\n(call function: (identifier) @_fn (#eq? @_fn \"route\") arguments: (argument_list (string) @name)) @definition.function\n",
+        )
+        .unwrap();
+
+        let extractors = Extractors::load(dir.path()).unwrap();
+        let source = "route('/users')\n\ndef handler():\n    pass\n";
+        let tree = ParserPool::parse(Language::Python, source.as_bytes()).unwrap();
+        let file = SymbolExtractor::extract_with(
+            &extractors,
+            &tree,
+            source.as_bytes(),
+            Path::new("routes.py"),
+            Language::Python,
+        )
+        .unwrap();
+
+        let names: Vec<&str> = file.symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["'/users'", "handler"]);
+
+        std::fs::write(dir.path().join(QUERY_DIR).join("rust.scm"), "(not_a_node) @name").unwrap();
+        assert!(Extractors::load(dir.path()).is_err());
+    }
+}
@@ -0,0 +1,1263 @@
+//! Rust crates and module trees
+//!
+//! A crate's modules are declared with `mod foo;`, which loads `foo.rs` or
+//! `foo/mod.rs` next to the declaring file, or the file named by a `#[path]`
+//! attribute, so the module a file defines cannot be read from its path
+//! alone. [`CargoWorkspace`] reads the workspace's `Cargo.toml` manifests,
+//! follows the `mod` declarations from every crate root and resolves `use`
+//! paths (`crate::`, `self::`, `super::` and sibling crates) to the files and
+//! symbols they name, following `pub use` re-exports on the way.
+//!
+//! Paths into crates outside the workspace are tagged with the crate's
+//! package name and its version from `Cargo.lock`.
+
+use super::imports::normalize_path;
+use crate::parser::extractor::{field_text, node_text};
+use crate::parser::pool::ParserPool;
+use crate::{Import, ImportTarget, ImportedSymbol, Language, Symbol};
+use serde::Deserialize;
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use tracing::warn;
+use tree_sitter::Node;
+
+/// Crates every Rust program can use without declaring them
+const SYSROOT_CRATES: [&str; 5] = ["std", "core", "alloc", "proc_macro", "test"];
+
+/// Maximum number of re-exports followed while resolving one path
+const MAX_REEXPORTS: usize = 8;
+
+/// Item kinds that define a name in their module
+const ITEM_KINDS: [&str; 10] = [
+    "function_item",
+    "function_signature_item",
+    "struct_item",
+    "enum_item",
+    "union_item",
+    "trait_item",
+    "type_item",
+    "const_item",
+    "static_item",
+    "macro_definition",
+];
+
+/// The crates of a Cargo workspace and their module trees
+///
+/// Only the manifests are read up front. The module tree of a package is
+/// built the first time an import from one of its files, or a path into
+/// its library, is resolved, so resolving the imports of a few changed
+/// files does not parse the whole workspace.
+#[derive(Debug, Default)]
+pub struct CargoWorkspace {
+    root: PathBuf,
+    packages: Vec<Package>,
+    /// Package of each workspace library, by crate name
+    crates: HashMap<String, usize>,
+    /// Crates from outside the workspace, by the name code refers to them
+    /// with
+    dependencies: HashMap<String, Dependency>,
+    tree: RwLock<ModuleTree>,
+}
+
+/// A workspace package and the roots of its crates
+#[derive(Debug)]
+struct Package {
+    /// Directory relative to the project root
+    dir: PathBuf,
+    /// Root of the library and the name code refers to it with
+    lib: Option<(PathBuf, String)>,
+    /// Roots of the binaries, tests, examples, benchmarks and build script
+    targets: Vec<PathBuf>,
+}
+
+/// The modules of the packages loaded so far
+#[derive(Debug, Default)]
+struct ModuleTree {
+    modules: Vec<Module>,
+    /// File-level module of each file, by path relative to the project root
+    files: HashMap<PathBuf, usize>,
+    /// Library root module of each loaded crate, by crate name
+    crates: HashMap<String, usize>,
+    loaded: HashSet<usize>,
+}
+
+/// Path resolution against the module trees loaded so far
+struct Lookup<'a> {
+    workspace: &'a CargoWorkspace,
+    tree: &'a ModuleTree,
+    /// Package a path led into before its modules were loaded
+    unloaded: Cell<Option<usize>>,
+}
+
+/// A crate from outside the workspace
+#[derive(Debug, Clone)]
+struct Dependency {
+    package: String,
+    version: Option<String>,
+}
+
+/// A module: a crate root, a file loaded by `mod foo;` or an inline
+/// `mod foo { ... }` block
+#[derive(Debug)]
+struct Module {
+    file: PathBuf,
+    /// Scopes of an inline module within its file
+    inline: Vec<String>,
+    /// First and last line of an inline module
+    lines: Option<(u32, u32)>,
+    parent: Option<usize>,
+    /// Root module of the crate
+    crate_root: usize,
+    children: HashMap<String, usize>,
+    /// Names of the items defined in the module
+    items: HashSet<String>,
+    /// Paths re-exported with `pub use`, by the name they are exported as
+    reexports: HashMap<String, Vec<String>>,
+    /// Paths whose items are all re-exported with `pub use path::*`
+    glob_reexports: Vec<Vec<String>>,
+}
+
+impl Module {
+    fn new(file: PathBuf, parent: Option<usize>, crate_root: usize) -> Self {
+        Self {
+            file,
+            inline: Vec::new(),
+            lines: None,
+            parent,
+            crate_root,
+            children: HashMap::new(),
+            items: HashSet::new(),
+            reexports: HashMap::new(),
+            glob_reexports: Vec::new(),
+        }
+    }
+}
+
+/// Where the `mod` items of a file or inline module find their files
+struct Scope {
+    module: usize,
+    /// Directory of `mod foo;` files
+    child_dir: PathBuf,
+    /// Directory `#[path]` attributes are relative to
+    path_dir: PathBuf,
+}
+
+/// Where a path leads
+#[derive(Debug)]
+enum Resolved {
+    /// A module and the path of an item within it, empty for the module
+    /// itself
+    Item {
+        module: usize,
+        rest: Vec<String>,
+    },
+    External(ImportTarget),
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Manifest {
+    package: Option<PackageSection>,
+    lib: Option<TargetSection>,
+    #[serde(default)]
+    bin: Vec<TargetSection>,
+    #[serde(default)]
+    test: Vec<TargetSection>,
+    #[serde(default)]
+    example: Vec<TargetSection>,
+    #[serde(default)]
+    bench: Vec<TargetSection>,
+    workspace: Option<WorkspaceSection>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, toml::Value>,
+    #[serde(default, rename = "dev-dependencies")]
+    dev_dependencies: BTreeMap<String, toml::Value>,
+    #[serde(default, rename = "build-dependencies")]
+    build_dependencies: BTreeMap<String, toml::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PackageSection {
+    name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TargetSection {
+    name: Option<String>,
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct WorkspaceSection {
+    #[serde(default)]
+    members: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, toml::Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Lockfile {
+    #[serde(default)]
+    package: Vec<LockedPackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LockedPackage {
+    name: String,
+    version: String,
+}
+
+impl CargoWorkspace {
+    /// Read the manifests of the package or workspace whose `Cargo.toml` is
+    /// at `root`, or `None` when there is none
+    ///
+    /// Manifests and sources that cannot be read or parsed are skipped with
+    /// a warning.
+    pub fn load(root: &Path) -> Option<Self> {
+        let manifest = read_manifest(&root.join("Cargo.toml"))?;
+
+        let mut packages = Vec::new();
+        if manifest.package.is_some() {
+            packages.push(PathBuf::new());
+        }
+        if let Some(section) = &manifest.workspace {
+            let excluded: Vec<PathBuf> = section
+                .exclude
+                .iter()
+                .flat_map(|pattern| expand_member(root, pattern))
+                .collect();
+            for member in &section.members {
+                for dir in expand_member(root, member) {
+                    if !excluded.contains(&dir) && !packages.contains(&dir) {
+                        packages.push(dir);
+                    }
+                }
+            }
+        }
+
+        let mut manifests = Vec::new();
+        for dir in packages {
+            if dir.as_os_str().is_empty() {
+                continue;
+            }
+            if let Some(member) = read_manifest(&root.join(&dir).join("Cargo.toml")) {
+                manifests.push((dir, member));
+            }
+        }
+        manifests.insert(0, (PathBuf::new(), manifest));
+
+        let mut workspace = Self {
+            root: root.to_path_buf(),
+            ..Self::default()
+        };
+        workspace.add_dependencies(&manifests, &read_lockfile(&root.join("Cargo.lock")));
+        for (dir, manifest) in &manifests {
+            workspace.add_package(root, dir, manifest);
+        }
+        Some(workspace)
+    }
+
+    /// Record the crates the workspace's manifests depend on, with their
+    /// locked versions
+    fn add_dependencies(
+        &mut self,
+        manifests: &[(PathBuf, Manifest)],
+        locked: &HashMap<String, Vec<String>>,
+    ) {
+        let specs = manifests.iter().flat_map(|(_, m)| {
+            m.workspace
+                .iter()
+                .flat_map(|w| &w.dependencies)
+                .chain(&m.dependencies)
+                .chain(&m.dev_dependencies)
+                .chain(&m.build_dependencies)
+        });
+        for (name, spec) in specs {
+            let package = spec
+                .get("package")
+                .and_then(|p| p.as_str())
+                .unwrap_or(name)
+                .to_string();
+            // With several versions locked, the lock file alone does not
+            // tell which one is meant
+            let version = match locked.get(&package).map(Vec::as_slice) {
+                Some([version]) => Some(version.clone()),
+                _ => None,
+            };
+            self.dependencies
+                .entry(crate_name(name))
+                .or_insert(Dependency { package, version });
+        }
+    }
+
+    /// Record the crates of the package in `dir`: its library, binaries,
+    /// tests, examples, benchmarks and build script
+    fn add_package(&mut self, root: &Path, dir: &Path, manifest: &Manifest) {
+        let Some(package) = &manifest.package else {
+            return;
+        };
+
+        let lib = manifest.lib.as_ref();
+        let lib_path = dir.join(
+            lib.and_then(|l| l.path.clone())
+                .unwrap_or_else(|| PathBuf::from("src/lib.rs")),
+        );
+        let lib = root.join(&lib_path).is_file().then(|| {
+            let name = lib.and_then(|l| l.name.as_deref()).unwrap_or(&package.name);
+            (lib_path, crate_name(name))
+        });
+        if let Some((_, name)) = &lib {
+            self.crates.insert(name.clone(), self.packages.len());
+        }
+
+        let mut targets: Vec<PathBuf> = [
+            &manifest.bin,
+            &manifest.test,
+            &manifest.example,
+            &manifest.bench,
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|target| target.path.as_ref().map(|p| dir.join(p)))
+        .collect();
+        targets.push(dir.join("src/main.rs"));
+        targets.push(dir.join("build.rs"));
+        for subdir in ["src/bin", "tests", "examples", "benches"] {
+            targets.extend(target_files(root, &dir.join(subdir)));
+        }
+        targets.retain(|path| root.join(path).is_file());
+
+        self.packages.push(Package {
+            dir: dir.to_path_buf(),
+            lib,
+            targets,
+        });
+    }
+
+    /// Resolve a `use` declaration or `extern crate` of a file in the
+    /// workspace
+    ///
+    /// Returns `None` for files outside every crate's module tree and for
+    /// paths that lead nowhere.
+    pub fn resolve(&self, import: &Import) -> Option<ImportTarget> {
+        self.load_package(self.package_of(&import.source_file)?);
+        // A path into a sibling crate that is not loaded yet loads it and
+        // starts over
+        loop {
+            let tree = self.tree.read().ok()?;
+            let lookup = Lookup {
+                workspace: self,
+                tree: &tree,
+                unloaded: Cell::new(None),
+            };
+            let target = lookup.resolve(import);
+            match lookup.unloaded.get() {
+                Some(package) => {
+                    drop(tree);
+                    self.load_package(package);
+                }
+                None => return target,
+            }
+        }
+    }
+
+    /// The package a file belongs to, the innermost one for nested
+    /// packages
+    fn package_of(&self, file: &Path) -> Option<usize> {
+        self.packages
+            .iter()
+            .enumerate()
+            .filter(|(_, package)| file.starts_with(&package.dir))
+            .max_by_key(|(_, package)| package.dir.components().count())
+            .map(|(index, _)| index)
+    }
+
+    /// Build the module trees of a package's crates unless already built
+    ///
+    /// The package is parsed into a tree of its own without holding the
+    /// lock, then merged in. When two threads race to load it, the one that
+    /// merges second discards its copy.
+    fn load_package(&self, package: usize) {
+        if self
+            .tree
+            .read()
+            .is_ok_and(|tree| tree.loaded.contains(&package))
+        {
+            return;
+        }
+
+        let mut part = ModuleTree::default();
+        part.add_package(&self.root, &self.packages[package]);
+
+        let Ok(mut tree) = self.tree.write() else {
+            return;
+        };
+        if tree.loaded.insert(package) {
+            tree.merge(part);
+        }
+    }
+}
+
+impl ModuleTree {
+    /// Add the module trees of a package's crates
+    fn add_package(&mut self, root: &Path, package: &Package) {
+        if let Some((path, name)) = &package.lib {
+            let module = self.add_file_module(root, path, None, true);
+            self.crates.insert(name.clone(), module);
+        }
+        for path in &package.targets {
+            if !self.files.contains_key(path) {
+                self.add_file_module(root, path, None, true);
+            }
+        }
+    }
+
+    /// Append the modules of a tree built separately, shifting their
+    /// indices past the modules already here
+    ///
+    /// Files already loaded by another package keep their module.
+    fn merge(&mut self, part: ModuleTree) {
+        let offset = self.modules.len();
+        for mut module in part.modules {
+            module.parent = module.parent.map(|p| p + offset);
+            module.crate_root += offset;
+            for child in module.children.values_mut() {
+                *child += offset;
+            }
+            self.modules.push(module);
+        }
+        for (path, module) in part.files {
+            self.files.entry(path).or_insert(module + offset);
+        }
+        for (name, module) in part.crates {
+            self.crates.insert(name, module + offset);
+        }
+    }
+
+    /// Add the module defined by a file and the modules it declares
+    ///
+    /// Crate roots, `mod.rs` files and files named by `#[path]` own their
+    /// directory; the modules declared in `foo.rs` live in `foo/`.
+    fn add_file_module(
+        &mut self,
+        root: &Path,
+        path: &Path,
+        parent: Option<usize>,
+        owns_dir: bool,
+    ) -> usize {
+        let index = self.modules.len();
+        let crate_root = parent.map_or(index, |p| self.modules[p].crate_root);
+        self.modules
+            .push(Module::new(path.to_path_buf(), parent, crate_root));
+        self.files.entry(path.to_path_buf()).or_insert(index);
+
+        let source = match std::fs::read(root.join(path)) {
+            Ok(source) => source,
+            Err(e) => {
+                warn!("Failed to read {:?}: {}", path, e);
+                return index;
+            }
+        };
+        let tree = match ParserPool::parse(Language::Rust, &source) {
+            Ok(tree) => tree,
+            Err(e) => {
+                warn!("Failed to parse {:?}: {}", path, e);
+                return index;
+            }
+        };
+
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        let child_dir = if owns_dir || stem == "mod" {
+            dir.to_path_buf()
+        } else {
+            dir.join(stem)
+        };
+        let scope = Scope {
+            module: index,
+            child_dir,
+            path_dir: dir.to_path_buf(),
+        };
+        self.add_items(root, tree.root_node(), &source, &scope);
+        index
+    }
+
+    /// Record the items, re-exports and modules declared in a file or an
+    /// inline module's body
+    fn add_items(&mut self, root: &Path, body: Node, source: &[u8], scope: &Scope) {
+        let mut path_attribute = None;
+        let mut cursor = body.walk();
+        for child in body.named_children(&mut cursor) {
+            match child.kind() {
+                "attribute_item" => {
+                    if let Some(path) = path_attribute_value(node_text(child, source)) {
+                        path_attribute = Some(path);
+                    }
+                    continue;
+                }
+                "use_declaration" if is_public(child) => {
+                    if let Some(argument) = child.child_by_field_name("argument") {
+                        let mut paths = Vec::new();
+                        use_paths(argument, &[], source, &mut paths);
+                        let module = &mut self.modules[scope.module];
+                        for (path, name) in paths {
+                            match name {
+                                Some(name) => {
+                                    module.reexports.insert(name, path);
+                                }
+                                None => module.glob_reexports.push(path),
+                            }
+                        }
+                    }
+                }
+                "mod_item" => {
+                    if let Some(name) = field_text(child, "name", source) {
+                        let path = path_attribute.take();
+                        self.add_module(root, child, name, path, source, scope);
+                    }
+                }
+                kind if ITEM_KINDS.contains(&kind) => {
+                    if let Some(name) = field_text(child, "name", source) {
+                        self.modules[scope.module].items.insert(name);
+                    }
+                }
+                _ => {}
+            }
+            path_attribute = None;
+        }
+    }
+
+    /// Add the module declared by a `mod` item
+    fn add_module(
+        &mut self,
+        root: &Path,
+        node: Node,
+        name: String,
+        path_attribute: Option<String>,
+        source: &[u8],
+        scope: &Scope,
+    ) {
+        // Alternatives selected by #[cfg] share a name; keep the first
+        if self.modules[scope.module].children.contains_key(&name) {
+            return;
+        }
+
+        let index = match node.child_by_field_name("body") {
+            Some(body) => {
+                let parent = &self.modules[scope.module];
+                let mut module =
+                    Module::new(parent.file.clone(), Some(scope.module), parent.crate_root);
+                module.inline = parent.inline.clone();
+                module.inline.push(name.clone());
+                module.lines = Some((
+                    node.start_position().row as u32 + 1,
+                    node.end_position().row as u32 + 1,
+                ));
+                let index = self.modules.len();
+                self.modules.push(module);
+
+                let child_dir = match &path_attribute {
+                    Some(path) => normalize_path(&scope.path_dir.join(path)),
+                    None => scope.child_dir.join(&name),
+                };
+                let inner = Scope {
+                    module: index,
+                    path_dir: child_dir.clone(),
+                    child_dir,
+                };
+                self.add_items(root, body, source, &inner);
+                index
+            }
+            None => {
+                let candidates = match &path_attribute {
+                    Some(path) => vec![normalize_path(&scope.path_dir.join(path))],
+                    None => vec![
+                        scope.child_dir.join(format!("{}.rs", name)),
+                        scope.child_dir.join(&name).join("mod.rs"),
+                    ],
+                };
+                let Some(path) = candidates.into_iter().find(|p| root.join(p).is_file()) else {
+                    return;
+                };
+                // A file is only loaded once, even if declared twice
+                if self.files.contains_key(&path) {
+                    return;
+                }
+                let owns_dir = path_attribute.is_some();
+                self.add_file_module(root, &path, Some(scope.module), owns_dir)
+            }
+        };
+        self.modules[scope.module].children.insert(name, index);
+    }
+}
+
+impl Lookup<'_> {
+    /// Resolve an import against the loaded module trees
+    fn resolve(&self, import: &Import) -> Option<ImportTarget> {
+        let from = self.module_at(&import.source_file, import.line)?;
+        let mut target = segments(&import.target);
+        // `use path::*` imports the module's items
+        let glob = target.last().is_some_and(|s| s == "*");
+        if glob {
+            target.pop();
+        }
+
+        let mut files = Vec::new();
+        let mut symbols = Vec::new();
+        if import.items.is_empty() {
+            match self.resolve_path(from, &target, 0)? {
+                Resolved::External(target) => return Some(target),
+                Resolved::Item { module, rest } => {
+                    if glob && !rest.is_empty() {
+                        return None;
+                    }
+                    files.push(self.tree.modules[module].file.clone());
+                    if !rest.is_empty() {
+                        let name = import.alias.as_deref().unwrap_or(&rest[rest.len() - 1]);
+                        symbols.push(self.symbol(module, &rest, name)?);
+                    }
+                }
+            }
+        } else {
+            for item in &import.items {
+                let mut path = target.clone();
+                path.extend(segments(item));
+                match self.resolve_path(from, &path, 0) {
+                    Some(Resolved::External(external)) => return Some(external),
+                    Some(Resolved::Item { module, rest }) => {
+                        let file = &self.tree.modules[module].file;
+                        if (rest.is_empty() || self.defines(module, &rest)) && !files.contains(file)
+                        {
+                            files.push(file.clone());
+                        }
+                        if !rest.is_empty()
+                            && let Some(symbol) = self.symbol(module, &rest, &rest[rest.len() - 1])
+                        {
+                            symbols.push(symbol);
+                        }
+                    }
+                    None => {}
+                }
+            }
+            if files.is_empty() {
+                return None;
+            }
+        }
+
+        // `use super::*` in an inline test module imports its own file
+        files.retain(|f| *f != import.source_file);
+        symbols.retain(|s| s.file_path != import.source_file);
+        Some(ImportTarget::Project { files, symbols })
+    }
+
+    /// Whether a module defines the item a path starts with
+    fn defines(&self, module: usize, rest: &[String]) -> bool {
+        rest.first()
+            .is_some_and(|name| self.tree.modules[module].items.contains(name))
+    }
+
+    /// The symbol a path names within a module, if the module defines it
+    fn symbol(&self, module: usize, rest: &[String], name: &str) -> Option<ImportedSymbol> {
+        if !self.defines(module, rest) {
+            return None;
+        }
+        let module = &self.tree.modules[module];
+        let scoped = module
+            .inline
+            .iter()
+            .chain(rest)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("::");
+        Some(ImportedSymbol {
+            name: name.to_string(),
+            id: Symbol::generate_id(&module.file, &scoped),
+            file_path: module.file.clone(),
+        })
+    }
+
+    /// The innermost module containing a line of a file
+    fn module_at(&self, file: &Path, line: u32) -> Option<usize> {
+        let mut module = *self.tree.files.get(file)?;
+        loop {
+            let current = &self.tree.modules[module];
+            let inner = current.children.values().copied().find(|&child| {
+                let child = &self.tree.modules[child];
+                child.file == current.file
+                    && child
+                        .lines
+                        .is_some_and(|(start, end)| start <= line && line <= end)
+            });
+            match inner {
+                Some(child) => module = child,
+                None => return Some(module),
+            }
+        }
+    }
+
+    /// Resolve a path as written in module `from`
+    fn resolve_path(&self, from: usize, path: &[String], depth: usize) -> Option<Resolved> {
+        let first = path.first()?;
+        let (module, rest) = match first.as_str() {
+            "crate" | "$crate" => (self.tree.modules[from].crate_root, &path[1..]),
+            "self" => (from, &path[1..]),
+            "super" => (from, path),
+            name => {
+                let module = &self.tree.modules[from];
+                let local = module.children.contains_key(name)
+                    || module.items.contains(name)
+                    || module.reexports.contains_key(name);
+                if local {
+                    (from, path)
+                } else if let Some(&package) = self.workspace.crates.get(name) {
+                    let Some(&crate_root) = self.tree.crates.get(name) else {
+                        self.unloaded.set(Some(package));
+                        return None;
+                    };
+                    (crate_root, &path[1..])
+                } else if let Some(dependency) = self.workspace.dependencies.get(name) {
+                    return Some(Resolved::External(ImportTarget::External {
+                        package: dependency.package.clone(),
+                        version: dependency.version.clone(),
+                    }));
+                } else if SYSROOT_CRATES.contains(&name) {
+                    return Some(Resolved::External(ImportTarget::External {
+                        package: name.to_string(),
+                        version: None,
+                    }));
+                } else {
+                    return None;
+                }
+            }
+        };
+        self.walk(module, rest, depth)
+    }
+
+    /// Follow a path down from a module, through child modules and
+    /// re-exports, to the module defining its item
+    fn walk(&self, mut module: usize, path: &[String], depth: usize) -> Option<Resolved> {
+        for (i, segment) in path.iter().enumerate() {
+            let current = &self.tree.modules[module];
+            match segment.as_str() {
+                "super" => {
+                    module = current.parent?;
+                    continue;
+                }
+                "self" => continue,
+                _ => {}
+            }
+            if let Some(&child) = current.children.get(segment) {
+                module = child;
+                continue;
+            }
+
+            let rest = &path[i..];
+            if !current.items.contains(segment) && depth < MAX_REEXPORTS {
+                if let Some(reexport) = current.reexports.get(segment) {
+                    let mut reexported = reexport.clone();
+                    reexported.extend_from_slice(&rest[1..]);
+                    return self.resolve_path(module, &reexported, depth + 1);
+                }
+                // The first glob re-export that leads to the name
+                for glob in &current.glob_reexports {
+                    let mut globbed = glob.clone();
+                    globbed.extend_from_slice(rest);
+                    if let Some(Resolved::Item { module, rest }) =
+                        self.resolve_path(module, &globbed, depth + 1)
+                        && (rest.is_empty() || self.defines(module, &rest))
+                    {
+                        return Some(Resolved::Item { module, rest });
+                    }
+                }
+            }
+            return Some(Resolved::Item {
+                module,
+                rest: rest.to_vec(),
+            });
+        }
+        Some(Resolved::Item {
+            module,
+            rest: Vec::new(),
+        })
+    }
+}
+
+/// Read a `Cargo.toml`, warning when it exists but cannot be parsed
+fn read_manifest(path: &Path) -> Option<Manifest> {
+    let content = std::fs::read_to_string(path).ok()?;
+    match toml::from_str(&content) {
+        Ok(manifest) => Some(manifest),
+        Err(e) => {
+            warn!("Failed to parse {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Versions of each package in a `Cargo.lock`
+fn read_lockfile(path: &Path) -> HashMap<String, Vec<String>> {
+    let lockfile: Lockfile = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| toml::from_str(&content).ok())
+        .unwrap_or_default();
+    let mut versions: HashMap<String, Vec<String>> = HashMap::new();
+    for package in lockfile.package {
+        versions
+            .entry(package.name)
+            .or_default()
+            .push(package.version);
+    }
+    versions
+}
+
+/// Package directories named by a workspace member, which may end in a `*`
+/// glob (`crates/*`)
+fn expand_member(root: &Path, member: &str) -> Vec<PathBuf> {
+    let member = member.trim_end_matches('/');
+    let Some(parent) = member.strip_suffix("/*") else {
+        return vec![PathBuf::from(member)];
+    };
+    let Ok(entries) = std::fs::read_dir(root.join(parent)) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| entry.path().join("Cargo.toml").is_file())
+        .map(|entry| Path::new(parent).join(entry.file_name()))
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// Name code uses for a crate, with dashes turned into underscores
+fn crate_name(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+/// Crate roots auto-discovered in a target directory such as `tests`: its
+/// `.rs` files and the `main.rs` of its subdirectories
+fn target_files(root: &Path, dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(root.join(dir)) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let name = entry.file_name();
+            if path.is_dir() {
+                path.join("main.rs")
+                    .is_file()
+                    .then(|| dir.join(&name).join("main.rs"))
+            } else {
+                (path.extension().is_some_and(|e| e == "rs")).then(|| dir.join(&name))
+            }
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// The file named by a `#[path = "..."]` attribute
+fn path_attribute_value(attribute: &str) -> Option<String> {
+    let inner = attribute
+        .strip_prefix("#[")?
+        .strip_suffix(']')?
+        .trim()
+        .strip_prefix("path")?
+        .trim_start()
+        .strip_prefix('=')?
+        .trim();
+    Some(inner.strip_prefix('"')?.strip_suffix('"')?.to_string())
+}
+
+/// Whether an item is visible outside its module
+fn is_public(node: Node) -> bool {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .any(|child| child.kind() == "visibility_modifier")
+}
+
+/// Paths named by a `use` tree, each with the name it binds or `None` for a
+/// glob
+fn use_paths(
+    node: Node,
+    prefix: &[String],
+    source: &[u8],
+    paths: &mut Vec<(Vec<String>, Option<String>)>,
+) {
+    let joined = |text: &str| {
+        let mut path = prefix.to_vec();
+        path.extend(segments(text));
+        path
+    };
+    match node.kind() {
+        "identifier" | "scoped_identifier" | "self" | "crate" | "super" => {
+            let path = joined(node_text(node, source));
+            let name = match path.last().map(String::as_str) {
+                // `use a::{self}` binds `a`
+                Some("self") => path.len().checked_sub(2).map(|i| path[i].clone()),
+                last => last.map(str::to_string),
+            };
+            if let Some(name) = name {
+                paths.push((path, Some(name)));
+            }
+        }
+        "use_as_clause" => {
+            if let (Some(path), Some(alias)) = (
+                field_text(node, "path", source),
+                field_text(node, "alias", source),
+            ) && alias != "_"
+            {
+                paths.push((joined(&path), Some(alias)));
+            }
+        }
+        "use_wildcard" => {
+            let path = node
+                .named_child(0)
+                .map(|n| joined(node_text(n, source)))
+                .unwrap_or_else(|| prefix.to_vec());
+            paths.push((path, None));
+        }
+        "scoped_use_list" => {
+            let path = field_text(node, "path", source)
+                .map(|p| joined(&p))
+                .unwrap_or_else(|| prefix.to_vec());
+            if let Some(list) = node.child_by_field_name("list") {
+                use_paths(list, &path, source, paths);
+            }
+        }
+        "use_list" => {
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                use_paths(child, prefix, source, paths);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Segments of a `::`-separated path, without a leading `::`
+fn segments(path: &str) -> Vec<String> {
+    path.split("::")
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn import(file: &str, target: &str, items: &[&str], line: u32) -> Import {
+        Import {
+            source_file: PathBuf::from(file),
+            target: target.to_string(),
+            alias: None,
+            items: items.iter().map(|s| s.to_string()).collect(),
+            line,
+            resolved: None,
+        }
+    }
+
+    fn files(target: &ImportTarget) -> Vec<PathBuf> {
+        match target {
+            ImportTarget::Project { files, .. } => files.clone(),
+            other => panic!("expected a project import, got {:?}", other),
+        }
+    }
+
+    fn symbol_ids(target: &ImportTarget) -> Vec<String> {
+        match target {
+            ImportTarget::Project { symbols, .. } => symbols.iter().map(|s| s.id.clone()).collect(),
+            other => panic!("expected a project import, got {:?}", other),
+        }
+    }
+
+    /// A two-crate workspace: `app` depends on `core-lib` and serde
+    fn workspace() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.dependencies]\nserde = \"1\"\n",
+        );
+        write(
+            root,
+            "Cargo.lock",
+            "[[package]]\nname = \"serde\"\nversion = \"1.0.210\"\n",
+        );
+        write(
+            root,
+            "crates/core-lib/Cargo.toml",
+            "[package]\nname = \"core-lib\"\n\n[dependencies]\nserde.workspace = true\n",
+        );
+        write(
+            root,
+            "crates/core-lib/src/lib.rs",
+            "pub mod graph;\n#[path = \"platform/unix.rs\"]\nmod sys;\npub use graph::writer::GraphWriter;\npub use graph::schema::*;\npub struct Config;\n",
+        );
+        write(
+            root,
+            "crates/core-lib/src/graph/mod.rs",
+            "pub mod writer;\npub mod schema;\n",
+        );
+        write(
+            root,
+            "crates/core-lib/src/graph/writer.rs",
+            "use super::schema::GraphSchema;\nuse crate::Config;\npub struct GraphWriter;\nmod batch {\n    use super::super::schema;\n    pub fn flush() {}\n}\n",
+        );
+        write(
+            root,
+            "crates/core-lib/src/graph/schema.rs",
+            "pub struct GraphSchema;\n",
+        );
+        write(
+            root,
+            "crates/core-lib/src/platform/unix.rs",
+            "mod signals;\n",
+        );
+        write(
+            root,
+            "crates/core-lib/src/platform/signals.rs",
+            "pub fn install() {}\n",
+        );
+        write(
+            root,
+            "crates/app/Cargo.toml",
+            "[package]\nname = \"app\"\n\n[dependencies]\ncore-lib = { path = \"../core-lib\" }\n",
+        );
+        write(
+            root,
+            "crates/app/src/main.rs",
+            "use core_lib::{GraphWriter, GraphSchema, graph::writer};\nuse serde::Serialize;\nuse std::path::Path;\n",
+        );
+        dir
+    }
+
+    #[test]
+    fn test_resolves_crate_super_and_self_paths() {
+        let dir = workspace();
+        let cargo = CargoWorkspace::load(dir.path()).unwrap();
+
+        let schema = cargo
+            .resolve(&import(
+                "crates/core-lib/src/graph/writer.rs",
+                "super::schema::GraphSchema",
+                &[],
+                1,
+            ))
+            .unwrap();
+        assert_eq!(
+            files(&schema),
+            vec![PathBuf::from("crates/core-lib/src/graph/schema.rs")]
+        );
+        assert_eq!(
+            symbol_ids(&schema),
+            vec!["crates/core-lib/src/graph/schema.rs::GraphSchema".to_string()]
+        );
+
+        let config = cargo
+            .resolve(&import(
+                "crates/core-lib/src/graph/writer.rs",
+                "crate::Config",
+                &[],
+                2,
+            ))
+            .unwrap();
+        assert_eq!(
+            files(&config),
+            vec![PathBuf::from("crates/core-lib/src/lib.rs")]
+        );
+
+        // `super` inside the inline `batch` module is the writer module
+        let schema = cargo
+            .resolve(&import(
+                "crates/core-lib/src/graph/writer.rs",
+                "super::super::schema",
+                &[],
+                5,
+            ))
+            .unwrap();
+        assert_eq!(
+            files(&schema),
+            vec![PathBuf::from("crates/core-lib/src/graph/schema.rs")]
+        );
+        assert!(symbol_ids(&schema).is_empty());
+    }
+
+    #[test]
+    fn test_follows_path_attributes() {
+        let dir = workspace();
+        let cargo = CargoWorkspace::load(dir.path()).unwrap();
+
+        let install = cargo
+            .resolve(&import(
+                "crates/core-lib/src/lib.rs",
+                "crate::sys::signals::install",
+                &[],
+                1,
+            ))
+            .unwrap();
+        assert_eq!(
+            files(&install),
+            vec![PathBuf::from("crates/core-lib/src/platform/signals.rs")]
+        );
+    }
+
+    #[test]
+    fn test_resolves_sibling_crates_through_reexports() {
+        let dir = workspace();
+        let cargo = CargoWorkspace::load(dir.path()).unwrap();
+
+        let target = cargo
+            .resolve(&import(
+                "crates/app/src/main.rs",
+                "core_lib",
+                &["GraphWriter", "GraphSchema", "graph::writer"],
+                1,
+            ))
+            .unwrap();
+        assert_eq!(
+            files(&target),
+            vec![
+                PathBuf::from("crates/core-lib/src/graph/writer.rs"),
+                PathBuf::from("crates/core-lib/src/graph/schema.rs"),
+            ]
+        );
+        assert_eq!(
+            symbol_ids(&target),
+            vec![
+                "crates/core-lib/src/graph/writer.rs::GraphWriter".to_string(),
+                "crates/core-lib/src/graph/schema.rs::GraphSchema".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_builds_module_trees_on_first_use() {
+        let dir = workspace();
+        let cargo = CargoWorkspace::load(dir.path()).unwrap();
+        assert!(cargo.tree.read().unwrap().modules.is_empty());
+
+        // Paths within the library leave the binary's package unparsed
+        cargo.resolve(&import(
+            "crates/core-lib/src/lib.rs",
+            "crate::Config",
+            &[],
+            1,
+        ));
+        let files = |cargo: &CargoWorkspace| cargo.tree.read().unwrap().files.len();
+        assert_eq!(files(&cargo), 6);
+
+        // An external path from the binary only adds the binary's package
+        cargo.resolve(&import(
+            "crates/app/src/main.rs",
+            "serde::Serialize",
+            &[],
+            2,
+        ));
+        assert_eq!(files(&cargo), 7);
+    }
+
+    #[test]
+    fn test_concurrent_loads_merge_a_package_once() {
+        let dir = workspace();
+        let cargo = CargoWorkspace::load(dir.path()).unwrap();
+        let app = import(
+            "crates/app/src/main.rs",
+            "core_lib",
+            &["GraphWriter", "GraphSchema", "graph::writer"],
+            1,
+        );
+
+        let targets: Vec<Option<ImportTarget>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| cargo.resolve(&app)))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        let expected = vec![
+            PathBuf::from("crates/core-lib/src/graph/writer.rs"),
+            PathBuf::from("crates/core-lib/src/graph/schema.rs"),
+        ];
+        for target in &targets {
+            assert_eq!(files(target.as_ref().unwrap()), expected);
+        }
+
+        // Each package merged once: six library files with an inline
+        // module, and the binary's main.rs
+        let tree = cargo.tree.read().unwrap();
+        assert_eq!(tree.files.len(), 7);
+        assert_eq!(tree.modules.len(), 8);
+    }
+
+    #[test]
+    fn test_tags_external_crates_with_locked_version() {
+        let dir = workspace();
+        let cargo = CargoWorkspace::load(dir.path()).unwrap();
+
+        assert_eq!(
+            cargo.resolve(&import(
+                "crates/app/src/main.rs",
+                "serde::Serialize",
+                &[],
+                2
+            )),
+            Some(ImportTarget::External {
+                package: "serde".to_string(),
+                version: Some("1.0.210".to_string()),
+            })
+        );
+        assert_eq!(
+            cargo.resolve(&import("crates/app/src/main.rs", "std::path::Path", &[], 3)),
+            Some(ImportTarget::External {
+                package: "std".to_string(),
+                version: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_ignores_files_outside_the_module_tree() {
+        let dir = workspace();
+        write(
+            dir.path(),
+            "crates/core-lib/src/orphan.rs",
+            "use crate::Config;\n",
+        );
+        let cargo = CargoWorkspace::load(dir.path()).unwrap();
+
+        assert!(
+            cargo
+                .resolve(&import(
+                    "crates/core-lib/src/orphan.rs",
+                    "crate::Config",
+                    &[],
+                    1
+                ))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_path_attribute_value() {
+        assert_eq!(
+            path_attribute_value("#[path = \"platform/unix.rs\"]"),
+            Some("platform/unix.rs".to_string())
+        );
+        assert_eq!(path_attribute_value("#[derive(Debug)]"), None);
+    }
+}
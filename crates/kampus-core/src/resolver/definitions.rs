@@ -0,0 +1,244 @@
+//! C++ declarations and their definitions
+//!
+//! A C++ function is usually declared in a header and defined in a source
+//! file, and member functions are defined outside their class
+//! (`double Shape::area() const { ... }`), often in another file than the
+//! class itself. Both ends share a qualified name, so this pairs them over
+//! the whole graph once every file is written.
+//!
+//! Overloads are told apart by their number of parameters. Out-of-line
+//! member definitions whose class was not declared in the same file are
+//! attached to the class with their qualifier's qualified name.
+
+use crate::{Symbol, SymbolKind};
+use std::collections::HashMap;
+
+/// Kinds of the symbols compared by [`definitions`] and [`members`]
+pub const KINDS: [SymbolKind; 4] = [
+    SymbolKind::Class,
+    SymbolKind::Struct,
+    SymbolKind::Function,
+    SymbolKind::Method,
+];
+
+/// A C++ class, function or method read back from the graph
+#[derive(Debug, Clone)]
+pub struct DefinitionEntry {
+    pub id: String,
+    pub kind: SymbolKind,
+    pub name: String,
+    pub qualified_name: String,
+    pub signature: Option<String>,
+    /// Declared without a body
+    pub is_declaration: bool,
+    pub parent_id: Option<String>,
+}
+
+impl From<&Symbol> for DefinitionEntry {
+    fn from(symbol: &Symbol) -> Self {
+        Self {
+            id: symbol.id.clone(),
+            kind: symbol.kind,
+            name: symbol.name.clone(),
+            qualified_name: symbol.qualified_name.clone(),
+            signature: symbol.signature.clone(),
+            is_declaration: symbol.modifiers.is_declaration,
+            parent_id: symbol.parent_id.clone(),
+        }
+    }
+}
+
+/// A declaration and the definition implementing it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definition {
+    pub declaration_id: String,
+    pub declaration_kind: SymbolKind,
+    pub definition_id: String,
+    pub definition_kind: SymbolKind,
+}
+
+/// An out-of-line method definition and the class it belongs to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub class_id: String,
+    pub class_kind: SymbolKind,
+    pub method_id: String,
+}
+
+/// Pair each function or method declaration with its definitions
+pub fn definitions(entries: &[DefinitionEntry]) -> Vec<Definition> {
+    let callable = |e: &&DefinitionEntry| {
+        matches!(e.kind, SymbolKind::Function | SymbolKind::Method)
+    };
+
+    // Definitions by qualified name and arity
+    let mut defined: HashMap<(&str, Option<usize>), Vec<&DefinitionEntry>> = HashMap::new();
+    for entry in entries.iter().filter(callable).filter(|e| !e.is_declaration) {
+        defined
+            .entry((entry.qualified_name.as_str(), arity_of(entry)))
+            .or_default()
+            .push(entry);
+    }
+
+    let mut definitions = Vec::new();
+    for declaration in entries.iter().filter(callable).filter(|e| e.is_declaration) {
+        let key = (declaration.qualified_name.as_str(), arity_of(declaration));
+        for definition in defined.get(&key).into_iter().flatten() {
+            definitions.push(Definition {
+                declaration_id: declaration.id.clone(),
+                declaration_kind: declaration.kind,
+                definition_id: definition.id.clone(),
+                definition_kind: definition.kind,
+            });
+        }
+    }
+
+    definitions.sort_by(|a, b| {
+        (&a.declaration_id, &a.definition_id).cmp(&(&b.declaration_id, &b.definition_id))
+    });
+    definitions
+}
+
+/// Find the class of each out-of-line method definition that has none yet
+pub fn members(entries: &[DefinitionEntry]) -> Vec<Member> {
+    let classes: HashMap<&str, &DefinitionEntry> = entries
+        .iter()
+        .filter(|e| matches!(e.kind, SymbolKind::Class | SymbolKind::Struct))
+        .map(|e| (e.qualified_name.as_str(), e))
+        .collect();
+
+    let mut members: Vec<Member> = entries
+        .iter()
+        .filter(|e| e.kind == SymbolKind::Method && !e.is_declaration && e.parent_id.is_none())
+        .filter_map(|method| {
+            let (owner, _) = method.qualified_name.rsplit_once("::")?;
+            let class = classes.get(owner)?;
+            Some(Member {
+                class_id: class.id.clone(),
+                class_kind: class.kind,
+                method_id: method.id.clone(),
+            })
+        })
+        .collect();
+    members.sort_by(|a, b| a.method_id.cmp(&b.method_id));
+    members
+}
+
+fn arity_of(entry: &DefinitionEntry) -> Option<usize> {
+    arity(entry.signature.as_deref()?, &entry.name)
+}
+
+/// Number of parameters of the function `name` in a declaration or
+/// definition (`int Shape::scale(int x, std::map<int, int> m) const`), with
+/// `(void)` declaring none
+pub fn arity(signature: &str, name: &str) -> Option<usize> {
+    // The name may also appear in the return type or the qualifier, as in
+    // `Shape::Shape()`, so find the occurrence followed by the parameters
+    let parameters = signature.match_indices(name).find_map(|(i, _)| {
+        let rest = signature[i + name.len()..].trim_start();
+        rest.starts_with('(').then_some(rest)
+    })?;
+
+    let mut depth = 0;
+    let mut count = 0;
+    let mut segment_start = 1;
+    for (i, c) in parameters.char_indices() {
+        match c {
+            '(' | '[' | '{' | '<' => depth += 1,
+            ')' | ']' | '}' | '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    let last = parameters[segment_start..i].trim();
+                    let is_void = count == 0 && last == "void";
+                    if !last.is_empty() && !is_void {
+                        count += 1;
+                    }
+                    return Some(count);
+                }
+            }
+            ',' if depth == 1 => {
+                count += 1;
+                segment_start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(
+        kind: SymbolKind,
+        id: &str,
+        qualified_name: &str,
+        signature: &str,
+        is_declaration: bool,
+    ) -> DefinitionEntry {
+        DefinitionEntry {
+            id: id.to_string(),
+            kind,
+            name: qualified_name.rsplit("::").next().unwrap().to_string(),
+            qualified_name: qualified_name.to_string(),
+            signature: Some(signature.to_string()),
+            is_declaration,
+            parent_id: None,
+        }
+    }
+
+    #[test]
+    fn test_arity() {
+        assert_eq!(arity("double Shape::area() const", "area"), Some(0));
+        assert_eq!(arity("Shape::Shape(int w, int h)", "Shape"), Some(2));
+        assert_eq!(arity("int tick(void)", "tick"), Some(0));
+        assert_eq!(
+            arity("void put(std::map<int, int> m, int n = f(1, 2))", "put"),
+            Some(2)
+        );
+        assert_eq!(
+            arity("template <typename T> T clamp(T v, T lo, T hi)", "clamp"),
+            Some(3)
+        );
+        assert_eq!(arity("int size", "size"), None);
+    }
+
+    #[test]
+    fn test_definitions() {
+        let entries = vec![
+            entry(SymbolKind::Class, "h::Shape", "geo::Shape", "class Shape {", false),
+            entry(SymbolKind::Method, "h::area", "geo::Shape::area", "double area() const", true),
+            entry(
+                SymbolKind::Method,
+                "c::area",
+                "geo::Shape::area",
+                "double Shape::area() const",
+                false,
+            ),
+            // Overloads
+            entry(SymbolKind::Function, "h::log1", "geo::log", "void log(int level)", true),
+            entry(SymbolKind::Function, "h::log0", "geo::log", "void log()", true),
+            entry(SymbolKind::Function, "c::log1", "geo::log", "void log(int level)", false),
+            // Declared but never defined
+            entry(SymbolKind::Function, "h::reset", "geo::reset", "void reset()", true),
+        ];
+
+        let definitions = definitions(&entries);
+        let found: Vec<(&str, &str)> = definitions
+            .iter()
+            .map(|d| (d.declaration_id.as_str(), d.definition_id.as_str()))
+            .collect();
+        assert_eq!(found, vec![("h::area", "c::area"), ("h::log1", "c::log1")]);
+
+        let members = members(&entries);
+        assert_eq!(
+            members,
+            vec![Member {
+                class_id: "h::Shape".to_string(),
+                class_kind: SymbolKind::Class,
+                method_id: "c::area".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,337 @@
+//! Implicit interface satisfaction
+//!
+//! A Go type implements an interface whenever its method set covers the
+//! interface's methods, without naming the interface anywhere. Methods are
+//! declared apart from their receiver type, often in other files of the
+//! package, so this runs over the whole graph once every file is written.
+//!
+//! Methods are compared by name and number of parameters. Pointer and value
+//! receivers both count towards a type's method set. An interface requires
+//! its own methods and, transitively, those of the interfaces it embeds
+//! (its `INHERITS` edges), so `ReadCloser` needs both `Read` and `Close`.
+//! Empty interfaces are skipped, since every type would satisfy them.
+
+use crate::{Symbol, SymbolKind};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Kinds of the symbols compared by [`implementations`]
+pub const KINDS: [SymbolKind; 4] = [
+    SymbolKind::Struct,
+    SymbolKind::TypeAlias,
+    SymbolKind::Interface,
+    SymbolKind::Method,
+];
+
+/// A Go type, interface or method read back from the graph
+#[derive(Debug, Clone)]
+pub struct MethodSetEntry {
+    pub id: String,
+    pub kind: SymbolKind,
+    /// `pkg.Type` for types, `pkg.Type.Method` for methods
+    pub qualified_name: String,
+    pub signature: Option<String>,
+    /// Ids of the interfaces an interface embeds
+    pub embeds: Vec<String>,
+}
+
+impl From<&Symbol> for MethodSetEntry {
+    fn from(symbol: &Symbol) -> Self {
+        Self {
+            id: symbol.id.clone(),
+            kind: symbol.kind,
+            qualified_name: symbol.qualified_name.clone(),
+            signature: symbol.signature.clone(),
+            embeds: Vec::new(),
+        }
+    }
+}
+
+/// A type satisfying an interface
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Implementation {
+    pub type_id: String,
+    pub type_kind: SymbolKind,
+    pub interface_id: String,
+}
+
+/// Find the interfaces each struct or named type implements
+pub fn implementations(entries: &[MethodSetEntry]) -> Vec<Implementation> {
+    // Method names and arities by the qualified name of their type
+    let mut method_sets: BTreeMap<&str, BTreeSet<(&str, usize)>> = BTreeMap::new();
+    for entry in entries.iter().filter(|e| e.kind == SymbolKind::Method) {
+        let Some((owner, name)) = entry.qualified_name.rsplit_once('.') else {
+            continue;
+        };
+        let Some(arity) = entry.signature.as_deref().and_then(|s| arity(s, name)) else {
+            continue;
+        };
+        method_sets.entry(owner).or_default().insert((name, arity));
+    }
+
+    let empty = BTreeSet::new();
+    let methods_of = |entry: &MethodSetEntry| {
+        method_sets
+            .get(entry.qualified_name.as_str())
+            .unwrap_or(&empty)
+    };
+
+    let interfaces: BTreeMap<&str, &MethodSetEntry> = entries
+        .iter()
+        .filter(|e| e.kind == SymbolKind::Interface)
+        .map(|e| (e.id.as_str(), e))
+        .collect();
+    // Own methods plus those of embedded interfaces, at any depth
+    let required_of = |interface: &MethodSetEntry| {
+        let mut required = BTreeSet::new();
+        let mut seen = BTreeSet::from([interface.id.as_str()]);
+        let mut pending = vec![interface];
+        while let Some(current) = pending.pop() {
+            required.extend(methods_of(current).iter().copied());
+            for embedded in &current.embeds {
+                if let Some(embedded) = interfaces.get(embedded.as_str())
+                    && seen.insert(embedded.id.as_str())
+                {
+                    pending.push(embedded);
+                }
+            }
+        }
+        required
+    };
+
+    let mut implementations = Vec::new();
+    for interface in interfaces.values() {
+        let required = required_of(interface);
+        if required.is_empty() {
+            continue;
+        }
+        // Unexported methods can only be implemented inside the package
+        let interface_package = package(&interface.qualified_name);
+        let exported = required.iter().all(|(name, _)| is_exported(name));
+
+        for candidate in entries
+            .iter()
+            .filter(|e| matches!(e.kind, SymbolKind::Struct | SymbolKind::TypeAlias))
+        {
+            if (exported || package(&candidate.qualified_name) == interface_package)
+                && methods_of(candidate).is_superset(&required)
+            {
+                implementations.push(Implementation {
+                    type_id: candidate.id.clone(),
+                    type_kind: candidate.kind,
+                    interface_id: interface.id.clone(),
+                });
+            }
+        }
+    }
+
+    implementations
+        .sort_by(|a, b| (&a.type_id, &a.interface_id).cmp(&(&b.type_id, &b.interface_id)));
+    implementations
+}
+
+/// Number of parameters of the method `name` in a method declaration
+/// (`func (s *Stack) Push(x, y int)`) or interface method (`Push(x, y int)`)
+pub fn arity(signature: &str, name: &str) -> Option<usize> {
+    let mut rest = signature.trim_start();
+    if let Some(after) = rest.strip_prefix("func") {
+        rest = after.trim_start();
+        // Skip the receiver
+        if rest.starts_with('(') {
+            let (_, len) = parameter_list(rest)?;
+            rest = rest[len..].trim_start();
+        }
+    }
+    let rest = rest.strip_prefix(name)?.trim_start();
+    parameter_list(rest).map(|(count, _)| count)
+}
+
+/// Count the parameters of the list `text` starts with, returning the count
+/// and the length of the list including its parentheses
+///
+/// `x, y int` declares two parameters, so every top-level comma separates
+/// one.
+fn parameter_list(text: &str) -> Option<(usize, usize)> {
+    if !text.starts_with('(') {
+        return None;
+    }
+    let mut depth = 0;
+    let mut count = 0;
+    let mut segment_start = 1;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    if !text[segment_start..i].trim().is_empty() {
+                        count += 1;
+                    }
+                    return Some((count, i + 1));
+                }
+            }
+            ',' if depth == 1 => {
+                if !text[segment_start..i].trim().is_empty() {
+                    count += 1;
+                }
+                segment_start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Package part of a qualified name (`internal/store` for
+/// `internal/store.Stack`)
+fn package(qualified_name: &str) -> &str {
+    qualified_name
+        .rsplit_once('.')
+        .map_or("", |(package, _)| package)
+}
+
+fn is_exported(name: &str) -> bool {
+    name.chars().next().is_some_and(char::is_uppercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(kind: SymbolKind, qualified_name: &str, signature: &str) -> MethodSetEntry {
+        MethodSetEntry {
+            id: qualified_name.to_string(),
+            kind,
+            qualified_name: qualified_name.to_string(),
+            signature: Some(signature.to_string()),
+            embeds: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_arity() {
+        assert_eq!(
+            arity("func (s *Stack) Push(x, y int) error", "Push"),
+            Some(2)
+        );
+        assert_eq!(arity("func (s Stack[T]) Len() int", "Len"), Some(0));
+        assert_eq!(arity("Read(p []byte) (n int, err error)", "Read"), Some(1));
+        assert_eq!(
+            arity("Each(f func(k, v string) bool, opts ...Option)", "Each"),
+            Some(2)
+        );
+        assert_eq!(
+            arity("Get(\n\tkey string,\n\tm map[string]int,\n)", "Get"),
+            Some(2)
+        );
+        assert_eq!(
+            arity("func (l *Log) Print(v interface{}, o struct{}) error", "Print"),
+            Some(2)
+        );
+        assert_eq!(arity("func (s *Stack) Push(x int)", "Pop"), None);
+    }
+
+    #[test]
+    fn test_implementations() {
+        let entries = vec![
+            entry(SymbolKind::Interface, "io.Reader", "Reader interface {"),
+            entry(
+                SymbolKind::Method,
+                "io.Reader.Read",
+                "Read(p []byte) (int, error)",
+            ),
+            entry(SymbolKind::Interface, "store.closer", "closer interface {"),
+            entry(SymbolKind::Method, "store.closer.close", "close()"),
+            entry(SymbolKind::Interface, "store.Any", "Any interface{}"),
+            // Methods declared in another file than the struct
+            entry(SymbolKind::Struct, "store.File", "File struct {"),
+            entry(
+                SymbolKind::Method,
+                "store.File.Read",
+                "func (f *File) Read(b []byte) (int, error)",
+            ),
+            entry(
+                SymbolKind::Method,
+                "store.File.close",
+                "func (f File) close()",
+            ),
+            // Wrong arity
+            entry(SymbolKind::Struct, "store.Pipe", "Pipe struct {"),
+            entry(
+                SymbolKind::Method,
+                "store.Pipe.Read",
+                "func (p Pipe) Read() error",
+            ),
+            // Unexported method of another package
+            entry(SymbolKind::TypeAlias, "net.Conn", "Conn int"),
+            entry(
+                SymbolKind::Method,
+                "net.Conn.Read",
+                "func (c Conn) Read(b []byte) (int, error)",
+            ),
+            entry(
+                SymbolKind::Method,
+                "net.Conn.close",
+                "func (c Conn) close()",
+            ),
+        ];
+
+        let implementations = implementations(&entries);
+        let found: Vec<(&str, &str)> = implementations
+            .iter()
+            .map(|i| (i.type_id.as_str(), i.interface_id.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("net.Conn", "io.Reader"),
+                ("store.File", "io.Reader"),
+                ("store.File", "store.closer"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_embedded_interfaces_add_required_methods() {
+        let mut entries = vec![
+            entry(SymbolKind::Interface, "io.Reader", "Reader interface {"),
+            entry(
+                SymbolKind::Method,
+                "io.Reader.Read",
+                "Read(p []byte) (int, error)",
+            ),
+            entry(SymbolKind::Interface, "io.ReadCloser", "ReadCloser interface {"),
+            entry(SymbolKind::Method, "io.ReadCloser.Close", "Close() error"),
+            // Only embeds, like io.ReadWriteCloser
+            entry(SymbolKind::Interface, "io.Stream", "Stream interface {"),
+            entry(SymbolKind::Struct, "io.OnlyClose", "OnlyClose struct {"),
+            entry(
+                SymbolKind::Method,
+                "io.OnlyClose.Close",
+                "func (OnlyClose) Close() error",
+            ),
+            entry(SymbolKind::Struct, "io.File", "File struct {"),
+            entry(
+                SymbolKind::Method,
+                "io.File.Read",
+                "func (f *File) Read(p []byte) (int, error)",
+            ),
+            entry(SymbolKind::Method, "io.File.Close", "func (f *File) Close() error"),
+        ];
+        entries[2].embeds = vec!["io.Reader".to_string()];
+        entries[4].embeds = vec!["io.ReadCloser".to_string()];
+
+        let implementations = implementations(&entries);
+        let found: Vec<(&str, &str)> = implementations
+            .iter()
+            .map(|i| (i.type_id.as_str(), i.interface_id.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("io.File", "io.ReadCloser"),
+                ("io.File", "io.Reader"),
+                ("io.File", "io.Stream"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,66 @@
+//! Import resolution
+//!
+//! Import statements are extracted as the text they were written with.
+//! [`ImportResolver`] reads the project's layout once per indexing run and
+//! points each import at the project files and symbols it names, or tags it
+//! with the external package it comes from. Rust imports are resolved
+//! through the Cargo workspace ([`cargo`](super::cargo)), Python imports
+//! against the package layout ([`python`](super::python)) and JavaScript and
+//! TypeScript imports the way Node and `tsc` do ([`node`](super::node)).
+
+use super::cargo::CargoWorkspace;
+use super::node::NodeModules;
+use super::python::PythonPackages;
+use crate::{FileSymbols, Import, ImportTarget, Language};
+use std::path::{Component, Path, PathBuf};
+
+/// Project layouts used to resolve imports
+#[derive(Debug, Default)]
+pub struct ImportResolver {
+    cargo: Option<CargoWorkspace>,
+    python: PythonPackages,
+    node: NodeModules,
+}
+
+impl ImportResolver {
+    /// Read the layouts found at the project root
+    pub fn load(root: &Path) -> Self {
+        Self {
+            cargo: CargoWorkspace::load(root),
+            python: PythonPackages::load(root),
+            node: NodeModules::load(root),
+        }
+    }
+
+    /// Fill in [`Import::resolved`](crate::Import::resolved) for the imports
+    /// of a parsed file; imports that cannot be resolved are left alone
+    pub fn resolve(&self, file_symbols: &mut FileSymbols) {
+        let resolve: &dyn Fn(&Import) -> Option<ImportTarget> = match file_symbols.language {
+            Some(Language::Rust) => match &self.cargo {
+                Some(cargo) => &|import| cargo.resolve(import),
+                None => return,
+            },
+            Some(Language::Python) => &|import| self.python.resolve(import),
+            Some(Language::JavaScript | Language::TypeScript) => &|import| self.node.resolve(import),
+            _ => return,
+        };
+        for import in &mut file_symbols.imports {
+            import.resolved = resolve(import);
+        }
+    }
+}
+
+/// Resolve `.` and `..` components of a relative path
+pub(super) fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
@@ -0,0 +1,775 @@
+//! Call resolution
+//!
+//! Picks the most likely target for each call instead of linking it to every
+//! symbol with the same name. Candidates are ranked by the caller's scope,
+//! the receiver expression, the caller file's imports and the package layout.
+//! JSX elements are resolved the same way, against components, and decorator
+//! and type references against the symbols they can name.
+//!
+//! [`implements`] links Go types to the interfaces they satisfy,
+//! [`definitions`] C++ declarations to their definitions, and [`imports`]
+//! import statements to the files and symbols they name.
+
+pub mod cargo;
+pub mod definitions;
+pub mod implements;
+pub mod imports;
+pub mod node;
+pub mod python;
+
+use crate::{Call, FileSymbols, Import, ImportTarget, Reference, ReferenceKind, Render, Symbol, SymbolKind};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Receivers that refer to the caller's own type
+const SELF_RECEIVERS: &[&str] = &["self", "Self", "this", "cls"];
+
+/// A symbol as seen by the resolver
+#[derive(Debug, Clone)]
+pub struct SymbolEntry {
+    pub id: String,
+    pub name: String,
+    pub kind: SymbolKind,
+    pub file_path: PathBuf,
+    pub parent_id: Option<String>,
+}
+
+impl From<&Symbol> for SymbolEntry {
+    fn from(symbol: &Symbol) -> Self {
+        Self {
+            id: symbol.id.clone(),
+            name: symbol.name.clone(),
+            kind: symbol.kind,
+            file_path: symbol.file_path.clone(),
+            parent_id: symbol.parent_id.clone(),
+        }
+    }
+}
+
+/// How a call target was chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Resolution {
+    /// Method on the caller's own type (`self.run()`)
+    SameScope,
+    /// Method on the type named by the receiver (`Config::load()`)
+    Receiver,
+    /// Defined in the caller's file
+    SameFile,
+    /// Defined in a module the caller's file imports
+    Import,
+    /// Defined in the caller's directory or package
+    SamePackage,
+    /// The only symbol with that name in the index
+    Global,
+    /// Several equally likely symbols, linked to a placeholder
+    Ambiguous,
+    /// No symbol with that name, linked to a placeholder
+    Unresolved,
+}
+
+impl Resolution {
+    /// Confidence of a match at this level before splitting between ties
+    pub fn confidence(&self) -> f64 {
+        match self {
+            Resolution::SameScope => 0.95,
+            Resolution::Receiver => 0.9,
+            Resolution::SameFile => 0.9,
+            Resolution::Import => 0.85,
+            Resolution::SamePackage => 0.7,
+            Resolution::Global => 0.5,
+            Resolution::Ambiguous | Resolution::Unresolved => 0.0,
+        }
+    }
+
+    /// Name stored on the `CALLS` edge
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Resolution::SameScope => "same_scope",
+            Resolution::Receiver => "receiver",
+            Resolution::SameFile => "same_file",
+            Resolution::Import => "import",
+            Resolution::SamePackage => "same_package",
+            Resolution::Global => "global",
+            Resolution::Ambiguous => "ambiguous",
+            Resolution::Unresolved => "unresolved",
+        }
+    }
+}
+
+impl std::fmt::Display for Resolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Target of a resolved call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CallTarget {
+    /// ID of the symbol being called
+    Symbol(String),
+    /// Name of a placeholder node for calls that could not be pinned down
+    Placeholder(String),
+}
+
+/// A call with its chosen target
+#[derive(Debug, Clone)]
+pub struct ResolvedCall {
+    pub call: Call,
+    pub target: CallTarget,
+    pub confidence: f64,
+    pub resolution: Resolution,
+}
+
+/// A rendered element with its chosen component
+#[derive(Debug, Clone)]
+pub struct ResolvedRender {
+    pub render: Render,
+    /// ID of the rendered component
+    pub target_id: String,
+    pub confidence: f64,
+    pub resolution: Resolution,
+}
+
+/// A decorator or type reference with its chosen target
+#[derive(Debug, Clone)]
+pub struct ResolvedReference {
+    pub reference: Reference,
+    /// ID of the referenced symbol
+    pub target_id: String,
+    pub confidence: f64,
+    pub resolution: Resolution,
+}
+
+/// Symbol table used to resolve calls to their most likely target
+#[derive(Debug, Default)]
+pub struct CallResolver {
+    symbols: HashMap<String, SymbolEntry>,
+    by_name: HashMap<String, Vec<String>>,
+    imports: HashMap<PathBuf, Vec<Import>>,
+}
+
+impl CallResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the symbols and imports of a parsed file
+    pub fn add_file(&mut self, file_symbols: &FileSymbols) {
+        for symbol in &file_symbols.symbols {
+            self.add_symbol(SymbolEntry::from(symbol));
+        }
+        for import in &file_symbols.imports {
+            self.add_import(import.clone());
+        }
+    }
+
+    /// Add a single symbol
+    pub fn add_symbol(&mut self, entry: SymbolEntry) {
+        if self.symbols.contains_key(&entry.id) {
+            return;
+        }
+        self.by_name
+            .entry(entry.name.clone())
+            .or_default()
+            .push(entry.id.clone());
+        self.symbols.insert(entry.id.clone(), entry);
+    }
+
+    /// Add an import of the file it appears in
+    pub fn add_import(&mut self, import: Import) {
+        self.imports
+            .entry(import.source_file.clone())
+            .or_default()
+            .push(import);
+    }
+
+    /// Look up a known symbol by id
+    pub fn get(&self, id: &str) -> Option<&SymbolEntry> {
+        self.symbols.get(id)
+    }
+
+    /// Number of known symbols
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Resolve a call to its most likely target(s)
+    ///
+    /// Returns one entry per target. Ties at the best level are all kept with
+    /// the confidence split between them. Calls without a usable candidate get
+    /// a single placeholder target.
+    pub fn resolve(&self, call: &Call) -> Vec<ResolvedCall> {
+        self.resolve_among(call, &[SymbolKind::Function, SymbolKind::Method, SymbolKind::Macro])
+    }
+
+    /// Resolve a JSX element to the component(s) it most likely renders
+    ///
+    /// Elements without a usable candidate, such as components from external
+    /// libraries, resolve to nothing.
+    pub fn resolve_render(&self, render: &Render) -> Vec<ResolvedRender> {
+        let call = Call {
+            caller_id: render.component_id.clone(),
+            callee_name: render.element_name.clone(),
+            receiver: render.receiver.clone(),
+            call_site_line: render.line,
+        };
+
+        self.resolve_among(&call, &[SymbolKind::Component])
+            .into_iter()
+            .filter_map(|resolved| match resolved.target {
+                CallTarget::Symbol(target_id) => Some(ResolvedRender {
+                    render: render.clone(),
+                    target_id,
+                    confidence: resolved.confidence,
+                    resolution: resolved.resolution,
+                }),
+                CallTarget::Placeholder(_) => None,
+            })
+            .collect()
+    }
+
+    /// Resolve a decorator or type reference to the symbol(s) it most likely
+    /// names
+    ///
+    /// Decorators resolve to functions, methods and classes, types to
+    /// class-like symbols. References to names defined outside the index,
+    /// such as builtins, resolve to nothing.
+    pub fn resolve_reference(&self, reference: &Reference) -> Vec<ResolvedReference> {
+        let kinds: &[SymbolKind] = match reference.kind {
+            ReferenceKind::DecoratedBy => {
+                &[SymbolKind::Function, SymbolKind::Method, SymbolKind::Class]
+            }
+            ReferenceKind::UsesType => &[
+                SymbolKind::Class,
+                SymbolKind::Struct,
+                SymbolKind::Interface,
+                SymbolKind::Trait,
+                SymbolKind::Enum,
+                SymbolKind::Union,
+                SymbolKind::TypeAlias,
+            ],
+        };
+        let call = Call {
+            caller_id: reference.source_id.clone(),
+            callee_name: reference.target_name.clone(),
+            receiver: reference.receiver.clone(),
+            call_site_line: reference.line,
+        };
+
+        self.resolve_among(&call, kinds)
+            .into_iter()
+            .filter_map(|resolved| match resolved.target {
+                CallTarget::Symbol(target_id) => Some(ResolvedReference {
+                    reference: reference.clone(),
+                    target_id,
+                    confidence: resolved.confidence,
+                    resolution: resolved.resolution,
+                }),
+                CallTarget::Placeholder(_) => None,
+            })
+            .collect()
+    }
+
+    /// Resolve a call against the symbols of the given kinds
+    fn resolve_among(&self, call: &Call, kinds: &[SymbolKind]) -> Vec<ResolvedCall> {
+        let caller = self.symbols.get(&call.caller_id);
+        let caller_file = caller.map(|c| c.file_path.as_path());
+        let imports = caller_file
+            .and_then(|f| self.imports.get(f))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        // Imports resolved to symbols name them under the name the caller
+        // uses (`export { fmt as format }`, `use a::b as c`)
+        if call.receiver.is_none() {
+            let mut seen = HashSet::new();
+            let imported: Vec<&SymbolEntry> = imports
+                .iter()
+                .filter_map(|i| match &i.resolved {
+                    Some(ImportTarget::Project { symbols, .. }) => Some(symbols),
+                    _ => None,
+                })
+                .flatten()
+                .filter(|s| s.name == call.callee_name && seen.insert(&s.id))
+                .filter_map(|s| self.symbols.get(&s.id))
+                .filter(|s| kinds.contains(&s.kind))
+                .collect();
+            if !imported.is_empty() {
+                return resolved(call, imported, Resolution::Import);
+            }
+        }
+
+        let mut candidates = self.named(&call.callee_name, kinds);
+
+        if candidates.is_empty() {
+            // `from x import y as z` makes `z()` a call to `y`
+            let renamed: Vec<&SymbolEntry> = imports
+                .iter()
+                .filter_map(|i| Some((i, renamed_item(i, &call.callee_name)?)))
+                .flat_map(|(i, item)| {
+                    self.named(item, kinds)
+                        .into_iter()
+                        .filter(|s| import_matches_file(i, &s.file_path))
+                })
+                .collect();
+            if !renamed.is_empty() {
+                return resolved(call, renamed, Resolution::Import);
+            }
+            return vec![placeholder(call, Resolution::Unresolved)];
+        }
+
+        if let Some(receiver) = call.receiver.as_deref() {
+            let receiver_name = last_segment(receiver);
+
+            // Type::method() or Type.static_method()
+            let on_type = filter(&candidates, |s| {
+                s.parent_id
+                    .as_ref()
+                    .and_then(|p| self.symbols.get(p))
+                    .is_some_and(|p| p.name == receiver_name)
+            });
+            if !on_type.is_empty() {
+                return resolved(call, on_type, Resolution::Receiver);
+            }
+
+            // self.method() or another instance of the caller's own type
+            if let Some(parent) = caller.and_then(|c| c.parent_id.as_deref()) {
+                let same_scope = filter(&candidates, |s| s.parent_id.as_deref() == Some(parent));
+                if !same_scope.is_empty()
+                    && (SELF_RECEIVERS.contains(&receiver_name) || same_scope.len() == 1)
+                {
+                    return resolved(call, same_scope, Resolution::SameScope);
+                }
+            }
+
+            // module::function(), pkg.Function(), alias.function()
+            let receiver_imports: Vec<&Import> = imports
+                .iter()
+                .filter(|i| {
+                    i.alias.as_deref() == Some(receiver_name)
+                        || last_segment(&i.target) == receiver_name
+                        || i.items.iter().any(|item| last_segment(item) == receiver_name)
+                })
+                .collect();
+            let in_module = filter(&candidates, |s| {
+                module_matches_file(receiver_name, &s.file_path)
+                    || receiver_imports
+                        .iter()
+                        .any(|i| import_matches_file(i, &s.file_path))
+            });
+            if !in_module.is_empty() {
+                return resolved(call, in_module, Resolution::Import);
+            }
+
+            // obj.method() on an unknown type only makes sense for methods
+            if receiver_imports.is_empty() {
+                let methods = filter(&candidates, |s| s.kind == SymbolKind::Method);
+                if !methods.is_empty() {
+                    candidates = methods;
+                }
+            }
+        }
+
+        if let Some(caller_file) = caller_file {
+            let same_file = filter(&candidates, |s| s.file_path == caller_file);
+            if !same_file.is_empty() {
+                return resolved(call, same_file, Resolution::SameFile);
+            }
+
+            let imported = filter(&candidates, |s| {
+                imports.iter().any(|i| {
+                    imports_name(i, &call.callee_name) && import_matches_file(i, &s.file_path)
+                })
+            });
+            if !imported.is_empty() {
+                return resolved(call, imported, Resolution::Import);
+            }
+
+            let caller_dir = caller_file.parent();
+            let same_package = filter(&candidates, |s| s.file_path.parent() == caller_dir);
+            if !same_package.is_empty() {
+                return resolved(call, same_package, Resolution::SamePackage);
+            }
+        }
+
+        if candidates.len() == 1 {
+            return resolved(call, candidates, Resolution::Global);
+        }
+
+        vec![placeholder(call, Resolution::Ambiguous)]
+    }
+
+    /// Known symbols of the given kinds with a name
+    fn named(&self, name: &str, kinds: &[SymbolKind]) -> Vec<&SymbolEntry> {
+        self.by_name
+            .get(name)
+            .into_iter()
+            .flatten()
+            .filter_map(|id| self.symbols.get(id))
+            .filter(|s| kinds.contains(&s.kind))
+            .collect()
+    }
+}
+
+fn filter<'a>(
+    candidates: &[&'a SymbolEntry],
+    predicate: impl Fn(&SymbolEntry) -> bool,
+) -> Vec<&'a SymbolEntry> {
+    candidates.iter().copied().filter(|s| predicate(s)).collect()
+}
+
+fn resolved(call: &Call, targets: Vec<&SymbolEntry>, resolution: Resolution) -> Vec<ResolvedCall> {
+    let confidence = resolution.confidence() / targets.len() as f64;
+    targets
+        .into_iter()
+        .map(|target| ResolvedCall {
+            call: call.clone(),
+            target: CallTarget::Symbol(target.id.clone()),
+            confidence,
+            resolution,
+        })
+        .collect()
+}
+
+fn placeholder(call: &Call, resolution: Resolution) -> ResolvedCall {
+    ResolvedCall {
+        call: call.clone(),
+        target: CallTarget::Placeholder(call.callee_name.clone()),
+        confidence: resolution.confidence(),
+        resolution,
+    }
+}
+
+/// Last segment of a path-like expression (`a::b::C` -> `C`, `x.y` -> `y`)
+fn last_segment(path: &str) -> &str {
+    path.rsplit([':', '.', '/', '>'])
+        .find(|s| !s.is_empty())
+        .unwrap_or(path)
+}
+
+/// Path segments of an import target, ignoring relative markers and globs
+fn target_segments(target: &str) -> Vec<&str> {
+    target
+        .split([':', '.', '/'])
+        .filter(|s| !s.is_empty() && !matches!(*s, "crate" | "self" | "super" | "*"))
+        .collect()
+}
+
+/// Whether an import brings `name` into scope
+///
+/// A plain module import (`import os`, `use a::b`) only names the module;
+/// its functions are reached through a receiver, which is handled
+/// separately. Only globs bring every name of the target into scope.
+fn imports_name(import: &Import, name: &str) -> bool {
+    // A renamed item is only in scope under its alias
+    if let (Some(_), [_]) = (&import.alias, import.items.as_slice()) {
+        return renamed_item(import, name).is_some();
+    }
+    import.items.iter().any(|item| last_segment(item) == name)
+        || last_segment(&import.target) == name
+        || is_glob(import)
+}
+
+/// The item an import renames to `name` (`from x import y as z`)
+fn renamed_item<'a>(import: &'a Import, name: &str) -> Option<&'a str> {
+    match (import.alias.as_deref(), import.items.as_slice()) {
+        (Some(alias), [item]) if alias == name => Some(last_segment(item)),
+        _ => None,
+    }
+}
+
+/// Whether an import brings every public name of its target into scope
+/// (`from a import *`, `use a::*`, `import a.*`, `export * from 'a'`)
+fn is_glob(import: &Import) -> bool {
+    import.items.iter().any(|item| item == "*") || import.target.ends_with('*')
+}
+
+/// Whether a module name refers to a file, by stem or by directory for
+/// `mod.rs`, `__init__.py`, `index.*` and Go packages
+fn module_matches_file(module: &str, file: &Path) -> bool {
+    let stem = file.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    let dir = file
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|s| s.to_str())
+        .unwrap_or("");
+    !module.is_empty() && (module == stem || module == dir)
+}
+
+/// Whether an import points at a file, going by its resolution when it has
+/// one and by its target's segments otherwise
+fn import_matches_file(import: &Import, file: &Path) -> bool {
+    match &import.resolved {
+        Some(ImportTarget::Project { files, symbols }) => {
+            return files.iter().any(|f| f == file) || symbols.iter().any(|s| s.file_path == file);
+        }
+        Some(ImportTarget::External { .. }) => return false,
+        None => {}
+    }
+    let segments = target_segments(&import.target);
+    segments
+        .iter()
+        .rev()
+        .take(2)
+        .any(|segment| module_matches_file(segment, file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ImportedSymbol;
+
+    fn entry(
+        id: &str,
+        name: &str,
+        kind: SymbolKind,
+        file: &str,
+        parent: Option<&str>,
+    ) -> SymbolEntry {
+        SymbolEntry {
+            id: id.to_string(),
+            name: name.to_string(),
+            kind,
+            file_path: PathBuf::from(file),
+            parent_id: parent.map(String::from),
+        }
+    }
+
+    fn call(caller: &str, callee: &str, receiver: Option<&str>) -> Call {
+        Call {
+            caller_id: caller.to_string(),
+            callee_name: callee.to_string(),
+            receiver: receiver.map(String::from),
+            call_site_line: 1,
+        }
+    }
+
+    #[test]
+    fn test_prefers_imported_module_over_global_match() {
+        let mut resolver = CallResolver::new();
+        resolver.add_symbol(entry("main", "main", SymbolKind::Function, "src/main.rs", None));
+        resolver.add_symbol(entry("a", "parse", SymbolKind::Function, "src/config.rs", None));
+        resolver.add_symbol(entry("b", "parse", SymbolKind::Function, "src/query.rs", None));
+        resolver.add_import(Import {
+            source_file: PathBuf::from("src/main.rs"),
+            target: "crate::config".to_string(),
+            alias: None,
+            items: vec!["parse".to_string()],
+            line: 1,
+            resolved: None,
+        });
+
+        let resolved = resolver.resolve(&call("main", "parse", None));
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].target, CallTarget::Symbol("a".to_string()));
+        assert_eq!(resolved[0].resolution, Resolution::Import);
+    }
+
+    #[test]
+    fn test_resolved_import_picks_the_imported_file() {
+        let mut resolver = CallResolver::new();
+        resolver.add_symbol(entry("main", "main", SymbolKind::Function, "src/main.rs", None));
+        resolver.add_symbol(entry("a", "parse", SymbolKind::Function, "src/config/mod.rs", None));
+        resolver.add_symbol(entry("b", "parse", SymbolKind::Function, "src/settings.rs", None));
+        // `config` is loaded from settings.rs through `#[path]`
+        resolver.add_import(Import {
+            source_file: PathBuf::from("src/main.rs"),
+            target: "crate::config".to_string(),
+            alias: None,
+            items: vec!["parse".to_string()],
+            line: 1,
+            resolved: Some(ImportTarget::Project {
+                files: vec![PathBuf::from("src/settings.rs")],
+                symbols: Vec::new(),
+            }),
+        });
+
+        let resolved = resolver.resolve(&call("main", "parse", None));
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].target, CallTarget::Symbol("b".to_string()));
+        assert_eq!(resolved[0].resolution, Resolution::Import);
+    }
+
+    #[test]
+    fn test_module_import_does_not_capture_unrelated_names() {
+        let mut resolver = CallResolver::new();
+        resolver.add_symbol(entry("main", "main", SymbolKind::Function, "app/main.py", None));
+        resolver.add_symbol(entry("a", "helper", SymbolKind::Function, "utils.py", None));
+        resolver.add_symbol(entry("b", "helper", SymbolKind::Function, "app/helpers.py", None));
+        let import = |target: &str, items: &[&str]| Import {
+            source_file: PathBuf::from("app/main.py"),
+            target: target.to_string(),
+            alias: None,
+            items: items.iter().map(|s| s.to_string()).collect(),
+            line: 1,
+            resolved: None,
+        };
+
+        // `import utils` only names the module; `helper()` is not from it
+        resolver.add_import(import("utils", &[]));
+        let resolved = resolver.resolve(&call("main", "helper", None));
+        assert_eq!(resolved[0].target, CallTarget::Symbol("b".to_string()));
+        assert_eq!(resolved[0].resolution, Resolution::SamePackage);
+
+        // `from utils import *` does bring it into scope
+        resolver.add_import(import("utils", &["*"]));
+        let resolved = resolver.resolve(&call("main", "helper", None));
+        assert_eq!(resolved[0].target, CallTarget::Symbol("a".to_string()));
+        assert_eq!(resolved[0].resolution, Resolution::Import);
+    }
+
+    #[test]
+    fn test_renamed_import_resolves_by_alias() {
+        let mut resolver = CallResolver::new();
+        resolver.add_symbol(entry("main", "main", SymbolKind::Function, "app/main.py", None));
+        resolver.add_symbol(entry("a", "h", SymbolKind::Function, "app/helpers.py", None));
+        resolver.add_symbol(entry("b", "h", SymbolKind::Function, "lib/other.py", None));
+        // from .helpers import h as hh
+        resolver.add_import(Import {
+            source_file: PathBuf::from("app/main.py"),
+            target: ".helpers".to_string(),
+            alias: Some("hh".to_string()),
+            items: vec!["h".to_string()],
+            line: 1,
+            resolved: None,
+        });
+
+        let resolved = resolver.resolve(&call("main", "hh", None));
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].target, CallTarget::Symbol("a".to_string()));
+        assert_eq!(resolved[0].resolution, Resolution::Import);
+
+        // The original name is not in scope through the import
+        let resolved = resolver.resolve(&call("main", "h", None));
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].resolution, Resolution::SamePackage);
+    }
+
+    #[test]
+    fn test_imported_symbol_resolves_by_its_local_name() {
+        let mut resolver = CallResolver::new();
+        resolver.add_symbol(entry("main", "main", SymbolKind::Function, "src/main.ts", None));
+        resolver.add_symbol(entry("fmt", "fmt", SymbolKind::Function, "src/utils/fmt.ts", None));
+        resolver.add_symbol(entry("other", "format", SymbolKind::Function, "src/date.ts", None));
+        // import { format } from './utils', re-exported as `export { fmt as format }`
+        resolver.add_import(Import {
+            source_file: PathBuf::from("src/main.ts"),
+            target: "./utils".to_string(),
+            alias: None,
+            items: vec!["format".to_string()],
+            line: 1,
+            resolved: Some(ImportTarget::Project {
+                files: vec![PathBuf::from("src/utils/index.ts"), PathBuf::from("src/utils/fmt.ts")],
+                symbols: vec![ImportedSymbol {
+                    name: "format".to_string(),
+                    id: "fmt".to_string(),
+                    file_path: PathBuf::from("src/utils/fmt.ts"),
+                }],
+            }),
+        });
+
+        let resolved = resolver.resolve(&call("main", "format", None));
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].target, CallTarget::Symbol("fmt".to_string()));
+        assert_eq!(resolved[0].resolution, Resolution::Import);
+
+        // `date.format()` is not a call to the imported name
+        let resolved = resolver.resolve(&call("main", "format", Some("date")));
+        assert_eq!(resolved[0].target, CallTarget::Symbol("other".to_string()));
+    }
+
+    #[test]
+    fn test_self_receiver_resolves_to_own_type() {
+        let mut resolver = CallResolver::new();
+        resolver.add_symbol(entry("T", "Runner", SymbolKind::Struct, "a.rs", None));
+        resolver.add_symbol(entry("U", "Other", SymbolKind::Struct, "b.rs", None));
+        resolver.add_symbol(entry("T.go", "go", SymbolKind::Method, "a.rs", Some("T")));
+        resolver.add_symbol(entry("T.run", "run", SymbolKind::Method, "a.rs", Some("T")));
+        resolver.add_symbol(entry("U.run", "run", SymbolKind::Method, "b.rs", Some("U")));
+
+        let resolved = resolver.resolve(&call("T.go", "run", Some("self")));
+        assert_eq!(resolved[0].target, CallTarget::Symbol("T.run".to_string()));
+        assert_eq!(resolved[0].resolution, Resolution::SameScope);
+
+        let resolved = resolver.resolve(&call("T.go", "run", Some("Other")));
+        assert_eq!(resolved[0].target, CallTarget::Symbol("U.run".to_string()));
+        assert_eq!(resolved[0].resolution, Resolution::Receiver);
+    }
+
+    #[test]
+    fn test_unknown_and_ambiguous_calls_use_placeholders() {
+        let mut resolver = CallResolver::new();
+        resolver.add_symbol(entry("main", "main", SymbolKind::Function, "app/main.py", None));
+        resolver.add_symbol(entry("a", "get", SymbolKind::Function, "lib/a.py", None));
+        resolver.add_symbol(entry("b", "get", SymbolKind::Function, "lib/b.py", None));
+
+        let resolved = resolver.resolve(&call("main", "missing", None));
+        assert_eq!(resolved[0].resolution, Resolution::Unresolved);
+        assert_eq!(resolved[0].target, CallTarget::Placeholder("missing".to_string()));
+
+        let resolved = resolver.resolve(&call("main", "get", None));
+        assert_eq!(resolved[0].resolution, Resolution::Ambiguous);
+    }
+
+    #[test]
+    fn test_renders_resolve_to_components_only() {
+        let mut resolver = CallResolver::new();
+        resolver.add_symbol(entry("App", "App", SymbolKind::Component, "src/App.tsx", None));
+        resolver.add_symbol(entry("a", "Header", SymbolKind::Component, "src/App.tsx", None));
+        resolver.add_symbol(entry("b", "Header", SymbolKind::Component, "src/admin/Header.tsx", None));
+        resolver.add_symbol(entry("c", "Footer", SymbolKind::Function, "src/Footer.ts", None));
+
+        let render = |name: &str| Render {
+            component_id: "App".to_string(),
+            element_name: name.to_string(),
+            receiver: None,
+            line: 3,
+        };
+
+        let resolved = resolver.resolve_render(&render("Header"));
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].target_id, "a");
+        assert_eq!(resolved[0].resolution, Resolution::SameFile);
+
+        assert!(resolver.resolve_render(&render("Footer")).is_empty());
+        assert!(resolver.resolve_render(&render("Router")).is_empty());
+    }
+
+    #[test]
+    fn test_references_resolve_by_kind() {
+        let mut resolver = CallResolver::new();
+        resolver.add_symbol(entry("save", "save", SymbolKind::Function, "app/views.py", None));
+        resolver.add_symbol(entry("User", "User", SymbolKind::Class, "app/models.py", None));
+        resolver.add_symbol(entry("cached", "cached", SymbolKind::Function, "app/cache.py", None));
+        resolver.add_symbol(entry("config", "Config", SymbolKind::Function, "app/conf.py", None));
+
+        let reference = |kind, name: &str, receiver: Option<&str>| Reference {
+            kind,
+            source_id: "save".to_string(),
+            target_name: name.to_string(),
+            receiver: receiver.map(String::from),
+            line: 1,
+        };
+
+        let user = reference(ReferenceKind::UsesType, "User", Some("models"));
+        let resolved = resolver.resolve_reference(&user);
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].target_id, "User");
+        assert_eq!(resolved[0].resolution, Resolution::Import);
+
+        let cached = reference(ReferenceKind::DecoratedBy, "cached", None);
+        assert_eq!(resolver.resolve_reference(&cached)[0].target_id, "cached");
+
+        // Functions are not types, and builtins are not indexed
+        let config = reference(ReferenceKind::UsesType, "Config", None);
+        assert!(resolver.resolve_reference(&config).is_empty());
+        let int = reference(ReferenceKind::UsesType, "int", None);
+        assert!(resolver.resolve_reference(&int).is_empty());
+    }
+}
@@ -0,0 +1,1056 @@
+//! JavaScript and TypeScript modules
+//!
+//! Import specifiers are resolved the way Node and the TypeScript compiler
+//! do: relative paths are probed with each source extension and as
+//! directories with an `index` file, `tsconfig.json` (or `jsconfig.json`)
+//! `paths` aliases and `baseUrl` map bare specifiers into the project, and
+//! the packages of an npm, Yarn or pnpm workspace are entered through their
+//! `package.json` `exports`, `module` or `main` fields.
+//!
+//! Names imported from a module are looked up in its exports and followed
+//! through `export { ... } from` and `export * from` re-exports. Modules are
+//! parsed the first time a name is looked up in them.
+//!
+//! Packages that are not part of the project are tagged with the version
+//! installed in `node_modules` or locked in `package-lock.json` or
+//! `yarn.lock`, and Node's built-in modules with the `node` package.
+
+use super::imports::normalize_path;
+use crate::parser::extractor::{field_text, find_child, node_text};
+use crate::parser::languages::exports::string_value;
+use crate::parser::pool::ParserPool;
+use crate::{Import, ImportTarget, ImportedSymbol, Language, Symbol};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use tracing::warn;
+use tree_sitter::Node;
+
+/// Extensions probed for a specifier without one, in order
+const EXTENSIONS: [&str; 7] = ["ts", "tsx", "d.ts", "js", "jsx", "mjs", "cjs"];
+
+/// Conditions of a `package.json` `exports` map, in order of preference;
+/// `source` points at the sources of packages that export built files
+const CONDITIONS: [&str; 7] = [
+    "source", "import", "module", "default", "require", "node", "types",
+];
+
+/// Maximum number of re-exports followed while resolving one name
+const MAX_REEXPORTS: usize = 8;
+
+/// Node's built-in modules
+const BUILTIN_MODULES: [&str; 40] = [
+    "assert", "async_hooks", "buffer", "child_process", "cluster", "console", "constants",
+    "crypto", "dgram", "diagnostics_channel", "dns", "domain", "events", "fs", "http", "http2",
+    "https", "inspector", "module", "net", "os", "path", "perf_hooks", "process", "punycode",
+    "querystring", "readline", "repl", "stream", "string_decoder", "sys", "timers", "tls",
+    "trace_events", "tty", "url", "util", "v8", "vm", "zlib",
+];
+
+/// The modules of a JavaScript or TypeScript project
+#[derive(Debug, Default)]
+pub struct NodeModules {
+    root: PathBuf,
+    /// Compiler options by the directory of their `tsconfig.json`, deepest
+    /// first
+    configs: Vec<(PathBuf, TsConfig)>,
+    /// Workspace packages by package name
+    packages: HashMap<String, Package>,
+    /// Versions of the packages the project depends on, when known
+    dependencies: HashMap<String, Option<String>>,
+    /// Exports of the modules looked into so far, by file
+    scopes: RwLock<HashMap<PathBuf, Arc<ModuleExports>>>,
+}
+
+/// Module resolution options of a `tsconfig.json`
+#[derive(Debug, Default, Clone)]
+struct TsConfig {
+    base_url: Option<PathBuf>,
+    /// `paths` patterns with their targets, longest prefix first
+    paths: Vec<(String, Vec<String>)>,
+    /// Directory `paths` targets are relative to
+    paths_base: PathBuf,
+}
+
+/// A package of the workspace
+#[derive(Debug)]
+struct Package {
+    dir: PathBuf,
+    manifest: Value,
+}
+
+/// Names a module exports
+#[derive(Debug, Default)]
+struct ModuleExports {
+    /// Local names by exported name
+    names: HashMap<String, String>,
+    /// Local name of the default export
+    default: Option<String>,
+    /// Names re-exported from other modules: the specifier and the name it
+    /// exports them as, `*` for a namespace
+    reexports: HashMap<String, (String, String)>,
+    /// Specifiers of `export * from` statements
+    stars: Vec<String>,
+}
+
+/// What a name imported from a module refers to
+enum Target {
+    Module(PathBuf),
+    Symbol { file: PathBuf, name: String },
+}
+
+impl NodeModules {
+    /// Read the project's `package.json`, workspace packages and
+    /// `tsconfig.json` files
+    pub fn load(root: &Path) -> Self {
+        let mut modules = Self {
+            root: root.to_path_buf(),
+            ..Default::default()
+        };
+
+        let manifest = read_json(&root.join("package.json"));
+        let mut package_dirs = vec![PathBuf::new()];
+        if let Some(manifest) = &manifest {
+            let patterns = match manifest.get("workspaces") {
+                Some(Value::Array(patterns)) => patterns.clone(),
+                Some(workspaces) => workspaces
+                    .get("packages")
+                    .and_then(Value::as_array)
+                    .cloned()
+                    .unwrap_or_default(),
+                None => Vec::new(),
+            };
+            package_dirs.extend(
+                patterns
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .flat_map(|pattern| expand_workspace(root, pattern)),
+            );
+        }
+        package_dirs.extend(
+            pnpm_workspace(root)
+                .iter()
+                .flat_map(|pattern| expand_workspace(root, pattern)),
+        );
+
+        let mut dependencies = HashSet::new();
+        let mut seen = HashSet::new();
+        for dir in package_dirs {
+            if !seen.insert(dir.clone()) {
+                continue;
+            }
+            for config in ["tsconfig.json", "jsconfig.json"] {
+                if let Some(config) = read_tsconfig(root, &dir.join(config), 0) {
+                    modules.configs.push((dir.clone(), config));
+                    break;
+                }
+            }
+            let manifest = match dir.as_os_str().is_empty() {
+                true => manifest.clone(),
+                false => read_json(&root.join(&dir).join("package.json")),
+            };
+            let Some(manifest) = manifest else {
+                continue;
+            };
+            for field in ["dependencies", "devDependencies", "peerDependencies"] {
+                if let Some(deps) = manifest.get(field).and_then(Value::as_object) {
+                    dependencies.extend(deps.keys().cloned());
+                }
+            }
+            if !dir.as_os_str().is_empty()
+                && let Some(name) = manifest.get("name").and_then(Value::as_str)
+            {
+                modules
+                    .packages
+                    .insert(name.to_string(), Package { dir, manifest });
+            }
+        }
+        modules
+            .configs
+            .sort_by_key(|(dir, _)| std::cmp::Reverse(dir.components().count()));
+
+        let locked = locked_versions(root);
+        modules.dependencies = dependencies
+            .into_iter()
+            .filter(|name| !modules.packages.contains_key(name))
+            .map(|name| {
+                let installed = read_json(&root.join("node_modules").join(&name).join("package.json"))
+                    .and_then(|m| m.get("version").and_then(Value::as_str).map(str::to_string));
+                let version = installed.or_else(|| locked.get(&name).cloned());
+                (name, version)
+            })
+            .collect();
+        modules
+    }
+
+    /// Resolve an `import`, `require` or `export ... from` of a file
+    ///
+    /// Returns `None` for specifiers that lead to no file and name no known
+    /// package.
+    pub fn resolve(&self, import: &Import) -> Option<ImportTarget> {
+        let file = match self.resolve_specifier(&import.source_file, &import.target) {
+            Some(file) => file,
+            None => return self.external(&import.target),
+        };
+
+        let mut files = vec![file.clone()];
+        let mut symbols = Vec::new();
+        for item in import.items.iter().filter(|i| *i != "*") {
+            // A default import has `default` as item and its local name as
+            // alias, like a renamed specifier
+            match self.resolve_name(&file, item, 0) {
+                Some(Target::Module(module)) => files.push(module),
+                Some(Target::Symbol { file, name }) => {
+                    symbols.push(ImportedSymbol {
+                        name: import.alias.clone().unwrap_or_else(|| item.clone()),
+                        id: Symbol::generate_id(&file, &name),
+                        file_path: file.clone(),
+                    });
+                    files.push(file);
+                }
+                None => {}
+            }
+        }
+
+        let mut seen = HashSet::new();
+        files.retain(|f| *f != import.source_file && seen.insert(f.clone()));
+        symbols.retain(|s| s.file_path != import.source_file);
+        Some(ImportTarget::Project { files, symbols })
+    }
+
+    /// The project file a specifier imported by `from` leads to
+    fn resolve_specifier(&self, from: &Path, specifier: &str) -> Option<PathBuf> {
+        if specifier.is_empty() {
+            return None;
+        }
+        if specifier.starts_with("./") || specifier.starts_with("../") || specifier == "." || specifier == ".." {
+            let dir = from.parent().unwrap_or(Path::new(""));
+            return self.probe(&normalize_path(&dir.join(specifier)));
+        }
+        if let Some(absolute) = specifier.strip_prefix('/') {
+            return self.probe(&normalize_path(Path::new(absolute)));
+        }
+
+        if let Some((_, config)) = self.configs.iter().find(|(dir, _)| from.starts_with(dir)) {
+            for (pattern, targets) in &config.paths {
+                let Some(matched) = match_pattern(pattern, specifier) else {
+                    continue;
+                };
+                for target in targets {
+                    let path = config.paths_base.join(target.replacen('*', matched, 1));
+                    if let Some(file) = self.probe(&normalize_path(&path)) {
+                        return Some(file);
+                    }
+                }
+            }
+            if let Some(base_url) = &config.base_url
+                && let Some(file) = self.probe(&normalize_path(&base_url.join(specifier)))
+            {
+                return Some(file);
+            }
+        }
+
+        let (name, subpath) = package_name(specifier);
+        let package = self.packages.get(name)?;
+        self.package_entry(package, &subpath)
+    }
+
+    /// The file a workspace package exposes at a subpath (`.` for the
+    /// package itself)
+    fn package_entry(&self, package: &Package, subpath: &str) -> Option<PathBuf> {
+        if let Some(exports) = package.manifest.get("exports") {
+            // A string or a conditions object maps the package root only
+            let is_subpath_map = exports
+                .as_object()
+                .is_some_and(|map| map.keys().any(|key| key.starts_with('.')));
+            let target = if is_subpath_map {
+                exports.as_object().and_then(|map| {
+                    map.get(subpath).cloned().or_else(|| {
+                        map.iter().find_map(|(pattern, target)| {
+                            let matched = match_pattern(pattern, subpath)?;
+                            Some(replace_star(target, matched))
+                        })
+                    })
+                })
+            } else {
+                (subpath == ".").then(|| exports.clone())
+            };
+            if let Some(file) = target.and_then(|t| self.export_target(&package.dir, &t)) {
+                return Some(file);
+            }
+        }
+
+        if subpath != "." {
+            return self.probe(&normalize_path(&package.dir.join(subpath)));
+        }
+        for field in ["source", "module", "main", "types"] {
+            if let Some(entry) = package.manifest.get(field).and_then(Value::as_str)
+                && let Some(file) = self.probe(&normalize_path(&package.dir.join(entry)))
+            {
+                return Some(file);
+            }
+        }
+        self.probe(&package.dir.join("index"))
+    }
+
+    /// The first existing file among the targets of an `exports` entry,
+    /// trying conditions in order of preference
+    fn export_target(&self, dir: &Path, target: &Value) -> Option<PathBuf> {
+        match target {
+            Value::String(path) => self.probe(&normalize_path(&dir.join(path))),
+            Value::Array(targets) => targets.iter().find_map(|t| self.export_target(dir, t)),
+            Value::Object(conditions) => CONDITIONS
+                .iter()
+                .filter_map(|condition| conditions.get(*condition))
+                .find_map(|t| self.export_target(dir, t)),
+            _ => None,
+        }
+    }
+
+    /// The file a path names: the path itself, the path with a source
+    /// extension, a TypeScript file behind a `.js` extension, or the
+    /// directory's `index` file
+    fn probe(&self, path: &Path) -> Option<PathBuf> {
+        let is_file = |p: &Path| self.root.join(p).is_file();
+        if is_file(path) {
+            return Some(path.to_path_buf());
+        }
+
+        let text = path.to_string_lossy();
+        // TypeScript sources are imported with the extension they compile to
+        let compiled: [(&str, &[&str]); 4] = [
+            (".js", &["ts", "tsx"]),
+            (".jsx", &["tsx"]),
+            (".mjs", &["mts"]),
+            (".cjs", &["cts"]),
+        ];
+        for (extension, sources) in compiled {
+            if let Some(stem) = text.strip_suffix(extension) {
+                for source in sources {
+                    let candidate = PathBuf::from(format!("{}.{}", stem, source));
+                    if is_file(&candidate) {
+                        return Some(candidate);
+                    }
+                }
+            }
+        }
+
+        for extension in EXTENSIONS {
+            let candidate = PathBuf::from(format!("{}.{}", text, extension));
+            if is_file(&candidate) {
+                return Some(candidate);
+            }
+        }
+        EXTENSIONS
+            .iter()
+            .map(|extension| path.join(format!("index.{}", extension)))
+            .find(|candidate| is_file(candidate))
+    }
+
+    /// Node's built-in modules and the packages the project depends on
+    fn external(&self, specifier: &str) -> Option<ImportTarget> {
+        let builtin = specifier.strip_prefix("node:").unwrap_or(specifier);
+        let builtin = builtin.split('/').next().unwrap_or_default();
+        if specifier.starts_with("node:") || BUILTIN_MODULES.contains(&builtin) {
+            return Some(ImportTarget::External {
+                package: "node".to_string(),
+                version: None,
+            });
+        }
+        let (name, _) = package_name(specifier);
+        self.dependencies
+            .get(name)
+            .map(|version| ImportTarget::External {
+                package: name.to_string(),
+                version: version.clone(),
+            })
+    }
+
+    /// What a name exported by a module refers to, following re-exports
+    fn resolve_name(&self, file: &Path, name: &str, depth: usize) -> Option<Target> {
+        let exports = self.exports(file);
+        let local = match name {
+            "default" => exports.default.as_ref(),
+            _ => exports.names.get(name),
+        };
+        if let Some(local) = local {
+            return Some(Target::Symbol {
+                file: file.to_path_buf(),
+                name: local.clone(),
+            });
+        }
+        if depth >= MAX_REEXPORTS {
+            return None;
+        }
+
+        if let Some((specifier, original)) = exports.reexports.get(name) {
+            let source = self.resolve_specifier(file, specifier)?;
+            return match original.as_str() {
+                "*" => Some(Target::Module(source)),
+                original => self.resolve_name(&source, original, depth + 1),
+            };
+        }
+
+        // `export * from` does not re-export the default export
+        if name == "default" {
+            return None;
+        }
+        exports.stars.iter().find_map(|specifier| {
+            let source = self.resolve_specifier(file, specifier)?;
+            self.resolve_name(&source, name, depth + 1)
+        })
+    }
+
+    /// The exports of a module file, parsed on first use
+    fn exports(&self, file: &Path) -> Arc<ModuleExports> {
+        if let Some(exports) = self.scopes.read().ok().and_then(|s| s.get(file).cloned()) {
+            return exports;
+        }
+
+        let mut exports = ModuleExports::default();
+        let language = Language::from_path(file).unwrap_or(Language::TypeScript);
+        match std::fs::read(self.root.join(file)) {
+            Ok(source) => match ParserPool::parse_file(language, file, &source) {
+                Ok(tree) => exports.add_statements(tree.root_node(), &source),
+                Err(e) => warn!("Failed to parse {:?}: {}", file, e),
+            },
+            Err(e) => warn!("Failed to read {:?}: {}", file, e),
+        }
+
+        let exports = Arc::new(exports);
+        if let Ok(mut scopes) = self.scopes.write() {
+            scopes.insert(file.to_path_buf(), Arc::clone(&exports));
+        }
+        exports
+    }
+}
+
+impl ModuleExports {
+    /// Record the exports of a module's top-level statements
+    fn add_statements(&mut self, program: Node, source: &[u8]) {
+        let mut cursor = program.walk();
+        for statement in program.named_children(&mut cursor) {
+            match statement.kind() {
+                "export_statement" => self.add_export(statement, source),
+                // module.exports = { a, b }, exports.name = value
+                "expression_statement" => {
+                    if let Some(assignment) = statement.named_child(0)
+                        && assignment.kind() == "assignment_expression"
+                        && let Some(left) = assignment.child_by_field_name("left")
+                    {
+                        let target = node_text(left, source);
+                        if let Some(name) = target
+                            .strip_prefix("module.exports.")
+                            .or_else(|| target.strip_prefix("exports."))
+                        {
+                            self.names.insert(name.to_string(), name.to_string());
+                        } else if target == "module.exports"
+                            && let Some(right) = assignment.child_by_field_name("right")
+                        {
+                            self.add_commonjs(right, source);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn add_export(&mut self, statement: Node, source: &[u8]) {
+        let specifier = statement
+            .child_by_field_name("source")
+            .map(|s| string_value(s, source));
+        let is_default = {
+            let mut cursor = statement.walk();
+            statement.children(&mut cursor).any(|c| c.kind() == "default")
+        };
+
+        if let Some(clause) = find_child(statement, "export_clause") {
+            let mut cursor = clause.walk();
+            for export in clause.named_children(&mut cursor) {
+                let Some(name) = field_text(export, "name", source) else {
+                    continue;
+                };
+                let exported = field_text(export, "alias", source).unwrap_or_else(|| name.clone());
+                match &specifier {
+                    Some(specifier) => {
+                        self.reexports
+                            .insert(exported, (specifier.clone(), name));
+                    }
+                    None if exported == "default" => self.default = Some(name),
+                    None => {
+                        self.names.insert(exported, name);
+                    }
+                }
+            }
+            return;
+        }
+
+        if let Some(specifier) = specifier {
+            match find_child(statement, "namespace_export").and_then(|n| n.named_child(0)) {
+                Some(namespace) => {
+                    self.reexports
+                        .insert(string_value(namespace, source), (specifier, "*".to_string()));
+                }
+                None => self.stars.push(specifier),
+            }
+            return;
+        }
+
+        if let Some(declaration) = statement.child_by_field_name("declaration") {
+            let names = declared_names(declaration, source);
+            if is_default {
+                self.default = names.into_iter().next();
+            } else {
+                self.names.extend(names.into_iter().map(|n| (n.clone(), n)));
+            }
+        } else if is_default
+            && let Some(value) = statement.child_by_field_name("value")
+        {
+            // export default Button, export default function Button() {}
+            self.default = match value.kind() {
+                "identifier" => Some(node_text(value, source).to_string()),
+                _ => field_text(value, "name", source),
+            };
+        }
+    }
+
+    /// Names exported by `module.exports = value`
+    fn add_commonjs(&mut self, value: Node, source: &[u8]) {
+        match value.kind() {
+            "identifier" => self.default = Some(node_text(value, source).to_string()),
+            "object" => {
+                let mut cursor = value.walk();
+                for property in value.named_children(&mut cursor) {
+                    match property.kind() {
+                        "shorthand_property_identifier" => {
+                            let name = node_text(property, source).to_string();
+                            self.names.insert(name.clone(), name);
+                        }
+                        "pair" => {
+                            if let (Some(key), Some(local)) = (
+                                field_text(property, "key", source),
+                                property
+                                    .child_by_field_name("value")
+                                    .filter(|v| v.kind() == "identifier"),
+                            ) {
+                                self.names
+                                    .insert(key, node_text(local, source).to_string());
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Names a declaration introduces: its own name, or each variable of a
+/// `const`/`let`/`var` declaration
+fn declared_names(declaration: Node, source: &[u8]) -> Vec<String> {
+    match declaration.kind() {
+        "lexical_declaration" | "variable_declaration" => {
+            let mut cursor = declaration.walk();
+            declaration
+                .named_children(&mut cursor)
+                .filter(|d| d.kind() == "variable_declarator")
+                .filter_map(|d| d.child_by_field_name("name"))
+                .filter(|n| n.kind() == "identifier")
+                .map(|n| node_text(n, source).to_string())
+                .collect()
+        }
+        _ => field_text(declaration, "name", source).into_iter().collect(),
+    }
+}
+
+/// Package name and `exports` subpath of a bare specifier: `@scope/pkg/a/b`
+/// is `@scope/pkg` and `./a/b`
+fn package_name(specifier: &str) -> (&str, String) {
+    let segments = if specifier.starts_with('@') { 2 } else { 1 };
+    match specifier.match_indices('/').nth(segments - 1) {
+        Some((i, _)) => (&specifier[..i], format!(".{}", &specifier[i..])),
+        None => (specifier, ".".to_string()),
+    }
+}
+
+/// The text matched by the `*` of a pattern (`@/*`, `./features/*`); a
+/// pattern without one matches only itself
+fn match_pattern<'a>(pattern: &str, text: &'a str) -> Option<&'a str> {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => text
+            .strip_prefix(prefix)?
+            .strip_suffix(suffix),
+        None => (pattern == text).then_some(""),
+    }
+}
+
+/// An `exports` target with `*` replaced by the matched subpath
+fn replace_star(target: &Value, matched: &str) -> Value {
+    match target {
+        Value::String(path) => Value::String(path.replace('*', matched)),
+        Value::Array(targets) => Value::Array(targets.iter().map(|t| replace_star(t, matched)).collect()),
+        Value::Object(conditions) => Value::Object(
+            conditions
+                .iter()
+                .map(|(k, v)| (k.clone(), replace_star(v, matched)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// A JSON file
+fn read_json(path: &Path) -> Option<Value> {
+    let content = std::fs::read_to_string(path).ok()?;
+    parse_json(path, &content)
+}
+
+/// A JSON file read leniently: `tsconfig.json` allows comments and
+/// trailing commas
+fn read_jsonc(path: &Path) -> Option<Value> {
+    let content = std::fs::read_to_string(path).ok()?;
+    parse_json(path, &strip_jsonc(&content))
+}
+
+fn parse_json(path: &Path, content: &str) -> Option<Value> {
+    match serde_json::from_str(content) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            warn!("Failed to parse {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// JSON text without `//` and `/* */` comments and trailing commas
+fn strip_jsonc(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                while chars.next_if(|c| *c != '\n').is_some() {}
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            (',', _) => {
+                // Drop the comma when only whitespace separates it from a
+                // closing bracket
+                let next = chars.clone().find(|c| !c.is_whitespace());
+                if !matches!(next, Some('}') | Some(']')) {
+                    out.push(c);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// The module resolution options of a `tsconfig.json`, relative to the
+/// project root, merged over those of the config it `extends`
+fn read_tsconfig(root: &Path, path: &Path, depth: usize) -> Option<TsConfig> {
+    let config = read_jsonc(&root.join(path))?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+
+    // Only configs in the project are followed, not shared configs
+    // installed as packages
+    let mut merged = config
+        .get("extends")
+        .and_then(Value::as_str)
+        .filter(|extends| extends.starts_with('.') && depth < MAX_REEXPORTS)
+        .and_then(|extends| {
+            let mut path = normalize_path(&dir.join(extends));
+            if path.extension().is_none() {
+                path.set_extension("json");
+            }
+            read_tsconfig(root, &path, depth + 1)
+        })
+        .unwrap_or_else(|| TsConfig {
+            paths_base: dir.to_path_buf(),
+            ..Default::default()
+        });
+
+    let options = config.get("compilerOptions");
+    if let Some(base_url) = options
+        .and_then(|o| o.get("baseUrl"))
+        .and_then(Value::as_str)
+    {
+        let base_url = normalize_path(&dir.join(base_url));
+        merged.paths_base = base_url.clone();
+        merged.base_url = Some(base_url);
+    }
+    if let Some(paths) = options
+        .and_then(|o| o.get("paths"))
+        .and_then(Value::as_object)
+    {
+        // Without a baseUrl, paths are relative to the config declaring them
+        if merged.base_url.is_none() {
+            merged.paths_base = dir.to_path_buf();
+        }
+        merged.paths = paths
+            .iter()
+            .map(|(pattern, targets)| {
+                let targets = targets
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect();
+                (pattern.clone(), targets)
+            })
+            .collect();
+        merged.paths.sort_by_key(|(pattern, _)| {
+            std::cmp::Reverse(pattern.split('*').next().unwrap_or_default().len())
+        });
+    }
+    Some(merged)
+}
+
+/// The package directories a workspace pattern (`packages/*`, `apps/web`)
+/// names, relative to the project root
+fn expand_workspace(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let pattern = pattern.trim_start_matches("./");
+    if pattern.starts_with('!') {
+        return Vec::new();
+    }
+    let Some(parent) = pattern
+        .strip_suffix("/**")
+        .or_else(|| pattern.strip_suffix("/*"))
+    else {
+        return vec![normalize_path(Path::new(pattern))];
+    };
+    let Ok(entries) = std::fs::read_dir(root.join(parent)) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| entry.path().join("package.json").is_file())
+        .map(|entry| Path::new(parent).join(entry.file_name()))
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// The `packages` patterns of a `pnpm-workspace.yaml`
+fn pnpm_workspace(root: &Path) -> Vec<String> {
+    let Ok(content) = std::fs::read_to_string(root.join("pnpm-workspace.yaml")) else {
+        return Vec::new();
+    };
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if !line.starts_with([' ', '\t', '-']) && !trimmed.is_empty() {
+            in_packages = trimmed.starts_with("packages:");
+        } else if in_packages && let Some(item) = trimmed.strip_prefix('-') {
+            patterns.push(item.trim().trim_matches(|c| c == '"' || c == '\'').to_string());
+        }
+    }
+    patterns
+}
+
+/// Package versions locked in `package-lock.json` or `yarn.lock`
+fn locked_versions(root: &Path) -> HashMap<String, String> {
+    let mut versions = HashMap::new();
+    if let Some(packages) = read_json(&root.join("package-lock.json"))
+        .as_ref()
+        .and_then(|lock| lock.get("packages"))
+        .and_then(Value::as_object)
+    {
+        for (path, package) in packages {
+            // Only top-level installs, not packages nested in another's
+            // node_modules
+            let Some(name) = path.strip_prefix("node_modules/") else {
+                continue;
+            };
+            if !name.contains("/node_modules/")
+                && let Some(version) = package.get("version").and_then(Value::as_str)
+            {
+                versions.insert(name.to_string(), version.to_string());
+            }
+        }
+    }
+
+    // "lodash@^4.17.0", "@scope/pkg@^1.0.0":
+    //   version "4.17.21"
+    if let Ok(content) = std::fs::read_to_string(root.join("yarn.lock")) {
+        let mut names = Vec::new();
+        for line in content.lines() {
+            if !line.starts_with([' ', '#']) && line.ends_with(':') {
+                names = line
+                    .trim_end_matches(':')
+                    .split(", ")
+                    .filter_map(|entry| {
+                        let entry = entry.trim_matches('"');
+                        let at = entry[1..].find('@')? + 1;
+                        Some(entry[..at].to_string())
+                    })
+                    .collect();
+            } else if let Some(version) = line.trim().strip_prefix("version ") {
+                let version = version.trim_matches('"');
+                for name in names.drain(..) {
+                    versions.entry(name).or_insert_with(|| version.to_string());
+                }
+            }
+        }
+    }
+    versions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn import(file: &str, target: &str, items: &[&str]) -> Import {
+        Import {
+            source_file: PathBuf::from(file),
+            target: target.to_string(),
+            alias: None,
+            items: items.iter().map(|s| s.to_string()).collect(),
+            line: 1,
+            resolved: None,
+        }
+    }
+
+    fn project(target: Option<ImportTarget>) -> (Vec<PathBuf>, Vec<String>) {
+        match target {
+            Some(ImportTarget::Project { files, symbols }) => {
+                (files, symbols.into_iter().map(|s| s.id).collect())
+            }
+            other => panic!("expected a project import, got {:?}", other),
+        }
+    }
+
+    /// A pnpm-style workspace with an app using a `@/*` alias, a library
+    /// package entered through `exports`, and one locked dependency
+    fn project_dir() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "package.json",
+            r#"{ "name": "monorepo", "workspaces": ["packages/*"], "dependencies": { "react": "^18.0.0" } }"#,
+        );
+        write(
+            root,
+            "package-lock.json",
+            r#"{ "packages": { "node_modules/react": { "version": "18.3.1" } } }"#,
+        );
+        write(
+            root,
+            "packages/app/package.json",
+            r#"{ "name": "@acme/app", "dependencies": { "@acme/ui": "workspace:*" } }"#,
+        );
+        write(
+            root,
+            "packages/app/tsconfig.json",
+            "{\n  // aliases\n  \"compilerOptions\": {\n    \"baseUrl\": \".\",\n    \"paths\": { \"@/*\": [\"src/*\"], },\n  },\n}\n",
+        );
+        write(
+            root,
+            "packages/app/src/components/Button.tsx",
+            "export default function Button() { return null; }\nexport const SIZES = [1, 2];\n",
+        );
+        write(
+            root,
+            "packages/app/src/utils/index.ts",
+            "export function formatDate() {}\n",
+        );
+        write(root, "packages/app/src/main.ts", "");
+        write(
+            root,
+            "packages/ui/package.json",
+            r#"{ "name": "@acme/ui", "main": "dist/index.js", "exports": { ".": { "import": "./src/index.ts" }, "./icons/*": "./src/icons/*.ts" } }"#,
+        );
+        write(
+            root,
+            "packages/ui/src/index.ts",
+            "export * from './theme';\nexport { Card as Panel } from './card.js';\n",
+        );
+        write(root, "packages/ui/src/theme.ts", "export const theme = {};\n");
+        write(root, "packages/ui/src/card.ts", "export class Card {}\n");
+        write(root, "packages/ui/src/icons/star.ts", "export const Star = 1;\n");
+        dir
+    }
+
+    #[test]
+    fn test_resolves_relative_imports() {
+        let dir = project_dir();
+        let modules = NodeModules::load(dir.path());
+
+        let (files, symbols) = project(modules.resolve(&import(
+            "packages/app/src/main.ts",
+            "./components/Button",
+            &["SIZES"],
+        )));
+        assert_eq!(files, vec![PathBuf::from("packages/app/src/components/Button.tsx")]);
+        assert_eq!(
+            symbols,
+            vec!["packages/app/src/components/Button.tsx::SIZES".to_string()]
+        );
+
+        // import Btn from './components/Button'
+        let default = Import {
+            alias: Some("Btn".to_string()),
+            ..import("packages/app/src/main.ts", "./components/Button", &["default"])
+        };
+        let Some(ImportTarget::Project { symbols, .. }) = modules.resolve(&default) else {
+            panic!("expected a project import");
+        };
+        let symbols: Vec<(&str, &str)> = symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.id.as_str()))
+            .collect();
+        assert_eq!(
+            symbols,
+            [("Btn", "packages/app/src/components/Button.tsx::Button")]
+        );
+
+        // A named import is not taken for the default export
+        let (_, symbols) = project(modules.resolve(&import(
+            "packages/app/src/main.ts",
+            "./components/Button",
+            &["Btn"],
+        )));
+        assert!(symbols.is_empty());
+
+        let (files, _) = project(modules.resolve(&import(
+            "packages/app/src/components/Button.tsx",
+            "../utils",
+            &[],
+        )));
+        assert_eq!(files, vec![PathBuf::from("packages/app/src/utils/index.ts")]);
+    }
+
+    #[test]
+    fn test_resolves_tsconfig_paths() {
+        let dir = project_dir();
+        let modules = NodeModules::load(dir.path());
+
+        let (files, symbols) = project(modules.resolve(&import(
+            "packages/app/src/main.ts",
+            "@/utils",
+            &["formatDate"],
+        )));
+        assert_eq!(files, vec![PathBuf::from("packages/app/src/utils/index.ts")]);
+        assert_eq!(
+            symbols,
+            vec!["packages/app/src/utils/index.ts::formatDate".to_string()]
+        );
+
+        // baseUrl makes src/ importable by its path too
+        let (files, _) = project(modules.resolve(&import(
+            "packages/app/src/main.ts",
+            "src/components/Button",
+            &[],
+        )));
+        assert_eq!(files, vec![PathBuf::from("packages/app/src/components/Button.tsx")]);
+    }
+
+    #[test]
+    fn test_resolves_workspace_packages_through_reexports() {
+        let dir = project_dir();
+        let modules = NodeModules::load(dir.path());
+
+        let (files, symbols) = project(modules.resolve(&import(
+            "packages/app/src/main.ts",
+            "@acme/ui",
+            &["theme", "Card"],
+        )));
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("packages/ui/src/index.ts"),
+                PathBuf::from("packages/ui/src/theme.ts"),
+            ]
+        );
+        assert_eq!(symbols, vec!["packages/ui/src/theme.ts::theme".to_string()]);
+
+        let (_, symbols) = project(modules.resolve(&import(
+            "packages/app/src/main.ts",
+            "@acme/ui",
+            &["Panel"],
+        )));
+        assert_eq!(symbols, vec!["packages/ui/src/card.ts::Card".to_string()]);
+
+        let (files, _) = project(modules.resolve(&import(
+            "packages/app/src/main.ts",
+            "@acme/ui/icons/star",
+            &[],
+        )));
+        assert_eq!(files, vec![PathBuf::from("packages/ui/src/icons/star.ts")]);
+    }
+
+    #[test]
+    fn test_reads_large_lockfiles() {
+        let dir = TempDir::new().unwrap();
+        let packages: Vec<String> = (0..20_000)
+            .map(|i| format!("\"node_modules/pkg-{}\": {{ \"version\": \"1.0.{}\" }}", i, i))
+            .collect();
+        let lock = format!(
+            "{{ \"lockfileVersion\": 3, \"packages\": {{ \"\": {{}}, {} }} }}",
+            packages.join(",\n")
+        );
+        write(dir.path(), "package-lock.json", &lock);
+
+        let versions = locked_versions(dir.path());
+        assert_eq!(versions.len(), 20_000);
+        assert_eq!(versions["pkg-19999"], "1.0.19999");
+
+        let stripped: Value = serde_json::from_str(&strip_jsonc(&lock)).unwrap();
+        assert_eq!(stripped, serde_json::from_str::<Value>(&lock).unwrap());
+        assert_eq!(strip_jsonc("{ \"a\": [1, 2,\n ], }"), "{ \"a\": [1, 2\n ] }");
+    }
+
+    #[test]
+    fn test_tags_external_packages() {
+        let dir = project_dir();
+        let modules = NodeModules::load(dir.path());
+
+        let external = |target: &str| match modules.resolve(&import("packages/app/src/main.ts", target, &[])) {
+            Some(ImportTarget::External { package, version }) => Some((package, version)),
+            _ => None,
+        };
+        assert_eq!(
+            external("react/jsx-runtime"),
+            Some(("react".to_string(), Some("18.3.1".to_string())))
+        );
+        assert_eq!(external("node:fs"), Some(("node".to_string(), None)));
+        assert_eq!(external("path"), Some(("node".to_string(), None)));
+        assert_eq!(external("left-pad"), None);
+    }
+}
@@ -0,0 +1,885 @@
+//! Python packages
+//!
+//! Absolute imports are looked up in the project's source roots: the
+//! directories configured in `pyproject.toml` (setuptools `package-dir` and
+//! `packages.find.where`, Poetry `packages`, Hatch wheel `packages` and
+//! pytest `pythonpath`), then `src/` and the project root. A module is a
+//! package directory with an `__init__.py`, a `.py` file or a directory
+//! without `__init__.py` (a namespace package). Relative imports are
+//! resolved from the importing file's package.
+//!
+//! Names imported from a module are followed through the module's own
+//! imports, so `from pkg import User` reaches the class even when
+//! `pkg/__init__.py` only re-exports it from `pkg.models`. Modules are parsed
+//! the first time a name is looked up in them.
+//!
+//! Imports that are not part of the project are tagged with the standard
+//! library or with the distribution providing them, found from the declared
+//! dependencies, lock files and the `*.dist-info` metadata of a virtual
+//! environment in the project.
+
+use crate::parser::extractor::{field_text, find_child, node_text};
+use crate::parser::pool::ParserPool;
+use crate::{Import, ImportTarget, ImportedSymbol, Language, Symbol};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use tracing::warn;
+use tree_sitter::Node;
+
+/// Maximum number of re-exports followed while resolving one name
+const MAX_REEXPORTS: usize = 8;
+
+/// Distributions whose import name differs from the distribution name
+const IMPORT_NAMES: [(&str, &str); 9] = [
+    ("beautifulsoup4", "bs4"),
+    ("pillow", "PIL"),
+    ("pyyaml", "yaml"),
+    ("scikit-learn", "sklearn"),
+    ("opencv-python", "cv2"),
+    ("python-dateutil", "dateutil"),
+    ("python-dotenv", "dotenv"),
+    ("pyjwt", "jwt"),
+    ("psycopg2-binary", "psycopg2"),
+];
+
+/// Virtual environment directories looked for in the project root
+const VENV_DIRS: [&str; 3] = [".venv", "venv", "env"];
+
+/// Lock files listing `[[package]]` entries with a name and version
+const LOCK_FILES: [&str; 3] = ["poetry.lock", "uv.lock", "pdm.lock"];
+
+/// Top-level modules of the standard library (`sys.stdlib_module_names`,
+/// without private modules)
+const STDLIB_MODULES: [&str; 217] = [
+    "abc", "aifc", "antigravity", "argparse", "array", "ast", "asynchat", "asyncio",
+    "asyncore", "atexit", "audioop", "base64", "bdb", "binascii", "bisect", "builtins",
+    "bz2", "cProfile", "calendar", "cgi", "cgitb", "chunk", "cmath", "cmd", "code",
+    "codecs", "codeop", "collections", "colorsys", "compileall", "concurrent",
+    "configparser", "contextlib", "contextvars", "copy", "copyreg", "crypt", "csv",
+    "ctypes", "curses", "dataclasses", "datetime", "dbm", "decimal", "difflib", "dis",
+    "distutils", "doctest", "email", "encodings", "ensurepip", "enum", "errno",
+    "faulthandler", "fcntl", "filecmp", "fileinput", "fnmatch", "fractions", "ftplib",
+    "functools", "gc", "genericpath", "getopt", "getpass", "gettext", "glob", "graphlib",
+    "grp", "gzip", "hashlib", "heapq", "hmac", "html", "http", "idlelib", "imaplib",
+    "imghdr", "imp", "importlib", "inspect", "io", "ipaddress", "itertools", "json",
+    "keyword", "lib2to3", "linecache", "locale", "logging", "lzma", "mailbox", "mailcap",
+    "marshal", "math", "mimetypes", "mmap", "modulefinder", "msilib", "msvcrt",
+    "multiprocessing", "netrc", "nis", "nntplib", "nt", "ntpath", "nturl2path", "numbers",
+    "opcode", "operator", "optparse", "os", "ossaudiodev", "pathlib", "pdb", "pickle",
+    "pickletools", "pipes", "pkgutil", "platform", "plistlib", "poplib", "posix",
+    "posixpath", "pprint", "profile", "pstats", "pty", "pwd", "py_compile", "pyclbr",
+    "pydoc", "pydoc_data", "pyexpat", "queue", "quopri", "random", "re", "readline",
+    "reprlib", "resource", "rlcompleter", "runpy", "sched", "secrets", "select",
+    "selectors", "shelve", "shlex", "shutil", "signal", "site", "smtpd", "smtplib",
+    "sndhdr", "socket", "socketserver", "spwd", "sqlite3", "sre_compile", "sre_constants",
+    "sre_parse", "ssl", "stat", "statistics", "string", "stringprep", "struct",
+    "subprocess", "sunau", "symtable", "sys", "sysconfig", "syslog", "tabnanny", "tarfile",
+    "telnetlib", "tempfile", "termios", "textwrap", "this", "threading", "time", "timeit",
+    "tkinter", "token", "tokenize", "tomllib", "trace", "traceback", "tracemalloc", "tty",
+    "turtle", "turtledemo", "types", "typing", "unicodedata", "unittest", "urllib", "uu",
+    "uuid", "venv", "warnings", "wave", "weakref", "webbrowser", "winreg", "winsound",
+    "wsgiref", "xdrlib", "xml", "xmlrpc", "zipapp", "zipfile", "zipimport", "zlib",
+    "zoneinfo",
+];
+
+/// The packages of a Python project and the distributions it depends on
+#[derive(Debug, Default)]
+pub struct PythonPackages {
+    root: PathBuf,
+    /// Directories absolute imports are looked up in, relative to the
+    /// project root, in order
+    roots: Vec<PathBuf>,
+    /// Third-party distributions by the top-level name they are imported as
+    distributions: HashMap<String, Distribution>,
+    /// Top-level names of the modules looked into so far, by file
+    scopes: RwLock<HashMap<PathBuf, Arc<ModuleScope>>>,
+}
+
+/// A third-party distribution
+#[derive(Debug, Clone)]
+struct Distribution {
+    name: String,
+    version: Option<String>,
+}
+
+/// Where a module lives: its file, and its directories for packages
+#[derive(Debug, Clone, Default)]
+struct Location {
+    /// `.py` file or `__init__.py`; `None` for namespace packages
+    file: Option<PathBuf>,
+    /// Package directories holding the submodules, several for a namespace
+    /// package spread over source roots
+    dirs: Vec<PathBuf>,
+}
+
+/// What a name imported from a module refers to
+enum Target {
+    Module(Location),
+    Symbol { file: PathBuf, name: String },
+}
+
+/// Names a module defines or imports at its top level
+#[derive(Debug, Default)]
+struct ModuleScope {
+    definitions: HashSet<String>,
+    /// Imported names: the module they come from and the name imported
+    /// from it, or `None` when the name is the module itself
+    bindings: HashMap<String, (String, Option<String>)>,
+    /// Modules imported with `from module import *`
+    globs: Vec<String>,
+}
+
+impl PythonPackages {
+    /// Read the source roots and dependencies of the project at `root`
+    pub fn load(root: &Path) -> Self {
+        let pyproject = std::fs::read_to_string(root.join("pyproject.toml"))
+            .ok()
+            .and_then(|content| match toml::from_str::<toml::Value>(&content) {
+                Ok(value) => Some(value),
+                Err(e) => {
+                    warn!("Failed to parse pyproject.toml: {}", e);
+                    None
+                }
+            });
+
+        let mut roots: Vec<PathBuf> = pyproject.as_ref().map(configured_roots).unwrap_or_default();
+        if root.join("src").is_dir() {
+            roots.push(PathBuf::from("src"));
+        }
+        roots.push(PathBuf::new());
+        let mut seen = HashSet::new();
+        roots.retain(|r| seen.insert(r.clone()));
+
+        Self {
+            root: root.to_path_buf(),
+            roots,
+            distributions: distributions(root, pyproject.as_ref()),
+            scopes: RwLock::default(),
+        }
+    }
+
+    /// Resolve an `import` or `from ... import` statement
+    ///
+    /// Returns `None` for imports that are neither in the project nor
+    /// provided by a known distribution.
+    pub fn resolve(&self, import: &Import) -> Option<ImportTarget> {
+        let location = if import.target.starts_with('.') {
+            self.relative(&import.source_file, &import.target)?
+        } else {
+            let top = import.target.split('.').next().unwrap_or_default();
+            match self.absolute(&import.target) {
+                Some(location) if location.file.is_some() => location,
+                found => match self.external(top) {
+                    Some(external) => return Some(external),
+                    None => found?,
+                },
+            }
+        };
+
+        let mut files: Vec<PathBuf> = location.file.iter().cloned().collect();
+        let mut symbols = Vec::new();
+        for item in import.items.iter().filter(|i| *i != "*") {
+            match self.resolve_name(&location, item, 0) {
+                Some(Target::Module(module)) => files.extend(module.file),
+                Some(Target::Symbol { file, name }) => {
+                    symbols.push(ImportedSymbol {
+                        name: import.alias.clone().unwrap_or_else(|| item.clone()),
+                        id: Symbol::generate_id(&file, &name),
+                        file_path: file.clone(),
+                    });
+                    files.push(file);
+                }
+                None => {}
+            }
+        }
+
+        let mut seen = HashSet::new();
+        files.retain(|f| *f != import.source_file && seen.insert(f.clone()));
+        symbols.retain(|s| s.file_path != import.source_file);
+        Some(ImportTarget::Project { files, symbols })
+    }
+
+    /// The module named by an absolute dotted path, looked up in each
+    /// source root in turn
+    fn absolute(&self, module: &str) -> Option<Location> {
+        let segments: Vec<&str> = module.split('.').collect();
+        let mut namespace = Location::default();
+        for root in &self.roots {
+            match self.find(root, &segments) {
+                Some(location) if location.file.is_some() => return Some(location),
+                // A namespace package may span several roots
+                Some(location) => namespace.dirs.extend(location.dirs),
+                None => {}
+            }
+        }
+        (!namespace.dirs.is_empty()).then_some(namespace)
+    }
+
+    /// The module named by a relative import (`.models`, `..`) in `from`
+    fn relative(&self, from: &Path, module: &str) -> Option<Location> {
+        let rest = module.trim_start_matches('.');
+        let levels = module.len() - rest.len();
+        let mut dir = from.parent()?.to_path_buf();
+        for _ in 1..levels {
+            if !dir.pop() {
+                return None;
+            }
+        }
+
+        if rest.is_empty() {
+            let init = dir.join("__init__.py");
+            return Some(Location {
+                file: self.root.join(&init).is_file().then_some(init),
+                dirs: vec![dir],
+            });
+        }
+        self.find(&dir, &rest.split('.').collect::<Vec<_>>())
+    }
+
+    /// The module at a dotted path below a directory
+    fn find(&self, dir: &Path, segments: &[&str]) -> Option<Location> {
+        let (last, parents) = segments.split_last()?;
+        let mut dir = dir.to_path_buf();
+        for segment in parents {
+            dir.push(segment);
+            if !self.root.join(&dir).is_dir() {
+                return None;
+            }
+        }
+
+        let package = dir.join(last);
+        let init = package.join("__init__.py");
+        if self.root.join(&init).is_file() {
+            return Some(Location {
+                file: Some(init),
+                dirs: vec![package],
+            });
+        }
+        let file = dir.join(format!("{}.py", last));
+        if self.root.join(&file).is_file() {
+            return Some(Location {
+                file: Some(file),
+                dirs: Vec::new(),
+            });
+        }
+        self.root.join(&package).is_dir().then_some(Location {
+            file: None,
+            dirs: vec![package],
+        })
+    }
+
+    /// The standard library or the distribution a top-level module comes
+    /// from
+    fn external(&self, top: &str) -> Option<ImportTarget> {
+        if STDLIB_MODULES.contains(&top) {
+            return Some(ImportTarget::External {
+                package: "python".to_string(),
+                version: None,
+            });
+        }
+        self.distributions
+            .get(top)
+            .map(|distribution| ImportTarget::External {
+                package: distribution.name.clone(),
+                version: distribution.version.clone(),
+            })
+    }
+
+    /// What a name imported from a module refers to: a name the module
+    /// defines, one it imports itself, or a submodule of a package
+    fn resolve_name(&self, location: &Location, name: &str, depth: usize) -> Option<Target> {
+        let scope = location.file.as_ref().map(|file| (file, self.scope(file)));
+
+        if let Some((file, scope)) = &scope {
+            if scope.definitions.contains(name) {
+                return Some(Target::Symbol {
+                    file: (*file).clone(),
+                    name: name.to_string(),
+                });
+            }
+            if let Some((module, item)) = scope.bindings.get(name)
+                && depth < MAX_REEXPORTS
+                && let Some(source) = self.module(file, module)
+            {
+                return match item {
+                    Some(item) => self.resolve_name(&source, item, depth + 1),
+                    None => Some(Target::Module(source)),
+                };
+            }
+        }
+
+        if let Some(submodule) = location.dirs.iter().find_map(|dir| self.find(dir, &[name])) {
+            return Some(Target::Module(submodule));
+        }
+
+        let (file, scope) = scope?;
+        if depth >= MAX_REEXPORTS {
+            return None;
+        }
+        scope.globs.iter().find_map(|module| {
+            let source = self.module(file, module)?;
+            self.resolve_name(&source, name, depth + 1)
+        })
+    }
+
+    /// The project module an import in `file` names
+    fn module(&self, file: &Path, module: &str) -> Option<Location> {
+        if module.starts_with('.') {
+            self.relative(file, module)
+        } else {
+            self.absolute(module)
+        }
+    }
+
+    /// The top-level names of a module file, parsed on first use
+    fn scope(&self, file: &Path) -> Arc<ModuleScope> {
+        if let Some(scope) = self.scopes.read().ok().and_then(|s| s.get(file).cloned()) {
+            return scope;
+        }
+
+        let mut scope = ModuleScope::default();
+        match std::fs::read(self.root.join(file)) {
+            Ok(source) => match ParserPool::parse(Language::Python, &source) {
+                Ok(tree) => scope.add_statements(tree.root_node(), &source),
+                Err(e) => warn!("Failed to parse {:?}: {}", file, e),
+            },
+            Err(e) => warn!("Failed to read {:?}: {}", file, e),
+        }
+
+        let scope = Arc::new(scope);
+        if let Ok(mut scopes) = self.scopes.write() {
+            scopes.insert(file.to_path_buf(), Arc::clone(&scope));
+        }
+        scope
+    }
+}
+
+impl ModuleScope {
+    /// Record the names bound by a block's statements, including those in
+    /// `if`, `try` and `with` blocks but not in functions or classes
+    fn add_statements(&mut self, block: Node, source: &[u8]) {
+        let mut cursor = block.walk();
+        for statement in block.named_children(&mut cursor) {
+            match statement.kind() {
+                "function_definition" | "class_definition" => {
+                    self.definitions
+                        .extend(field_text(statement, "name", source));
+                }
+                "decorated_definition" => {
+                    if let Some(definition) = statement.child_by_field_name("definition") {
+                        self.definitions
+                            .extend(field_text(definition, "name", source));
+                    }
+                }
+                "expression_statement" => {
+                    let mut inner = statement.walk();
+                    for assignment in statement.named_children(&mut inner) {
+                        if assignment.kind() == "assignment"
+                            && let Some(left) = assignment.child_by_field_name("left")
+                        {
+                            self.add_targets(left, source);
+                        }
+                    }
+                }
+                "import_statement" => {
+                    let mut inner = statement.walk();
+                    for name in statement.children_by_field_name("name", &mut inner) {
+                        let (module, bound) = match name.kind() {
+                            "aliased_import" => (
+                                field_text(name, "name", source).unwrap_or_default(),
+                                field_text(name, "alias", source).unwrap_or_default(),
+                            ),
+                            // `import a.b` binds `a`
+                            _ => {
+                                let top = node_text(name, source).split('.').next().unwrap_or("");
+                                (top.to_string(), top.to_string())
+                            }
+                        };
+                        self.bindings.entry(bound).or_insert((module, None));
+                    }
+                }
+                "import_from_statement" => {
+                    let Some(module) = field_text(statement, "module_name", source) else {
+                        continue;
+                    };
+                    if find_child(statement, "wildcard_import").is_some() {
+                        self.globs.push(module.clone());
+                    }
+                    let mut inner = statement.walk();
+                    for name in statement.children_by_field_name("name", &mut inner) {
+                        let (item, bound) = match name.kind() {
+                            "aliased_import" => (
+                                field_text(name, "name", source).unwrap_or_default(),
+                                field_text(name, "alias", source).unwrap_or_default(),
+                            ),
+                            _ => {
+                                let item = node_text(name, source).to_string();
+                                (item.clone(), item)
+                            }
+                        };
+                        self.bindings
+                            .entry(bound)
+                            .or_insert((module.clone(), Some(item)));
+                    }
+                }
+                "if_statement" | "elif_clause" | "else_clause" | "try_statement"
+                | "except_clause" | "finally_clause" | "with_statement" | "block" => {
+                    self.add_statements(statement, source);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Record the names assigned by the left side of an assignment
+    fn add_targets(&mut self, target: Node, source: &[u8]) {
+        match target.kind() {
+            "identifier" => {
+                self.definitions
+                    .insert(node_text(target, source).to_string());
+            }
+            "pattern_list" | "tuple_pattern" | "list_pattern" => {
+                let mut cursor = target.walk();
+                for child in target.named_children(&mut cursor) {
+                    self.add_targets(child, source);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Source roots configured in `pyproject.toml`
+fn configured_roots(pyproject: &toml::Value) -> Vec<PathBuf> {
+    let tool = |path: &[&str]| {
+        path.iter()
+            .try_fold(pyproject.get("tool")?, |value, key| value.get(key))
+    };
+    let strings = |value: Option<&toml::Value>| -> Vec<String> {
+        match value {
+            Some(toml::Value::String(s)) => vec![s.clone()],
+            Some(toml::Value::Array(values)) => values
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect(),
+            _ => Vec::new(),
+        }
+    };
+
+    let mut roots = Vec::new();
+    roots.extend(strings(tool(&["setuptools", "package-dir", ""])));
+    roots.extend(strings(tool(&["setuptools", "packages", "find", "where"])));
+    if let Some(packages) = tool(&["poetry", "packages"]).and_then(|p| p.as_array()) {
+        roots.extend(
+            packages
+                .iter()
+                .filter_map(|p| p.get("from").and_then(|f| f.as_str()).map(str::to_string)),
+        );
+    }
+    // Hatch lists package directories rather than the roots holding them
+    for package in strings(tool(&["hatch", "build", "targets", "wheel", "packages"])) {
+        if let Some(parent) = Path::new(&package).parent() {
+            roots.push(parent.to_string_lossy().to_string());
+        }
+    }
+    roots.extend(strings(tool(&["pytest", "ini_options", "pythonpath"])));
+
+    roots
+        .iter()
+        .map(|r| r.trim_start_matches("./").trim_end_matches('/'))
+        .map(|r| {
+            if r == "." {
+                PathBuf::new()
+            } else {
+                PathBuf::from(r)
+            }
+        })
+        .collect()
+}
+
+/// Third-party distributions by top-level import name, from the declared
+/// dependencies, lock files and an installed virtual environment
+fn distributions(root: &Path, pyproject: Option<&toml::Value>) -> HashMap<String, Distribution> {
+    let mut locked: HashMap<String, String> = HashMap::new();
+    for lock in LOCK_FILES {
+        let Some(packages) = std::fs::read_to_string(root.join(lock))
+            .ok()
+            .and_then(|content| toml::from_str::<toml::Value>(&content).ok())
+            .and_then(|value| value.get("package").and_then(|p| p.as_array()).cloned())
+        else {
+            continue;
+        };
+        for package in packages {
+            if let (Some(name), Some(version)) = (
+                package.get("name").and_then(|n| n.as_str()),
+                package.get("version").and_then(|v| v.as_str()),
+            ) {
+                locked.insert(normalize(name), version.to_string());
+            }
+        }
+    }
+
+    let mut distributions = HashMap::new();
+    for (name, version, top_level) in installed(root) {
+        let version = locked.get(&normalize(&name)).cloned().or(Some(version));
+        for module in top_level {
+            distributions.entry(module).or_insert(Distribution {
+                name: name.clone(),
+                version: version.clone(),
+            });
+        }
+    }
+
+    for name in declared(root, pyproject) {
+        let normalized = normalize(&name);
+        let module = IMPORT_NAMES
+            .iter()
+            .find(|(distribution, _)| *distribution == normalized)
+            .map(|(_, module)| module.to_string())
+            .unwrap_or_else(|| normalized.replace('-', "_"));
+        distributions.entry(module).or_insert(Distribution {
+            version: locked.get(&normalized).cloned(),
+            name,
+        });
+    }
+    distributions
+}
+
+/// Names of the distributions listed as dependencies in `pyproject.toml`
+/// and `requirements*.txt`
+fn declared(root: &Path, pyproject: Option<&toml::Value>) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut add_requirement = |requirement: &str| {
+        let name: String = requirement
+            .trim()
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            .collect();
+        if !name.is_empty() {
+            names.push(name);
+        }
+    };
+
+    if let Some(pyproject) = pyproject {
+        let project = pyproject.get("project");
+        let lists = project
+            .and_then(|p| p.get("dependencies"))
+            .into_iter()
+            .chain(
+                project
+                    .and_then(|p| p.get("optional-dependencies"))
+                    .and_then(|o| o.as_table())
+                    .into_iter()
+                    .flat_map(|t| t.values()),
+            );
+        for requirement in lists.filter_map(|l| l.as_array()).flatten() {
+            if let Some(requirement) = requirement.as_str() {
+                add_requirement(requirement);
+            }
+        }
+
+        let poetry = pyproject.get("tool").and_then(|t| t.get("poetry"));
+        let tables = poetry
+            .and_then(|p| p.get("dependencies"))
+            .into_iter()
+            .chain(
+                poetry
+                    .and_then(|p| p.get("group"))
+                    .and_then(|g| g.as_table())
+                    .into_iter()
+                    .flat_map(|t| t.values())
+                    .filter_map(|group| group.get("dependencies")),
+            );
+        for name in tables.filter_map(|t| t.as_table()).flat_map(|t| t.keys()) {
+            if name != "python" {
+                add_requirement(name);
+            }
+        }
+    }
+
+    let mut requirements: Vec<PathBuf> = std::fs::read_dir(root)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("requirements") && n.ends_with(".txt"))
+        })
+        .collect();
+    requirements.sort();
+    for path in requirements {
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            // Options such as -r, -e and --index-url name no distribution
+            if !line.starts_with('-') && !line.contains("://") {
+                add_requirement(line);
+            }
+        }
+    }
+
+    names
+}
+
+/// Name, version and top-level modules of the distributions installed in a
+/// virtual environment in the project
+fn installed(root: &Path) -> Vec<(String, String, Vec<String>)> {
+    let mut site_packages = Vec::new();
+    for venv in VENV_DIRS {
+        let lib = root.join(venv).join("lib");
+        for entry in std::fs::read_dir(&lib).into_iter().flatten().flatten() {
+            site_packages.push(entry.path().join("site-packages"));
+        }
+        // Windows layout
+        site_packages.push(root.join(venv).join("Lib").join("site-packages"));
+    }
+
+    let mut distributions = Vec::new();
+    for dir in site_packages {
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(&dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|e| e == "dist-info"))
+            .collect();
+        entries.sort();
+        for path in entries {
+            let stem = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default();
+            let Some((name, version)) = stem.split_once('-') else {
+                continue;
+            };
+            let top_level = match std::fs::read_to_string(path.join("top_level.txt")) {
+                Ok(content) => content
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+                Err(_) => vec![normalize(name).replace('-', "_")],
+            };
+            distributions.push((name.to_string(), version.to_string(), top_level));
+        }
+    }
+    distributions
+}
+
+/// Normalized distribution name: lowercase with runs of `-`, `_` and `.`
+/// replaced by `-`
+fn normalize(name: &str) -> String {
+    let mut normalized = String::new();
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !normalized.ends_with('-') {
+                normalized.push('-');
+            }
+        } else {
+            normalized.push(c.to_ascii_lowercase());
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn import(file: &str, target: &str, items: &[&str]) -> Import {
+        Import {
+            source_file: PathBuf::from(file),
+            target: target.to_string(),
+            alias: None,
+            items: items.iter().map(|s| s.to_string()).collect(),
+            line: 1,
+            resolved: None,
+        }
+    }
+
+    fn project(target: Option<ImportTarget>) -> (Vec<PathBuf>, Vec<String>) {
+        match target {
+            Some(ImportTarget::Project { files, symbols }) => {
+                (files, symbols.into_iter().map(|s| s.id).collect())
+            }
+            other => panic!("expected a project import, got {:?}", other),
+        }
+    }
+
+    /// A src-layout package, a namespace package split over `src/` and a
+    /// pytest source root, and two declared dependencies
+    fn project_dir() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "pyproject.toml",
+            "[project]\ndependencies = [\"requests>=2\", \"PyYAML\"]\n\n[tool.pytest.ini_options]\npythonpath = [\"lib\"]\n",
+        );
+        write(
+            root,
+            "poetry.lock",
+            "[[package]]\nname = \"requests\"\nversion = \"2.32.3\"\n",
+        );
+        write(
+            root,
+            "src/shop/__init__.py",
+            "from .models import User as Customer\nfrom .util import *\n",
+        );
+        write(
+            root,
+            "src/shop/models.py",
+            "class User:\n    pass\n\nDEFAULT = User()\n",
+        );
+        write(root, "src/shop/util.py", "def helper():\n    pass\n");
+        write(
+            root,
+            "src/shop/api/handlers.py",
+            "from ..models import DEFAULT\n",
+        );
+        write(root, "src/plugins/audit.py", "def register():\n    pass\n");
+        write(
+            root,
+            "lib/plugins/billing.py",
+            "def register():\n    pass\n",
+        );
+        write(root, "lib/tools.py", "def run():\n    pass\n");
+        dir
+    }
+
+    #[test]
+    fn test_resolves_relative_imports() {
+        let dir = project_dir();
+        let packages = PythonPackages::load(dir.path());
+
+        let (files, symbols) = project(packages.resolve(&import(
+            "src/shop/api/handlers.py",
+            "..models",
+            &["DEFAULT"],
+        )));
+        assert_eq!(files, vec![PathBuf::from("src/shop/models.py")]);
+        assert_eq!(symbols, vec!["src/shop/models.py::DEFAULT".to_string()]);
+
+        let (files, _) = project(packages.resolve(&import("src/shop/__init__.py", ".", &["util"])));
+        assert_eq!(files, vec![PathBuf::from("src/shop/util.py")]);
+    }
+
+    #[test]
+    fn test_renamed_items_are_named_by_alias() {
+        let dir = project_dir();
+        let packages = PythonPackages::load(dir.path());
+
+        let renamed = Import {
+            alias: Some("Customer".to_string()),
+            ..import("src/shop/__init__.py", ".models", &["User"])
+        };
+        let Some(ImportTarget::Project { symbols, .. }) = packages.resolve(&renamed) else {
+            panic!("expected a project import");
+        };
+        let names: Vec<(&str, &str)> = symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.id.as_str()))
+            .collect();
+        assert_eq!(names, [("Customer", "src/shop/models.py::User")]);
+    }
+
+    #[test]
+    fn test_follows_package_reexports() {
+        let dir = project_dir();
+        let packages = PythonPackages::load(dir.path());
+
+        let (files, symbols) = project(packages.resolve(&import(
+            "tests/test_shop.py",
+            "shop",
+            &["Customer", "helper"],
+        )));
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("src/shop/__init__.py"),
+                PathBuf::from("src/shop/models.py"),
+                PathBuf::from("src/shop/util.py"),
+            ]
+        );
+        assert_eq!(
+            symbols,
+            vec![
+                "src/shop/models.py::User".to_string(),
+                "src/shop/util.py::helper".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolves_configured_roots_and_namespace_packages() {
+        let dir = project_dir();
+        let packages = PythonPackages::load(dir.path());
+
+        let (files, _) = project(packages.resolve(&import("tests/test_tools.py", "tools", &[])));
+        assert_eq!(files, vec![PathBuf::from("lib/tools.py")]);
+
+        let (files, _) = project(packages.resolve(&import(
+            "tests/test_plugins.py",
+            "plugins",
+            &["audit", "billing"],
+        )));
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("src/plugins/audit.py"),
+                PathBuf::from("lib/plugins/billing.py"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tags_external_imports() {
+        let dir = project_dir();
+        let packages = PythonPackages::load(dir.path());
+
+        assert_eq!(
+            packages.resolve(&import("lib/tools.py", "requests.adapters", &[])),
+            Some(ImportTarget::External {
+                package: "requests".to_string(),
+                version: Some("2.32.3".to_string()),
+            })
+        );
+        assert_eq!(
+            packages.resolve(&import("lib/tools.py", "yaml", &["safe_load"])),
+            Some(ImportTarget::External {
+                package: "PyYAML".to_string(),
+                version: None,
+            })
+        );
+        assert_eq!(
+            packages.resolve(&import("lib/tools.py", "os.path", &[])),
+            Some(ImportTarget::External {
+                package: "python".to_string(),
+                version: None,
+            })
+        );
+        assert_eq!(
+            packages.resolve(&import("lib/tools.py", "numpy", &[])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("Typing_Extensions"), "typing-extensions");
+        assert_eq!(normalize("zope.interface"), "zope-interface");
+    }
+}